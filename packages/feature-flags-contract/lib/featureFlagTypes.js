@@ -5,7 +5,11 @@
  * @class
  *
  * @property {string} FeatureFlagTypes.UPDATE_CONSENSUS_PARAMS
+ * @property {string} FeatureFlagTypes.UPDATE_FEE_MULTIPLIER
+ * @property {string} FeatureFlagTypes.UPDATE_FEE_SCHEDULE
  */
 module.exports = {
   UPDATE_CONSENSUS_PARAMS: 'updateConsensusParams',
+  UPDATE_FEE_MULTIPLIER: 'updateFeeMultiplier',
+  UPDATE_FEE_SCHEDULE: 'updateFeeSchedule',
 };
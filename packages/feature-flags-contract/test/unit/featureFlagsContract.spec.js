@@ -604,5 +604,270 @@ describe('Feature Flags contract', () => {
         expect(result.isValid()).to.be.true();
       });
     });
+
+    describe('updateFeeMultiplier', () => {
+      let rawUpdateFeeMultiplierDocument;
+
+      beforeEach(() => {
+        rawUpdateFeeMultiplierDocument = {
+          enableAtHeight: 42,
+          feeMultiplier: 1.5,
+        };
+      });
+
+      it('should not have additional properties', async () => {
+        rawUpdateFeeMultiplierDocument.someOtherProperty = 42;
+
+        try {
+          dpp.document.create(dataContract, identityId, 'updateFeeMultiplier', rawUpdateFeeMultiplierDocument);
+
+          expect.fail('should throw error');
+        } catch (e) {
+          expect(e.name).to.equal('InvalidDocumentError');
+          expect(e.getErrors()).to.have.a.lengthOf(1);
+
+          const [error] = e.getErrors();
+
+          expect(error.name).to.equal('JsonSchemaError');
+          expect(error.keyword).to.equal('additionalProperties');
+          expect(error.params.additionalProperty).to.equal('someOtherProperty');
+        }
+      });
+
+      describe('enabledAtHeight', () => {
+        it('should be present', async () => {
+          delete rawUpdateFeeMultiplierDocument.enableAtHeight;
+
+          try {
+            dpp.document.create(dataContract, identityId, 'updateFeeMultiplier', rawUpdateFeeMultiplierDocument);
+
+            expect.fail('should throw error');
+          } catch (e) {
+            expect(e.name).to.equal('InvalidDocumentError');
+            expect(e.getErrors()).to.have.a.lengthOf(1);
+
+            const [error] = e.getErrors();
+
+            expect(error.name).to.equal('JsonSchemaError');
+            expect(error.keyword).to.equal('required');
+            expect(error.params.missingProperty).to.equal('enableAtHeight');
+          }
+        });
+
+        it('should be at least 1', () => {
+          rawUpdateFeeMultiplierDocument.enableAtHeight = 0;
+
+          try {
+            dpp.document.create(dataContract, identityId, 'updateFeeMultiplier', rawUpdateFeeMultiplierDocument);
+
+            expect.fail('should throw error');
+          } catch (e) {
+            expect(e.name).to.equal('InvalidDocumentError');
+            expect(e.getErrors()).to.have.a.lengthOf(1);
+
+            const [error] = e.getErrors();
+
+            expect(error.name).to.equal('JsonSchemaError');
+            expect(error.keyword).to.equal('minimum');
+            expect(error.params.limit).to.equal(1);
+          }
+        });
+      });
+
+      describe('feeMultiplier', () => {
+        it('should be present', async () => {
+          delete rawUpdateFeeMultiplierDocument.feeMultiplier;
+
+          try {
+            dpp.document.create(dataContract, identityId, 'updateFeeMultiplier', rawUpdateFeeMultiplierDocument);
+
+            expect.fail('should throw error');
+          } catch (e) {
+            expect(e.name).to.equal('InvalidDocumentError');
+            expect(e.getErrors()).to.have.a.lengthOf(1);
+
+            const [error] = e.getErrors();
+
+            expect(error.name).to.equal('JsonSchemaError');
+            expect(error.keyword).to.equal('required');
+            expect(error.params.missingProperty).to.equal('feeMultiplier');
+          }
+        });
+
+        it('should be a number', () => {
+          rawUpdateFeeMultiplierDocument.feeMultiplier = 'string';
+
+          try {
+            dpp.document.create(dataContract, identityId, 'updateFeeMultiplier', rawUpdateFeeMultiplierDocument);
+
+            expect.fail('should throw error');
+          } catch (e) {
+            expect(e.name).to.equal('InvalidDocumentError');
+            expect(e.getErrors()).to.have.a.lengthOf(1);
+
+            const [error] = e.getErrors();
+
+            expect(error.name).to.equal('JsonSchemaError');
+            expect(error.keyword).to.equal('type');
+            expect(error.params.type).to.equal('number');
+          }
+        });
+
+        it('should be greater than 0', () => {
+          rawUpdateFeeMultiplierDocument.feeMultiplier = 0;
+
+          try {
+            dpp.document.create(dataContract, identityId, 'updateFeeMultiplier', rawUpdateFeeMultiplierDocument);
+
+            expect.fail('should throw error');
+          } catch (e) {
+            expect(e.name).to.equal('InvalidDocumentError');
+            expect(e.getErrors()).to.have.a.lengthOf(1);
+
+            const [error] = e.getErrors();
+
+            expect(error.name).to.equal('JsonSchemaError');
+            expect(error.keyword).to.equal('exclusiveMinimum');
+            expect(error.params.limit).to.equal(0);
+          }
+        });
+      });
+
+      it('should be valid', async () => {
+        const updateFeeMultiplier = dpp.document.create(dataContract, identityId, 'updateFeeMultiplier', rawUpdateFeeMultiplierDocument);
+
+        const result = await dpp.document.validate(updateFeeMultiplier);
+
+        expect(result.isValid()).to.be.true();
+      });
+    });
+
+    describe('updateFeeSchedule', () => {
+      let rawUpdateFeeScheduleDocument;
+
+      beforeEach(() => {
+        rawUpdateFeeScheduleDocument = {
+          enableAtHeight: 42,
+          feeSchedule: {
+            storageDiskUsageCreditPerByte: 27000,
+            storageProcessingCreditPerByte: 400,
+            storageLoadCreditPerByte: 400,
+            nonStorageLoadCreditPerByte: 30,
+            queryCreditPerByte: 10,
+            storageSeekCost: 4000,
+            sha256Cost: 4000,
+            sha2562Cost: 8000,
+            blake3Cost: 1000,
+          },
+        };
+      });
+
+      it('should not have additional properties', async () => {
+        rawUpdateFeeScheduleDocument.someOtherProperty = 42;
+
+        try {
+          dpp.document.create(dataContract, identityId, 'updateFeeSchedule', rawUpdateFeeScheduleDocument);
+
+          expect.fail('should throw error');
+        } catch (e) {
+          expect(e.name).to.equal('InvalidDocumentError');
+          expect(e.getErrors()).to.have.a.lengthOf(1);
+
+          const [error] = e.getErrors();
+
+          expect(error.name).to.equal('JsonSchemaError');
+          expect(error.keyword).to.equal('additionalProperties');
+          expect(error.params.additionalProperty).to.equal('someOtherProperty');
+        }
+      });
+
+      describe('enabledAtHeight', () => {
+        it('should be present', async () => {
+          delete rawUpdateFeeScheduleDocument.enableAtHeight;
+
+          try {
+            dpp.document.create(dataContract, identityId, 'updateFeeSchedule', rawUpdateFeeScheduleDocument);
+
+            expect.fail('should throw error');
+          } catch (e) {
+            expect(e.name).to.equal('InvalidDocumentError');
+            expect(e.getErrors()).to.have.a.lengthOf(1);
+
+            const [error] = e.getErrors();
+
+            expect(error.name).to.equal('JsonSchemaError');
+            expect(error.keyword).to.equal('required');
+            expect(error.params.missingProperty).to.equal('enableAtHeight');
+          }
+        });
+      });
+
+      describe('feeSchedule', () => {
+        it('should be present', async () => {
+          delete rawUpdateFeeScheduleDocument.feeSchedule;
+
+          try {
+            dpp.document.create(dataContract, identityId, 'updateFeeSchedule', rawUpdateFeeScheduleDocument);
+
+            expect.fail('should throw error');
+          } catch (e) {
+            expect(e.name).to.equal('InvalidDocumentError');
+            expect(e.getErrors()).to.have.a.lengthOf(1);
+
+            const [error] = e.getErrors();
+
+            expect(error.name).to.equal('JsonSchemaError');
+            expect(error.keyword).to.equal('required');
+            expect(error.params.missingProperty).to.equal('feeSchedule');
+          }
+        });
+
+        it('should have all costs', async () => {
+          delete rawUpdateFeeScheduleDocument.feeSchedule.storageSeekCost;
+
+          try {
+            dpp.document.create(dataContract, identityId, 'updateFeeSchedule', rawUpdateFeeScheduleDocument);
+
+            expect.fail('should throw error');
+          } catch (e) {
+            expect(e.name).to.equal('InvalidDocumentError');
+            expect(e.getErrors()).to.have.a.lengthOf(1);
+
+            const [error] = e.getErrors();
+
+            expect(error.name).to.equal('JsonSchemaError');
+            expect(error.keyword).to.equal('required');
+            expect(error.params.missingProperty).to.equal('storageSeekCost');
+          }
+        });
+
+        it('should have non negative integer costs', async () => {
+          rawUpdateFeeScheduleDocument.feeSchedule.storageSeekCost = -1;
+
+          try {
+            dpp.document.create(dataContract, identityId, 'updateFeeSchedule', rawUpdateFeeScheduleDocument);
+
+            expect.fail('should throw error');
+          } catch (e) {
+            expect(e.name).to.equal('InvalidDocumentError');
+            expect(e.getErrors()).to.have.a.lengthOf(1);
+
+            const [error] = e.getErrors();
+
+            expect(error.name).to.equal('JsonSchemaError');
+            expect(error.keyword).to.equal('minimum');
+            expect(error.params.limit).to.equal(0);
+          }
+        });
+      });
+
+      it('should be valid', async () => {
+        const updateFeeSchedule = dpp.document.create(dataContract, identityId, 'updateFeeSchedule', rawUpdateFeeScheduleDocument);
+
+        const result = await dpp.document.validate(updateFeeSchedule);
+
+        expect(result.isValid()).to.be.true();
+      });
+    });
   });
 });
@@ -1,5 +1,7 @@
 const { hash } = require('@dashevo/dpp/lib/util/hash');
 
+const featureFlagTypes = require('@dashevo/feature-flags-contract/lib/featureFlagTypes');
+
 const NotSupportedNetworkProtocolVersionError = require('../errors/NotSupportedNetworkProtocolVersionError');
 const NetworkProtocolVersionIsNotSetError = require('../errors/NetworkProtocolVersionIsNotSetError');
 
@@ -18,6 +20,7 @@ const protoTimestampToMillis = require('../../../util/protoTimestampToMillis');
  * @param {updateSimplifiedMasternodeList} updateSimplifiedMasternodeList
  * @param {waitForChainLockedHeight} waitForChainLockedHeight
  * @param {synchronizeMasternodeIdentities} synchronizeMasternodeIdentities
+ * @param {getLatestFeatureFlag} getLatestFeatureFlag
  * @param {RSAbci} rsAbci
  * @param {ExecutionTimer} executionTimer
  *
@@ -33,6 +36,7 @@ function beginBlockFactory(
   updateSimplifiedMasternodeList,
   waitForChainLockedHeight,
   synchronizeMasternodeIdentities,
+  getLatestFeatureFlag,
   rsAbci,
   executionTimer,
 ) {
@@ -118,12 +122,37 @@ function beginBlockFactory(
       proposerProTxHash,
       // TODO replace with real value
       validatorSetQuorumHash: Buffer.alloc(32),
+      protocolVersion: version.app.toNumber(),
     };
 
     if (!latestBlockExecutionContext.isEmpty()) {
       rsRequest.previousBlockTimeMs = latestBlockExecutionContext.getTimeMs();
     }
 
+    // Processing fee multiplier is chosen by governance for epochs started by the block
+
+    const updateFeeMultiplierFeatureFlag = await getLatestFeatureFlag(
+      featureFlagTypes.UPDATE_FEE_MULTIPLIER,
+      height,
+      true,
+    );
+
+    if (updateFeeMultiplierFeatureFlag) {
+      rsRequest.feeMultiplier = updateFeeMultiplierFeatureFlag.get('feeMultiplier');
+    }
+
+    // Fee schedule is activated by governance for epochs started by the block
+
+    const updateFeeScheduleFeatureFlag = await getLatestFeatureFlag(
+      featureFlagTypes.UPDATE_FEE_SCHEDULE,
+      height,
+      true,
+    );
+
+    if (updateFeeScheduleFeatureFlag) {
+      rsRequest.feeSchedule = updateFeeScheduleFeatureFlag.get('feeSchedule');
+    }
+
     consensusLogger.debug(rsRequest, 'Request RS Drive\'s BlockBegin method');
 
     const rsResponse = await rsAbci.blockBegin(rsRequest, true);
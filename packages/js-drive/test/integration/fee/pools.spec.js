@@ -92,6 +92,7 @@ describe('Fee Pools', () => {
         blockTimeMs,
         proposerProTxHash: mnIdentity.getId(),
         validatorSetQuorumHash: Buffer.alloc(32),
+        protocolVersion: 1,
       };
 
       if (previousBlockTimeMs) {
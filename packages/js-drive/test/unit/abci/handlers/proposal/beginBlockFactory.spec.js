@@ -9,6 +9,8 @@ const {
 
 const { hash } = require('@dashevo/dpp/lib/util/hash');
 
+const featureFlagTypes = require('@dashevo/feature-flags-contract/lib/featureFlagTypes');
+
 const beginBlockFactory = require('../../../../../lib/abci/handlers/proposal/beginBlockFactory');
 
 const BlockExecutionContextMock = require('../../../../../lib/test/mock/BlockExecutionContextMock');
@@ -33,6 +35,7 @@ describe('beginBlockFactory', () => {
   let dppMock;
   let transactionalDppMock;
   let synchronizeMasternodeIdentitiesMock;
+  let getLatestFeatureFlagMock;
   let groveDBStoreMock;
   let version;
   let rsAbciMock;
@@ -96,6 +99,8 @@ describe('beginBlockFactory', () => {
       toHeight: 42,
     });
 
+    getLatestFeatureFlagMock = this.sinon.stub().resolves(null);
+
     groveDBStoreMock = new GroveDBStoreMock(this.sinon);
 
     rsResponseMock = {
@@ -116,6 +121,7 @@ describe('beginBlockFactory', () => {
       updateSimplifiedMasternodeListMock,
       waitForChainLockedHeightMock,
       synchronizeMasternodeIdentitiesMock,
+      getLatestFeatureFlagMock,
       rsAbciMock,
       executionTimerMock,
     );
@@ -187,6 +193,60 @@ describe('beginBlockFactory', () => {
       .to.be.calledOnceWithExactly(epochInfo);
   });
 
+  it('should pass the fee multiplier chosen by governance to RS Drive', async function it() {
+    const updateFeeMultiplierFeatureFlag = {
+      get: this.sinon.stub().withArgs('feeMultiplier').returns(1.5),
+    };
+
+    getLatestFeatureFlagMock
+      .withArgs(featureFlagTypes.UPDATE_FEE_MULTIPLIER)
+      .resolves(updateFeeMultiplierFeatureFlag);
+
+    await beginBlock(request, loggerMock);
+
+    expect(getLatestFeatureFlagMock).to.be.calledWithExactly(
+      featureFlagTypes.UPDATE_FEE_MULTIPLIER,
+      blockHeight,
+      true,
+    );
+
+    expect(rsAbciMock.blockBegin).to.be.calledOnce();
+    expect(rsAbciMock.blockBegin.getCall(0).args[0].feeMultiplier).to.equal(1.5);
+  });
+
+  it('should pass the fee schedule activated by governance to RS Drive', async function it() {
+    const feeSchedule = {
+      storageDiskUsageCreditPerByte: 27000,
+      storageProcessingCreditPerByte: 400,
+      storageLoadCreditPerByte: 400,
+      nonStorageLoadCreditPerByte: 30,
+      queryCreditPerByte: 10,
+      storageSeekCost: 4000,
+      sha256Cost: 4000,
+      sha2562Cost: 8000,
+      blake3Cost: 1000,
+    };
+
+    const updateFeeScheduleFeatureFlag = {
+      get: this.sinon.stub().withArgs('feeSchedule').returns(feeSchedule),
+    };
+
+    getLatestFeatureFlagMock
+      .withArgs(featureFlagTypes.UPDATE_FEE_SCHEDULE)
+      .resolves(updateFeeScheduleFeatureFlag);
+
+    await beginBlock(request, loggerMock);
+
+    expect(getLatestFeatureFlagMock).to.be.calledWithExactly(
+      featureFlagTypes.UPDATE_FEE_SCHEDULE,
+      blockHeight,
+      true,
+    );
+
+    expect(rsAbciMock.blockBegin).to.be.calledOnce();
+    expect(rsAbciMock.blockBegin.getCall(0).args[0].feeSchedule).to.deep.equal(feeSchedule);
+  });
+
   it('should synchronize masternode identities if SML is updated', async () => {
     updateSimplifiedMasternodeListMock.resolves(true);
 
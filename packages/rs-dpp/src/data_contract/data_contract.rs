@@ -20,6 +20,7 @@ use crate::{
 };
 
 use super::errors::*;
+use super::extra::common::cbor_owned_map_to_btree_map;
 use super::extra::DocumentType;
 use super::extra::{get_definitions, get_document_types, get_mutability, ContractConfig};
 
@@ -381,6 +382,31 @@ impl DataContract {
         })
     }
 
+    /// Generates the document types used by Drive from the document schemas. Contracts
+    /// decoded from CBOR have them already, contracts created from a raw object need
+    /// them to be generated before being passed to Drive.
+    pub fn generate_document_types(&mut self) -> Result<(), ProtocolError> {
+        let contract_cbor_map = match self.to_cbor_canonical_map()?.to_value_sorted() {
+            CborValue::Map(map) => cbor_owned_map_to_btree_map(map),
+            _ => {
+                return Err(ProtocolError::EncodingError(String::from(
+                    "contract must be encoded as a map",
+                )))
+            }
+        };
+
+        let definition_references = get_definitions(&contract_cbor_map);
+        self.document_types = get_document_types(
+            &contract_cbor_map,
+            definition_references,
+            self.config.documents_keep_history_contract_default,
+            self.config.documents_mutable_contract_default,
+        )
+        .map_err(|e| ProtocolError::ParsingError(e.to_string()))?;
+
+        Ok(())
+    }
+
     fn generate_binary_properties(&mut self) {
         self.binary_properties = self
             .documents
@@ -466,6 +492,28 @@ mod test {
         assert_eq!(data_contract.documents, data_contract_restored.documents);
     }
 
+    #[test]
+    fn generated_document_types_should_match_decoded_ones() {
+        init();
+        let mut data_contract = get_data_contract_fixture(None);
+
+        let data_contract_bytes = data_contract
+            .to_buffer()
+            .expect("data contract should be converted into the bytes");
+        let data_contract_restored = DataContract::from_buffer(&data_contract_bytes)
+            .expect("data contract should be created from bytes");
+
+        data_contract
+            .generate_document_types()
+            .expect("document types should be generated");
+
+        assert!(!data_contract.document_types.is_empty());
+        assert_eq!(
+            data_contract.document_types,
+            data_contract_restored.document_types
+        );
+    }
+
     #[test]
     fn conversion_from_json() -> Result<()> {
         init();
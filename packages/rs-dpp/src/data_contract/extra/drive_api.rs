@@ -4,15 +4,23 @@ use crate::data_contract::DataContract;
 use crate::ProtocolError;
 
 use super::document_type::DocumentType;
+use super::drive_protobuf::{cbor_to_drive_protobuf, drive_protobuf_to_cbor};
 use super::errors::ContractError;
 use super::mutability;
 use super::root_tree::RootTree;
 
+/// The encodings Drive accepts for serialized contracts, documents and queries
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DriveEncoding {
+    /// CBOR
     DriveCbor,
+    /// Protobuf `DriveValue` messages, see [`super::value_to_drive_protobuf`]
     DriveProtobuf,
 }
 
+/// The length of the protocol version prefixing serialized contracts and documents
+pub const PROTOCOL_VERSION_PREFIX_LENGTH: usize = 4;
+
 /// The traits provides method specific for RS-Drive
 pub trait DriveContractExt {
     fn id(&self) -> &[u8; 32];
@@ -53,6 +61,8 @@ pub trait DriveContractExt {
 
     fn to_cbor(&self) -> Result<Vec<u8>, ContractError>;
 
+    fn serialize(&self, encoding: DriveEncoding) -> Result<Vec<u8>, ContractError>;
+
     fn document_type_for_name(
         &self,
         document_type_name: &str,
@@ -129,9 +139,10 @@ impl DriveContractExt for DataContract {
     {
         let mut data_contract = match encoding {
             DriveEncoding::DriveCbor => DataContract::from_cbor(serialized_contract)?,
-            DriveEncoding::DriveProtobuf => {
-                todo!()
-            }
+            DriveEncoding::DriveProtobuf => DataContract::from_cbor(drive_protobuf_to_cbor(
+                serialized_contract,
+                PROTOCOL_VERSION_PREFIX_LENGTH,
+            )?)?,
         };
         if let Some(id) = contract_id {
             data_contract.id.buffer = id
@@ -177,6 +188,16 @@ impl DriveContractExt for DataContract {
         Ok(buf)
     }
 
+    fn serialize(&self, encoding: DriveEncoding) -> Result<Vec<u8>, ContractError> {
+        let contract_cbor = DriveContractExt::to_cbor(self)?;
+        match encoding {
+            DriveEncoding::DriveCbor => Ok(contract_cbor),
+            DriveEncoding::DriveProtobuf => {
+                cbor_to_drive_protobuf(&contract_cbor, PROTOCOL_VERSION_PREFIX_LENGTH)
+            }
+        }
+    }
+
     fn document_type_for_name(
         &self,
         document_type_name: &str,
@@ -0,0 +1,364 @@
+//! The protobuf encoding of Drive values, an alternative to CBOR for contracts, documents
+//! and queries. A value is encoded as the following message:
+//!
+//! ```protobuf
+//! message DriveValue {
+//!   oneof value {
+//!     bool null = 1;
+//!     bool boolean = 2;
+//!     sint64 integer = 3;
+//!     uint64 unsigned_integer = 4;
+//!     double float = 5;
+//!     string text = 6;
+//!     bytes bytes = 7;
+//!     DriveValueArray array = 8;
+//!     DriveValueMap map = 9;
+//!   }
+//! }
+//!
+//! message DriveValueArray {
+//!   repeated DriveValue values = 1;
+//! }
+//!
+//! message DriveValueMap {
+//!   repeated DriveValueMapEntry entries = 1;
+//! }
+//!
+//! message DriveValueMapEntry {
+//!   DriveValue key = 1;
+//!   DriveValue value = 2;
+//! }
+//! ```
+//!
+//! Integers that fit an `i64` use the `integer` field, larger ones `unsigned_integer`.
+//! Map entries keep their order so converting CBOR to protobuf and back is lossless.
+
+use std::convert::{TryFrom, TryInto};
+
+use ciborium::value::{Integer, Value};
+use integer_encoding::VarInt;
+
+use super::errors::ContractError;
+
+const WIRE_TYPE_VARINT: u8 = 0;
+const WIRE_TYPE_FIXED_64: u8 = 1;
+const WIRE_TYPE_LENGTH_DELIMITED: u8 = 2;
+
+const FIELD_NULL: u64 = 1;
+const FIELD_BOOLEAN: u64 = 2;
+const FIELD_INTEGER: u64 = 3;
+const FIELD_UNSIGNED_INTEGER: u64 = 4;
+const FIELD_FLOAT: u64 = 5;
+const FIELD_TEXT: u64 = 6;
+const FIELD_BYTES: u64 = 7;
+const FIELD_ARRAY: u64 = 8;
+const FIELD_MAP: u64 = 9;
+
+/// The field of the repeated values of arrays and entries of maps.
+const FIELD_ELEMENTS: u64 = 1;
+const FIELD_ENTRY_KEY: u64 = 1;
+const FIELD_ENTRY_VALUE: u64 = 2;
+
+/// The maximum nesting of arrays and maps accepted when decoding.
+pub const MAX_DRIVE_PROTOBUF_DEPTH: usize = 256;
+
+fn corrupted(message: &'static str) -> ContractError {
+    ContractError::CorruptedSerialization(message)
+}
+
+fn write_key(field: u64, wire_type: u8, buffer: &mut Vec<u8>) {
+    buffer.extend(((field << 3) | wire_type as u64).encode_var_vec());
+}
+
+fn write_length_delimited(field: u64, bytes: &[u8], buffer: &mut Vec<u8>) {
+    write_key(field, WIRE_TYPE_LENGTH_DELIMITED, buffer);
+    buffer.extend((bytes.len() as u64).encode_var_vec());
+    buffer.extend_from_slice(bytes);
+}
+
+fn write_value(value: &Value, buffer: &mut Vec<u8>) -> Result<(), ContractError> {
+    match value {
+        Value::Null => {
+            write_key(FIELD_NULL, WIRE_TYPE_VARINT, buffer);
+            buffer.push(1);
+        }
+        Value::Bool(boolean) => {
+            write_key(FIELD_BOOLEAN, WIRE_TYPE_VARINT, buffer);
+            buffer.push(*boolean as u8);
+        }
+        Value::Integer(integer) => {
+            let integer = i128::from(*integer);
+            if let Ok(signed) = i64::try_from(integer) {
+                write_key(FIELD_INTEGER, WIRE_TYPE_VARINT, buffer);
+                // zigzag encoding of sint64
+                buffer.extend((((signed << 1) ^ (signed >> 63)) as u64).encode_var_vec());
+            } else {
+                let unsigned = u64::try_from(integer).map_err(|_| {
+                    ContractError::EncodingDataStructureNotSupported(
+                        "integers must fit in an i64 or a u64",
+                    )
+                })?;
+                write_key(FIELD_UNSIGNED_INTEGER, WIRE_TYPE_VARINT, buffer);
+                buffer.extend(unsigned.encode_var_vec());
+            }
+        }
+        Value::Float(float) => {
+            write_key(FIELD_FLOAT, WIRE_TYPE_FIXED_64, buffer);
+            buffer.extend_from_slice(&float.to_le_bytes());
+        }
+        Value::Text(text) => write_length_delimited(FIELD_TEXT, text.as_bytes(), buffer),
+        Value::Bytes(bytes) => write_length_delimited(FIELD_BYTES, bytes, buffer),
+        Value::Array(values) => {
+            let mut array = vec![];
+            for value in values {
+                let mut element = vec![];
+                write_value(value, &mut element)?;
+                write_length_delimited(FIELD_ELEMENTS, &element, &mut array);
+            }
+            write_length_delimited(FIELD_ARRAY, &array, buffer);
+        }
+        Value::Map(entries) => {
+            let mut map = vec![];
+            for (key, value) in entries {
+                let mut entry = vec![];
+                let mut element = vec![];
+                write_value(key, &mut element)?;
+                write_length_delimited(FIELD_ENTRY_KEY, &element, &mut entry);
+                element.clear();
+                write_value(value, &mut element)?;
+                write_length_delimited(FIELD_ENTRY_VALUE, &element, &mut entry);
+                write_length_delimited(FIELD_ELEMENTS, &entry, &mut map);
+            }
+            write_length_delimited(FIELD_MAP, &map, buffer);
+        }
+        _ => {
+            return Err(ContractError::EncodingDataStructureNotSupported(
+                "tagged values can not be encoded in protobuf",
+            ))
+        }
+    }
+    Ok(())
+}
+
+fn read_varint(bytes: &mut &[u8]) -> Result<u64, ContractError> {
+    let (value, read) =
+        u64::decode_var(*bytes).ok_or_else(|| corrupted("protobuf varint is malformed"))?;
+    *bytes = &bytes[read..];
+    Ok(value)
+}
+
+fn read_key(bytes: &mut &[u8]) -> Result<(u64, u8), ContractError> {
+    let key = read_varint(bytes)?;
+    Ok((key >> 3, (key & 0x07) as u8))
+}
+
+fn read_length_delimited<'a>(bytes: &mut &'a [u8]) -> Result<&'a [u8], ContractError> {
+    let length = read_varint(bytes)?;
+    if length > bytes.len() as u64 {
+        return Err(corrupted("protobuf length exceeds the message"));
+    }
+    let (field, rest) = bytes.split_at(length as usize);
+    *bytes = rest;
+    Ok(field)
+}
+
+/// Reads the length delimited fields of a message, all of which must have the given number.
+fn read_repeated<'a>(mut bytes: &'a [u8], field: u64) -> Result<Vec<&'a [u8]>, ContractError> {
+    let mut elements = vec![];
+    while !bytes.is_empty() {
+        let (element_field, wire_type) = read_key(&mut bytes)?;
+        if element_field != field || wire_type != WIRE_TYPE_LENGTH_DELIMITED {
+            return Err(corrupted("unexpected field in protobuf message"));
+        }
+        elements.push(read_length_delimited(&mut bytes)?);
+    }
+    Ok(elements)
+}
+
+fn read_value(mut bytes: &[u8], depth: usize) -> Result<Value, ContractError> {
+    if depth > MAX_DRIVE_PROTOBUF_DEPTH {
+        return Err(corrupted("protobuf value is nested too deeply"));
+    }
+    let (field, wire_type) = read_key(&mut bytes)?;
+    let value = match (field, wire_type) {
+        (FIELD_NULL, WIRE_TYPE_VARINT) => {
+            read_varint(&mut bytes)?;
+            Value::Null
+        }
+        (FIELD_BOOLEAN, WIRE_TYPE_VARINT) => Value::Bool(read_varint(&mut bytes)? != 0),
+        (FIELD_INTEGER, WIRE_TYPE_VARINT) => {
+            let zigzag = read_varint(&mut bytes)?;
+            let signed = (zigzag >> 1) as i64 ^ -((zigzag & 1) as i64);
+            Value::Integer(Integer::from(signed))
+        }
+        (FIELD_UNSIGNED_INTEGER, WIRE_TYPE_VARINT) => {
+            Value::Integer(Integer::from(read_varint(&mut bytes)?))
+        }
+        (FIELD_FLOAT, WIRE_TYPE_FIXED_64) => {
+            if bytes.len() < 8 {
+                return Err(corrupted("protobuf double is truncated"));
+            }
+            let (float_bytes, rest) = bytes.split_at(8);
+            bytes = rest;
+            Value::Float(f64::from_le_bytes(
+                float_bytes.try_into().expect("slice is 8 bytes long"),
+            ))
+        }
+        (FIELD_TEXT, WIRE_TYPE_LENGTH_DELIMITED) => {
+            let text = read_length_delimited(&mut bytes)?;
+            Value::Text(
+                String::from_utf8(text.to_vec())
+                    .map_err(|_| corrupted("protobuf string is not utf8"))?,
+            )
+        }
+        (FIELD_BYTES, WIRE_TYPE_LENGTH_DELIMITED) => {
+            Value::Bytes(read_length_delimited(&mut bytes)?.to_vec())
+        }
+        (FIELD_ARRAY, WIRE_TYPE_LENGTH_DELIMITED) => {
+            let array = read_length_delimited(&mut bytes)?;
+            Value::Array(
+                read_repeated(array, FIELD_ELEMENTS)?
+                    .into_iter()
+                    .map(|element| read_value(element, depth + 1))
+                    .collect::<Result<Vec<Value>, ContractError>>()?,
+            )
+        }
+        (FIELD_MAP, WIRE_TYPE_LENGTH_DELIMITED) => {
+            let map = read_length_delimited(&mut bytes)?;
+            Value::Map(
+                read_repeated(map, FIELD_ELEMENTS)?
+                    .into_iter()
+                    .map(|mut entry| {
+                        let (key_field, key_wire_type) = read_key(&mut entry)?;
+                        if key_field != FIELD_ENTRY_KEY
+                            || key_wire_type != WIRE_TYPE_LENGTH_DELIMITED
+                        {
+                            return Err(corrupted("protobuf map entry must start with its key"));
+                        }
+                        let key = read_value(read_length_delimited(&mut entry)?, depth + 1)?;
+                        let value = match read_repeated(entry, FIELD_ENTRY_VALUE)?.as_slice() {
+                            [value] => read_value(value, depth + 1)?,
+                            _ => {
+                                return Err(corrupted(
+                                    "protobuf map entry must have exactly one value",
+                                ))
+                            }
+                        };
+                        Ok((key, value))
+                    })
+                    .collect::<Result<Vec<(Value, Value)>, ContractError>>()?,
+            )
+        }
+        _ => return Err(corrupted("unknown field in protobuf value")),
+    };
+    if !bytes.is_empty() {
+        return Err(corrupted("protobuf value must have exactly one field"));
+    }
+    Ok(value)
+}
+
+/// Encodes a value as a `DriveValue` protobuf message.
+pub fn value_to_drive_protobuf(value: &Value) -> Result<Vec<u8>, ContractError> {
+    let mut buffer = vec![];
+    write_value(value, &mut buffer)?;
+    Ok(buffer)
+}
+
+/// Decodes a value from a `DriveValue` protobuf message.
+pub fn value_from_drive_protobuf(bytes: &[u8]) -> Result<Value, ContractError> {
+    read_value(bytes, 0)
+}
+
+/// Converts a CBOR serialization to protobuf, the first `prefix_length` bytes (such as a
+/// protocol version) are copied as they are.
+pub fn cbor_to_drive_protobuf(
+    serialized: &[u8],
+    prefix_length: usize,
+) -> Result<Vec<u8>, ContractError> {
+    if serialized.len() < prefix_length {
+        return Err(corrupted("serialization is shorter than its prefix"));
+    }
+    let (prefix, cbor) = serialized.split_at(prefix_length);
+    let value: Value = ciborium::de::from_reader(cbor)
+        .map_err(|_| ContractError::ValueDecodingError("unable to decode cbor"))?;
+    let mut buffer = prefix.to_vec();
+    write_value(&value, &mut buffer)?;
+    Ok(buffer)
+}
+
+/// Converts a protobuf serialization to CBOR, the first `prefix_length` bytes (such as a
+/// protocol version) are copied as they are.
+pub fn drive_protobuf_to_cbor(
+    serialized: &[u8],
+    prefix_length: usize,
+) -> Result<Vec<u8>, ContractError> {
+    if serialized.len() < prefix_length {
+        return Err(corrupted("serialization is shorter than its prefix"));
+    }
+    let (prefix, protobuf) = serialized.split_at(prefix_length);
+    let value = value_from_drive_protobuf(protobuf)?;
+    let mut buffer = prefix.to_vec();
+    ciborium::ser::into_writer(&value, &mut buffer)
+        .map_err(|_| ContractError::CorruptedCodeExecution("unable to encode cbor"))?;
+    Ok(buffer)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn should_round_trip_values_through_protobuf() {
+        let value = Value::Map(vec![
+            (Value::Text("null".into()), Value::Null),
+            (Value::Text("bool".into()), Value::Bool(true)),
+            (
+                Value::Text("negative".into()),
+                Value::Integer(Integer::from(-42i64)),
+            ),
+            (
+                Value::Text("large".into()),
+                Value::Integer(Integer::from(u64::MAX)),
+            ),
+            (Value::Text("float".into()), Value::Float(1.5)),
+            (Value::Text("bytes".into()), Value::Bytes(vec![0, 1, 2])),
+            (
+                Value::Text("array".into()),
+                Value::Array(vec![Value::Text("a".into()), Value::Array(vec![])]),
+            ),
+            (Value::Integer(Integer::from(7)), Value::Map(vec![])),
+        ]);
+
+        let encoded = value_to_drive_protobuf(&value).expect("expected to encode");
+        let decoded = value_from_drive_protobuf(&encoded).expect("expected to decode");
+
+        assert_eq!(decoded, value);
+    }
+
+    #[test]
+    fn should_convert_cbor_to_protobuf_and_back_keeping_the_prefix() {
+        let mut cbor = vec![1, 0, 0, 0];
+        ciborium::ser::into_writer(
+            &Value::Map(vec![(
+                Value::Text("name".into()),
+                Value::Text("alice".into()),
+            )]),
+            &mut cbor,
+        )
+        .expect("expected to encode cbor");
+
+        let protobuf = cbor_to_drive_protobuf(&cbor, 4).expect("expected to convert");
+        assert_eq!(&protobuf[..4], &[1, 0, 0, 0]);
+
+        let converted = drive_protobuf_to_cbor(&protobuf, 4).expect("expected to convert back");
+        assert_eq!(converted, cbor);
+    }
+
+    #[test]
+    fn should_reject_values_with_trailing_fields() {
+        let mut encoded = value_to_drive_protobuf(&Value::Bool(false)).expect("expected to encode");
+        encoded.extend(value_to_drive_protobuf(&Value::Null).expect("expected to encode"));
+
+        value_from_drive_protobuf(&encoded).expect_err("expected a single field");
+    }
+}
@@ -14,7 +14,11 @@ pub use {
         DocumentFieldType,
     },
     document_type::{DocumentType, IndexLevel},
-    drive_api::{DriveContractExt, DriveEncoding},
+    drive_api::{DriveContractExt, DriveEncoding, PROTOCOL_VERSION_PREFIX_LENGTH},
+    drive_protobuf::{
+        cbor_to_drive_protobuf, drive_protobuf_to_cbor, value_from_drive_protobuf,
+        value_to_drive_protobuf,
+    },
     errors::{ContractError, StructureError},
     index::{Index, IndexProperty},
     mutability::ContractConfig,
@@ -27,6 +31,7 @@ mod array_field;
 mod document_field;
 mod document_type;
 mod drive_api;
+mod drive_protobuf;
 mod errors;
 mod index;
 mod mutability;
@@ -9,7 +9,7 @@ use crate::{
     identity::KeyID,
     prelude::Identifier,
     state_transition::{
-        state_transition_execution_context::StateTransitionExecutionContext,
+        fee::Credits, state_transition_execution_context::StateTransitionExecutionContext,
         StateTransitionConvert, StateTransitionIdentitySigned, StateTransitionLike,
         StateTransitionType,
     },
@@ -34,6 +34,9 @@ pub struct DataContractCreateTransition {
     pub entropy: [u8; 32],
     pub signature_public_key_id: KeyID,
     pub signature: Vec<u8>,
+    /// Credits paid to the block proposer on top of the fees
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub user_tip: Option<Credits>,
     #[serde(skip)]
     pub execution_context: StateTransitionExecutionContext,
 }
@@ -46,6 +49,7 @@ impl std::default::Default for DataContractCreateTransition {
             entropy: [0u8; 32],
             signature_public_key_id: 0,
             signature: vec![],
+            user_tip: None,
             data_contract: Default::default(),
             execution_context: Default::default(),
         }
@@ -69,6 +73,7 @@ impl DataContractCreateTransition {
                 .unwrap_or_else(|_| [0u8; 32].to_vec())
                 .try_into()
                 .map_err(|_| anyhow!("entropy isn't 32 bytes long"))?,
+            user_tip: raw_data_contract_update_transition.get_u64(USER_TIP).ok(),
             data_contract: DataContract::from_raw_object(
                 raw_data_contract_update_transition.remove(DATA_CONTRACT)?,
             )?,
@@ -130,6 +135,10 @@ impl StateTransitionLike for DataContractCreateTransition {
         self.signature = signature
     }
 
+    fn get_user_tip(&self) -> Credits {
+        self.user_tip.unwrap_or_default()
+    }
+
     fn get_execution_context(&self) -> &StateTransitionExecutionContext {
         &self.execution_context
     }
@@ -0,0 +1,35 @@
+use anyhow::Result;
+
+use crate::{state_repository::StateRepositoryLike, state_transition::StateTransitionLike};
+
+use super::DataContractDeleteTransition;
+
+pub struct ApplyDataContractDeleteTransition<SR>
+where
+    SR: StateRepositoryLike,
+{
+    state_repository: SR,
+}
+
+impl<SR: StateRepositoryLike> ApplyDataContractDeleteTransition<SR> {
+    pub fn new(state_repository: SR) -> Self {
+        ApplyDataContractDeleteTransition { state_repository }
+    }
+}
+
+impl<SR> ApplyDataContractDeleteTransition<SR>
+where
+    SR: StateRepositoryLike,
+{
+    pub async fn apply_data_contract_delete_transition(
+        &self,
+        state_transition: &DataContractDeleteTransition,
+    ) -> Result<()> {
+        self.state_repository
+            .delete_data_contract(
+                &state_transition.data_contract_id,
+                state_transition.get_execution_context(),
+            )
+            .await
+    }
+}
@@ -0,0 +1,303 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+use crate::{
+    identity::KeyID,
+    prelude::Identifier,
+    state_transition::{
+        fee::Credits, state_transition_execution_context::StateTransitionExecutionContext,
+        StateTransitionConvert, StateTransitionIdentitySigned, StateTransitionLike,
+        StateTransitionType,
+    },
+    util::json_value::{JsonValueExt, ReplaceWith},
+    ProtocolError,
+};
+
+use super::property_names::*;
+
+pub mod apply_data_contract_delete_transition_factory;
+pub mod validation;
+
+/// Deletes a Data Contract created with `canBeDeleted` along with all its Documents
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataContractDeleteTransition {
+    pub protocol_version: u32,
+    #[serde(rename = "type")]
+    pub transition_type: StateTransitionType,
+    pub data_contract_id: Identifier,
+    pub owner_id: Identifier,
+    pub signature_public_key_id: KeyID,
+    pub signature: Vec<u8>,
+    /// Credits paid to the block proposer on top of the fees
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub user_tip: Option<Credits>,
+    #[serde(skip)]
+    pub execution_context: StateTransitionExecutionContext,
+}
+
+impl std::default::Default for DataContractDeleteTransition {
+    fn default() -> Self {
+        DataContractDeleteTransition {
+            protocol_version: Default::default(),
+            transition_type: StateTransitionType::DataContractDelete,
+            data_contract_id: Identifier::default(),
+            owner_id: Identifier::default(),
+            signature_public_key_id: 0,
+            signature: vec![],
+            user_tip: None,
+            execution_context: Default::default(),
+        }
+    }
+}
+
+impl DataContractDeleteTransition {
+    pub fn from_raw_object(
+        mut raw_data_contract_delete_transition: JsonValue,
+    ) -> Result<DataContractDeleteTransition, ProtocolError> {
+        Ok(DataContractDeleteTransition {
+            protocol_version: raw_data_contract_delete_transition.get_u64(PROTOCOL_VERSION)? as u32,
+            signature: raw_data_contract_delete_transition
+                .remove_into(SIGNATURE)
+                .unwrap_or_default(),
+            signature_public_key_id: raw_data_contract_delete_transition
+                .get_u64(SIGNATURE_PUBLIC_KEY_ID)
+                .unwrap_or_default(),
+            user_tip: raw_data_contract_delete_transition.get_u64(USER_TIP).ok(),
+            data_contract_id: Identifier::from_bytes(
+                &raw_data_contract_delete_transition.get_bytes(DATA_CONTRACT_ID)?,
+            )?,
+            owner_id: Identifier::from_bytes(
+                &raw_data_contract_delete_transition.get_bytes(OWNER_ID)?,
+            )?,
+            ..Default::default()
+        })
+    }
+
+    pub fn get_data_contract_id(&self) -> &Identifier {
+        &self.data_contract_id
+    }
+
+    /// Returns ID of the deleted contract
+    pub fn get_modified_data_ids(&self) -> Vec<&Identifier> {
+        vec![&self.data_contract_id]
+    }
+}
+
+impl StateTransitionIdentitySigned for DataContractDeleteTransition {
+    /// Get owner ID
+    fn get_owner_id(&self) -> &Identifier {
+        &self.owner_id
+    }
+
+    fn get_signature_public_key_id(&self) -> KeyID {
+        self.signature_public_key_id
+    }
+
+    fn set_signature_public_key_id(&mut self, key_id: crate::identity::KeyID) {
+        self.signature_public_key_id = key_id
+    }
+}
+
+impl StateTransitionLike for DataContractDeleteTransition {
+    fn get_protocol_version(&self) -> u32 {
+        self.protocol_version
+    }
+    /// returns the type of State Transition
+    fn get_type(&self) -> StateTransitionType {
+        self.transition_type
+    }
+    /// returns the signature as a byte-array
+    fn get_signature(&self) -> &Vec<u8> {
+        &self.signature
+    }
+    /// set a new signature
+    fn set_signature(&mut self, signature: Vec<u8>) {
+        self.signature = signature
+    }
+
+    fn get_user_tip(&self) -> Credits {
+        self.user_tip.unwrap_or_default()
+    }
+
+    fn get_execution_context(&self) -> &StateTransitionExecutionContext {
+        &self.execution_context
+    }
+
+    fn get_execution_context_mut(&mut self) -> &mut StateTransitionExecutionContext {
+        &mut self.execution_context
+    }
+
+    fn set_execution_context(&mut self, execution_context: StateTransitionExecutionContext) {
+        self.execution_context = execution_context
+    }
+}
+
+impl StateTransitionConvert for DataContractDeleteTransition {
+    fn signature_property_paths() -> Vec<&'static str> {
+        vec![SIGNATURE, SIGNATURE_PUBLIC_KEY_ID]
+    }
+
+    fn identifiers_property_paths() -> Vec<&'static str> {
+        vec![DATA_CONTRACT_ID, OWNER_ID]
+    }
+
+    fn binary_property_paths() -> Vec<&'static str> {
+        vec![SIGNATURE]
+    }
+
+    fn to_json(&self, skip_signature: bool) -> Result<JsonValue, ProtocolError> {
+        let mut json_value: JsonValue = serde_json::to_value(self)?;
+
+        if skip_signature {
+            if let JsonValue::Object(ref mut o) = json_value {
+                for path in Self::signature_property_paths() {
+                    o.remove(path);
+                }
+            }
+        }
+
+        json_value.replace_binary_paths(Self::binary_property_paths(), ReplaceWith::Base64)?;
+        json_value
+            .replace_identifier_paths(Self::identifiers_property_paths(), ReplaceWith::Base58)?;
+
+        Ok(json_value)
+    }
+
+    fn to_object(&self, skip_signature: bool) -> Result<JsonValue, ProtocolError> {
+        let mut json_object: JsonValue = serde_json::to_value(self)?;
+        json_object
+            .replace_identifier_paths(Self::identifiers_property_paths(), ReplaceWith::Bytes)?;
+
+        if skip_signature {
+            if let JsonValue::Object(ref mut o) = json_object {
+                for path in Self::signature_property_paths() {
+                    o.remove(path);
+                }
+            }
+        }
+        Ok(json_object)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use crate::data_contract::DataContract;
+    use crate::tests::fixtures::get_data_contract_fixture;
+    use crate::util::string_encoding::Encoding;
+    use crate::{util::deserializer::get_protocol_version, version};
+
+    use super::*;
+
+    struct TestData {
+        state_transition: DataContractDeleteTransition,
+        data_contract: DataContract,
+    }
+
+    fn get_test_data() -> TestData {
+        let data_contract = get_data_contract_fixture(None);
+
+        let state_transition = DataContractDeleteTransition::from_raw_object(json!({
+                    PROTOCOL_VERSION: version::LATEST_VERSION,
+                    DATA_CONTRACT_ID: data_contract.id.as_bytes(),
+                    OWNER_ID: data_contract.owner_id.as_bytes(),
+        }))
+        .expect("state transition should be created without errors");
+
+        TestData {
+            data_contract,
+            state_transition,
+        }
+    }
+
+    #[test]
+    fn should_return_transition_type() {
+        let data = get_test_data();
+        assert_eq!(
+            StateTransitionType::DataContractDelete,
+            data.state_transition.get_type()
+        );
+    }
+
+    #[test]
+    fn should_return_data_contract_id_and_owner_id() {
+        let data = get_test_data();
+
+        assert_eq!(
+            &data.data_contract.id,
+            data.state_transition.get_data_contract_id()
+        );
+        assert_eq!(
+            &data.data_contract.owner_id,
+            data.state_transition.get_owner_id()
+        );
+    }
+
+    #[test]
+    fn should_return_state_transition_in_json_format() {
+        let data = get_test_data();
+        let mut json_object = data
+            .state_transition
+            .to_json(false)
+            .expect("conversion to JSON shouldn't fail");
+
+        assert_eq!(
+            7,
+            json_object
+                .get_u64(TRANSITION_TYPE)
+                .expect("the transition type should be present") as u8
+        );
+        assert_eq!(
+            data.data_contract.id.to_string(Encoding::Base58),
+            json_object
+                .remove_into::<String>(DATA_CONTRACT_ID)
+                .expect("the data contract id should be present")
+        );
+    }
+
+    #[test]
+    fn should_be_created_from_its_raw_object() {
+        let data = get_test_data();
+
+        let raw_state_transition = data
+            .state_transition
+            .to_object(false)
+            .expect("conversion to object shouldn't fail");
+
+        let state_transition = DataContractDeleteTransition::from_raw_object(raw_state_transition)
+            .expect("state transition should be created without errors");
+
+        assert_eq!(
+            &data.data_contract.id,
+            state_transition.get_data_contract_id()
+        );
+        assert_eq!(
+            &data.data_contract.owner_id,
+            state_transition.get_owner_id()
+        );
+    }
+
+    #[test]
+    fn should_return_serialized_state_transition_to_buffer() {
+        let data = get_test_data();
+        let state_transition_bytes = data
+            .state_transition
+            .to_buffer(false)
+            .expect("state transition should be converted to buffer");
+        let (protocol_bytes, _) = state_transition_bytes.split_at(4);
+        assert_eq!(
+            version::LATEST_VERSION,
+            get_protocol_version(protocol_bytes).expect("version should be valid")
+        )
+    }
+
+    #[test]
+    fn is_data_contract_state_transition() {
+        let data = get_test_data();
+        assert!(data.state_transition.is_data_contract_state_transition());
+        assert!(!data.state_transition.is_document_state_transition());
+        assert!(!data.state_transition.is_identity_state_transition());
+    }
+}
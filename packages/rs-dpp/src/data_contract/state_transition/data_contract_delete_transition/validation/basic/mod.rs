@@ -0,0 +1,2 @@
+mod validate_data_contract_delete_transition_basic;
+pub use validate_data_contract_delete_transition_basic::*;
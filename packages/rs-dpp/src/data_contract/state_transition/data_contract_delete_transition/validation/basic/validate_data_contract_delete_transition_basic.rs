@@ -0,0 +1,146 @@
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use lazy_static::lazy_static;
+use serde_json::Value;
+
+use crate::{
+    data_contract::state_transition::property_names,
+    state_transition::state_transition_execution_context::StateTransitionExecutionContext,
+    util::json_value::JsonValueExt,
+    validation::{
+        DataValidator, DataValidatorWithContext, JsonSchemaValidator, SimpleValidationResult,
+    },
+    version::ProtocolVersionValidator,
+    ProtocolError,
+};
+
+lazy_static! {
+    static ref DATA_CONTRACT_DELETE_SCHEMA: Value = serde_json::from_str(include_str!(
+        "../../../../../schema/data_contract/stateTransition/dataContractDelete.json"
+    ))
+    .expect("schema for Data Contract Delete should be a valid json");
+}
+
+pub struct DataContractDeleteTransitionBasicValidator {
+    json_schema_validator: JsonSchemaValidator,
+    protocol_validator: Arc<ProtocolVersionValidator>,
+}
+
+impl DataContractDeleteTransitionBasicValidator {
+    pub fn new(protocol_validator: Arc<ProtocolVersionValidator>) -> Result<Self, ProtocolError> {
+        let json_schema_validator = JsonSchemaValidator::new(DATA_CONTRACT_DELETE_SCHEMA.clone())
+            .map_err(|e| {
+            anyhow!("cannot create instance of json validator for Data Contract Delete schema: {e}")
+        })?;
+
+        Ok(Self {
+            protocol_validator,
+            json_schema_validator,
+        })
+    }
+}
+
+impl DataValidatorWithContext for DataContractDeleteTransitionBasicValidator {
+    type Item = Value;
+    fn validate(
+        &self,
+        data: &Self::Item,
+        execution_context: &StateTransitionExecutionContext,
+    ) -> Result<SimpleValidationResult, ProtocolError> {
+        validate_data_contract_delete_transition_basic(
+            &self.json_schema_validator,
+            self.protocol_validator.as_ref(),
+            data,
+            execution_context,
+        )
+    }
+}
+
+pub fn validate_data_contract_delete_transition_basic(
+    json_schema_validator: &impl DataValidator<Item = Value>,
+    protocol_validator: &impl DataValidator<Item = u32>,
+    raw_state_transition: &Value,
+    _execution_context: &StateTransitionExecutionContext,
+) -> Result<SimpleValidationResult, ProtocolError> {
+    let result = json_schema_validator.validate(raw_state_transition)?;
+    if !result.is_valid() {
+        return Ok(result);
+    }
+
+    let protocol_version = raw_state_transition.get_u64(property_names::PROTOCOL_VERSION)? as u32;
+    protocol_validator.validate(&protocol_version)
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use serde_json::json;
+
+    use crate::{
+        data_contract::state_transition::{
+            property_names::*, DataContractDeleteTransitionBasicValidator,
+        },
+        state_transition::{
+            state_transition_execution_context::StateTransitionExecutionContext,
+            StateTransitionType,
+        },
+        tests::fixtures::get_data_contract_fixture,
+        validation::DataValidatorWithContext,
+        version::{ProtocolVersionValidator, LATEST_VERSION},
+    };
+
+    fn get_raw_state_transition() -> serde_json::Value {
+        let data_contract = get_data_contract_fixture(None);
+
+        json!({
+            PROTOCOL_VERSION: LATEST_VERSION,
+            TRANSITION_TYPE: StateTransitionType::DataContractDelete as u8,
+            DATA_CONTRACT_ID: data_contract.id.as_bytes(),
+            OWNER_ID: data_contract.owner_id.as_bytes(),
+            SIGNATURE_PUBLIC_KEY_ID: 0,
+            SIGNATURE: vec![0_u8; 65],
+        })
+    }
+
+    #[test]
+    fn should_return_valid_result() {
+        let validator = DataContractDeleteTransitionBasicValidator::new(Arc::new(
+            ProtocolVersionValidator::default(),
+        ))
+        .expect("validator should be created");
+
+        let result = validator
+            .validate(
+                &get_raw_state_transition(),
+                &StateTransitionExecutionContext::default(),
+            )
+            .expect("the validation result should be returned");
+
+        assert!(result.is_valid());
+    }
+
+    #[test]
+    fn should_return_invalid_result_if_data_contract_id_is_missing() {
+        let validator = DataContractDeleteTransitionBasicValidator::new(Arc::new(
+            ProtocolVersionValidator::default(),
+        ))
+        .expect("validator should be created");
+
+        let mut raw_state_transition = get_raw_state_transition();
+        raw_state_transition
+            .as_object_mut()
+            .unwrap()
+            .remove(DATA_CONTRACT_ID);
+
+        let result = validator
+            .validate(
+                &raw_state_transition,
+                &StateTransitionExecutionContext::default(),
+            )
+            .expect("the validation result should be returned");
+
+        assert!(!result.is_valid());
+    }
+}
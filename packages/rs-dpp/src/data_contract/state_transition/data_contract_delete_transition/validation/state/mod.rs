@@ -0,0 +1 @@
+pub mod validate_data_contract_delete_transition_state;
@@ -0,0 +1,209 @@
+use std::convert::TryInto;
+
+use anyhow::Result;
+use async_trait::async_trait;
+
+use crate::{
+    consensus::basic::BasicError,
+    data_contract::{state_transition::DataContractDeleteTransition, DataContract},
+    errors::StateError,
+    state_repository::StateRepositoryLike,
+    state_transition::StateTransitionLike,
+    validation::{AsyncDataValidator, SimpleValidationResult, ValidationResult},
+    ProtocolError,
+};
+
+pub struct DataContractDeleteTransitionStateValidator<SR>
+where
+    SR: StateRepositoryLike,
+{
+    state_repository: SR,
+}
+
+#[async_trait]
+impl<SR> AsyncDataValidator for DataContractDeleteTransitionStateValidator<SR>
+where
+    SR: StateRepositoryLike,
+{
+    type Item = DataContractDeleteTransition;
+    async fn validate(
+        &self,
+        state_transition: &DataContractDeleteTransition,
+    ) -> Result<SimpleValidationResult, ProtocolError> {
+        validate_data_contract_delete_transition_state(&self.state_repository, state_transition)
+            .await
+    }
+}
+
+impl<SR> DataContractDeleteTransitionStateValidator<SR>
+where
+    SR: StateRepositoryLike,
+{
+    pub fn new(state_repository: SR) -> Self {
+        DataContractDeleteTransitionStateValidator { state_repository }
+    }
+}
+
+/// Validates the Data Contract exists, is owned by the identity signing the transition
+/// and was created with `canBeDeleted`
+pub async fn validate_data_contract_delete_transition_state(
+    state_repository: &impl StateRepositoryLike,
+    state_transition: &DataContractDeleteTransition,
+) -> Result<ValidationResult<()>, ProtocolError> {
+    let mut result = ValidationResult::default();
+
+    // Data contract should exist
+    let maybe_existing_data_contract: Option<DataContract> = state_repository
+        .fetch_data_contract(
+            &state_transition.data_contract_id,
+            state_transition.get_execution_context(),
+        )
+        .await?
+        .map(TryInto::try_into)
+        .transpose()
+        .map_err(Into::into)?;
+
+    if state_transition.execution_context.is_dry_run() {
+        return Ok(result);
+    }
+
+    let existing_data_contract: DataContract = match maybe_existing_data_contract {
+        None => {
+            result.add_error(BasicError::DataContractNotPresent {
+                data_contract_id: state_transition.data_contract_id.clone(),
+            });
+            return Ok(result);
+        }
+        Some(dc) => dc,
+    };
+
+    if existing_data_contract.owner_id != state_transition.owner_id {
+        result.add_error(StateError::DataContractOwnerIdMismatchError {
+            data_contract_id: state_transition.data_contract_id.clone(),
+            owner_id: state_transition.owner_id.clone(),
+            existing_owner_id: existing_data_contract.owner_id.clone(),
+        });
+        return Ok(result);
+    }
+
+    if !existing_data_contract.config.can_be_deleted {
+        result.add_error(StateError::DataContractCanNotBeDeletedError {
+            data_contract_id: state_transition.data_contract_id.clone(),
+        });
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        prelude::Identifier, state_repository::MockStateRepositoryLike,
+        tests::fixtures::get_data_contract_fixture,
+    };
+
+    fn get_state_transition(data_contract: &DataContract) -> DataContractDeleteTransition {
+        DataContractDeleteTransition {
+            data_contract_id: data_contract.id.clone(),
+            owner_id: data_contract.owner_id.clone(),
+            ..Default::default()
+        }
+    }
+
+    fn mock_state_repository(data_contract: Option<DataContract>) -> MockStateRepositoryLike {
+        let mut mock_state_repository = MockStateRepositoryLike::new();
+
+        mock_state_repository
+            .expect_fetch_data_contract()
+            .return_once(move |_, _| Ok(data_contract));
+
+        mock_state_repository
+    }
+
+    #[tokio::test]
+    async fn should_return_valid_result_on_dry_run() {
+        let data_contract = get_data_contract_fixture(None);
+        let state_transition = get_state_transition(&data_contract);
+        state_transition.get_execution_context().enable_dry_run();
+
+        let result = validate_data_contract_delete_transition_state(
+            &mock_state_repository(None),
+            &state_transition,
+        )
+        .await
+        .expect("the validation result should be returned");
+
+        assert!(result.is_valid());
+    }
+
+    #[tokio::test]
+    async fn should_return_invalid_result_if_data_contract_is_not_present() {
+        let data_contract = get_data_contract_fixture(None);
+        let state_transition = get_state_transition(&data_contract);
+
+        let result = validate_data_contract_delete_transition_state(
+            &mock_state_repository(None),
+            &state_transition,
+        )
+        .await
+        .expect("the validation result should be returned");
+
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].code(), 1018);
+    }
+
+    #[tokio::test]
+    async fn should_return_invalid_result_if_owner_id_mismatch() {
+        let mut data_contract = get_data_contract_fixture(None);
+        data_contract.config.can_be_deleted = true;
+
+        let mut state_transition = get_state_transition(&data_contract);
+        state_transition.owner_id = Identifier::new([1; 32]);
+
+        let result = validate_data_contract_delete_transition_state(
+            &mock_state_repository(Some(data_contract)),
+            &state_transition,
+        )
+        .await
+        .expect("the validation result should be returned");
+
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].code(), 4027);
+    }
+
+    #[tokio::test]
+    async fn should_return_invalid_result_if_data_contract_can_not_be_deleted() {
+        let mut data_contract = get_data_contract_fixture(None);
+        data_contract.config.can_be_deleted = false;
+
+        let state_transition = get_state_transition(&data_contract);
+
+        let result = validate_data_contract_delete_transition_state(
+            &mock_state_repository(Some(data_contract)),
+            &state_transition,
+        )
+        .await
+        .expect("the validation result should be returned");
+
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].code(), 4028);
+    }
+
+    #[tokio::test]
+    async fn should_return_valid_result_if_data_contract_can_be_deleted_by_its_owner() {
+        let mut data_contract = get_data_contract_fixture(None);
+        data_contract.config.can_be_deleted = true;
+
+        let state_transition = get_state_transition(&data_contract);
+
+        let result = validate_data_contract_delete_transition_state(
+            &mock_state_repository(Some(data_contract)),
+            &state_transition,
+        )
+        .await
+        .expect("the validation result should be returned");
+
+        assert!(result.is_valid());
+    }
+}
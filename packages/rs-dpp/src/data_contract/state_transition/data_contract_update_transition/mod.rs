@@ -6,7 +6,7 @@ use crate::{
     identity::KeyID,
     prelude::Identifier,
     state_transition::{
-        state_transition_execution_context::StateTransitionExecutionContext,
+        fee::Credits, state_transition_execution_context::StateTransitionExecutionContext,
         StateTransitionConvert, StateTransitionIdentitySigned, StateTransitionLike,
         StateTransitionType,
     },
@@ -30,6 +30,9 @@ pub struct DataContractUpdateTransition {
     pub data_contract: DataContract,
     pub signature_public_key_id: KeyID,
     pub signature: Vec<u8>,
+    /// Credits paid to the block proposer on top of the fees
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub user_tip: Option<Credits>,
     #[serde(skip)]
     pub execution_context: StateTransitionExecutionContext,
 }
@@ -41,6 +44,7 @@ impl std::default::Default for DataContractUpdateTransition {
             transition_type: StateTransitionType::DataContractUpdate,
             signature_public_key_id: 0,
             signature: vec![],
+            user_tip: None,
             data_contract: Default::default(),
             execution_context: Default::default(),
         }
@@ -59,6 +63,7 @@ impl DataContractUpdateTransition {
             signature_public_key_id: raw_data_contract_update_transition
                 .get_u64(SIGNATURE_PUBLIC_KEY_ID)
                 .unwrap_or_default(),
+            user_tip: raw_data_contract_update_transition.get_u64(USER_TIP).ok(),
             data_contract: DataContract::from_raw_object(
                 raw_data_contract_update_transition.remove(DATA_CONTRACT)?,
             )?,
@@ -112,6 +117,10 @@ impl StateTransitionLike for DataContractUpdateTransition {
         self.signature = signature
     }
 
+    fn get_user_tip(&self) -> Credits {
+        self.user_tip.unwrap_or_default()
+    }
+
     fn get_execution_context(&self) -> &StateTransitionExecutionContext {
         &self.execution_context
     }
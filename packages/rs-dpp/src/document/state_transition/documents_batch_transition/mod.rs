@@ -6,6 +6,7 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 
 use crate::data_contract::DataContract;
+use crate::state_transition::fee::Credits;
 use crate::state_transition::state_transition_execution_context::StateTransitionExecutionContext;
 use crate::{
     identity::{KeyID, SecurityLevel},
@@ -31,6 +32,7 @@ const PROPERTY_OWNER_ID: &str = "ownerId";
 const PROPERTY_SIGNATURE_PUBLIC_KEY_ID: &str = "signaturePublicKeyId";
 const PROPERTY_SIGNATURE: &str = "signature";
 const PROPERTY_PROTOCOL_VERSION: &str = "protocolVersion";
+const PROPERTY_USER_TIP: &str = "userTip";
 const PROPERTY_SECURITY_LEVEL_REQUIREMENT: &str = "signatureSecurityLevelRequirement";
 const DEFAULT_SECURITY_LEVEL: SecurityLevel = SecurityLevel::HIGH;
 
@@ -46,6 +48,9 @@ pub struct DocumentsBatchTransition {
     pub transitions: Vec<DocumentTransition>,
     pub signature_public_key_id: KeyID,
     pub signature: Vec<u8>,
+    /// Credits paid to the block proposer on top of the fees
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub user_tip: Option<Credits>,
     #[serde(skip)]
     pub execution_context: StateTransitionExecutionContext,
 }
@@ -59,6 +64,7 @@ impl std::default::Default for DocumentsBatchTransition {
             transitions: vec![],
             signature_public_key_id: 0,
             signature: vec![],
+            user_tip: None,
             execution_context: Default::default(),
         }
     }
@@ -82,6 +88,7 @@ impl DocumentsBatchTransition {
                 .get_u64(PROPERTY_SIGNATURE_PUBLIC_KEY_ID)
                 .unwrap_or_default(),
             owner_id: Identifier::from_bytes(&raw_object.get_bytes(PROPERTY_OWNER_ID)?)?,
+            user_tip: raw_object.get_u64(PROPERTY_USER_TIP).ok(),
             ..Default::default()
         };
 
@@ -243,6 +250,10 @@ impl StateTransitionLike for DocumentsBatchTransition {
     fn set_signature(&mut self, signature: Vec<u8>) {
         self.signature = signature;
     }
+    fn get_user_tip(&self) -> Credits {
+        self.user_tip.unwrap_or_default()
+    }
+
     fn get_execution_context(&self) -> &StateTransitionExecutionContext {
         &self.execution_context
     }
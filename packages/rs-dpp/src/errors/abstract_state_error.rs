@@ -49,6 +49,18 @@ pub enum StateError {
     #[error("Data Contract {data_contract_id} is already present")]
     DataContractAlreadyPresentError { data_contract_id: Identifier },
 
+    #[error("Provided data contract {data_contract_id} owner ID {owner_id} mismatch with existing {existing_owner_id}")]
+    DataContractOwnerIdMismatchError {
+        data_contract_id: Identifier,
+        owner_id: Identifier,
+        existing_owner_id: Identifier,
+    },
+
+    #[error(
+        "Data Contract {data_contract_id} was not created with canBeDeleted and can not be deleted"
+    )]
+    DataContractCanNotBeDeletedError { data_contract_id: Identifier },
+
     #[error(transparent)]
     DataTriggerError(Box<DataTriggerError>),
 
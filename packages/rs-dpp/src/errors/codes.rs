@@ -71,6 +71,8 @@ impl ErrorWithCode for StateError {
             Self::InvalidDocumentRevisionError { .. } => 4010,
             // Data contract
             Self::DataContractAlreadyPresentError { .. } => 4000,
+            Self::DataContractOwnerIdMismatchError { .. } => 4027,
+            Self::DataContractCanNotBeDeletedError { .. } => 4028,
             Self::DataTriggerError(ref e) => e.get_code(),
 
             // Identity
@@ -47,6 +47,9 @@ pub enum ProtocolError {
     #[error(transparent)]
     Document(Box<DocumentError>),
 
+    #[error(transparent)]
+    FeeError(#[from] crate::state_transition::fee::errors::FeeError),
+
     #[error("Generic Error: {0}")]
     Generic(String),
 
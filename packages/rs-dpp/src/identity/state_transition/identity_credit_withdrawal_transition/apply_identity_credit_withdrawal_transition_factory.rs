@@ -64,7 +64,11 @@ where
             .map_err(|e| anyhow!(e))?;
 
         self.state_repository
-            .enqueue_withdrawal_transaction(latest_withdrawal_index, transaction_buffer)
+            .enqueue_withdrawal_transaction(
+                latest_withdrawal_index,
+                transaction_buffer,
+                state_transition.get_execution_context(),
+            )
             .await?;
 
         let maybe_existing_identity: Option<Identity> = self
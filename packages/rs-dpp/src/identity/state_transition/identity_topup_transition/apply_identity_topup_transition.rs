@@ -67,7 +67,10 @@ where
                 .await?;
 
             self.state_repository
-                .mark_asset_lock_transaction_out_point_as_used(&out_point)
+                .mark_asset_lock_transaction_out_point_as_used(
+                    &out_point,
+                    state_transition.get_execution_context(),
+                )
                 .await?;
 
             Ok(())
@@ -110,7 +113,7 @@ mod test {
             .return_once(|_, _| Ok(()));
         state_repository_for_apply
             .expect_mark_asset_lock_transaction_out_point_as_used()
-            .return_once(|_| Ok(()));
+            .return_once(|_, _| Ok(()));
 
         let asset_lock_transaction_fetcher =
             AssetLockTransactionOutputFetcher::new(Arc::new(state_repository_for_fetcher));
@@ -6,7 +6,7 @@ use crate::{
     identity::{KeyID, SecurityLevel},
     prelude::{Identifier, IdentityPublicKey, Revision, TimestampMillis},
     state_transition::{
-        state_transition_execution_context::StateTransitionExecutionContext,
+        fee::Credits, state_transition_execution_context::StateTransitionExecutionContext,
         state_transition_helpers, StateTransitionConvert, StateTransitionIdentitySigned,
         StateTransitionLike, StateTransitionType,
     },
@@ -25,6 +25,7 @@ pub mod property_names {
     pub const PUBLIC_KEYS_DISABLED_AT: &str = "publicKeysDisabledAt";
     pub const SIGNATURE: &str = "signature";
     pub const SIGNATURE_PUBLIC_KEY_ID: &str = "signaturePublicKeyId";
+    pub const USER_TIP: &str = "userTip";
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -59,6 +60,10 @@ pub struct IdentityUpdateTransition {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub public_keys_disabled_at: Option<TimestampMillis>,
 
+    /// Credits paid to the block proposer on top of the fees
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub user_tip: Option<Credits>,
+
     #[serde(skip)]
     pub execution_context: StateTransitionExecutionContext,
 }
@@ -75,6 +80,7 @@ impl Default for IdentityUpdateTransition {
             add_public_keys: Default::default(),
             disable_public_keys: Default::default(),
             public_keys_disabled_at: Default::default(),
+            user_tip: Default::default(),
             execution_context: Default::default(),
         }
     }
@@ -103,6 +109,7 @@ impl IdentityUpdateTransition {
         let public_keys_disabled_at = raw_object
             .remove_into::<u64>(property_names::PUBLIC_KEYS_DISABLED_AT)
             .ok();
+        let user_tip = raw_object.get_u64(property_names::USER_TIP).ok();
 
         Ok(IdentityUpdateTransition {
             protocol_version,
@@ -113,6 +120,7 @@ impl IdentityUpdateTransition {
             add_public_keys,
             disable_public_keys,
             public_keys_disabled_at,
+            user_tip,
             transition_type: StateTransitionType::IdentityUpdate,
             execution_context: Default::default(),
         })
@@ -292,6 +300,10 @@ impl StateTransitionLike for IdentityUpdateTransition {
         self.signature = signature;
     }
 
+    fn get_user_tip(&self) -> Credits {
+        self.user_tip.unwrap_or_default()
+    }
+
     fn get_execution_context(&self) -> &StateTransitionExecutionContext {
         &self.execution_context
     }
@@ -40,6 +40,13 @@ pub trait StateRepositoryLike: Send + Sync {
         execution_context: &StateTransitionExecutionContext,
     ) -> AnyResult<()>;
 
+    /// Delete Data Contract with all its Documents
+    async fn delete_data_contract(
+        &self,
+        data_contract_id: &Identifier,
+        execution_context: &StateTransitionExecutionContext,
+    ) -> AnyResult<()>;
+
     /// Fetch Documents by Data Contract Id and type
     /// By default, the method should return data as bytes (`Vec<u8>`), but the deserialization to [`Document`] should be also possible
     async fn fetch_documents<T>(
@@ -136,6 +143,7 @@ pub trait StateRepositoryLike: Send + Sync {
     async fn mark_asset_lock_transaction_out_point_as_used(
         &self,
         out_point_buffer: &[u8],
+        execution_context: &StateTransitionExecutionContext,
     ) -> AnyResult<()>;
 
     /// Fetch Simplified Masternode List Store
@@ -166,5 +174,6 @@ pub trait StateRepositoryLike: Send + Sync {
         &self,
         index: u64,
         transaction_bytes: Vec<u8>,
+        execution_context: &StateTransitionExecutionContext,
     ) -> AnyResult<()>;
 }
@@ -16,9 +16,9 @@ use crate::{
 };
 
 use super::{
-    fee::calculate_state_transition_fee::calculate_state_transition_fee,
-    state_transition_execution_context::StateTransitionExecutionContext, StateTransition,
-    StateTransitionType,
+    fee::{calculate_state_transition_fee::calculate_state_transition_fee, Credits},
+    state_transition_execution_context::StateTransitionExecutionContext,
+    StateTransition, StateTransitionType,
 };
 
 const PROPERTY_SIGNATURE: &str = "signature";
@@ -32,9 +32,10 @@ pub const IDENTITY_TRANSITION_TYPE: [StateTransitionType; 2] = [
     StateTransitionType::IdentityTopUp,
 ];
 
-pub const DATA_CONTRACT_TRANSITION_TYPES: [StateTransitionType; 2] = [
+pub const DATA_CONTRACT_TRANSITION_TYPES: [StateTransitionType; 3] = [
     StateTransitionType::DataContractCreate,
     StateTransitionType::DataContractUpdate,
+    StateTransitionType::DataContractDelete,
 ];
 
 /// The StateTransitionLike represents set of methods that are shared for all types of State Transition.
@@ -50,9 +51,13 @@ pub trait StateTransitionLike:
     fn get_signature(&self) -> &Vec<u8>;
     /// set a new signature
     fn set_signature(&mut self, signature: Vec<u8>);
-    /// Calculates the ST fee in credits
-    fn calculate_fee(&self) -> i64 {
-        calculate_state_transition_fee(self)
+    /// returns the credits paid to the block proposer on top of the fees
+    fn get_user_tip(&self) -> Credits {
+        0
+    }
+    /// Calculates the ST fee in credits, the user tip included
+    fn calculate_fee(&self) -> Result<i64, ProtocolError> {
+        Ok(calculate_state_transition_fee(self)?)
     }
 
     /// Signs data with the private key
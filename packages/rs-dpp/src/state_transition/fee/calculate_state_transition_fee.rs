@@ -1,26 +1,55 @@
 use crate::state_transition::StateTransitionLike;
 
-use super::{calculate_operations_fees, constants::DEFAULT_USER_TIP};
+use std::convert::TryFrom;
 
-pub fn calculate_state_transition_fee(state_transition: &impl StateTransitionLike) -> i64 {
+use super::{calculate_operations_fees, errors::FeeError, fee_result::FeeResult};
+
+/// Calculates the fees, refunds included, of the operations executed by the state transition
+/// with the fee schedule and the fee multiplier set in its execution context
+pub fn calculate_state_transition_fee_result(
+    state_transition: &impl StateTransitionLike,
+) -> Result<FeeResult, FeeError> {
     let execution_context = state_transition.get_execution_context();
-    let fee = calculate_operations_fees(execution_context.get_operations());
+    let fee_schedule = execution_context.get_fee_schedule()?;
+    calculate_operations_fees(
+        execution_context.get_operations(),
+        &fee_schedule,
+        execution_context.get_fee_multiplier(),
+    )
+}
+
+/// Calculates the credits the state transition costs, the storage refunds for data it
+/// removed are deducted and the user tip is added
+pub fn calculate_state_transition_fee(
+    state_transition: &impl StateTransitionLike,
+) -> Result<i64, FeeError> {
+    let fee_result = calculate_state_transition_fee_result(state_transition)?;
 
-    // Is not implemented yet
-    let storage_refund = 0;
+    let user_tip = i64::try_from(state_transition.get_user_tip())
+        .map_err(|_| FeeError::Overflow("user tip overflow error"))?;
 
-    (fee.storage + fee.processing) + DEFAULT_USER_TIP - storage_refund
+    fee_result
+        .balance_change()?
+        .checked_add(user_tip)
+        .ok_or(FeeError::Overflow("fee overflow error"))
 }
 
 #[cfg(test)]
 mod test {
     use crate::{
+        data_contract::state_transition::DataContractCreateTransition,
         identity::{
             state_transition::identity_create_transition::IdentityCreateTransition, KeyType,
         },
         state_transition::{
-            fee::operations::{
-                DeleteOperation, Operation, PreCalculatedOperation, ReadOperation, WriteOperation,
+            fee::{
+                constants::STORAGE_DISK_USAGE_CREDIT_PER_BYTE,
+                fee_result::{refunds::FeeRefunds, FeeResult},
+                fee_schedule::{FeeSchedule, FEE_SCHEDULE_V1},
+                operations::{
+                    DeleteOperation, Operation, PreCalculatedOperation, ReadOperation,
+                    WriteOperation,
+                },
             },
             state_transition_execution_context::StateTransitionExecutionContext,
             StateTransitionLike,
@@ -29,7 +58,7 @@ mod test {
         NativeBlsModule,
     };
 
-    use super::calculate_state_transition_fee;
+    use super::{calculate_state_transition_fee, calculate_state_transition_fee_result};
 
     // TODO: Must be more comprehensive. After we settle all factors and formula.
     #[test]
@@ -51,9 +80,110 @@ mod test {
         execution_context.add_operation(Operation::PreCalculated(PreCalculatedOperation::new(
             12, 12,
         )));
+        execution_context.add_operation(Operation::PreCalculated(
+            PreCalculatedOperation::from_fee_result(FeeResult {
+                fee_refunds: FeeRefunds::from_storage_removal([([1; 32], [(0, 2)])])
+                    .expect("expected refunds"),
+                ..Default::default()
+            }),
+        ));
         state_transition.set_execution_context(execution_context);
 
-        let result = calculate_state_transition_fee(&state_transition);
-        assert_eq!(13616, result)
+        let fee_result = calculate_state_transition_fee_result(&state_transition)
+            .expect("expected to calculate fees");
+        assert_eq!(270012, fee_result.storage_fee);
+        assert_eq!(24812, fee_result.processing_fee);
+        assert_eq!(12, fee_result.removed_bytes_from_system);
+        assert_eq!(
+            Some(2 * STORAGE_DISK_USAGE_CREDIT_PER_BYTE),
+            fee_result
+                .fee_refunds
+                .get(&[1; 32])
+                .and_then(|r| r.get(&0))
+                .copied()
+        );
+
+        let result =
+            calculate_state_transition_fee(&state_transition).expect("expected to calculate fee");
+        assert_eq!(240824, result)
+    }
+
+    #[test]
+    fn should_add_user_tip_to_fee() {
+        let execution_context = StateTransitionExecutionContext::default();
+        execution_context.add_operation(Operation::PreCalculated(PreCalculatedOperation::new(
+            80, 10,
+        )));
+        let state_transition = DataContractCreateTransition {
+            execution_context,
+            user_tip: Some(1000),
+            ..Default::default()
+        };
+
+        let result =
+            calculate_state_transition_fee(&state_transition).expect("expected to calculate fee");
+        assert_eq!(1090, result)
+    }
+
+    #[test]
+    fn should_use_fee_schedule_of_execution_context() {
+        let create_state_transition = |fee_schedule: Option<FeeSchedule>| {
+            let execution_context = StateTransitionExecutionContext::default();
+            execution_context.add_operation(Operation::Read(ReadOperation::new(10)));
+            if let Some(fee_schedule) = fee_schedule {
+                execution_context.set_fee_schedule(fee_schedule);
+            }
+            DataContractCreateTransition {
+                execution_context,
+                ..Default::default()
+            }
+        };
+
+        let latest_fee_result =
+            calculate_state_transition_fee_result(&create_state_transition(None))
+                .expect("expected to calculate fees");
+        let fee_result =
+            calculate_state_transition_fee_result(&create_state_transition(Some(FeeSchedule {
+                storage_seek_cost: FEE_SCHEDULE_V1.storage_seek_cost * 2,
+                storage_load_credit_per_byte: FEE_SCHEDULE_V1.storage_load_credit_per_byte * 2,
+                ..FEE_SCHEDULE_V1
+            })))
+            .expect("expected to calculate fees");
+
+        assert_eq!(
+            latest_fee_result.processing_fee * 2,
+            fee_result.processing_fee
+        );
+    }
+
+    #[test]
+    fn should_apply_fee_multiplier_of_execution_context_to_processing_fees() {
+        let create_state_transition = |fee_multiplier: Option<f64>| {
+            let execution_context = StateTransitionExecutionContext::default();
+            execution_context.add_operation(Operation::Read(ReadOperation::new(10)));
+            execution_context.add_operation(Operation::PreCalculated(PreCalculatedOperation::new(
+                100, 10,
+            )));
+            if let Some(fee_multiplier) = fee_multiplier {
+                execution_context.set_fee_multiplier(fee_multiplier);
+            }
+            DataContractCreateTransition {
+                execution_context,
+                ..Default::default()
+            }
+        };
+
+        let neutral_fee_result =
+            calculate_state_transition_fee_result(&create_state_transition(None))
+                .expect("expected to calculate fees");
+        let fee_result = calculate_state_transition_fee_result(&create_state_transition(Some(2.0)))
+            .expect("expected to calculate fees");
+
+        assert_eq!(neutral_fee_result.storage_fee, fee_result.storage_fee);
+        // Drive already applied the fee multiplier to precalculated fees
+        assert_eq!(
+            (neutral_fee_result.processing_fee - 10) * 2 + 10,
+            fee_result.processing_fee
+        );
     }
 }
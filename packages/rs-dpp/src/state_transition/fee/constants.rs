@@ -1,15 +1,19 @@
 use crate::identity::KeyType;
 
-pub const BASE_ST_PROCESSING_FEE: i64 = 10000; // 84000
-pub const FEE_MULTIPLIER: i64 = 2;
-pub const DEFAULT_USER_TIP: i64 = 0;
-pub const STORAGE_CREDIT_PER_BYTE: i64 = 5000;
-pub const PROCESSING_CREDIT_PER_BYTE: i64 = 12;
-pub const DELETE_BASE_PROCESSING_COST: i64 = 2000; // 20000
-pub const READ_BASE_PROCESSING_COST: i64 = 8400; // 8400
-pub const WRITE_BASE_PROCESSING_COST: i64 = 6000; // 60000
+/// Storage disk usage credit per byte
+pub const STORAGE_DISK_USAGE_CREDIT_PER_BYTE: u64 = 27000;
+/// Storage processing credit per byte
+pub const STORAGE_PROCESSING_CREDIT_PER_BYTE: u64 = 400;
+/// Storage load credit per byte
+pub const STORAGE_LOAD_CREDIT_PER_BYTE: u64 = 400;
+/// Non storage load credit per byte
+pub const NON_STORAGE_LOAD_CREDIT_PER_BYTE: u64 = 30;
+/// Query credit per byte
+pub const QUERY_CREDIT_PER_BYTE: u64 = 10;
+/// Storage seek cost
+pub const STORAGE_SEEK_COST: u64 = 4000;
 
-pub const fn signature_verify_cost(key_type: KeyType) -> i64 {
+pub const fn signature_verify_cost(key_type: KeyType) -> u64 {
     match key_type {
         KeyType::ECDSA_SECP256K1 => 3000,
         KeyType::BLS12_381 => 6000,
@@ -0,0 +1,110 @@
+//! Fee Result
+//!
+//! Each operation returns a FeeResult after execution.
+//! This result contains fees which are required to pay for
+//! computation and storage. It also contains fees to refund
+//! for removed data from the state.
+//!
+
+use std::convert::TryFrom;
+
+use serde::{Deserialize, Serialize};
+
+use super::errors::FeeError;
+use super::Credits;
+use refunds::FeeRefunds;
+
+pub mod refunds;
+
+/// Fee Result
+#[derive(Debug, Clone, Eq, PartialEq, Default, Serialize, Deserialize)]
+pub struct FeeResult {
+    /// Storage fee
+    pub storage_fee: Credits,
+    /// Processing fee
+    pub processing_fee: Credits,
+    /// Credits to refund to identities
+    pub fee_refunds: FeeRefunds,
+    /// Removed bytes not needing to be refunded to identities
+    pub removed_bytes_from_system: u32,
+}
+
+impl FeeResult {
+    /// Creates a FeeResult instance with specified storage and processing fees
+    pub fn from_fees(storage_fee: Credits, processing_fee: Credits) -> Self {
+        FeeResult {
+            storage_fee,
+            processing_fee,
+            ..Default::default()
+        }
+    }
+
+    /// Adds and self assigns result between two Fee Results
+    pub fn checked_add_assign(&mut self, rhs: Self) -> Result<(), FeeError> {
+        self.storage_fee = self
+            .storage_fee
+            .checked_add(rhs.storage_fee)
+            .ok_or(FeeError::Overflow("storage fee overflow error"))?;
+        self.processing_fee = self
+            .processing_fee
+            .checked_add(rhs.processing_fee)
+            .ok_or(FeeError::Overflow("processing fee overflow error"))?;
+        self.fee_refunds.checked_add_assign(rhs.fee_refunds)?;
+        self.removed_bytes_from_system = self
+            .removed_bytes_from_system
+            .checked_add(rhs.removed_bytes_from_system)
+            .ok_or(FeeError::Overflow(
+                "removed_bytes_from_system overflow error",
+            ))?;
+        Ok(())
+    }
+
+    /// The fees to pay minus the credits refunded, it is negative if more credits are
+    /// refunded than paid
+    pub fn balance_change(&self) -> Result<i64, FeeError> {
+        let to_pay = self
+            .storage_fee
+            .checked_add(self.processing_fee)
+            .ok_or(FeeError::Overflow("fee overflow error"))?;
+        let to_pay = i64::try_from(to_pay).map_err(|_| FeeError::Overflow("fee overflow error"))?;
+        let refunded = i64::try_from(self.fee_refunds.total()?)
+            .map_err(|_| FeeError::Overflow("refunds overflow error"))?;
+        to_pay
+            .checked_sub(refunded)
+            .ok_or(FeeError::Overflow("fee overflow error"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::refunds::FeeRefunds;
+    use super::FeeResult;
+
+    #[test]
+    fn should_add_fees_and_refunds() {
+        let mut fee_result = FeeResult::from_fees(100, 10);
+        fee_result
+            .checked_add_assign(FeeResult {
+                storage_fee: 50,
+                processing_fee: 5,
+                fee_refunds: FeeRefunds::from_storage_removal([([1; 32], [(0, 1), (2, 1)])])
+                    .expect("expected refunds"),
+                removed_bytes_from_system: 3,
+            })
+            .expect("expected to add fee results");
+
+        assert_eq!(fee_result.storage_fee, 150);
+        assert_eq!(fee_result.processing_fee, 15);
+        assert_eq!(fee_result.removed_bytes_from_system, 3);
+        assert_eq!(
+            fee_result.fee_refunds.total().expect("expected total"),
+            2 * super::super::constants::STORAGE_DISK_USAGE_CREDIT_PER_BYTE
+        );
+        assert_eq!(
+            fee_result
+                .balance_change()
+                .expect("expected balance change"),
+            165 - 2 * super::super::constants::STORAGE_DISK_USAGE_CREDIT_PER_BYTE as i64
+        );
+    }
+}
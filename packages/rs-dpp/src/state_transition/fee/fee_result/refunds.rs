@@ -0,0 +1,166 @@
+//! Fee Refunds
+//!
+//! Fee refunds are calculated based on removed bytes per epoch.
+//!
+
+use std::collections::btree_map::{IntoIter, Iter};
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+
+use bincode::Options;
+use serde::{Deserialize, Serialize};
+
+use crate::state_transition::fee::constants::STORAGE_DISK_USAGE_CREDIT_PER_BYTE;
+use crate::state_transition::fee::errors::FeeError;
+use crate::state_transition::fee::{Credits, CreditsPerEpoch};
+
+/// Identifier of the identity data is refunded to
+pub type RefundedIdentifier = [u8; 32];
+
+/// Credits per Epoch by Identifier
+pub type CreditsPerEpochByIdentifier = BTreeMap<RefundedIdentifier, CreditsPerEpoch>;
+
+/// Fee refunds to identities based on removed data from specific epochs
+#[derive(Debug, Clone, Eq, PartialEq, Default, Serialize, Deserialize)]
+pub struct FeeRefunds(pub CreditsPerEpochByIdentifier);
+
+impl FeeRefunds {
+    /// Create fee refunds from the bytes removed per epoch by identifier, such as GroveDB's
+    /// StorageRemovalPerEpochByIdentifier
+    pub fn from_storage_removal<I, B>(storage_removal: I) -> Result<Self, FeeError>
+    where
+        I: IntoIterator<Item = (RefundedIdentifier, B)>,
+        B: IntoIterator<Item = (u64, u32)>,
+    {
+        let refunds_per_epoch_by_identifier = storage_removal
+            .into_iter()
+            .map(|(identifier, bytes_per_epochs)| {
+                bytes_per_epochs
+                    .into_iter()
+                    .map(|(key, bytes)| {
+                        let epoch_index = u16::try_from(key).map_err(|_| FeeError::Overflow("can't fit u64 epoch index from StorageRemovalPerEpochByIdentifier to u16 EpochIndex"))?;
+
+                        // TODO We should use multipliers
+
+                        let credits: Credits = (bytes as Credits)
+                            .checked_mul(STORAGE_DISK_USAGE_CREDIT_PER_BYTE)
+                            .ok_or(FeeError::Overflow("storage written bytes cost overflow"))?;
+
+                        Ok((epoch_index, credits))
+                    })
+                    .collect::<Result<CreditsPerEpoch, FeeError>>()
+                    .map(|credits_per_epochs| (identifier, credits_per_epochs))
+            })
+            .collect::<Result<CreditsPerEpochByIdentifier, FeeError>>()?;
+
+        Ok(Self(refunds_per_epoch_by_identifier))
+    }
+
+    /// Adds and self assigns result between two Fee Results
+    pub fn checked_add_assign(&mut self, rhs: Self) -> Result<(), FeeError> {
+        for (identifier, mut int_map_b) in rhs.0.into_iter() {
+            let to_insert_int_map = if let Some(sint_map_a) = self.0.remove(&identifier) {
+                // other has an int_map with the same identifier
+                let intersection = sint_map_a
+                    .into_iter()
+                    .map(|(k, v)| {
+                        let combined = if let Some(value_b) = int_map_b.remove(&k) {
+                            v.checked_add(value_b)
+                                .ok_or(FeeError::Overflow("storage fee overflow error"))
+                        } else {
+                            Ok(v)
+                        };
+                        combined.map(|c| (k, c))
+                    })
+                    .collect::<Result<CreditsPerEpoch, FeeError>>()?;
+                intersection.into_iter().chain(int_map_b).collect()
+            } else {
+                int_map_b
+            };
+            // reinsert the now combined IntMap
+            self.0.insert(identifier, to_insert_int_map);
+        }
+        Ok(())
+    }
+
+    /// Credits refunded to an identity over all epochs
+    pub fn total_for_identifier(
+        &self,
+        identifier: &RefundedIdentifier,
+    ) -> Result<Credits, FeeError> {
+        self.0.get(identifier).map_or(Ok(0), |credits_per_epoch| {
+            credits_per_epoch
+                .values()
+                .try_fold(0 as Credits, |total, credits| total.checked_add(*credits))
+                .ok_or(FeeError::Overflow("refunds overflow error"))
+        })
+    }
+
+    /// Credits refunded to all identities over all epochs
+    pub fn total(&self) -> Result<Credits, FeeError> {
+        self.0.keys().try_fold(0 as Credits, |total, identifier| {
+            total
+                .checked_add(self.total_for_identifier(identifier)?)
+                .ok_or(FeeError::Overflow("refunds overflow error"))
+        })
+    }
+
+    /// Returns true if nothing is refunded
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Passthrough method for get
+    pub fn get(&self, key: &RefundedIdentifier) -> Option<&CreditsPerEpoch> {
+        self.0.get(key)
+    }
+
+    /// Passthrough method for iteration
+    pub fn iter(&self) -> Iter<RefundedIdentifier, CreditsPerEpoch> {
+        self.0.iter()
+    }
+
+    /// Passthrough method for into iteration
+    pub fn into_iter(self) -> IntoIter<RefundedIdentifier, CreditsPerEpoch> {
+        self.0.into_iter()
+    }
+
+    /// Serialize the structure
+    pub fn serialize(&self) -> Result<Vec<u8>, FeeError> {
+        bincode::DefaultOptions::default()
+            .with_varint_encoding()
+            .reject_trailing_bytes()
+            .serialize(&self.0)
+            .map_err(|_| {
+                FeeError::CorruptedRemovedBytesFromIdentitiesSerialization("unable to serialize")
+            })
+    }
+
+    /// Returns serialized size
+    pub fn serialized_size(&self) -> Result<u64, FeeError> {
+        bincode::DefaultOptions::default()
+            .with_varint_encoding()
+            .reject_trailing_bytes()
+            .serialized_size(&self.0)
+            .map_err(|_| {
+                FeeError::CorruptedRemovedBytesFromIdentitiesSerialization(
+                    "unable to serialize and get size",
+                )
+            })
+    }
+
+    /// Deserialized struct from bytes
+    pub fn deserialize(bytes: &[u8]) -> Result<Self, FeeError> {
+        Ok(FeeRefunds(
+            bincode::DefaultOptions::default()
+                .with_varint_encoding()
+                .reject_trailing_bytes()
+                .deserialize(bytes)
+                .map_err(|_| {
+                    FeeError::CorruptedRemovedBytesFromIdentitiesSerialization(
+                        "unable to deserialize",
+                    )
+                })?,
+        ))
+    }
+}
@@ -0,0 +1,183 @@
+use std::convert::TryInto;
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    constants::{
+        NON_STORAGE_LOAD_CREDIT_PER_BYTE, QUERY_CREDIT_PER_BYTE,
+        STORAGE_DISK_USAGE_CREDIT_PER_BYTE, STORAGE_LOAD_CREDIT_PER_BYTE,
+        STORAGE_PROCESSING_CREDIT_PER_BYTE, STORAGE_SEEK_COST,
+    },
+    errors::FeeError,
+    Credits,
+};
+
+/// Fee version type. A fee version is the protocol version which introduced the fee schedule
+pub type FeeVersion = u32;
+
+/// The fee version used for epochs started by this version of the software
+pub const LATEST_FEE_VERSION: FeeVersion = 1;
+
+/// The fee version of epochs started before fee versions were stored with epochs
+pub const INITIAL_FEE_VERSION: FeeVersion = 1;
+
+/// Costs used to calculate storage and processing fees. Epochs are bound to a fee
+/// version when they start, so fees can change at the start of an epoch without
+/// changing the fees of epochs that already started. Governance can start an epoch
+/// with a fee schedule which isn't known to the software, it is then stored with the epoch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeSchedule {
+    /// Storage disk usage credit per byte
+    pub storage_disk_usage_credit_per_byte: Credits,
+    /// Storage processing credit per byte
+    pub storage_processing_credit_per_byte: Credits,
+    /// Storage load credit per byte
+    pub storage_load_credit_per_byte: Credits,
+    /// Non storage load credit per byte
+    pub non_storage_load_credit_per_byte: Credits,
+    /// Query credit per byte
+    pub query_credit_per_byte: Credits,
+    /// Storage seek cost
+    pub storage_seek_cost: Credits,
+    /// SHA256 function cost
+    pub sha256_cost: Credits,
+    /// Double SHA256 function cost
+    pub sha256_2_cost: Credits,
+    /// BLAKE3 function cost
+    pub blake3_cost: Credits,
+}
+
+/// The fee schedule introduced with protocol version 1
+pub const FEE_SCHEDULE_V1: FeeSchedule = FeeSchedule {
+    storage_disk_usage_credit_per_byte: STORAGE_DISK_USAGE_CREDIT_PER_BYTE,
+    storage_processing_credit_per_byte: STORAGE_PROCESSING_CREDIT_PER_BYTE,
+    storage_load_credit_per_byte: STORAGE_LOAD_CREDIT_PER_BYTE,
+    non_storage_load_credit_per_byte: NON_STORAGE_LOAD_CREDIT_PER_BYTE,
+    query_credit_per_byte: QUERY_CREDIT_PER_BYTE,
+    storage_seek_cost: STORAGE_SEEK_COST,
+    sha256_cost: 4000,
+    sha256_2_cost: 8000,
+    blake3_cost: 1000,
+};
+
+/// Fee schedules known to this version of the software, ordered by fee version
+const FEE_SCHEDULES: &[(FeeVersion, FeeSchedule)] = &[(1, FEE_SCHEDULE_V1)];
+
+/// The number of costs in an encoded fee schedule
+const FEE_SCHEDULE_ENCODED_COSTS_COUNT: usize = 9;
+
+impl FeeSchedule {
+    /// Returns the fee schedule of the given fee version
+    pub fn for_version(fee_version: FeeVersion) -> Result<&'static FeeSchedule, FeeError> {
+        FEE_SCHEDULES
+            .iter()
+            .find(|(version, _)| *version == fee_version)
+            .map(|(_, fee_schedule)| fee_schedule)
+            .ok_or(FeeError::UnknownFeeVersion(fee_version))
+    }
+
+    /// Returns the fee version in effect for the given protocol version, which is the
+    /// latest fee schedule introduced at or before it
+    pub fn version_for_protocol_version(protocol_version: u32) -> Result<FeeVersion, FeeError> {
+        FEE_SCHEDULES
+            .iter()
+            .rev()
+            .map(|(version, _)| *version)
+            .find(|version| *version <= protocol_version)
+            .ok_or(FeeError::UnknownFeeVersion(protocol_version))
+    }
+
+    /// Returns the fee schedule for epochs started by this version of the software
+    pub fn latest() -> Result<&'static FeeSchedule, FeeError> {
+        Self::for_version(LATEST_FEE_VERSION)
+    }
+
+    /// Encodes the costs as big endian u64s in the order of the fields,
+    /// which is how fee schedules are stored with epochs
+    pub fn to_be_bytes(&self) -> Vec<u8> {
+        [
+            self.storage_disk_usage_credit_per_byte,
+            self.storage_processing_credit_per_byte,
+            self.storage_load_credit_per_byte,
+            self.non_storage_load_credit_per_byte,
+            self.query_credit_per_byte,
+            self.storage_seek_cost,
+            self.sha256_cost,
+            self.sha256_2_cost,
+            self.blake3_cost,
+        ]
+        .iter()
+        .flat_map(|cost| cost.to_be_bytes())
+        .collect()
+    }
+
+    /// Decodes costs encoded with `to_be_bytes`
+    pub fn from_be_bytes(bytes: &[u8]) -> Result<FeeSchedule, FeeError> {
+        if bytes.len() != FEE_SCHEDULE_ENCODED_COSTS_COUNT * 8 {
+            return Err(FeeError::CorruptedFeeScheduleSerialization(
+                "fee schedule must be 9 big endian u64s",
+            ));
+        }
+
+        let costs: Vec<Credits> = bytes
+            .chunks_exact(8)
+            .map(|chunk| Credits::from_be_bytes(chunk.try_into().expect("chunks are 8 bytes")))
+            .collect();
+
+        Ok(FeeSchedule {
+            storage_disk_usage_credit_per_byte: costs[0],
+            storage_processing_credit_per_byte: costs[1],
+            storage_load_credit_per_byte: costs[2],
+            non_storage_load_credit_per_byte: costs[3],
+            query_credit_per_byte: costs[4],
+            storage_seek_cost: costs[5],
+            sha256_cost: costs[6],
+            sha256_2_cost: costs[7],
+            blake3_cost: costs[8],
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_fee_schedule_lookup_by_version() {
+        assert_eq!(FeeSchedule::for_version(1).unwrap(), &FEE_SCHEDULE_V1);
+        assert_eq!(
+            FeeSchedule::for_version(LATEST_FEE_VERSION).unwrap(),
+            FeeSchedule::latest().unwrap()
+        );
+        assert!(matches!(
+            FeeSchedule::for_version(0),
+            Err(FeeError::UnknownFeeVersion(0))
+        ));
+
+        assert_eq!(FeeSchedule::version_for_protocol_version(1).unwrap(), 1);
+        assert_eq!(FeeSchedule::version_for_protocol_version(5).unwrap(), 1);
+        assert!(FeeSchedule::version_for_protocol_version(0).is_err());
+    }
+
+    #[test]
+    fn test_fee_schedule_encoding() {
+        let fee_schedule = FeeSchedule {
+            sha256_cost: 1,
+            blake3_cost: u64::MAX,
+            ..FEE_SCHEDULE_V1
+        };
+
+        let encoded_fee_schedule = fee_schedule.to_be_bytes();
+
+        assert_eq!(encoded_fee_schedule.len(), 72);
+        assert_eq!(
+            FeeSchedule::from_be_bytes(&encoded_fee_schedule).unwrap(),
+            fee_schedule
+        );
+        assert!(matches!(
+            FeeSchedule::from_be_bytes(&encoded_fee_schedule[1..]),
+            Err(FeeError::CorruptedFeeScheduleSerialization(_))
+        ));
+    }
+}
@@ -1,33 +1,85 @@
 use std::borrow::Borrow;
 
+use nohash_hasher::IntMap;
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::Decimal;
+
 use self::{
-    constants::FEE_MULTIPLIER,
+    errors::FeeError,
+    fee_result::FeeResult,
+    fee_schedule::FeeSchedule,
     operations::{Operation, OperationLike},
 };
 
 pub mod calculate_state_transition_fee;
 pub mod constants;
+pub mod errors;
+pub mod fee_result;
+pub mod fee_schedule;
 pub mod operations;
 
-#[derive(Default)]
-pub struct Fees {
-    storage: i64,
-    processing: i64,
-}
+/// Credits type
+pub type Credits = u64;
+
+/// Epoch index type
+pub type EpochIndex = u16;
 
+/// Credits per epoch map
+pub type CreditsPerEpoch = IntMap<EpochIndex, Credits>;
+
+/// Fee multiplier which leaves processing fees unchanged
+pub const NEUTRAL_FEE_MULTIPLIER: f64 = 1.0;
+
+/// Calculates the fees of the operations the same way Drive does for the operations it
+/// executes, so fees of dry runs match the fees charged once the operations are applied.
+/// The fee schedule and the fee multiplier must be the ones of the epoch the operations
+/// are executed in.
 pub fn calculate_operations_fees(
     operations: impl IntoIterator<Item = impl Borrow<Operation>>,
-) -> Fees {
-    let mut fees = Fees::default();
+    fee_schedule: &FeeSchedule,
+    fee_multiplier: f64,
+) -> Result<FeeResult, FeeError> {
+    let mut fee_result = FeeResult::default();
 
     for operation in operations.into_iter() {
         let operation = operation.borrow();
-        fees.processing += operation.get_processing_cost();
-        fees.storage += operation.get_storage_cost();
+
+        let processing_fee = match operation {
+            // Fees calculated by Drive already have the fee multiplier of the epoch applied
+            Operation::PreCalculated(operation) => operation.get_processing_cost(fee_schedule)?,
+            operation => {
+                apply_fee_multiplier(operation.get_processing_cost(fee_schedule)?, fee_multiplier)?
+            }
+        };
+
+        fee_result.checked_add_assign(FeeResult {
+            storage_fee: operation.get_storage_cost(fee_schedule)?,
+            processing_fee,
+            fee_refunds: operation.get_refunds().cloned().unwrap_or_default(),
+            removed_bytes_from_system: operation.get_removed_bytes_from_system()?,
+        })?;
+    }
+
+    Ok(fee_result)
+}
+
+/// Scales processing fees by the fee multiplier, rounding down to whole credits
+pub fn apply_fee_multiplier(
+    processing_fee: Credits,
+    fee_multiplier: f64,
+) -> Result<Credits, FeeError> {
+    if fee_multiplier == NEUTRAL_FEE_MULTIPLIER {
+        return Ok(processing_fee);
     }
 
-    fees.storage *= FEE_MULTIPLIER;
-    fees.processing *= FEE_MULTIPLIER;
+    let fee_multiplier = Decimal::from_f64(fee_multiplier)
+        .filter(|multiplier| multiplier.is_sign_positive())
+        .ok_or(FeeError::DecimalConversion(
+            "fee multiplier must be a positive number",
+        ))?;
 
-    fees
+    Decimal::from(processing_fee)
+        .checked_mul(fee_multiplier)
+        .and_then(|fee| fee.floor().to_u64())
+        .ok_or(FeeError::Overflow("processing fee multiplication overflow"))
 }
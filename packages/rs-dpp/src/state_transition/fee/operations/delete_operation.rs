@@ -1,10 +1,12 @@
+use std::convert::TryFrom;
+
 use serde::{Deserialize, Serialize};
 
-use super::OperationLike;
-use crate::state_transition::fee::constants::{
-    DELETE_BASE_PROCESSING_COST, PROCESSING_CREDIT_PER_BYTE, STORAGE_CREDIT_PER_BYTE,
-};
+use super::{bytes_cost, OperationLike};
+use crate::state_transition::fee::{errors::FeeError, fee_schedule::FeeSchedule, Credits};
 
+/// Deletion of data of unknown owners, the removed bytes are not refunded to identities.
+/// Refunds come with the operations Drive calculates.
 #[derive(Default, Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct DeleteOperation {
@@ -22,13 +24,22 @@ impl DeleteOperation {
 }
 
 impl OperationLike for DeleteOperation {
-    fn get_processing_cost(&self) -> i64 {
-        (DELETE_BASE_PROCESSING_COST
-            + ((self.key_size as i64 + self.value_size as i64) * PROCESSING_CREDIT_PER_BYTE))
-            as i64
+    fn get_processing_cost(&self, fee_schedule: &FeeSchedule) -> Result<Credits, FeeError> {
+        fee_schedule
+            .storage_seek_cost
+            .checked_add(bytes_cost(
+                self.key_size + self.value_size,
+                fee_schedule.storage_processing_credit_per_byte,
+            )?)
+            .ok_or(FeeError::Overflow("delete processing cost overflow"))
+    }
+
+    fn get_storage_cost(&self, _fee_schedule: &FeeSchedule) -> Result<Credits, FeeError> {
+        Ok(0)
     }
 
-    fn get_storage_cost(&self) -> i64 {
-        -((self.key_size + self.value_size) as i64 * STORAGE_CREDIT_PER_BYTE)
+    fn get_removed_bytes_from_system(&self) -> Result<u32, FeeError> {
+        u32::try_from(self.key_size + self.value_size)
+            .map_err(|_| FeeError::Overflow("removed bytes overflow"))
     }
 }
@@ -16,10 +16,11 @@ pub use write_operation::*;
 mod signature_verification_operation;
 pub use signature_verification_operation::*;
 
-pub const STORAGE_CREDIT_PER_BYTE: i64 = 5000;
-pub const STORAGE_PROCESSING_CREDIT_PER_BYTE: i64 = 5000;
+use super::{
+    errors::FeeError, fee_result::refunds::FeeRefunds, fee_schedule::FeeSchedule, Credits,
+};
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(tag = "type", rename_all = "camelCase")]
 pub enum Operation {
     Read(ReadOperation),
@@ -30,31 +31,54 @@ pub enum Operation {
 }
 
 pub trait OperationLike {
-    /// Get CPU cost of the operation
-    fn get_processing_cost(&self) -> i64;
-    /// Get storage cost of the operation
-    fn get_storage_cost(&self) -> i64;
+    /// Get CPU cost of the operation in the given fee schedule
+    fn get_processing_cost(&self, fee_schedule: &FeeSchedule) -> Result<Credits, FeeError>;
+    /// Get storage cost of the operation in the given fee schedule
+    fn get_storage_cost(&self, fee_schedule: &FeeSchedule) -> Result<Credits, FeeError>;
+    /// Get the credits refunded to identities for the data removed by the operation
+    fn get_refunds(&self) -> Option<&FeeRefunds> {
+        None
+    }
+    /// Get the bytes removed by the operation that are not refunded to identities
+    fn get_removed_bytes_from_system(&self) -> Result<u32, FeeError> {
+        Ok(0)
+    }
+}
+
+/// Cost of a number of bytes
+pub(crate) fn bytes_cost(bytes: usize, credits_per_byte: Credits) -> Result<Credits, FeeError> {
+    (bytes as Credits)
+        .checked_mul(credits_per_byte)
+        .ok_or(FeeError::Overflow("bytes cost overflow"))
 }
 
 macro_rules! call_method {
-    ($operation_type:expr, $method:ident ) => {
+    ($operation_type:expr, $method:ident $(, $args:expr)* ) => {
         match $operation_type {
-            Operation::Read(op) => op.$method(),
-            Operation::Write(op) => op.$method(),
-            Operation::Delete(op) => op.$method(),
-            Operation::PreCalculated(op) => op.$method(),
-            Operation::SignatureVerification(op) => op.$method(),
+            Operation::Read(op) => op.$method($($args),*),
+            Operation::Write(op) => op.$method($($args),*),
+            Operation::Delete(op) => op.$method($($args),*),
+            Operation::PreCalculated(op) => op.$method($($args),*),
+            Operation::SignatureVerification(op) => op.$method($($args),*),
         }
     };
 }
 
 impl OperationLike for Operation {
-    fn get_processing_cost(&self) -> i64 {
-        call_method!(self, get_processing_cost)
+    fn get_processing_cost(&self, fee_schedule: &FeeSchedule) -> Result<Credits, FeeError> {
+        call_method!(self, get_processing_cost, fee_schedule)
     }
 
-    fn get_storage_cost(&self) -> i64 {
-        call_method!(self, get_storage_cost)
+    fn get_storage_cost(&self, fee_schedule: &FeeSchedule) -> Result<Credits, FeeError> {
+        call_method!(self, get_storage_cost, fee_schedule)
+    }
+
+    fn get_refunds(&self) -> Option<&FeeRefunds> {
+        call_method!(self, get_refunds)
+    }
+
+    fn get_removed_bytes_from_system(&self) -> Result<u32, FeeError> {
+        call_method!(self, get_removed_bytes_from_system)
     }
 }
 
@@ -138,10 +162,7 @@ mod test {
                     "storageCost" : 12357,
                     "processingCost" : 468910,
                 }),
-                operation: Operation::PreCalculated(PreCalculatedOperation {
-                    storage_cost: 12357,
-                    processing_cost: 468910,
-                }),
+                operation: Operation::PreCalculated(PreCalculatedOperation::new(12357, 468910)),
             },
             TestCase {
                 json_str: json_string!({
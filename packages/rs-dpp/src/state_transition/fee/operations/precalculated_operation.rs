@@ -1,29 +1,63 @@
 use serde::{Deserialize, Serialize};
 
 use super::OperationLike;
+use crate::state_transition::fee::{
+    errors::FeeError,
+    fee_result::{refunds::FeeRefunds, FeeResult},
+    fee_schedule::FeeSchedule,
+    Credits,
+};
 
-#[derive(Default, Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+/// Operation whose fees were already calculated, i.e. by Drive
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct PreCalculatedOperation {
-    pub storage_cost: i64,
-    pub processing_cost: i64,
+    pub storage_cost: Credits,
+    pub processing_cost: Credits,
+    #[serde(default, skip_serializing_if = "FeeRefunds::is_empty")]
+    pub fee_refunds: FeeRefunds,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub removed_bytes_from_system: u32,
+}
+
+fn is_zero(value: &u32) -> bool {
+    *value == 0
 }
 
 impl PreCalculatedOperation {
-    pub fn new(storage_cost: i64, processing_cost: i64) -> Self {
+    pub fn new(storage_cost: Credits, processing_cost: Credits) -> Self {
         Self {
             storage_cost,
             processing_cost,
+            ..Default::default()
+        }
+    }
+
+    /// Creates the operation from fees calculated by Drive, keeping its refunds
+    pub fn from_fee_result(fee_result: FeeResult) -> Self {
+        Self {
+            storage_cost: fee_result.storage_fee,
+            processing_cost: fee_result.processing_fee,
+            fee_refunds: fee_result.fee_refunds,
+            removed_bytes_from_system: fee_result.removed_bytes_from_system,
         }
     }
 }
 
 impl OperationLike for PreCalculatedOperation {
-    fn get_processing_cost(&self) -> i64 {
-        self.processing_cost
+    fn get_processing_cost(&self, _fee_schedule: &FeeSchedule) -> Result<Credits, FeeError> {
+        Ok(self.processing_cost)
+    }
+
+    fn get_storage_cost(&self, _fee_schedule: &FeeSchedule) -> Result<Credits, FeeError> {
+        Ok(self.storage_cost)
+    }
+
+    fn get_refunds(&self) -> Option<&FeeRefunds> {
+        Some(&self.fee_refunds)
     }
 
-    fn get_storage_cost(&self) -> i64 {
-        self.storage_cost
+    fn get_removed_bytes_from_system(&self) -> Result<u32, FeeError> {
+        Ok(self.removed_bytes_from_system)
     }
 }
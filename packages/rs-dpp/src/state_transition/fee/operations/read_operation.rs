@@ -1,10 +1,8 @@
 use serde::{Deserialize, Serialize};
 
-use super::OperationLike;
+use super::{bytes_cost, OperationLike};
 
-use crate::state_transition::fee::constants::{
-    PROCESSING_CREDIT_PER_BYTE, READ_BASE_PROCESSING_COST,
-};
+use crate::state_transition::fee::{errors::FeeError, fee_schedule::FeeSchedule, Credits};
 
 #[derive(Default, Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
@@ -19,11 +17,17 @@ impl ReadOperation {
 }
 
 impl OperationLike for ReadOperation {
-    fn get_processing_cost(&self) -> i64 {
-        READ_BASE_PROCESSING_COST + (self.value_size as i64 * PROCESSING_CREDIT_PER_BYTE)
+    fn get_processing_cost(&self, fee_schedule: &FeeSchedule) -> Result<Credits, FeeError> {
+        fee_schedule
+            .storage_seek_cost
+            .checked_add(bytes_cost(
+                self.value_size,
+                fee_schedule.storage_load_credit_per_byte,
+            )?)
+            .ok_or(FeeError::Overflow("read processing cost overflow"))
     }
 
-    fn get_storage_cost(&self) -> i64 {
-        0
+    fn get_storage_cost(&self, _fee_schedule: &FeeSchedule) -> Result<Credits, FeeError> {
+        Ok(0)
     }
 }
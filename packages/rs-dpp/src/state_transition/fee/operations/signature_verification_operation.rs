@@ -1,7 +1,12 @@
 use serde::{Deserialize, Serialize};
 
 use super::OperationLike;
-use crate::{identity::KeyType, state_transition::fee::constants::signature_verify_cost};
+use crate::{
+    identity::KeyType,
+    state_transition::fee::{
+        constants::signature_verify_cost, errors::FeeError, fee_schedule::FeeSchedule, Credits,
+    },
+};
 
 #[derive(Default, Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
@@ -16,11 +21,11 @@ impl SignatureVerificationOperation {
 }
 
 impl OperationLike for SignatureVerificationOperation {
-    fn get_processing_cost(&self) -> i64 {
-        signature_verify_cost(self.signature_type)
+    fn get_processing_cost(&self, _fee_schedule: &FeeSchedule) -> Result<Credits, FeeError> {
+        Ok(signature_verify_cost(self.signature_type))
     }
 
-    fn get_storage_cost(&self) -> i64 {
-        0
+    fn get_storage_cost(&self, _fee_schedule: &FeeSchedule) -> Result<Credits, FeeError> {
+        Ok(0)
     }
 }
@@ -1,9 +1,7 @@
 use serde::{Deserialize, Serialize};
 
-use super::OperationLike;
-use crate::state_transition::fee::constants::{
-    PROCESSING_CREDIT_PER_BYTE, STORAGE_CREDIT_PER_BYTE, WRITE_BASE_PROCESSING_COST,
-};
+use super::{bytes_cost, OperationLike};
+use crate::state_transition::fee::{errors::FeeError, fee_schedule::FeeSchedule, Credits};
 
 #[derive(Default, Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
@@ -22,12 +20,20 @@ impl WriteOperation {
 }
 
 impl OperationLike for WriteOperation {
-    fn get_processing_cost(&self) -> i64 {
-        WRITE_BASE_PROCESSING_COST
-            + ((self.key_size + self.value_size) as i64 * PROCESSING_CREDIT_PER_BYTE)
+    fn get_processing_cost(&self, fee_schedule: &FeeSchedule) -> Result<Credits, FeeError> {
+        fee_schedule
+            .storage_seek_cost
+            .checked_add(bytes_cost(
+                self.key_size + self.value_size,
+                fee_schedule.storage_processing_credit_per_byte,
+            )?)
+            .ok_or(FeeError::Overflow("write processing cost overflow"))
     }
 
-    fn get_storage_cost(&self) -> i64 {
-        (self.key_size + self.value_size) as i64 * STORAGE_CREDIT_PER_BYTE
+    fn get_storage_cost(&self, fee_schedule: &FeeSchedule) -> Result<Credits, FeeError> {
+        bytes_cost(
+            self.key_size + self.value_size,
+            fee_schedule.storage_disk_usage_credit_per_byte,
+        )
     }
 }
@@ -7,7 +7,7 @@ pub use abstract_state_transition_identity_signed::StateTransitionIdentitySigned
 pub use state_transition_types::*;
 
 use crate::data_contract::state_transition::{
-    DataContractCreateTransition, DataContractUpdateTransition,
+    DataContractCreateTransition, DataContractDeleteTransition, DataContractUpdateTransition,
 };
 // TODO unify the import paths ::object::state_transition::*
 use crate::document::DocumentsBatchTransition;
@@ -35,6 +35,7 @@ macro_rules! call_method {
         match $state_transition {
             StateTransition::DataContractCreate(st) => st.$method($args),
             StateTransition::DataContractUpdate(st) => st.$method($args),
+            StateTransition::DataContractDelete(st) => st.$method($args),
             StateTransition::DocumentsBatch(st) => st.$method($args),
             StateTransition::IdentityCreate(st) => st.$method($args),
             StateTransition::IdentityTopUp(st) => st.$method($args),
@@ -46,6 +47,7 @@ macro_rules! call_method {
         match $state_transition {
             StateTransition::DataContractCreate(st) => st.$method(),
             StateTransition::DataContractUpdate(st) => st.$method(),
+            StateTransition::DataContractDelete(st) => st.$method(),
             StateTransition::DocumentsBatch(st) => st.$method(),
             StateTransition::IdentityCreate(st) => st.$method(),
             StateTransition::IdentityTopUp(st) => st.$method(),
@@ -60,6 +62,7 @@ macro_rules! call_static_method {
         match $state_transition {
             StateTransition::DataContractCreate(_) => DataContractCreateTransition::$method(),
             StateTransition::DataContractUpdate(_) => DataContractUpdateTransition::$method(),
+            StateTransition::DataContractDelete(_) => DataContractDeleteTransition::$method(),
             StateTransition::DocumentsBatch(_) => DocumentsBatchTransition::$method(),
             StateTransition::IdentityCreate(_) => IdentityCreateTransition::$method(),
             StateTransition::IdentityTopUp(_) => IdentityTopUpTransition::$method(),
@@ -75,6 +78,7 @@ macro_rules! call_static_method {
 pub enum StateTransition {
     DataContractCreate(DataContractCreateTransition),
     DataContractUpdate(DataContractUpdateTransition),
+    DataContractDelete(DataContractDeleteTransition),
     DocumentsBatch(DocumentsBatchTransition),
     IdentityCreate(IdentityCreateTransition),
     IdentityTopUp(IdentityTopUpTransition),
@@ -144,6 +148,10 @@ impl StateTransitionLike for StateTransition {
         call_method!(self, set_signature, signature)
     }
 
+    fn get_user_tip(&self) -> fee::Credits {
+        call_method!(self, get_user_tip)
+    }
+
     fn get_execution_context(&self) -> &StateTransitionExecutionContext {
         call_method!(self, get_execution_context)
     }
@@ -169,6 +177,12 @@ impl From<DataContractUpdateTransition> for StateTransition {
     }
 }
 
+impl From<DataContractDeleteTransition> for StateTransition {
+    fn from(d: DataContractDeleteTransition) -> Self {
+        Self::DataContractDelete(d)
+    }
+}
+
 impl From<DocumentsBatchTransition> for StateTransition {
     fn from(d: DocumentsBatchTransition) -> Self {
         Self::DocumentsBatch(d)
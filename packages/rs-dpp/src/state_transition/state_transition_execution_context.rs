@@ -1,6 +1,9 @@
 use std::sync::{Arc, Mutex};
 
+use super::fee::errors::FeeError;
+use super::fee::fee_schedule::FeeSchedule;
 use super::fee::operations::Operation;
+use super::fee::NEUTRAL_FEE_MULTIPLIER;
 
 #[derive(Debug, Clone, Default)]
 pub struct StateTransitionExecutionContext {
@@ -12,6 +15,8 @@ struct StateTransitionContextInner {
     actual_operations: Vec<Operation>,
     dry_run_operations: Vec<Operation>,
     is_dry_run: bool,
+    fee_schedule: Option<FeeSchedule>,
+    fee_multiplier: Option<f64>,
 }
 
 impl StateTransitionExecutionContext {
@@ -47,8 +52,8 @@ impl StateTransitionExecutionContext {
         inner
             .actual_operations
             .iter()
-            .copied()
-            .chain(inner.dry_run_operations.iter().copied())
+            .cloned()
+            .chain(inner.dry_run_operations.iter().cloned())
             .collect()
     }
 
@@ -73,4 +78,35 @@ impl StateTransitionExecutionContext {
         let inner = self.inner.lock().unwrap();
         inner.is_dry_run
     }
+
+    /// Set the fee schedule used to calculate the fees of the operations,
+    /// which is the fee schedule of the epoch the state transition is executed in
+    pub fn set_fee_schedule(&self, fee_schedule: FeeSchedule) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.fee_schedule = Some(fee_schedule);
+    }
+
+    /// Returns the fee schedule used to calculate the fees of the operations,
+    /// the latest fee schedule if it is not set
+    pub fn get_fee_schedule(&self) -> Result<FeeSchedule, FeeError> {
+        let inner = self.inner.lock().unwrap();
+        match inner.fee_schedule {
+            Some(fee_schedule) => Ok(fee_schedule),
+            None => FeeSchedule::latest().copied(),
+        }
+    }
+
+    /// Set the multiplier applied to processing fees of the operations,
+    /// which is the fee multiplier of the epoch the state transition is executed in
+    pub fn set_fee_multiplier(&self, fee_multiplier: f64) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.fee_multiplier = Some(fee_multiplier);
+    }
+
+    /// Returns the multiplier applied to processing fees of the operations,
+    /// the neutral fee multiplier if it is not set
+    pub fn get_fee_multiplier(&self) -> f64 {
+        let inner = self.inner.lock().unwrap();
+        inner.fee_multiplier.unwrap_or(NEUTRAL_FEE_MULTIPLIER)
+    }
 }
@@ -4,7 +4,10 @@ use std::convert::{TryFrom, TryInto};
 use crate::{
     consensus::{basic::BasicError, ConsensusError},
     data_contract::{
-        state_transition::{DataContractCreateTransition, DataContractUpdateTransition},
+        state_transition::{
+            DataContractCreateTransition, DataContractDeleteTransition,
+            DataContractUpdateTransition,
+        },
         DataContract,
     },
     document::DocumentsBatchTransition,
@@ -41,6 +44,10 @@ pub async fn create_state_transition(
             let transition = DataContractUpdateTransition::from_raw_object(raw_state_transition)?;
             Ok(StateTransition::DataContractUpdate(transition))
         }
+        StateTransitionType::DataContractDelete => {
+            let transition = DataContractDeleteTransition::from_raw_object(raw_state_transition)?;
+            Ok(StateTransition::DataContractDelete(transition))
+        }
         StateTransitionType::IdentityCreate => {
             let transition = IdentityCreateTransition::new(raw_state_transition)?;
             Ok(StateTransition::IdentityCreate(transition))
@@ -22,6 +22,7 @@ pub enum StateTransitionType {
     DataContractUpdate = 4,
     IdentityUpdate = 5,
     IdentityCreditWithdrawal = 6,
+    DataContractDelete = 7,
 }
 
 impl std::fmt::Display for StateTransitionType {
@@ -23,7 +23,7 @@ impl<SR> StateTransitionFeeValidator<SR>
 where
     SR: StateRepositoryLike,
 {
-    fn new(state_repository: Arc<SR>) -> Self {
+    pub fn new(state_repository: Arc<SR>) -> Self {
         let asset_lock_transition_output_fetcher =
             AssetLockTransactionOutputFetcher::new(state_repository.clone());
         StateTransitionFeeValidator {
@@ -90,6 +90,13 @@ where
                 }
                 balance
             }
+            StateTransition::DataContractDelete(st) => {
+                let balance = self.get_identity_owner_balance(st).await?;
+                if execution_context.is_dry_run() {
+                    return Ok(result);
+                }
+                balance
+            }
             StateTransition::DocumentsBatch(st) => {
                 let balance = self.get_identity_owner_balance(st).await?;
                 if execution_context.is_dry_run() {
@@ -114,7 +121,7 @@ where
             return Ok(result);
         }
 
-        let fee = state_transition.calculate_fee();
+        let fee = state_transition.calculate_fee()?;
         // ? make sure Fee cannot be negative and refunds are handled differently
         if (balance as i64) < fee {
             result.add_error(FeeError::BalanceIsNotEnoughError { balance, fee })
@@ -168,8 +175,8 @@ mod test {
     use super::StateTransitionFeeValidator;
 
     fn execution_context_with_cost(
-        storage_cost: i64,
-        processing_cost: i64,
+        storage_cost: u64,
+        processing_cost: u64,
     ) -> StateTransitionExecutionContext {
         let ctx = StateTransitionExecutionContext::default();
         ctx.add_operation(Operation::PreCalculated(PreCalculatedOperation::new(
@@ -193,7 +200,7 @@ mod test {
         let data_contract_create_transition = DataContractCreateTransition {
             entropy: data_contract.entropy().to_owned(),
             data_contract,
-            execution_context: execution_context_with_cost(40, 5),
+            execution_context: execution_context_with_cost(80, 10),
             ..Default::default()
         };
 
@@ -226,7 +233,7 @@ mod test {
         let data_contract_create_transition = DataContractCreateTransition {
             entropy: data_contract.entropy().to_owned(),
             data_contract,
-            execution_context: execution_context_with_cost(40, 5),
+            execution_context: execution_context_with_cost(80, 10),
             ..Default::default()
         };
 
@@ -238,6 +245,40 @@ mod test {
         assert!(result.is_valid())
     }
 
+    #[tokio::test]
+    async fn data_contract_crate_transition_invalid_result_if_balance_does_not_cover_user_tip() {
+        let mut identity = identity_fixture();
+        let mut state_repository_mock = MockStateRepositoryLike::new();
+
+        identity.balance = 90;
+        state_repository_mock
+            .expect_fetch_identity()
+            .returning(move |_, _| Ok(Some(identity.clone())));
+
+        let data_contract = get_data_contract_fixture(None);
+        let data_contract_create_transition = DataContractCreateTransition {
+            entropy: data_contract.entropy().to_owned(),
+            data_contract,
+            execution_context: execution_context_with_cost(80, 10),
+            user_tip: Some(5),
+            ..Default::default()
+        };
+
+        let validator = StateTransitionFeeValidator::new(Arc::new(state_repository_mock));
+        let result = validator
+            .validate(&data_contract_create_transition.into())
+            .await
+            .expect("the validation result should be returned");
+
+        let fee_error = get_fee_error_from_result(&result, 0);
+        assert!(
+            matches!(fee_error, FeeError::BalanceIsNotEnoughError { balance, fee } if {
+                *balance == 90 &&
+                *fee == 95
+            })
+        );
+    }
+
     #[tokio::test]
     async fn documents_batch_transition_invalid_result_if_balance_is_not_enough() {
         let mut identity = identity_fixture();
@@ -255,7 +296,7 @@ mod test {
         let documents_batch_transition = DocumentsBatchTransition {
             owner_id: data_contract.owner_id().to_owned(),
             transitions,
-            execution_context: execution_context_with_cost(40, 5),
+            execution_context: execution_context_with_cost(80, 10),
             ..Default::default()
         };
 
@@ -291,7 +332,7 @@ mod test {
         let documents_batch_transition = DocumentsBatchTransition {
             owner_id: data_contract.owner_id().to_owned(),
             transitions,
-            execution_context: execution_context_with_cost(40, 5),
+            execution_context: execution_context_with_cost(80, 10),
             ..Default::default()
         };
 
@@ -317,7 +358,7 @@ mod test {
         let documents =
             get_documents_fixture_with_owner_id_from_contract(data_contract.clone()).unwrap();
         let transitions = get_document_transitions_fixture([(Action::Create, documents)]);
-        let execution_context = execution_context_with_cost(40, 5);
+        let execution_context = execution_context_with_cost(80, 10);
         execution_context.enable_dry_run();
 
         let documents_batch_transition = DocumentsBatchTransition {
@@ -346,7 +387,7 @@ mod test {
 
         let mut identity_topup_transition =
             IdentityTopUpTransition::new(identity_topup_transition_fixture_json(None)).unwrap();
-        identity_topup_transition.set_execution_context(execution_context_with_cost(45000000, 5));
+        identity_topup_transition.set_execution_context(execution_context_with_cost(90000000, 10));
 
         let validator = StateTransitionFeeValidator::new(Arc::new(state_repository_mock));
         let result = validator
@@ -44,6 +44,7 @@ fn setup_test() -> TestData {
         signature: vec![0; 65],
         signature_public_key_id: 0,
         transition_type: StateTransitionType::DataContractUpdate,
+        user_tip: None,
         execution_context: Default::default(),
     };
 
@@ -34,10 +34,13 @@
 
 use crate::abci::messages::{
     AfterFinalizeBlockRequest, AfterFinalizeBlockResponse, BlockBeginRequest, BlockBeginResponse,
-    BlockEndRequest, BlockEndResponse, InitChainRequest, InitChainResponse,
+    BlockEndRequest, BlockEndResponse, CheckTxRequest, CheckTxResponse, DeliverTxRequest,
+    DeliverTxResponse, InitChainRequest, InitChainResponse,
 };
 use crate::block::{BlockExecutionContext, BlockInfo};
 use crate::execution::fee_pools::epoch::EpochInfo;
+use drive::drive::block_info::BlockInfo as DriveBlockInfo;
+use drive::fee_pools::epochs::Epoch;
 use drive::grovedb::TransactionArg;
 
 use crate::error::execution::ExecutionError;
@@ -60,6 +63,16 @@ pub trait TenderdashAbci {
         transaction: TransactionArg,
     ) -> Result<BlockBeginResponse, Error>;
 
+    /// Called with JS Drive to validate a state transition before it is added to the mempool
+    fn check_tx(&self, request: CheckTxRequest) -> Result<CheckTxResponse, Error>;
+
+    /// Called with JS Drive to execute a state transition within the block transaction
+    fn deliver_tx(
+        &self,
+        request: DeliverTxRequest,
+        transaction: TransactionArg,
+    ) -> Result<DeliverTxResponse, Error>;
+
     /// Called with JS Drive on block end
     fn block_end(
         &self,
@@ -115,9 +128,35 @@ impl TenderdashAbci for Platform {
 
         let epoch_info = EpochInfo::from_genesis_time_and_block_info(genesis_time_ms, &block_info)?;
 
+        // A new epoch starts with the fee schedule of the block protocol version, unless
+        // governance activated another one, and the fee multiplier chosen by governance,
+        // while a started epoch keeps the fee parameters stored when it was initialized
+        let (fee_version, fee_schedule, fee_multiplier) = if epoch_info.is_epoch_change {
+            (
+                block_info.new_epoch_fee_version()?,
+                block_info.fee_schedule,
+                block_info.new_epoch_fee_multiplier()?,
+            )
+        } else {
+            let current_epoch = Epoch::new(epoch_info.current_epoch_index);
+
+            (
+                self.drive
+                    .get_epoch_fee_version(&current_epoch, transaction)?,
+                self.drive
+                    .get_epoch_fee_schedule(&current_epoch, transaction)?,
+                self.drive
+                    .get_epoch_fee_multiplier(&current_epoch, transaction)?,
+            )
+        };
+
         let block_execution_context = BlockExecutionContext {
             block_info,
             epoch_info: epoch_info.clone(),
+            fee_version,
+            fee_schedule,
+            fee_multiplier,
+            user_tips: 0,
         };
 
         self.block_execution_context
@@ -138,6 +177,74 @@ impl TenderdashAbci for Platform {
         Ok(response)
     }
 
+    /// Validates a state transition against committed state and returns its estimated fees
+    fn check_tx(&self, request: CheckTxRequest) -> Result<CheckTxResponse, Error> {
+        // Use the latest block for time based validation if there is one
+        let block_info = self
+            .block_execution_context
+            .borrow()
+            .as_ref()
+            .map_or_else(DriveBlockInfo::default, |block_execution_context| {
+                block_execution_context.to_drive_block_info()
+            });
+
+        let execution_result =
+            self.execute_state_transition(&request.state_transition, &block_info, false, None)?;
+
+        Ok(CheckTxResponse {
+            code: execution_result.code(),
+            fee_result: execution_result.fee_result,
+            user_tip: execution_result.user_tip,
+        })
+    }
+
+    /// Validates and applies a state transition and returns its fees
+    fn deliver_tx(
+        &self,
+        request: DeliverTxRequest,
+        transaction: TransactionArg,
+    ) -> Result<DeliverTxResponse, Error> {
+        // Retrieve block execution context
+        let block_info = self
+            .block_execution_context
+            .borrow()
+            .as_ref()
+            .ok_or(Error::Execution(ExecutionError::CorruptedCodeExecution(
+                "block execution context must be set in block begin handler",
+            )))?
+            .to_drive_block_info();
+
+        let execution_result = self.execute_state_transition(
+            &request.state_transition,
+            &block_info,
+            true,
+            transaction,
+        )?;
+
+        // Only tips removed from the owner balance are paid to the proposer
+        if execution_result.user_tip > 0 {
+            let mut block_execution_context = self.block_execution_context.borrow_mut();
+            let block_execution_context = block_execution_context.as_mut().ok_or(
+                Error::Execution(ExecutionError::CorruptedCodeExecution(
+                    "block execution context must be set in block begin handler",
+                )),
+            )?;
+
+            block_execution_context.user_tips = block_execution_context
+                .user_tips
+                .checked_add(execution_result.user_tip)
+                .ok_or(Error::Execution(ExecutionError::Overflow(
+                    "user tips overflow",
+                )))?;
+        }
+
+        Ok(DeliverTxResponse {
+            code: execution_result.code(),
+            fee_result: execution_result.fee_result,
+            user_tip: execution_result.user_tip,
+        })
+    }
+
     /// Processes block fees and returns response
     fn block_end(
         &self,
@@ -157,11 +264,13 @@ impl TenderdashAbci for Platform {
             &block_execution_context.block_info,
             &block_execution_context.epoch_info,
             request.fees,
+            block_execution_context.user_tips,
             transaction,
         )?;
 
         Ok(BlockEndResponse::from_process_block_fees_result(
             &process_block_fees_result,
+            block_execution_context.fee_multiplier,
         ))
     }
 
@@ -169,7 +278,7 @@ impl TenderdashAbci for Platform {
         &self,
         _: AfterFinalizeBlockRequest,
     ) -> Result<AfterFinalizeBlockResponse, Error> {
-        let mut drive_cache = self.drive.cache.borrow_mut();
+        let mut drive_cache = self.drive.write_cache()?;
 
         drive_cache.cached_contracts.clear_block_cache();
 
@@ -186,14 +295,122 @@ mod tests {
         use drive::common::helpers::identities::create_test_masternode_identities;
         use drive::drive::batch::GroveDbOpBatch;
         use drive::fee::epoch::CreditsPerEpoch;
+        use drive::fee::NEUTRAL_FEE_MULTIPLIER;
         use rust_decimal::prelude::ToPrimitive;
         use std::ops::Div;
 
         use crate::abci::messages::{
             AfterFinalizeBlockRequest, BlockBeginRequest, BlockEndRequest, BlockFees,
-            InitChainRequest,
+            CheckTxRequest, DeliverTxRequest, InitChainRequest,
+        };
+        use crate::common::helpers::setup::{
+            setup_platform, setup_platform_with_initial_state_structure,
         };
-        use crate::common::helpers::setup::setup_platform;
+        use crate::common::helpers::state_transitions::{
+            create_contract_transition, insert_identity,
+        };
+
+        const INITIAL_BALANCE: u64 = 100_000_000_000;
+
+        #[test]
+        fn test_check_tx() {
+            let platform = setup_platform_with_initial_state_structure();
+
+            let (owner_id, identity_key) = insert_identity(&platform, INITIAL_BALANCE);
+
+            let (_, state_transition) = create_contract_transition(&owner_id, &identity_key, None);
+
+            let check_tx_response = platform
+                .check_tx(CheckTxRequest { state_transition })
+                .expect("should check tx");
+
+            assert_eq!(check_tx_response.code, 0);
+            assert!(check_tx_response.fee_result.storage_fee > 0);
+            assert!(check_tx_response.fee_result.processing_fee > 0);
+
+            let balance = platform
+                .drive
+                .fetch_identity_balance(owner_id.to_buffer(), None)
+                .expect("should fetch balance");
+
+            assert_eq!(balance, INITIAL_BALANCE);
+        }
+
+        #[test]
+        fn test_check_tx_with_balance_not_enough_for_the_user_tip() {
+            let platform = setup_platform_with_initial_state_structure();
+
+            let (owner_id, identity_key) = insert_identity(&platform, INITIAL_BALANCE);
+
+            let (_, state_transition) =
+                create_contract_transition(&owner_id, &identity_key, Some(INITIAL_BALANCE));
+
+            let check_tx_response = platform
+                .check_tx(CheckTxRequest { state_transition })
+                .expect("should check tx");
+
+            assert_eq!(check_tx_response.code, 3000);
+            assert_eq!(check_tx_response.user_tip, 0);
+        }
+
+        #[test]
+        fn test_deliver_tx() {
+            let platform = setup_platform();
+
+            platform
+                .init_chain(InitChainRequest {}, None)
+                .expect("should init chain");
+
+            let block_begin_request = BlockBeginRequest {
+                block_height: 1,
+                block_time_ms: Utc::now().timestamp_millis() as u64,
+                previous_block_time_ms: None,
+                proposer_pro_tx_hash: [0; 32],
+                validator_set_quorum_hash: Default::default(),
+                protocol_version: 1,
+                fee_multiplier: None,
+                fee_schedule: None,
+            };
+
+            platform
+                .block_begin(block_begin_request, None)
+                .expect("should begin block");
+
+            let (owner_id, identity_key) = insert_identity(&platform, INITIAL_BALANCE);
+
+            let (_, state_transition) =
+                create_contract_transition(&owner_id, &identity_key, Some(1000));
+
+            let deliver_tx_response = platform
+                .deliver_tx(DeliverTxRequest { state_transition }, None)
+                .expect("should deliver tx");
+
+            assert_eq!(deliver_tx_response.code, 0);
+            assert_eq!(deliver_tx_response.user_tip, 1000);
+
+            let balance = platform
+                .drive
+                .fetch_identity_balance(owner_id.to_buffer(), None)
+                .expect("should fetch balance");
+
+            assert_eq!(
+                balance,
+                INITIAL_BALANCE
+                    - deliver_tx_response.fee_result.storage_fee
+                    - deliver_tx_response.fee_result.processing_fee
+                    - 1000
+            );
+
+            let block_execution_context = platform.block_execution_context.borrow();
+
+            assert_eq!(
+                block_execution_context
+                    .as_ref()
+                    .expect("should have block execution context")
+                    .user_tips,
+                1000
+            );
+        }
 
         #[test]
         fn test_abci_flow() {
@@ -284,6 +501,9 @@ mod tests {
                         proposer_pro_tx_hash: proposers
                             [block_height as usize % (proposers_count as usize)],
                         validator_set_quorum_hash: Default::default(),
+                        protocol_version: 1,
+                        fee_multiplier: None,
+                        fee_schedule: None,
                     };
 
                     let block_begin_response = platform
@@ -374,6 +594,8 @@ mod tests {
                             )
                         });
 
+                    assert_eq!(block_end_response.fee_multiplier, NEUTRAL_FEE_MULTIPLIER);
+
                     let after_finalize_block_request = AfterFinalizeBlockRequest {
                         updated_data_contract_ids: Vec::new(),
                     };
@@ -476,6 +698,9 @@ mod tests {
                         proposer_pro_tx_hash: proposers
                             [block_height as usize % (proposers_count as usize)],
                         validator_set_quorum_hash: Default::default(),
+                        protocol_version: 1,
+                        fee_multiplier: None,
+                        fee_schedule: None,
                     };
 
                     let block_begin_response = platform
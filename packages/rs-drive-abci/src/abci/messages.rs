@@ -37,7 +37,9 @@ use crate::error::serialization::SerializationError;
 use crate::error::Error;
 use crate::execution::fee_pools::epoch::EpochInfo;
 use crate::execution::fee_pools::process_block_fees::ProcessedBlockFeesResult;
+use drive::fee::default_costs::FeeSchedule;
 use drive::fee::epoch::CreditsPerEpoch;
+use drive::fee::result::FeeResult;
 use serde::{Deserialize, Serialize};
 
 /// A struct for handling chain initialization requests
@@ -64,6 +66,16 @@ pub struct BlockBeginRequest {
     pub proposer_pro_tx_hash: [u8; 32],
     /// Validator set quorum hash
     pub validator_set_quorum_hash: [u8; 32],
+    /// Network protocol version of the block, it selects the fee schedule
+    /// of the epoch started by this block
+    pub protocol_version: u32,
+    /// Processing fee multiplier chosen by governance for the epoch started by this block.
+    /// Ignored if the block doesn't start a new epoch
+    pub fee_multiplier: Option<f64>,
+    /// Fee schedule activated by governance for the epoch started by this block, used instead
+    /// of the fee schedule of the protocol version. Ignored if the block doesn't start a new epoch
+    #[serde(default)]
+    pub fee_schedule: Option<FeeSchedule>,
 }
 
 /// A struct for handling block begin responses
@@ -116,12 +128,15 @@ pub struct BlockEndResponse {
     pub proposers_paid_count: Option<u16>,
     /// Index of the last epoch that marked as paid
     pub paid_epoch_index: Option<u16>,
+    /// Multiplier applied to the processing fees of the block
+    pub fee_multiplier: f64,
 }
 
 impl BlockEndResponse {
     /// Retrieves fee info for the block to be implemented in the BlockEndResponse
     pub(crate) fn from_process_block_fees_result(
         process_block_fees_result: &ProcessedBlockFeesResult,
+        fee_multiplier: f64,
     ) -> Self {
         let (proposers_paid_count, paid_epoch_index) = process_block_fees_result
             .payouts
@@ -136,10 +151,51 @@ impl BlockEndResponse {
         Self {
             proposers_paid_count,
             paid_epoch_index,
+            fee_multiplier,
         }
     }
 }
 
+/// A struct for handling check tx requests
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckTxRequest {
+    /// Serialized state transition
+    pub state_transition: Vec<u8>,
+}
+
+/// A struct for handling check tx responses
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckTxResponse {
+    /// Consensus error code, 0 if the state transition is valid
+    pub code: u32,
+    /// Estimated fees for applying the state transition
+    pub fee_result: FeeResult,
+    /// Tip paid to the block proposer, can be used to prioritize the state transition
+    pub user_tip: u64,
+}
+
+/// A struct for handling deliver tx requests
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeliverTxRequest {
+    /// Serialized state transition
+    pub state_transition: Vec<u8>,
+}
+
+/// A struct for handling deliver tx responses
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeliverTxResponse {
+    /// Consensus error code, 0 if the state transition is valid
+    pub code: u32,
+    /// Fees for applying the state transition
+    pub fee_result: FeeResult,
+    /// Tip paid to the block proposer, 0 if the state transition is invalid
+    pub user_tip: u64,
+}
+
 /// A struct for handling finalize block responses
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -159,6 +215,10 @@ impl<'a> Serializable<'a> for BlockBeginRequest {}
 impl<'a> Serializable<'a> for BlockBeginResponse {}
 impl<'a> Serializable<'a> for BlockEndRequest {}
 impl<'a> Serializable<'a> for BlockEndResponse {}
+impl<'a> Serializable<'a> for CheckTxRequest {}
+impl<'a> Serializable<'a> for CheckTxResponse {}
+impl<'a> Serializable<'a> for DeliverTxRequest {}
+impl<'a> Serializable<'a> for DeliverTxResponse {}
 impl<'a> Serializable<'a> for AfterFinalizeBlockRequest {}
 impl<'a> Serializable<'a> for AfterFinalizeBlockResponse {}
 
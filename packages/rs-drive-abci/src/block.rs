@@ -28,7 +28,13 @@
 //
 
 use crate::abci::messages::BlockBeginRequest;
+use crate::error::execution::ExecutionError;
+use crate::error::Error;
 use crate::execution::fee_pools::epoch::EpochInfo;
+use drive::drive::block_info::BlockInfo as DriveBlockInfo;
+use drive::fee::default_costs::{FeeSchedule, FeeVersion};
+use drive::fee::NEUTRAL_FEE_MULTIPLIER;
+use drive::fee_pools::epochs::Epoch;
 
 /// Block info
 pub struct BlockInfo {
@@ -40,6 +46,14 @@ pub struct BlockInfo {
     pub previous_block_time_ms: Option<u64>,
     /// Block proposer's proTxHash
     pub proposer_pro_tx_hash: [u8; 32],
+    /// Network protocol version of the block
+    pub protocol_version: u32,
+    /// Processing fee multiplier chosen by governance with the feature flags contract,
+    /// it's applied if the block starts a new epoch
+    pub fee_multiplier: Option<f64>,
+    /// Fee schedule activated by governance with the feature flags contract,
+    /// it's applied if the block starts a new epoch
+    pub fee_schedule: Option<FeeSchedule>,
 }
 
 impl BlockInfo {
@@ -50,8 +64,34 @@ impl BlockInfo {
             block_time_ms: block_begin_request.block_time_ms,
             previous_block_time_ms: block_begin_request.previous_block_time_ms,
             proposer_pro_tx_hash: block_begin_request.proposer_pro_tx_hash,
+            protocol_version: block_begin_request.protocol_version,
+            fee_multiplier: block_begin_request.fee_multiplier,
+            fee_schedule: block_begin_request.fee_schedule,
         }
     }
+
+    /// Returns the version of the fee schedule for an epoch started by this block,
+    /// which is the fee schedule in effect for the protocol version of the block
+    pub fn new_epoch_fee_version(&self) -> Result<FeeVersion, Error> {
+        FeeSchedule::version_for_protocol_version(self.protocol_version)
+            .map_err(|e| Error::Drive(e.into()))
+    }
+
+    /// Returns the processing fee multiplier for an epoch started by this block,
+    /// processing fees aren't scaled if governance didn't choose a multiplier
+    pub fn new_epoch_fee_multiplier(&self) -> Result<f64, Error> {
+        let Some(fee_multiplier) = self.fee_multiplier else {
+            return Ok(NEUTRAL_FEE_MULTIPLIER);
+        };
+
+        if !fee_multiplier.is_finite() || fee_multiplier <= 0.0 {
+            return Err(Error::Execution(ExecutionError::InvalidFeeMultiplier(
+                "fee multiplier must be a positive number",
+            )));
+        }
+
+        Ok(fee_multiplier)
+    }
 }
 
 /// Block execution context
@@ -60,4 +100,30 @@ pub struct BlockExecutionContext {
     pub block_info: BlockInfo,
     /// Epoch info
     pub epoch_info: EpochInfo,
+    /// Version of the fee schedule used by the current epoch
+    pub fee_version: FeeVersion,
+    /// Fee schedule activated by governance for the current epoch
+    pub fee_schedule: Option<FeeSchedule>,
+    /// Processing fee multiplier of the current epoch
+    pub fee_multiplier: f64,
+    /// Tips removed from the balances of the state transition owners so far,
+    /// they are paid to the block proposer at the end of the block
+    pub user_tips: u64,
+}
+
+impl BlockExecutionContext {
+    /// Returns the block info in the form used by Drive operations
+    pub fn to_drive_block_info(&self) -> DriveBlockInfo {
+        DriveBlockInfo {
+            time_ms: self.block_info.block_time_ms,
+            height: self.block_info.block_height,
+            epoch: Epoch::new_with_fee_parameters(
+                self.epoch_info.current_epoch_index,
+                self.fee_version,
+                self.fee_schedule,
+                self.fee_multiplier,
+            ),
+            protocol_version: self.block_info.protocol_version,
+        }
+    }
 }
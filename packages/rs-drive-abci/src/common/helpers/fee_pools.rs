@@ -42,6 +42,7 @@ use drive::contract::document::Document;
 use drive::contract::Contract;
 use drive::dpp::data_contract::extra::DriveContractExt;
 use drive::drive::block_info::BlockInfo;
+use drive::drive::defaults::PROTOCOL_VERSION;
 use drive::drive::flags::StorageFlags;
 use drive::drive::object_size_info::DocumentInfo::DocumentRefAndSerialization;
 use drive::drive::object_size_info::{DocumentAndContractInfo, OwnedDocumentInfo};
@@ -117,7 +118,7 @@ pub fn create_test_masternode_share_identities_and_documents(
     transaction: TransactionArg,
 ) -> Vec<(Identity, Document)> {
     drive
-        .fetch_identities(pro_tx_hashes, transaction)
+        .fetch_identities(pro_tx_hashes, PROTOCOL_VERSION, transaction)
         .expect("expected to fetch identities")
         .iter()
         .map(|mn_identity| {
@@ -151,6 +152,6 @@ pub fn refetch_identities(
         .collect();
 
     drive
-        .fetch_identities(&ids, transaction)
+        .fetch_identities(&ids, PROTOCOL_VERSION, transaction)
         .map_err(Error::Drive)
 }
@@ -1,5 +1,6 @@
 pub mod fee_pools;
 pub mod setup;
+pub mod state_transitions;
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,137 @@
+// MIT LICENSE
+//
+// Copyright (c) 2022 Dash Core Group
+//
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+//
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+//
+
+//! State transition helpers.
+//!
+//! This module defines helper functions inserting identities and creating signed state
+//! transitions to execute them with Platform.
+//!
+
+use std::sync::Arc;
+
+use dashcore::secp256k1::{PublicKey, Secp256k1, SecretKey};
+use drive::dpp::data_contract::validation::data_contract_validator::DataContractValidator;
+use drive::dpp::data_contract::{DataContract, DataContractFactory};
+use drive::dpp::identity::{Identity, IdentityPublicKey, KeyType, Purpose, SecurityLevel};
+use drive::dpp::prelude::Identifier;
+use drive::dpp::state_transition::{StateTransitionConvert, StateTransitionIdentitySigned};
+use drive::dpp::version::ProtocolVersionValidator;
+use drive::dpp::NativeBlsModule;
+use drive::drive::block_info::BlockInfo;
+use serde_json::json;
+
+use crate::platform::Platform;
+
+/// The private key of the identity key of identities inserted with `insert_identity`
+pub const IDENTITY_PRIVATE_KEY: &str =
+    "af432c476f65211f45f48f1d42c9c0b497e56696aa1736b40544ef1a496af837";
+
+/// Inserts an identity with a balance and a key signing with `IDENTITY_PRIVATE_KEY`
+pub fn insert_identity(platform: &Platform, balance: u64) -> (Identifier, IdentityPublicKey) {
+    let private_key = SecretKey::from_slice(&hex::decode(IDENTITY_PRIVATE_KEY).unwrap())
+        .expect("expected a valid private key");
+    let public_key = PublicKey::from_secret_key(&Secp256k1::new(), &private_key);
+
+    let identity_key = IdentityPublicKey {
+        id: 1,
+        key_type: KeyType::ECDSA_SECP256K1,
+        data: public_key.serialize().to_vec(),
+        purpose: Purpose::AUTHENTICATION,
+        security_level: SecurityLevel::HIGH,
+        read_only: false,
+        disabled_at: None,
+        signature: Default::default(),
+    };
+
+    let identity = Identity {
+        id: Identifier::new(rand::random::<[u8; 32]>()),
+        revision: 0,
+        balance,
+        protocol_version: 1,
+        public_keys: vec![identity_key.clone()],
+        asset_lock_proof: None,
+        metadata: None,
+    };
+
+    platform
+        .drive
+        .insert_identity(identity.clone(), BlockInfo::default(), true, None, None)
+        .expect("expected to insert identity");
+
+    (identity.id, identity_key)
+}
+
+/// Creates a contract with a single document type and the signed transition creating it
+pub fn create_contract_transition(
+    owner_id: &Identifier,
+    identity_key: &IdentityPublicKey,
+    user_tip: Option<u64>,
+) -> (DataContract, Vec<u8>) {
+    let factory = DataContractFactory::new(
+        1,
+        DataContractValidator::new(Arc::new(ProtocolVersionValidator::default())),
+    );
+
+    let data_contract = factory
+        .create(
+            owner_id.clone(),
+            json!({
+                "niceDocument": {
+                    "type": "object",
+                    "properties": {
+                        "name": {
+                            "type": "string"
+                        }
+                    },
+                    "additionalProperties": false
+                }
+            }),
+        )
+        .expect("expected to create a contract");
+
+    let mut state_transition = factory
+        .create_data_contract_create_transition(data_contract.clone())
+        .expect("expected to create a transition");
+
+    state_transition.user_tip = user_tip;
+    state_transition.set_signature_public_key_id(identity_key.id);
+    state_transition
+        .sign(
+            identity_key,
+            &hex::decode(IDENTITY_PRIVATE_KEY).unwrap(),
+            &NativeBlsModule::default(),
+        )
+        .expect("expected to sign the transition");
+
+    let raw_state_transition = state_transition
+        .to_buffer(false)
+        .expect("expected to serialize the transition");
+
+    (data_contract, raw_state_transition)
+}
@@ -24,4 +24,12 @@ pub enum ExecutionError {
     /// Error
     #[error("drive missing data error: {0}")]
     DriveMissingData(&'static str),
+
+    /// Error
+    #[error("invalid fee multiplier error: {0}")]
+    InvalidFeeMultiplier(&'static str),
+
+    /// Error
+    #[error("not supported error: {0}")]
+    NotSupported(&'static str),
 }
@@ -1,5 +1,6 @@
 use crate::error::execution::ExecutionError;
 use crate::error::serialization::SerializationError;
+use drive::dpp::ProtocolError;
 use drive::error::Error as DriveError;
 
 /// Execution errors module
@@ -20,4 +21,7 @@ pub enum Error {
     /// Error
     #[error("serialization: {0}")]
     Serialization(#[from] SerializationError),
+    /// Error
+    #[error("protocol: {0}")]
+    Protocol(#[from] ProtocolError),
 }
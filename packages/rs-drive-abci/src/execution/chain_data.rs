@@ -0,0 +1,120 @@
+// MIT LICENSE
+//
+// Copyright (c) 2022 Dash Core Group
+//
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+//
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+//
+
+//! Chain Data.
+//!
+//! This module defines the chain data provider which the Drive state repository
+//! uses while executing state transitions of a block. Platform doesn't connect to
+//! Core yet, so Core data is not available and requesting it is an error.
+//!
+
+use anyhow::{anyhow, Result as AnyResult};
+use drive::dpp::dashcore::{BlockHeader, InstantLock};
+use drive::drive::block_info::BlockInfo;
+use drive::drive::state_repository::ChainDataProviderLike;
+use serde_json::Value as JsonValue;
+
+/// Provides the chain data of the block being executed.
+pub struct BlockChainDataProvider {
+    /// Info of the block being executed
+    block_info: BlockInfo,
+}
+
+impl BlockChainDataProvider {
+    /// Creates a chain data provider for the block being executed.
+    pub fn new(block_info: BlockInfo) -> Self {
+        BlockChainDataProvider { block_info }
+    }
+}
+
+impl ChainDataProviderLike for BlockChainDataProvider {
+    fn fetch_transaction(&self, _id: &str) -> AnyResult<Option<JsonValue>> {
+        Err(anyhow!(
+            "fetching core transactions is not supported until platform is connected to core"
+        ))
+    }
+
+    fn verify_instant_lock(&self, _instant_lock: &InstantLock) -> AnyResult<bool> {
+        Err(anyhow!(
+            "verifying instant locks is not supported until platform is connected to core"
+        ))
+    }
+
+    fn fetch_sml_store(&self) -> AnyResult<JsonValue> {
+        Err(anyhow!(
+            "fetching the masternode list is not supported until platform is connected to core"
+        ))
+    }
+
+    /// Returns the header of the block being executed, so state transitions are validated
+    /// against the time of the block they are included in. Only the time is set, in seconds.
+    fn fetch_latest_platform_block_header(&self) -> AnyResult<JsonValue> {
+        let block_header = BlockHeader {
+            version: 0,
+            prev_blockhash: Default::default(),
+            merkle_root: Default::default(),
+            time: (self.block_info.time_ms / 1000) as u32,
+            bits: 0,
+            nonce: 0,
+        };
+
+        Ok(serde_json::to_value(block_header)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_block_header_has_the_time_of_the_block() {
+        let provider = BlockChainDataProvider::new(BlockInfo::default_with_time(1_650_000_000_500));
+
+        let block_header: BlockHeader = serde_json::from_value(
+            provider
+                .fetch_latest_platform_block_header()
+                .expect("expected to fetch the block header"),
+        )
+        .expect("expected to deserialize the block header");
+
+        assert_eq!(block_header.time, 1_650_000_000);
+    }
+
+    #[test]
+    fn test_core_data_is_not_available() {
+        let provider = BlockChainDataProvider::new(BlockInfo::default());
+
+        provider
+            .fetch_transaction("abcd")
+            .expect_err("expected core to be unavailable");
+        provider
+            .fetch_sml_store()
+            .expect_err("expected core to be unavailable");
+    }
+}
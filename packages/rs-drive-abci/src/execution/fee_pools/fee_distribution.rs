@@ -41,6 +41,7 @@ use drive::drive::batch::GroveDbOpBatch;
 use drive::error::fee::FeeError;
 use drive::fee::credits::Credits;
 use drive::fee::epoch::GENESIS_EPOCH_INDEX;
+use drive::fee::op::DriveOperation;
 use drive::fee_pools::epochs::Epoch;
 use drive::fee_pools::{
     update_storage_fee_distribution_pool_operation, update_unpaid_epoch_index_operation,
@@ -354,7 +355,7 @@ impl Platform {
     }
 
     /// Adds operations to an op batch which pay a reward to an identity's balance
-    fn add_pay_reward_to_identity_operations(
+    pub(crate) fn add_pay_reward_to_identity_operations(
         &self,
         id: &[u8],
         reward: u64,
@@ -363,18 +364,24 @@ impl Platform {
     ) -> Result<(), Error> {
         // We don't need additional verification, since we ensure an identity
         // existence in the data contract triggers in DPP
-        let (mut identity, storage_flags) = self.drive.fetch_identity(id, transaction)?;
-
-        identity.balance = identity
-            .balance
-            .checked_add(reward)
-            .ok_or(Error::Execution(ExecutionError::Overflow(
-                "pay reward overflow",
-            )))?;
-
-        self.drive
-            .add_insert_identity_operations(identity, storage_flags.as_ref(), batch)
-            .map_err(Error::Drive)
+        let identity_id: [u8; 32] = id.try_into().map_err(|_| {
+            Error::Execution(ExecutionError::CorruptedCodeExecution(
+                "identity id must be 32 bytes",
+            ))
+        })?;
+
+        let drive_operations = self.drive.add_to_identity_balance_operations(
+            identity_id,
+            reward,
+            &mut None,
+            transaction,
+        )?;
+
+        batch.append(&mut DriveOperation::grovedb_operations_batch(
+            &drive_operations,
+        ));
+
+        Ok(())
     }
 
     /// Adds operations to an op batch which update total storage fees
@@ -1187,6 +1194,7 @@ mod tests {
         use crate::common::helpers::fee_pools::{
             create_test_masternode_share_identities_and_documents, refetch_identities,
         };
+        use drive::drive::defaults::PROTOCOL_VERSION;
 
         #[test]
         fn test_payout_to_proposers() {
@@ -1275,7 +1283,7 @@ mod tests {
             // check we paid 500 to every mn identity
             let paid_mn_identities = platform
                 .drive
-                .fetch_identities(&pro_tx_hashes, Some(&transaction))
+                .fetch_identities(&pro_tx_hashes, PROTOCOL_VERSION, Some(&transaction))
                 .expect("expected to get identities");
 
             let total_fees = Decimal::from(storage_fees + processing_fees);
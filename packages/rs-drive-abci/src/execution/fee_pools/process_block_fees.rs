@@ -47,8 +47,8 @@ use crate::execution::fee_pools::distribute_storage_pool::DistributionStorageFee
 use crate::execution::fee_pools::epoch::EpochInfo;
 use crate::execution::fee_pools::fee_distribution::{FeesInPools, ProposersPayouts};
 use crate::platform::Platform;
+use drive::fee::credits::Credits;
 use drive::fee::epoch::{GENESIS_EPOCH_INDEX, PERPETUAL_STORAGE_EPOCHS};
-use drive::fee::DEFAULT_ORIGINAL_FEE_MULTIPLIER;
 
 /// From the Dash Improvement Proposal:
 
@@ -98,10 +98,17 @@ impl Platform {
         }
 
         // init current epoch pool for processing
-        let current_epoch = Epoch::new(epoch_info.current_epoch_index);
+        let fee_multiplier = block_info.new_epoch_fee_multiplier()?;
+
+        let current_epoch = Epoch::new_with_fee_parameters(
+            epoch_info.current_epoch_index,
+            block_info.new_epoch_fee_version()?,
+            block_info.fee_schedule,
+            fee_multiplier,
+        );
 
         current_epoch.add_init_current_operations(
-            DEFAULT_ORIGINAL_FEE_MULTIPLIER, // TODO use a data contract to choose the fee multiplier
+            fee_multiplier,
             block_info.block_height,
             block_info.block_time_ms,
             batch,
@@ -132,6 +139,8 @@ impl Platform {
 
     /// Adds operations to GroveDB op batch related to processing
     /// and distributing the block fees from the previous block and applies the batch.
+    /// The `user_tips` already removed from the balances of the state transition owners
+    /// are paid to the block proposer.
     ///
     /// Returns `ProcessedBlockFeesResult`.
     pub fn process_block_fees(
@@ -139,6 +148,7 @@ impl Platform {
         block_info: &BlockInfo,
         epoch_info: &EpochInfo,
         block_fees: BlockFees,
+        user_tips: Credits,
         transaction: TransactionArg,
     ) -> Result<ProcessedBlockFeesResult, Error> {
         let current_epoch = Epoch::new(epoch_info.current_epoch_index);
@@ -218,6 +228,22 @@ impl Platform {
 
         self.drive.grove_apply_batch(batch, false, transaction)?;
 
+        // User tips go straight to the block proposer and never reach the epoch pools.
+        // They are applied separately since the proposer might be paid out above as well.
+        if user_tips > 0 {
+            let mut user_tips_batch = GroveDbOpBatch::new();
+
+            self.add_pay_reward_to_identity_operations(
+                &block_info.proposer_pro_tx_hash,
+                user_tips,
+                transaction,
+                &mut user_tips_batch,
+            )?;
+
+            self.drive
+                .grove_apply_batch(user_tips_batch, false, transaction)?;
+        }
+
         Ok(ProcessedBlockFeesResult {
             fees_in_pools,
             payouts,
@@ -241,6 +267,7 @@ mod tests {
 
         mod helpers {
             use super::*;
+            use drive::fee::default_costs::{FeeSchedule, FEE_SCHEDULE_V1};
             use drive::fee::epoch::CreditsPerEpoch;
 
             /// Process and validate an epoch change
@@ -284,11 +311,19 @@ mod tests {
 
                 let block_time_ms = genesis_time_ms + epoch_index as u64 * EPOCH_CHANGE_TIME_MS;
 
+                let fee_schedule = FeeSchedule {
+                    storage_seek_cost: FEE_SCHEDULE_V1.storage_seek_cost * 2,
+                    ..FEE_SCHEDULE_V1
+                };
+
                 let block_info = BlockInfo {
                     block_height,
                     block_time_ms,
                     previous_block_time_ms,
                     proposer_pro_tx_hash,
+                    protocol_version: 1,
+                    fee_multiplier: Some(2.5),
+                    fee_schedule: Some(fee_schedule),
                 };
 
                 let epoch_info =
@@ -336,6 +371,22 @@ mod tests {
 
                 assert_eq!(epoch_start_block_height, block_height);
 
+                // epoch should use the fee multiplier chosen by governance
+                let fee_multiplier = platform
+                    .drive
+                    .get_epoch_fee_multiplier(&current_epoch, transaction)
+                    .expect("should get fee multiplier");
+
+                assert_eq!(fee_multiplier, 2.5);
+
+                // epoch should use the fee schedule activated by governance
+                let stored_fee_schedule = platform
+                    .drive
+                    .get_epoch_fee_schedule(&current_epoch, transaction)
+                    .expect("should get fee schedule");
+
+                assert_eq!(stored_fee_schedule, Some(fee_schedule));
+
                 // storage fee should be distributed
                 assert_eq!(distribute_storage_pool_result.is_some(), should_distribute);
 
@@ -454,6 +505,9 @@ mod tests {
                     block_time_ms,
                     previous_block_time_ms,
                     proposer_pro_tx_hash,
+                    protocol_version: 1,
+                    fee_multiplier: None,
+                    fee_schedule: None,
                 };
 
                 let epoch_info =
@@ -467,7 +521,13 @@ mod tests {
                 };
 
                 let distribute_storage_pool_result = platform
-                    .process_block_fees(&block_info, &epoch_info, block_fees.clone(), transaction)
+                    .process_block_fees(
+                        &block_info,
+                        &epoch_info,
+                        block_fees.clone(),
+                        0,
+                        transaction,
+                    )
                     .expect("should process block fees");
 
                 // Should process epoch change
@@ -661,5 +721,60 @@ mod tests {
                 Some(&transaction),
             );
         }
+
+        #[test]
+        fn test_process_block_fees_pays_user_tips_to_proposer() {
+            let platform = setup_platform_with_initial_state_structure();
+            let transaction = platform.drive.grove.start_transaction();
+
+            platform.create_mn_shares_contract(Some(&transaction));
+
+            let proposers =
+                create_test_masternode_identities(&platform.drive, 1, Some(&transaction));
+
+            let genesis_time_ms = Utc::now()
+                .timestamp_millis()
+                .to_u64()
+                .expect("block time can not be before 1970");
+
+            let block_info = BlockInfo {
+                block_height: 1,
+                block_time_ms: genesis_time_ms,
+                previous_block_time_ms: None,
+                proposer_pro_tx_hash: proposers[0],
+                protocol_version: 1,
+                fee_multiplier: None,
+                fee_schedule: None,
+            };
+
+            let epoch_info =
+                EpochInfo::from_genesis_time_and_block_info(genesis_time_ms, &block_info)
+                    .expect("should calculate epoch info");
+
+            let balance_before = platform
+                .drive
+                .fetch_identity_balance(proposers[0], Some(&transaction))
+                .expect("should fetch proposer balance");
+
+            let result = platform
+                .process_block_fees(
+                    &block_info,
+                    &epoch_info,
+                    BlockFees::from_fees(1000, 10000),
+                    500,
+                    Some(&transaction),
+                )
+                .expect("should process block fees");
+
+            let balance_after = platform
+                .drive
+                .fetch_identity_balance(proposers[0], Some(&transaction))
+                .expect("should fetch proposer balance");
+
+            assert_eq!(balance_after, balance_before + 500);
+
+            // Tips must not be distributed into the pools
+            assert_eq!(result.fees_in_pools.processing_fees, 10000);
+        }
     }
 }
@@ -1,2 +1,6 @@
+/// Chain data module
+pub mod chain_data;
 /// Fee pools module
 pub mod fee_pools;
+/// State transition execution module
+pub mod state_transition;
@@ -0,0 +1,1184 @@
+// MIT LICENSE
+//
+// Copyright (c) 2022 Dash Core Group
+//
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+//
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+//
+
+//! State Transition Execution.
+//!
+//! This module defines functions within the Platform struct related to executing
+//! state transitions. A state transition is decoded and validated by DPP against
+//! Drive state, then converted to Drive operations which are applied within the
+//! block transaction.
+//!
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use drive::dpp::consensus::basic::BasicError;
+use drive::dpp::consensus::fee::FeeError;
+use drive::dpp::consensus::ConsensusError;
+use drive::dpp::data_contract::state_transition::data_contract_create_transition::validation::state::validate_data_contract_create_transition_basic::DataContractCreateTransitionBasicValidator;
+use drive::dpp::data_contract::state_transition::data_contract_create_transition::validation::state::validate_data_contract_create_transition_state::validate_data_contract_create_transition_state;
+use drive::dpp::data_contract::state_transition::data_contract_delete_transition::validation::basic::DataContractDeleteTransitionBasicValidator;
+use drive::dpp::data_contract::state_transition::data_contract_delete_transition::validation::state::validate_data_contract_delete_transition_state::validate_data_contract_delete_transition_state;
+use drive::dpp::data_contract::state_transition::data_contract_update_transition::validation::basic::DataContractUpdateTransitionBasicValidator;
+use drive::dpp::data_contract::state_transition::data_contract_update_transition::validation::state::validate_data_contract_update_transition_state::validate_data_contract_update_transition_state;
+use drive::dpp::decode_protocol_entity_factory::DecodeProtocolEntity;
+use drive::dpp::document::document_transition::{Action, DocumentTransition};
+use drive::dpp::document::validation::basic::validate_documents_batch_transition_basic::validate_documents_batch_transition_basic;
+use drive::dpp::document::validation::state::fetch_documents::fetch_documents;
+use drive::dpp::document::validation::state::validate_documents_batch_transition_state::validate_document_batch_transition_state;
+use drive::dpp::document::{Document, DocumentsBatchTransition};
+use drive::dpp::data_contract::extra::DriveContractExt;
+use drive::dpp::prelude::{DataContract, Identifier};
+use drive::dpp::state_transition::fee::calculate_state_transition_fee::calculate_state_transition_fee_result;
+use drive::dpp::state_transition::state_transition_execution_context::StateTransitionExecutionContext;
+use drive::dpp::state_transition::validation::validate_state_transition_fee::StateTransitionFeeValidator;
+use drive::dpp::state_transition::validation::validate_state_transition_identity_signature::validate_state_transition_identity_signature;
+use drive::dpp::state_transition::{
+    create_state_transition, StateTransition, StateTransitionIdentitySigned, StateTransitionLike,
+    StateTransitionType,
+};
+use drive::dpp::validation::{DataValidatorWithContext, SimpleValidationResult, ValidationResult};
+use drive::dpp::version::ProtocolVersionValidator;
+use drive::dpp::{NativeBlsModule, ProtocolError};
+use drive::drive::batch::{ContractOperationType, DocumentOperationType, DriveOperationType};
+use drive::drive::block_info::BlockInfo;
+use drive::contract::document::Document as DriveDocument;
+use drive::drive::contract::ContractFetchInfo;
+use drive::drive::flags::StorageFlags;
+use drive::drive::object_size_info::DocumentInfo::DocumentRefAndSerialization;
+use drive::drive::object_size_info::{DocumentAndContractInfo, OwnedDocumentInfo};
+use drive::drive::state_repository::DriveStateRepository;
+use drive::fee::result::FeeResult;
+use drive::fee_pools::epochs::Epoch;
+use drive::grovedb::TransactionArg;
+use futures::executor::block_on;
+use serde_json::Value as JsonValue;
+
+use crate::error::execution::ExecutionError;
+use crate::error::Error;
+use crate::execution::chain_data::BlockChainDataProvider;
+use crate::platform::Platform;
+
+/// The result of executing a state transition
+pub struct StateTransitionExecutionResult {
+    /// Consensus errors found during validation, empty if the state transition is valid
+    pub validation_result: SimpleValidationResult,
+    /// Fees for applying the state transition, default if it is invalid
+    pub fee_result: FeeResult,
+    /// Tip paid to the block proposer, removed from the owner balance with the fees when applied.
+    /// 0 if the state transition is invalid
+    pub user_tip: u64,
+}
+
+impl StateTransitionExecutionResult {
+    /// The code of the first consensus error, or 0 if the state transition is valid
+    pub fn code(&self) -> u32 {
+        self.validation_result
+            .first_error()
+            .map_or(0, ConsensusError::code)
+    }
+}
+
+/// A document with the data needed to apply it with Drive operations
+struct DocumentForDrive {
+    /// The document transition action
+    action: Action,
+    /// The document id
+    document_id: [u8; 32],
+    /// The document converted from DPP and its serialization, not present for deletions
+    document: Option<(DriveDocument, Vec<u8>)>,
+    /// The contract fetched from Drive
+    contract_fetch_info: Arc<ContractFetchInfo>,
+    /// The document type name
+    document_type_name: String,
+    /// The document owner id
+    owner_id: [u8; 32],
+    /// Storage flags for the document
+    storage_flags: StorageFlags,
+}
+
+/// Owned data of a state transition the Drive operations will borrow from
+enum StateTransitionForDrive {
+    /// A contract to create or update
+    Contract {
+        /// The contract with its document types
+        contract: DataContract,
+        /// The serialized contract
+        serialized_contract: Vec<u8>,
+        /// Storage flags for the contract
+        storage_flags: StorageFlags,
+    },
+    /// A contract to delete with all its documents
+    ContractDeletion {
+        /// The contract id
+        contract_id: [u8; 32],
+    },
+    /// Documents to create, replace or delete
+    Documents(Vec<DocumentForDrive>),
+}
+
+impl StateTransitionForDrive {
+    /// Returns the Drive operations applying the state transition
+    fn to_drive_operations(&self) -> Result<Vec<DriveOperationType>, Error> {
+        match self {
+            StateTransitionForDrive::Contract {
+                contract,
+                serialized_contract,
+                storage_flags,
+            } => Ok(vec![DriveOperationType::ContractOperation(
+                ContractOperationType::ApplyContractWithSerialization {
+                    contract,
+                    serialized_contract: serialized_contract.clone(),
+                    storage_flags: Some(storage_flags),
+                },
+            )]),
+            StateTransitionForDrive::ContractDeletion { contract_id } => {
+                Ok(vec![DriveOperationType::ContractOperation(
+                    ContractOperationType::DeleteContract {
+                        contract_id: *contract_id,
+                    },
+                )])
+            }
+            StateTransitionForDrive::Documents(documents) => documents
+                .iter()
+                .map(|document| {
+                    let contract = &document.contract_fetch_info.contract;
+
+                    let document_operation = match (&document.action, &document.document) {
+                        (Action::Create, Some((drive_document, serialized_document))) => {
+                            DocumentOperationType::AddDocumentForContract {
+                                document_and_contract_info: DocumentAndContractInfo {
+                                    owned_document_info: OwnedDocumentInfo {
+                                        document_info: DocumentRefAndSerialization((
+                                            drive_document,
+                                            serialized_document,
+                                            Some(&document.storage_flags),
+                                        )),
+                                        owner_id: Some(document.owner_id),
+                                    },
+                                    contract,
+                                    document_type: contract
+                                        .document_type_for_name(&document.document_type_name)
+                                        .map_err(drive::error::Error::from)?,
+                                },
+                                override_document: false,
+                            }
+                        }
+                        (Action::Replace, Some((drive_document, serialized_document))) => {
+                            DocumentOperationType::UpdateDocumentForContract {
+                                document: drive_document,
+                                serialized_document,
+                                contract,
+                                document_type_name: &document.document_type_name,
+                                owner_id: Some(document.owner_id),
+                                storage_flags: Some(&document.storage_flags),
+                            }
+                        }
+                        _ => DocumentOperationType::DeleteDocumentForContract {
+                            document_id: document.document_id,
+                            contract,
+                            document_type_name: &document.document_type_name,
+                            owner_id: Some(document.owner_id),
+                        },
+                    };
+
+                    Ok(DriveOperationType::DocumentOperation(document_operation))
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Platform {
+    /// Decodes and validates a state transition, then applies it within the given transaction.
+    /// If `apply` is false, state is not changed and only the estimated fees are returned.
+    /// In both cases the state transition is invalid if the owner balance can't pay the estimated
+    /// fees and the user tip. Identity state transitions are not supported yet.
+    pub fn execute_state_transition(
+        &self,
+        raw_state_transition: &[u8],
+        block_info: &BlockInfo,
+        apply: bool,
+        transaction: TransactionArg,
+    ) -> Result<StateTransitionExecutionResult, Error> {
+        let chain_data_provider = BlockChainDataProvider::new(block_info.clone());
+
+        let state_repository = Arc::new(DriveStateRepository::new(
+            &self.drive,
+            &chain_data_provider,
+            block_info.clone(),
+            transaction,
+        ));
+
+        let validation_result = block_on(validate_state_transition(
+            &state_repository,
+            raw_state_transition,
+            &block_info.epoch,
+        ))?;
+
+        let state_transition = match validation_result.data() {
+            Some(state_transition) if validation_result.is_valid() => state_transition.clone(),
+            _ => {
+                return Ok(StateTransitionExecutionResult {
+                    validation_result: validation_result.into_result_without_data(),
+                    fee_result: FeeResult::default(),
+                    user_tip: 0,
+                })
+            }
+        };
+
+        let state_transition_for_drive = block_on(self.prepare_state_transition_for_drive(
+            &state_repository,
+            &state_transition,
+            block_info,
+            transaction,
+        ))?;
+
+        let owner_id = state_transition_owner_id(&state_transition)?;
+
+        let validation_fee_result = calculate_state_transition_fee_result(&state_transition)
+            .map_err(drive::error::Error::from)?;
+
+        let user_tip = state_transition.get_user_tip();
+
+        let mut validation_result = validation_result.into_result_without_data();
+
+        // Estimated fees are the most applying can cost, the owner must be able to pay them
+        // as state can't be reverted if the balance is not enough, so state transitions it
+        // can't pay for are rejected before they get into the mempool as well
+        let mut estimated_fee_result = self.drive.apply_drive_operations(
+            state_transition_for_drive.to_drive_operations()?,
+            false,
+            block_info,
+            transaction,
+        )?;
+        estimated_fee_result
+            .checked_add_assign(validation_fee_result.clone())
+            .map_err(drive::error::Error::from)?;
+
+        // Refunds can't pay for a state transition, the tip is paid from the balance as well
+        let required_balance = u64::try_from(
+            estimated_fee_result
+                .balance_change()
+                .map_err(drive::error::Error::from)?,
+        )
+        .unwrap_or_default()
+        .saturating_add(user_tip);
+
+        let balance = self.drive.fetch_identity_balance(owner_id, transaction)?;
+
+        if balance < required_balance {
+            validation_result.add_error(FeeError::BalanceIsNotEnoughError {
+                balance,
+                fee: i64::try_from(required_balance).unwrap_or(i64::MAX),
+            });
+
+            return Ok(StateTransitionExecutionResult {
+                validation_result,
+                fee_result: FeeResult::default(),
+                user_tip: 0,
+            });
+        }
+
+        let fee_result = self.drive.apply_drive_operations_paid_by_identity(
+            state_transition_for_drive.to_drive_operations()?,
+            owner_id,
+            validation_fee_result,
+            user_tip,
+            apply,
+            block_info,
+            transaction,
+        )?;
+
+        Ok(StateTransitionExecutionResult {
+            validation_result,
+            fee_result,
+            user_tip,
+        })
+    }
+
+    /// Collects the data needed to build Drive operations for a valid state transition
+    async fn prepare_state_transition_for_drive(
+        &self,
+        state_repository: &Arc<DriveStateRepository<'_>>,
+        state_transition: &StateTransition,
+        block_info: &BlockInfo,
+        transaction: TransactionArg<'_, '_>,
+    ) -> Result<StateTransitionForDrive, Error> {
+        match state_transition {
+            StateTransition::DataContractCreate(st) => {
+                Self::prepare_contract_for_drive(&st.data_contract, block_info)
+            }
+            StateTransition::DataContractUpdate(st) => {
+                Self::prepare_contract_for_drive(&st.data_contract, block_info)
+            }
+            StateTransition::DataContractDelete(st) => {
+                Ok(StateTransitionForDrive::ContractDeletion {
+                    contract_id: st.data_contract_id.to_buffer(),
+                })
+            }
+            StateTransition::DocumentsBatch(st) => Ok(StateTransitionForDrive::Documents(
+                self.prepare_documents_for_drive(state_repository, st, block_info, transaction)
+                    .await?,
+            )),
+            _ => Err(Error::Execution(ExecutionError::CorruptedCodeExecution(
+                "only validated contract and document state transitions can be applied",
+            ))),
+        }
+    }
+
+    /// Builds the contract of a contract state transition as it will be stored by Drive
+    fn prepare_contract_for_drive(
+        data_contract: &DataContract,
+        block_info: &BlockInfo,
+    ) -> Result<StateTransitionForDrive, Error> {
+        let mut contract = data_contract.clone();
+        contract.generate_document_types()?;
+
+        let serialized_contract = contract.to_cbor()?;
+
+        let storage_flags = StorageFlags::new_single_epoch(
+            block_info.epoch.index,
+            Some(contract.owner_id.to_buffer()),
+        );
+
+        Ok(StateTransitionForDrive::Contract {
+            contract,
+            serialized_contract,
+            storage_flags,
+        })
+    }
+
+    /// Builds the documents of a documents batch transition as they will be stored by Drive
+    async fn prepare_documents_for_drive(
+        &self,
+        state_repository: &Arc<DriveStateRepository<'_>>,
+        state_transition: &DocumentsBatchTransition,
+        block_info: &BlockInfo,
+        transaction: TransactionArg<'_, '_>,
+    ) -> Result<Vec<DocumentForDrive>, Error> {
+        let replace_transitions = state_transition
+            .get_transitions()
+            .iter()
+            .filter(|transition| transition.base().action == Action::Replace);
+
+        let fetched_documents = fetch_documents(
+            state_repository.as_ref(),
+            replace_transitions,
+            state_transition.get_execution_context(),
+        )
+        .await
+        .map_err(ProtocolError::from)?;
+
+        let fetched_documents_by_id: HashMap<&Identifier, &Document> = fetched_documents
+            .iter()
+            .map(|document| (&document.id, document))
+            .collect();
+
+        let owner_id = state_transition.owner_id.to_buffer();
+
+        let mut contracts: HashMap<[u8; 32], Arc<ContractFetchInfo>> = HashMap::new();
+
+        state_transition
+            .get_transitions()
+            .iter()
+            .map(|document_transition| {
+                let base = document_transition.base();
+
+                let contract_id = base.data_contract_id.to_buffer();
+
+                let contract_fetch_info = match contracts.get(&contract_id) {
+                    Some(contract_fetch_info) => contract_fetch_info.clone(),
+                    None => {
+                        let (_, contract_fetch_info) = self.drive.get_contract_with_fetch_info(
+                            contract_id,
+                            Some(&block_info.epoch),
+                            transaction,
+                        )?;
+
+                        let contract_fetch_info = contract_fetch_info.ok_or(Error::Execution(
+                            ExecutionError::DriveMissingData(
+                                "contract of a validated document transition must exist",
+                            ),
+                        ))?;
+
+                        contracts.insert(contract_id, contract_fetch_info.clone());
+
+                        contract_fetch_info
+                    }
+                };
+
+                let contract = &contract_fetch_info.contract;
+
+                let document = match document_transition {
+                    DocumentTransition::Create(transition) => {
+                        let document = Document {
+                            protocol_version: state_transition.protocol_version,
+                            id: base.id.clone(),
+                            document_type: base.document_type.clone(),
+                            revision: transition.get_revision(),
+                            data_contract_id: base.data_contract_id.clone(),
+                            owner_id: state_transition.owner_id.clone(),
+                            created_at: transition.created_at,
+                            updated_at: transition.updated_at,
+                            data: transition
+                                .data
+                                .clone()
+                                .unwrap_or_else(|| JsonValue::Object(Default::default())),
+                            data_contract: contract.clone(),
+                            metadata: None,
+                            entropy: transition.entropy,
+                        };
+
+                        Some(document)
+                    }
+                    DocumentTransition::Replace(transition) => {
+                        let mut document =
+                            (*fetched_documents_by_id
+                                .get(&base.id)
+                                .ok_or(Error::Execution(ExecutionError::DriveMissingData(
+                                    "document of a validated replace transition must exist",
+                                )))?)
+                            .clone();
+
+                        document.revision = transition.revision;
+                        document.updated_at = transition.updated_at;
+                        document.data = transition
+                            .data
+                            .clone()
+                            .unwrap_or_else(|| JsonValue::Object(Default::default()));
+                        // the contract of fetched documents isn't kept by the state repository
+                        document.data_contract = contract.clone();
+
+                        Some(document)
+                    }
+                    DocumentTransition::Delete(_) => None,
+                };
+
+                let document = document
+                    .map(|document| {
+                        let drive_document = DriveDocument::try_from(&document)?;
+
+                        let document_type = contract
+                            .document_type_for_name(&base.document_type)
+                            .map_err(drive::error::Error::from)?;
+
+                        let serialized_document = drive_document.serialize(document_type)?;
+
+                        Ok::<_, drive::error::Error>((drive_document, serialized_document))
+                    })
+                    .transpose()?;
+
+                Ok(DocumentForDrive {
+                    action: base.action,
+                    document_id: base.id.to_buffer(),
+                    document,
+                    contract_fetch_info,
+                    document_type_name: base.document_type.clone(),
+                    owner_id,
+                    storage_flags: StorageFlags::new_single_epoch(
+                        block_info.epoch.index,
+                        Some(owner_id),
+                    ),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Returns the id of the identity paying for a contract or documents state transition
+fn state_transition_owner_id(state_transition: &StateTransition) -> Result<[u8; 32], Error> {
+    let owner_id = match state_transition {
+        StateTransition::DataContractCreate(st) => st.get_owner_id(),
+        StateTransition::DataContractUpdate(st) => st.get_owner_id(),
+        StateTransition::DataContractDelete(st) => st.get_owner_id(),
+        StateTransition::DocumentsBatch(st) => st.get_owner_id(),
+        _ => {
+            return Err(Error::Execution(ExecutionError::CorruptedCodeExecution(
+                "only validated contract and document state transitions can be applied",
+            )))
+        }
+    };
+
+    Ok(owner_id.to_buffer())
+}
+
+/// Decodes a state transition and runs basic, signature, fee and state validation.
+/// Fees are calculated with the fee schedule and the fee multiplier of the given epoch,
+/// so they match the fees Drive charges for the epoch.
+/// If the state transition is valid it is set as the data of the validation result.
+async fn validate_state_transition(
+    state_repository: &Arc<DriveStateRepository<'_>>,
+    raw_state_transition: &[u8],
+    epoch: &Epoch,
+) -> Result<ValidationResult<StateTransition>, Error> {
+    let mut result = ValidationResult::<StateTransition>::new(None);
+
+    // The protocol version is stored in the first 4 bytes
+    if raw_state_transition.len() < 4 {
+        result.add_error(ConsensusError::ProtocolVersionParsingError {
+            parsing_error: anyhow!("state transition is too short to contain a protocol version"),
+        });
+        return Ok(result);
+    }
+
+    let raw_state_transition =
+        match DecodeProtocolEntity::decode_protocol_entity(raw_state_transition) {
+            Ok((_, raw_state_transition)) => raw_state_transition,
+            Err(ProtocolError::AbstractConsensusError(error)) => {
+                result.add_error(*error);
+                return Ok(result);
+            }
+            Err(error) => return Err(error.into()),
+        };
+
+    // Basic validation
+
+    let raw_transition_type = match raw_state_transition.get("type").and_then(JsonValue::as_u64) {
+        Some(raw_transition_type) => raw_transition_type as u8,
+        None => {
+            result.add_error(BasicError::MissingStateTransitionTypeError);
+            return Ok(result);
+        }
+    };
+
+    let state_transition_type = match StateTransitionType::try_from(raw_transition_type) {
+        Ok(state_transition_type) => state_transition_type,
+        Err(_) => {
+            result.add_error(BasicError::InvalidStateTransitionTypeError {
+                transition_type: raw_transition_type,
+            });
+            return Ok(result);
+        }
+    };
+
+    let protocol_version_validator = Arc::new(ProtocolVersionValidator::default());
+    let execution_context = StateTransitionExecutionContext::default();
+
+    let basic_validation_result = match state_transition_type {
+        StateTransitionType::DataContractCreate => {
+            DataContractCreateTransitionBasicValidator::new(protocol_version_validator)?
+                .validate(&raw_state_transition, &execution_context)?
+        }
+        StateTransitionType::DataContractUpdate => {
+            DataContractUpdateTransitionBasicValidator::new(
+                state_repository.clone(),
+                protocol_version_validator,
+            )
+            .map_err(|_| {
+                Error::Execution(ExecutionError::CorruptedCodeExecution(
+                    "unable to create data contract update transition basic validator",
+                ))
+            })?
+            .validate(&raw_state_transition, &execution_context)
+            .await?
+        }
+        StateTransitionType::DataContractDelete => {
+            DataContractDeleteTransitionBasicValidator::new(protocol_version_validator)?
+                .validate(&raw_state_transition, &execution_context)?
+        }
+        StateTransitionType::DocumentsBatch => {
+            validate_documents_batch_transition_basic(
+                protocol_version_validator.as_ref(),
+                &raw_state_transition,
+                state_repository.as_ref(),
+                &execution_context,
+            )
+            .await?
+        }
+        // Identity state transitions require Core and are still processed by JS Drive
+        _ => {
+            return Err(Error::Execution(ExecutionError::NotSupported(
+                "identity state transitions are not supported yet",
+            )))
+        }
+    };
+
+    result.merge(basic_validation_result);
+    if !result.is_valid() {
+        return Ok(result);
+    }
+
+    let mut state_transition =
+        create_state_transition(state_repository.as_ref(), raw_state_transition).await?;
+
+    let state_transition_execution_context = state_transition.get_execution_context();
+    state_transition_execution_context.set_fee_schedule(*epoch.fee_schedule()?);
+    state_transition_execution_context.set_fee_multiplier(epoch.fee_multiplier);
+    state_transition_execution_context.add_operations(execution_context.get_operations());
+
+    // Signature validation
+
+    let bls = NativeBlsModule::default();
+
+    let signature_validation_result = match &mut state_transition {
+        StateTransition::DataContractCreate(st) => {
+            validate_state_transition_identity_signature(state_repository.as_ref(), st, &bls)
+                .await?
+        }
+        StateTransition::DataContractUpdate(st) => {
+            validate_state_transition_identity_signature(state_repository.as_ref(), st, &bls)
+                .await?
+        }
+        StateTransition::DataContractDelete(st) => {
+            validate_state_transition_identity_signature(state_repository.as_ref(), st, &bls)
+                .await?
+        }
+        StateTransition::DocumentsBatch(st) => {
+            validate_state_transition_identity_signature(state_repository.as_ref(), st, &bls)
+                .await?
+        }
+        _ => {
+            return Err(Error::Execution(ExecutionError::CorruptedCodeExecution(
+                "state transition type must be checked during basic validation",
+            )))
+        }
+    };
+
+    result.merge(signature_validation_result);
+    if !result.is_valid() {
+        return Ok(result);
+    }
+
+    // Fee validation
+
+    let fee_validation_result = StateTransitionFeeValidator::new(state_repository.clone())
+        .validate(&state_transition)
+        .await?;
+
+    result.merge(fee_validation_result);
+    if !result.is_valid() {
+        return Ok(result);
+    }
+
+    // State validation
+
+    let state_validation_result = match &state_transition {
+        StateTransition::DataContractCreate(st) => {
+            validate_data_contract_create_transition_state(state_repository.as_ref(), st).await?
+        }
+        StateTransition::DataContractUpdate(st) => {
+            validate_data_contract_update_transition_state(state_repository.as_ref(), st).await?
+        }
+        StateTransition::DataContractDelete(st) => {
+            validate_data_contract_delete_transition_state(state_repository.as_ref(), st).await?
+        }
+        StateTransition::DocumentsBatch(st) => {
+            validate_document_batch_transition_state(state_repository.as_ref(), st).await?
+        }
+        _ => {
+            return Err(Error::Execution(ExecutionError::CorruptedCodeExecution(
+                "state transition type must be checked during basic validation",
+            )))
+        }
+    };
+
+    result.merge(state_validation_result);
+    if result.is_valid() {
+        result.set_data(state_transition);
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use drive::common::value_to_cbor;
+    use drive::contract::document::Document as DriveDocument;
+    use drive::dpp::data_contract::extra::DriveContractExt;
+    use drive::dpp::data_contract::state_transition::DataContractDeleteTransition;
+    use drive::dpp::data_contract::DataContract;
+    use drive::dpp::document::document_factory::DocumentFactory;
+    use drive::dpp::document::document_transition::Action;
+    use drive::dpp::document::document_validator::DocumentValidator;
+    use drive::dpp::document::Document;
+    use drive::dpp::mocks;
+    use drive::dpp::prelude::Identifier;
+    use drive::dpp::state_transition::{StateTransitionConvert, StateTransitionIdentitySigned};
+    use drive::dpp::version::ProtocolVersionValidator;
+    use drive::dpp::NativeBlsModule;
+    use drive::drive::block_info::BlockInfo;
+    use drive::drive::flags::StorageFlags;
+    use drive::fee::result::FeeResult;
+    use serde_json::json;
+
+    use crate::common::helpers::setup::setup_platform_with_initial_state_structure;
+    use crate::common::helpers::state_transitions::{
+        create_contract_transition, insert_identity, IDENTITY_PRIVATE_KEY,
+    };
+    use crate::error::execution::ExecutionError;
+    use crate::error::Error;
+    use crate::platform::Platform;
+
+    const INITIAL_BALANCE: u64 = 100_000_000_000;
+
+    fn fetch_balance(platform: &Platform, identity_id: &Identifier) -> u64 {
+        platform
+            .drive
+            .fetch_identity_balance(identity_id.to_buffer(), None)
+            .expect("expected to fetch the balance")
+    }
+
+    #[test]
+    fn test_execute_data_contract_create_transition() {
+        let platform = setup_platform_with_initial_state_structure();
+
+        let (owner_id, identity_key) = insert_identity(&platform, INITIAL_BALANCE);
+
+        let (data_contract, raw_state_transition) =
+            create_contract_transition(&owner_id, &identity_key, None);
+
+        let result = platform
+            .execute_state_transition(&raw_state_transition, &BlockInfo::default(), true, None)
+            .expect("expected to execute state transition");
+
+        assert_eq!(result.code(), 0);
+        assert!(result.fee_result.storage_fee > 0);
+        assert!(result.fee_result.processing_fee > 0);
+
+        let (_, contract_fetch_info) = platform
+            .drive
+            .get_contract_with_fetch_info(data_contract.id.to_buffer(), None, None)
+            .expect("expected to fetch the contract");
+
+        assert!(contract_fetch_info.is_some());
+
+        assert_eq!(
+            fetch_balance(&platform, &owner_id),
+            INITIAL_BALANCE - result.fee_result.storage_fee - result.fee_result.processing_fee
+        );
+    }
+
+    #[test]
+    fn test_execute_data_contract_delete_transition_for_contract_which_can_not_be_deleted() {
+        let platform = setup_platform_with_initial_state_structure();
+
+        let (owner_id, identity_key) = insert_identity(&platform, INITIAL_BALANCE);
+
+        let (data_contract, raw_state_transition) =
+            create_contract_transition(&owner_id, &identity_key, None);
+
+        let result = platform
+            .execute_state_transition(&raw_state_transition, &BlockInfo::default(), true, None)
+            .expect("expected to execute state transition");
+
+        assert_eq!(result.code(), 0);
+
+        let balance_after_contract = fetch_balance(&platform, &owner_id);
+
+        let mut state_transition = DataContractDeleteTransition {
+            protocol_version: 1,
+            data_contract_id: data_contract.id.clone(),
+            owner_id: owner_id.clone(),
+            ..Default::default()
+        };
+
+        state_transition.set_signature_public_key_id(identity_key.id);
+        state_transition
+            .sign(
+                &identity_key,
+                &hex::decode(IDENTITY_PRIVATE_KEY).unwrap(),
+                &NativeBlsModule::default(),
+            )
+            .expect("expected to sign the transition");
+
+        let raw_state_transition = state_transition
+            .to_buffer(false)
+            .expect("expected to serialize the transition");
+
+        let result = platform
+            .execute_state_transition(&raw_state_transition, &BlockInfo::default(), true, None)
+            .expect("expected to execute state transition");
+
+        // Contracts are created without canBeDeleted
+        assert_eq!(result.code(), 4028);
+
+        let (_, contract_fetch_info) = platform
+            .drive
+            .get_contract_with_fetch_info(data_contract.id.to_buffer(), None, None)
+            .expect("expected to fetch the contract");
+
+        assert!(contract_fetch_info.is_some());
+
+        assert_eq!(fetch_balance(&platform, &owner_id), balance_after_contract);
+    }
+
+    #[test]
+    fn test_execute_data_contract_delete_transition_refunds_the_owner() {
+        let platform = setup_platform_with_initial_state_structure();
+
+        let (owner_id, identity_key) = insert_identity(&platform, INITIAL_BALANCE);
+
+        let (data_contract, _) = create_contract_transition(&owner_id, &identity_key, None);
+
+        // The contract meta schema doesn't allow canBeDeleted, so the contract is stored
+        // directly with the owner paying for its storage
+        let mut contract_json = data_contract
+            .to_json()
+            .expect("expected to convert the contract to json");
+        contract_json
+            .as_object_mut()
+            .expect("expected the contract to be an object")
+            .insert("canBeDeleted".to_string(), json!(true));
+
+        let contract_cbor = value_to_cbor(contract_json, Some(1));
+        let data_contract =
+            DataContract::from_cbor(&contract_cbor).expect("expected to decode the contract");
+
+        assert!(data_contract.can_be_deleted());
+
+        platform
+            .drive
+            .apply_contract(
+                &data_contract,
+                contract_cbor,
+                BlockInfo::default(),
+                true,
+                Some(&StorageFlags::SingleEpochOwned(0, owner_id.to_buffer())),
+                None,
+            )
+            .expect("expected to apply the contract");
+
+        let mut state_transition = DataContractDeleteTransition {
+            protocol_version: 1,
+            data_contract_id: data_contract.id.clone(),
+            owner_id: owner_id.clone(),
+            ..Default::default()
+        };
+
+        state_transition.set_signature_public_key_id(identity_key.id);
+        state_transition
+            .sign(
+                &identity_key,
+                &hex::decode(IDENTITY_PRIVATE_KEY).unwrap(),
+                &NativeBlsModule::default(),
+            )
+            .expect("expected to sign the transition");
+
+        let raw_state_transition = state_transition
+            .to_buffer(false)
+            .expect("expected to serialize the transition");
+
+        let result = platform
+            .execute_state_transition(&raw_state_transition, &BlockInfo::default(), true, None)
+            .expect("expected to execute state transition");
+
+        assert_eq!(result.code(), 0);
+
+        let refunded = result
+            .fee_result
+            .fee_refunds
+            .total_for_identifier(&owner_id.to_buffer())
+            .expect("expected to sum the refunds");
+
+        assert!(refunded > 0);
+
+        let (_, contract_fetch_info) = platform
+            .drive
+            .get_contract_with_fetch_info(data_contract.id.to_buffer(), None, None)
+            .expect("expected to fetch the contract");
+
+        assert!(contract_fetch_info.is_none());
+
+        assert_eq!(
+            fetch_balance(&platform, &owner_id),
+            INITIAL_BALANCE + refunded
+                - result.fee_result.storage_fee
+                - result.fee_result.processing_fee
+        );
+    }
+
+    #[test]
+    fn test_execute_documents_batch_transition() {
+        let platform = setup_platform_with_initial_state_structure();
+
+        let (owner_id, identity_key) = insert_identity(&platform, INITIAL_BALANCE);
+
+        let (data_contract, raw_state_transition) =
+            create_contract_transition(&owner_id, &identity_key, None);
+
+        let result = platform
+            .execute_state_transition(&raw_state_transition, &BlockInfo::default(), true, None)
+            .expect("expected to execute state transition");
+
+        assert_eq!(result.code(), 0);
+
+        let balance_after_contract = fetch_balance(&platform, &owner_id);
+
+        // Document types are only initialized when the contract is decoded
+        let data_contract = DataContract::from_cbor(
+            data_contract
+                .to_cbor()
+                .expect("expected to encode the contract"),
+        )
+        .expect("expected to decode the contract");
+
+        let document_factory = DocumentFactory::new(
+            1,
+            DocumentValidator::new(Arc::new(ProtocolVersionValidator::default())),
+            mocks::FetchAndValidateDataContract {},
+        );
+
+        let document = document_factory
+            .create(
+                data_contract.clone(),
+                owner_id.clone(),
+                "niceDocument".to_string(),
+                json!({ "name": "Ivan" }),
+            )
+            .expect("expected to create a document");
+
+        let mut state_transition = document_factory
+            .create_state_transition([(Action::Create, vec![document.clone()])])
+            .expect("expected to create a transition");
+
+        state_transition.set_signature_public_key_id(identity_key.id);
+        state_transition
+            .sign(
+                &identity_key,
+                &hex::decode(IDENTITY_PRIVATE_KEY).unwrap(),
+                &NativeBlsModule::default(),
+            )
+            .expect("expected to sign the transition");
+
+        let raw_state_transition = state_transition
+            .to_buffer(false)
+            .expect("expected to serialize the transition");
+
+        let result = platform
+            .execute_state_transition(&raw_state_transition, &BlockInfo::default(), true, None)
+            .expect("expected to execute state transition");
+
+        assert_eq!(result.code(), 0);
+        assert!(result.fee_result.storage_fee > 0);
+
+        // an empty CBOR map queries all documents
+        let (documents, _, _) = platform
+            .drive
+            .query_documents(
+                &[0xa0],
+                data_contract.id.to_buffer(),
+                "niceDocument",
+                None,
+                None,
+            )
+            .expect("expected to query documents");
+
+        assert_eq!(documents.len(), 1);
+
+        assert_eq!(
+            fetch_balance(&platform, &owner_id),
+            balance_after_contract
+                - result.fee_result.storage_fee
+                - result.fee_result.processing_fee
+        );
+
+        let mut replaced_document = document;
+        replaced_document.data = json!({ "name": "Ivan Ivanovich" });
+
+        let mut state_transition = document_factory
+            .create_state_transition([(Action::Replace, vec![replaced_document])])
+            .expect("expected to create a transition");
+
+        state_transition.set_signature_public_key_id(identity_key.id);
+        state_transition
+            .sign(
+                &identity_key,
+                &hex::decode(IDENTITY_PRIVATE_KEY).unwrap(),
+                &NativeBlsModule::default(),
+            )
+            .expect("expected to sign the transition");
+
+        let raw_state_transition = state_transition
+            .to_buffer(false)
+            .expect("expected to serialize the transition");
+
+        let result = platform
+            .execute_state_transition(&raw_state_transition, &BlockInfo::default(), true, None)
+            .expect("expected to execute state transition");
+
+        assert_eq!(result.code(), 0);
+
+        let (documents, _, _) = platform
+            .drive
+            .query_documents(
+                &[0xa0],
+                data_contract.id.to_buffer(),
+                "niceDocument",
+                None,
+                None,
+            )
+            .expect("expected to query documents");
+
+        let document_type = data_contract
+            .document_type_for_name("niceDocument")
+            .expect("expected to get the document type");
+
+        let stored_document = DriveDocument::from_stored_bytes(&documents[0], document_type)
+            .expect("expected to decode the stored document");
+
+        let stored_document =
+            Document::try_from((&stored_document, &data_contract, "niceDocument"))
+                .expect("expected to convert the stored document");
+
+        assert_eq!(stored_document.revision, 2);
+        assert_eq!(stored_document.data, json!({ "name": "Ivan Ivanovich" }));
+        assert_eq!(stored_document.data_contract, data_contract);
+    }
+
+    #[test]
+    fn test_execute_state_transition_removes_user_tip_from_balance() {
+        let platform = setup_platform_with_initial_state_structure();
+
+        let (owner_id, identity_key) = insert_identity(&platform, INITIAL_BALANCE);
+
+        let (_, raw_state_transition) =
+            create_contract_transition(&owner_id, &identity_key, Some(1000));
+
+        let result = platform
+            .execute_state_transition(&raw_state_transition, &BlockInfo::default(), true, None)
+            .expect("expected to execute state transition");
+
+        assert_eq!(result.code(), 0);
+        assert_eq!(result.user_tip, 1000);
+
+        assert_eq!(
+            fetch_balance(&platform, &owner_id),
+            INITIAL_BALANCE
+                - result.fee_result.storage_fee
+                - result.fee_result.processing_fee
+                - 1000
+        );
+    }
+
+    #[test]
+    fn test_check_state_transition_does_not_change_balance() {
+        let platform = setup_platform_with_initial_state_structure();
+
+        let (owner_id, identity_key) = insert_identity(&platform, INITIAL_BALANCE);
+
+        let (_, raw_state_transition) = create_contract_transition(&owner_id, &identity_key, None);
+
+        let result = platform
+            .execute_state_transition(&raw_state_transition, &BlockInfo::default(), false, None)
+            .expect("expected to execute state transition");
+
+        assert_eq!(result.code(), 0);
+        assert!(result.fee_result.storage_fee > 0);
+
+        assert_eq!(fetch_balance(&platform, &owner_id), INITIAL_BALANCE);
+    }
+
+    #[test]
+    fn test_execute_state_transition_with_balance_not_enough_for_the_user_tip() {
+        let platform = setup_platform_with_initial_state_structure();
+
+        let (owner_id, identity_key) = insert_identity(&platform, INITIAL_BALANCE);
+
+        let (data_contract, raw_state_transition) =
+            create_contract_transition(&owner_id, &identity_key, Some(INITIAL_BALANCE));
+
+        for apply in [false, true] {
+            let result = platform
+                .execute_state_transition(&raw_state_transition, &BlockInfo::default(), apply, None)
+                .expect("expected to execute state transition");
+
+            assert_eq!(result.code(), 3000);
+            assert_eq!(result.fee_result, FeeResult::default());
+            assert_eq!(result.user_tip, 0);
+        }
+
+        let (_, contract_fetch_info) = platform
+            .drive
+            .get_contract_with_fetch_info(data_contract.id.to_buffer(), None, None)
+            .expect("expected to fetch the contract");
+
+        assert!(contract_fetch_info.is_none());
+
+        assert_eq!(fetch_balance(&platform, &owner_id), INITIAL_BALANCE);
+    }
+
+    #[test]
+    fn test_execute_identity_state_transition_is_not_supported() {
+        let platform = setup_platform_with_initial_state_structure();
+
+        // protocol version followed by a CBOR map with the identity create type
+        let raw_state_transition = [1, 0, 0, 0, 0xa1, 0x64, b't', b'y', b'p', b'e', 0x02];
+
+        let result = platform.execute_state_transition(
+            &raw_state_transition,
+            &BlockInfo::default(),
+            true,
+            None,
+        );
+
+        assert!(matches!(
+            result,
+            Err(Error::Execution(ExecutionError::NotSupported(_)))
+        ));
+    }
+
+    #[test]
+    fn test_execute_state_transition_too_short_for_protocol_version() {
+        let platform = setup_platform_with_initial_state_structure();
+
+        let result = platform
+            .execute_state_transition(&[1, 0], &BlockInfo::default(), true, None)
+            .expect("expected to execute state transition");
+
+        assert_eq!(result.code(), 1000);
+        assert_eq!(result.fee_result.processing_fee, 0);
+    }
+
+    #[test]
+    fn test_execute_state_transition_with_invalid_cbor() {
+        let platform = setup_platform_with_initial_state_structure();
+
+        let result = platform
+            .execute_state_transition(&[1, 0, 0, 0, 0xff, 0xff], &BlockInfo::default(), true, None)
+            .expect("expected to execute state transition");
+
+        assert_eq!(result.code(), 1001);
+    }
+
+    #[test]
+    fn test_execute_state_transition_without_type() {
+        let platform = setup_platform_with_initial_state_structure();
+
+        // protocol version followed by an empty CBOR map
+        let result = platform
+            .execute_state_transition(&[1, 0, 0, 0, 0xa0], &BlockInfo::default(), true, None)
+            .expect("expected to execute state transition");
+
+        assert_eq!(result.code(), 1044);
+    }
+}
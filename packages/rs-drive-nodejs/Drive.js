@@ -495,6 +495,9 @@ Drive.calculateStorageFeeDistributionAmountAndLeftovers = calculateStorageFeeDis
  * @property {number} [previousBlockTimeMs] - timestamp in milliseconds
  * @property {Buffer} proposerProTxHash
  * @property {Buffer} validatorSetQuorumHash
+ * @property {number} protocolVersion - selects the fee schedule of an epoch started by the block
+ * @property {number} [feeMultiplier] - fee multiplier of a new epoch chosen by governance
+ * @property {Object} [feeSchedule] - fee schedule of a new epoch activated by governance
  */
 
 /**
@@ -527,6 +530,7 @@ Drive.calculateStorageFeeDistributionAmountAndLeftovers = calculateStorageFeeDis
  * @typedef BlockEndResponse
  * @property {number} [proposersPaidCount]
  * @property {number} [paidEpochIndex]
+ * @property {number} feeMultiplier - multiplier applied to processing fees of the block
  */
 
 /**
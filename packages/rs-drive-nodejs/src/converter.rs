@@ -410,10 +410,12 @@ pub fn js_object_to_block_info<'a, C: Context<'a>>(
 
     let epoch = Epoch::new(js_epoch.value(cx) as u16);
 
+    // JS doesn't pass the protocol version of the block, the one of Drive is used
     let block_info = BlockInfo {
         height: js_height.value(cx) as u64,
         time_ms: js_time.value(cx) as u64,
         epoch,
+        ..Default::default()
     };
 
     Ok(block_info)
@@ -145,15 +145,18 @@ impl PlatformWrapper {
                     }
                     PlatformWrapperMessage::CommitTransaction(callback) => {
                         let result = if maybe_transaction.is_some() {
-                            let mut drive_cache = platform.drive.cache.borrow_mut();
-
-                            drive_cache.cached_contracts.merge_block_cache();
-
-                            drive_cache.cached_contracts.clear_block_cache();
-
                             platform
                                 .drive
-                                .commit_transaction(maybe_transaction.take().unwrap())
+                                .write_cache()
+                                .and_then(|mut drive_cache| {
+                                    drive_cache.cached_contracts.merge_block_cache();
+
+                                    drive_cache.cached_contracts.clear_block_cache();
+
+                                    platform
+                                        .drive
+                                        .commit_transaction(maybe_transaction.take().unwrap())
+                                })
                                 .map_err(|err| err.to_string())
                         } else {
                             Err("transaction is not started".to_string())
@@ -163,13 +166,14 @@ impl PlatformWrapper {
                     }
                     PlatformWrapperMessage::RollbackTransaction(callback) => {
                         let result = if let Some(transaction) = &maybe_transaction {
-                            let mut drive_cache = platform.drive.cache.borrow_mut();
-
-                            drive_cache.cached_contracts.clear_block_cache();
-
                             platform
                                 .drive
-                                .rollback_transaction(transaction)
+                                .write_cache()
+                                .and_then(|mut drive_cache| {
+                                    drive_cache.cached_contracts.clear_block_cache();
+
+                                    platform.drive.rollback_transaction(transaction)
+                                })
                                 .map_err(|err| err.to_string())
                         } else {
                             Err("transaction is not started".to_string())
@@ -179,13 +183,15 @@ impl PlatformWrapper {
                     }
                     PlatformWrapperMessage::AbortTransaction(callback) => {
                         let result = if maybe_transaction.is_some() {
-                            let mut drive_cache = platform.drive.cache.borrow_mut();
-
-                            drive_cache.cached_contracts.clear_block_cache();
-
-                            drop(maybe_transaction.take());
+                            platform
+                                .drive
+                                .write_cache()
+                                .map(|mut drive_cache| {
+                                    drive_cache.cached_contracts.clear_block_cache();
 
-                            Ok(())
+                                    drop(maybe_transaction.take());
+                                })
+                                .map_err(|err| err.to_string())
                         } else {
                             Err("transaction is not started".to_string())
                         };
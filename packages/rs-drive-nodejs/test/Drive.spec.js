@@ -513,6 +513,7 @@ describe('Drive', () => {
           blockTimeMs: (new Date()).getTime(),
           proposerProTxHash: Buffer.alloc(32, 1),
           validatorSetQuorumHash: Buffer.alloc(32, 2),
+          protocolVersion: 1,
         };
 
         const response = await drive.getAbci().blockBegin(request);
@@ -533,6 +534,7 @@ describe('Drive', () => {
           blockTimeMs,
           proposerProTxHash: Buffer.alloc(32, 1),
           validatorSetQuorumHash: Buffer.alloc(32, 2),
+          protocolVersion: 1,
         });
 
         const response = await drive.getAbci().blockBegin({
@@ -541,6 +543,7 @@ describe('Drive', () => {
           proposerProTxHash: Buffer.alloc(32, 1),
           previousBlockTimeMs: blockTimeMs,
           validatorSetQuorumHash: Buffer.alloc(32, 2),
+          protocolVersion: 1,
         });
 
         expect(response.unsignedWithdrawalTransactions).to.be.empty();
@@ -561,6 +564,7 @@ describe('Drive', () => {
           blockTimeMs: (new Date()).getTime(),
           proposerProTxHash: Buffer.alloc(32, 1),
           validatorSetQuorumHash: Buffer.alloc(32, 2),
+          protocolVersion: 1,
         });
       });
 
@@ -598,6 +602,7 @@ describe('Drive', () => {
           blockTimeMs: (new Date()).getTime(),
           proposerProTxHash: Buffer.alloc(32, 1),
           validatorSetQuorumHash: Buffer.alloc(32, 2),
+          protocolVersion: 1,
         });
 
         await drive.getAbci().blockEnd({
@@ -32,6 +32,7 @@
 //! This module defines encoding functions.
 //!
 
+use crate::error::drive::DriveError;
 use crate::error::Error;
 use byteorder::{BigEndian, WriteBytesExt};
 
@@ -61,6 +62,20 @@ pub fn encode_unsigned_integer(val: u64) -> Result<Vec<u8>, Error> {
     Ok(wtr)
 }
 
+/// Decodes an unsigned integer encoded with `encode_unsigned_integer`.
+pub fn decode_unsigned_integer(bytes: &[u8]) -> Result<u64, Error> {
+    let mut wtr: [u8; 8] = bytes.try_into().map_err(|_| {
+        Error::Drive(DriveError::CorruptedSerialization(
+            "encoded unsigned integer must be 8 bytes",
+        ))
+    })?;
+
+    // Flip the sign bit back
+    wtr[0] ^= 0b1000_0000;
+
+    Ok(u64::from_be_bytes(wtr))
+}
+
 /// Encodes a signed integer.
 pub fn encode_signed_integer(val: i64) -> Result<Vec<u8>, Error> {
     // Positive integers are represented in binary with the signed bit set to 0
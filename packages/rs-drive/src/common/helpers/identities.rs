@@ -45,7 +45,7 @@ pub fn create_test_identity(drive: &Drive, id: [u8; 32], transaction: Transactio
     let identity_key = IdentityPublicKey {
         id: 1,
         key_type: KeyType::ECDSA_SECP256K1,
-        data: vec![0, 1, 2, 3],
+        data: id.to_vec(),
         purpose: dpp::identity::Purpose::AUTHENTICATION,
         security_level: dpp::identity::SecurityLevel::MASTER,
         read_only: false,
@@ -111,6 +111,20 @@ pub fn json_document_to_cbor(path: impl AsRef<Path>, protocol_version: Option<u3
     value_to_cbor(json, protocol_version)
 }
 
+/// Serializes a JSON document file to CBOR with the given revision.
+pub fn json_document_with_revision_to_cbor(
+    path: impl AsRef<Path>,
+    revision: u64,
+    protocol_version: Option<u32>,
+) -> Vec<u8> {
+    let file = File::open(path).expect("file not found");
+    let reader = BufReader::new(file);
+    let mut json: serde_json::Value =
+        serde_json::from_reader(reader).expect("expected a valid json");
+    json["$revision"] = serde_json::Value::from(revision);
+    value_to_cbor(json, protocol_version)
+}
+
 /// Serializes a JSON value to CBOR.
 pub fn value_to_cbor(value: serde_json::Value, protocol_version: Option<u32>) -> Vec<u8> {
     let mut buffer: Vec<u8> = Vec::new();
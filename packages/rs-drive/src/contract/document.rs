@@ -34,19 +34,30 @@
 
 use std::collections::BTreeMap;
 use std::fmt;
-use std::io::{BufReader, Read};
+use std::io::{BufRead, BufReader, Read};
 
-use byteorder::{BigEndian, WriteBytesExt};
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
 use ciborium::value::Value;
 use dpp::data_contract::extra::DriveContractExt;
+use dpp::document::Document as DppDocument;
+use dpp::identifier::MEDIA_TYPE as IDENTIFIER_MEDIA_TYPE;
+use dpp::prelude::Identifier;
+use dpp::util::cbor_value::cbor_value_to_json_value;
+use integer_encoding::{VarInt, VarIntReader};
 use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
 
 use crate::common::{bytes_for_system_value_from_tree_map, get_key_from_cbor_map};
 use crate::contract::{reduced_value_string_representation, Contract};
 use crate::drive::defaults::PROTOCOL_VERSION;
 use crate::drive::Drive;
-use dpp::data_contract::extra::{ContractError, DocumentType};
+use dpp::data_contract::extra::{
+    cbor_to_drive_protobuf, drive_protobuf_to_cbor, ContractError, DocumentFieldType, DocumentType,
+    DriveEncoding, PROTOCOL_VERSION_PREFIX_LENGTH,
+};
+use dpp::prelude::Revision;
 
+use crate::error::document::DocumentError;
 use crate::error::drive::DriveError;
 use crate::error::structure::StructureError;
 use crate::error::Error;
@@ -67,14 +78,100 @@ pub struct Document {
     pub owner_id: [u8; 32],
 }
 
+/// The version of the binary serialization written by `Document::serialize`.
+/// Version 0 is the original layout, which starts right away with the document id.
+pub const DOCUMENT_SERIALIZATION_VERSION: u64 = 1;
+
+/// The marker starting every versioned binary serialization, followed by the version.
+/// Read as a protocol version prefix it is `u32::MAX` in either byte order, which is not
+/// a protocol version, so documents stored as CBOR never start with it. Serializations
+/// without the marker are version 0.
+pub const SERIALIZED_DOCUMENT_MARKER: [u8; 4] = [0xFF; 4];
+
+/// The system fields carried after the document type properties from version 1 on with
+/// their types, the bit of each field in the presence byte is its position in this list.
+/// System fields defined by the document type are serialized with its other properties.
+const SERIALIZED_SYSTEM_FIELDS: [(&str, DocumentFieldType); 3] = [
+    ("$revision", DocumentFieldType::Integer),
+    ("$createdAt", DocumentFieldType::Integer),
+    ("$updatedAt", DocumentFieldType::Integer),
+];
+
+/// Maps a failed read of a serialized document to an error.
+fn read_error<E>(_: E) -> Error {
+    Error::Drive(DriveError::CorruptedSerialization(
+        "error reading from serialized document",
+    ))
+}
+
+/// Returns a timestamp in milliseconds as an integer. Timestamps held as floats must be
+/// whole numbers, which f64 represents exactly.
+fn timestamp_as_integer(value: &Value) -> Option<i64> {
+    match value {
+        Value::Integer(timestamp) => i64::try_from(*timestamp).ok(),
+        Value::Float(timestamp)
+            if timestamp.fract() == 0.0
+                && *timestamp >= i64::MIN as f64
+                && *timestamp < i64::MAX as f64 =>
+        {
+            Some(*timestamp as i64)
+        }
+        _ => None,
+    }
+}
+
 impl Document {
+    /// Serializes the marker and version, followed by the id and the owner id.
+    fn serialize_header(&self, buffer: &mut Vec<u8>) {
+        buffer.extend(SERIALIZED_DOCUMENT_MARKER.as_slice());
+        buffer.extend(DOCUMENT_SERIALIZATION_VERSION.encode_var_vec());
+        buffer.extend(self.id.as_slice());
+        buffer.extend(self.owner_id.as_slice());
+    }
+
+    /// Serializes the system fields not defined by the document type, a byte flagging
+    /// the present fields followed by the value of each of them. Timestamps are
+    /// serialized as integers so they are read back as they were written.
+    fn serialize_system_fields(
+        &self,
+        document_type: &DocumentType,
+        buffer: &mut Vec<u8>,
+    ) -> Result<(), Error> {
+        let mut presence: u8 = 0;
+        let mut values: Vec<u8> = vec![];
+        for (i, (field_name, field_type)) in SERIALIZED_SYSTEM_FIELDS.iter().enumerate() {
+            if document_type.properties.contains_key(*field_name) {
+                continue;
+            }
+            if let Some(value) = self.properties.get(*field_name) {
+                presence |= 1 << i;
+                let value = if *field_name == "$revision" {
+                    value.clone()
+                } else {
+                    Value::Integer(
+                        timestamp_as_integer(value)
+                            .ok_or(Error::Document(DocumentError::InvalidDocumentPropertyType(
+                                "document timestamps must be whole numbers of milliseconds",
+                            )))?
+                            .into(),
+                    )
+                };
+                values.extend(field_type.encode_value_ref_with_size(&value, true)?);
+            }
+        }
+        buffer.push(presence);
+        buffer.append(&mut values);
+        Ok(())
+    }
+
     /// Serializes the document.
     ///
     /// The serialization of a document follows the pattern:
-    /// id 32 bytes + owner_id 32 bytes + encoded values byte arrays
+    /// marker 4 bytes + version varint + id 32 bytes + owner_id 32 bytes
+    /// + encoded values byte arrays + system fields
     pub fn serialize(&self, document_type: &DocumentType) -> Result<Vec<u8>, Error> {
-        let mut buffer: Vec<u8> = self.id.as_slice().to_vec();
-        buffer.extend(self.owner_id.as_slice());
+        let mut buffer: Vec<u8> = vec![];
+        self.serialize_header(&mut buffer);
         document_type
             .properties
             .iter()
@@ -95,17 +192,18 @@ impl Document {
                     Ok(())
                 }
             })?;
+        self.serialize_system_fields(document_type, &mut buffer)?;
         Ok(buffer)
     }
 
     /// Serializes and consumes the document.
     ///
     /// The serialization of a document follows the pattern:
-    /// id 32 bytes + owner_id 32 bytes + encoded values byte arrays
+    /// marker 4 bytes + version varint + id 32 bytes + owner_id 32 bytes
+    /// + encoded values byte arrays + system fields
     pub fn serialize_consume(mut self, document_type: &DocumentType) -> Result<Vec<u8>, Error> {
-        let mut buffer: Vec<u8> = Vec::try_from(self.id).unwrap();
-        let mut owner_id = Vec::try_from(self.owner_id).unwrap();
-        buffer.append(&mut owner_id);
+        let mut buffer: Vec<u8> = vec![];
+        self.serialize_header(&mut buffer);
         document_type
             .properties
             .iter()
@@ -126,35 +224,44 @@ impl Document {
                     Ok(())
                 }
             })?;
+        // The system fields defined by the document type were removed above
+        self.serialize_system_fields(document_type, &mut buffer)?;
         Ok(buffer)
     }
 
     /// Reads a serialized document and creates a Document from it.
+    /// Every version of the binary serialization can be read, the version is read first
+    /// and selects the layout of the rest of the serialization.
     pub fn from_bytes(
         serialized_document: &[u8],
         document_type: &DocumentType,
     ) -> Result<Self, Error> {
         let mut buf = BufReader::new(serialized_document);
-        if serialized_document.len() < 64 {
+
+        let version: u64 = if serialized_document.starts_with(&SERIALIZED_DOCUMENT_MARKER) {
+            let mut marker = [0; SERIALIZED_DOCUMENT_MARKER.len()];
+            buf.read_exact(&mut marker).map_err(read_error)?;
+            buf.read_varint().map_err(read_error)?
+        } else {
+            0
+        };
+        if version > DOCUMENT_SERIALIZATION_VERSION {
             return Err(Error::Drive(DriveError::CorruptedSerialization(
-                "serialized document is too small, must have id and owner id",
+                "unknown document serialization version",
             )));
         }
-        let mut id = [0; 32];
-        buf.read_exact(&mut id).map_err(|_| {
-            Error::Drive(DriveError::CorruptedSerialization(
-                "error reading from serialized document",
-            ))
-        })?;
 
+        let mut id = [0; 32];
         let mut owner_id = [0; 32];
-        buf.read_exact(&mut owner_id).map_err(|_| {
-            Error::Drive(DriveError::CorruptedSerialization(
-                "error reading from serialized document",
-            ))
-        })?;
+        buf.read_exact(&mut id)
+            .and_then(|_| buf.read_exact(&mut owner_id))
+            .map_err(|_| {
+                Error::Drive(DriveError::CorruptedSerialization(
+                    "serialized document is too small, must have id and owner id",
+                ))
+            })?;
 
-        let properties = document_type
+        let mut properties = document_type
             .properties
             .iter()
             .filter_map(|(key, field)| {
@@ -165,6 +272,35 @@ impl Document {
                 }
             })
             .collect::<Result<BTreeMap<String, Value>, ContractError>>()?;
+
+        // document types defining the timestamps serialize them as dates, which are floats,
+        // timestamps are milliseconds so they are read back as integers
+        for field_name in ["$createdAt", "$updatedAt"] {
+            if let Some(value) = properties.get_mut(field_name) {
+                if let (Value::Float(_), Some(timestamp)) = (&value, timestamp_as_integer(value)) {
+                    *value = Value::Integer(timestamp.into());
+                }
+            }
+        }
+
+        // version 0 ends after the document type properties
+        if version >= 1 {
+            let presence = buf.read_u8().map_err(read_error)?;
+            for (i, (field_name, field_type)) in SERIALIZED_SYSTEM_FIELDS.iter().enumerate() {
+                if presence & (1 << i) != 0 {
+                    if let Some(value) = field_type.read_from(&mut buf, true)? {
+                        properties.insert(field_name.to_string(), value);
+                    }
+                }
+            }
+        }
+
+        if !buf.fill_buf().map_err(read_error)?.is_empty() {
+            return Err(Error::Drive(DriveError::CorruptedSerialization(
+                "serialized document has trailing bytes",
+            )));
+        }
+
         Ok(Document {
             id,
             properties,
@@ -172,6 +308,37 @@ impl Document {
         })
     }
 
+    /// Whether a document stored by Drive is a binary serialization rather than CBOR.
+    /// Binary serializations are recognized by their marker. Documents without it are
+    /// stored in CBOR, as a protocol version followed by a CBOR map, except for version 0
+    /// binary serializations written before the marker existed, which are not CBOR maps.
+    pub fn is_binary_serialization(stored_document: &[u8]) -> bool {
+        if stored_document.starts_with(&SERIALIZED_DOCUMENT_MARKER) {
+            return true;
+        }
+
+        if stored_document.len() <= PROTOCOL_VERSION_PREFIX_LENGTH {
+            return true;
+        }
+
+        let mut cbor = &stored_document[PROTOCOL_VERSION_PREFIX_LENGTH..];
+        ciborium::de::from_reader::<BTreeMap<String, Value>, _>(&mut cbor).is_err()
+            || !cbor.is_empty()
+    }
+
+    /// Reads a document stored by Drive, which is either CBOR or the binary serialization
+    /// of its document type.
+    pub fn from_stored_bytes(
+        stored_document: &[u8],
+        document_type: &DocumentType,
+    ) -> Result<Self, Error> {
+        if Self::is_binary_serialization(stored_document) {
+            Self::from_bytes(stored_document, document_type)
+        } else {
+            Self::from_cbor(stored_document, None, None)
+        }
+    }
+
     /// Reads a CBOR-serialized document and creates a Document from it.
     /// If Document and Owner IDs are provided, they are used, otherwise they are created.
     pub fn from_cbor(
@@ -243,6 +410,24 @@ impl Document {
         })
     }
 
+    /// Reads a document serialized in the given encoding and creates a Document from it.
+    /// If Document and Owner IDs are provided, they are used, otherwise they are created.
+    pub fn from_encoded(
+        serialized_document: &[u8],
+        document_id: Option<[u8; 32]>,
+        owner_id: Option<[u8; 32]>,
+        encoding: DriveEncoding,
+    ) -> Result<Self, Error> {
+        match encoding {
+            DriveEncoding::DriveCbor => Self::from_cbor(serialized_document, document_id, owner_id),
+            DriveEncoding::DriveProtobuf => Self::from_cbor(
+                &drive_protobuf_to_cbor(serialized_document, PROTOCOL_VERSION_PREFIX_LENGTH)?,
+                document_id,
+                owner_id,
+            ),
+        }
+    }
+
     /// Reads a CBOR-serialized document and creates a Document from it with the provided IDs.
     pub fn from_cbor_with_id(
         document_cbor: &[u8],
@@ -288,6 +473,21 @@ impl Document {
         })
     }
 
+    /// Returns the revision of the document, documents created without one have none.
+    pub fn revision(&self) -> Result<Option<Revision>, Error> {
+        self.properties
+            .get("$revision")
+            .map(|value| {
+                value
+                    .as_integer()
+                    .and_then(|revision| Revision::try_from(revision).ok())
+                    .ok_or(Error::Contract(ContractError::ValueWrongType(
+                        "expected $revision to be an unsigned integer",
+                    )))
+            })
+            .transpose()
+    }
+
     /// Serializes the Document to CBOR.
     pub fn to_cbor(&self) -> Vec<u8> {
         let mut buffer: Vec<u8> = Vec::new();
@@ -298,6 +498,17 @@ impl Document {
         buffer
     }
 
+    /// Serializes the Document in the given encoding.
+    pub fn to_encoded(&self, encoding: DriveEncoding) -> Result<Vec<u8>, Error> {
+        match encoding {
+            DriveEncoding::DriveCbor => Ok(self.to_cbor()),
+            DriveEncoding::DriveProtobuf => Ok(cbor_to_drive_protobuf(
+                &self.to_cbor(),
+                PROTOCOL_VERSION_PREFIX_LENGTH,
+            )?),
+        }
+    }
+
     /// Return a value given the path to its key for a document type.
     pub fn get_raw_for_document_type<'a>(
         &'a self,
@@ -382,6 +593,266 @@ impl Document {
     }
 }
 
+/// Converts a JSON value of a DPP document to CBOR. Values under the binary property paths
+/// of the document type are converted to bytes, from arrays of bytes or encoded strings.
+fn json_value_to_cbor_value(
+    value: &JsonValue,
+    path: &str,
+    binary_properties: &BTreeMap<String, JsonValue>,
+) -> Result<Value, Error> {
+    if let Some(schema) = binary_properties.get(path) {
+        return json_value_to_bytes(value, schema).map(Value::Bytes);
+    }
+
+    match value {
+        JsonValue::Null => Ok(Value::Null),
+        JsonValue::Bool(value) => Ok(Value::Bool(*value)),
+        JsonValue::Number(number) => {
+            if let Some(value) = number.as_u64() {
+                Ok(Value::Integer(value.into()))
+            } else if let Some(value) = number.as_i64() {
+                Ok(Value::Integer(value.into()))
+            } else {
+                number.as_f64().map(Value::Float).ok_or(Error::Document(
+                    DocumentError::InvalidDocumentPropertyType("unsupported number"),
+                ))
+            }
+        }
+        JsonValue::String(value) => Ok(Value::Text(value.clone())),
+        JsonValue::Array(items) => items
+            .iter()
+            .enumerate()
+            .map(|(index, item)| {
+                // Items of tuples are addressed by index, items of arrays of objects
+                // share the path of the array
+                let item_path = format!("{}[{}]", path, index);
+                let item_path = if binary_properties
+                    .keys()
+                    .any(|binary_path| binary_path.starts_with(&item_path))
+                {
+                    item_path
+                } else {
+                    path.to_string()
+                };
+                json_value_to_cbor_value(item, &item_path, binary_properties)
+            })
+            .collect::<Result<Vec<Value>, Error>>()
+            .map(Value::Array),
+        JsonValue::Object(map) => map
+            .iter()
+            .map(|(key, value)| {
+                let value_path = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", path, key)
+                };
+                Ok((
+                    Value::Text(key.clone()),
+                    json_value_to_cbor_value(value, &value_path, binary_properties)?,
+                ))
+            })
+            .collect::<Result<Vec<(Value, Value)>, Error>>()
+            .map(Value::Map),
+    }
+}
+
+/// Converts a binary property of a DPP document to bytes. Identifiers encoded as strings
+/// are base58, other binary data is base64.
+fn json_value_to_bytes(value: &JsonValue, schema: &JsonValue) -> Result<Vec<u8>, Error> {
+    match value {
+        JsonValue::Array(items) => items
+            .iter()
+            .map(|item| {
+                item.as_u64()
+                    .and_then(|byte| u8::try_from(byte).ok())
+                    .ok_or(Error::Document(DocumentError::InvalidDocumentPropertyType(
+                        "binary property must be an array of bytes",
+                    )))
+            })
+            .collect(),
+        JsonValue::String(encoded) => {
+            if schema.get("contentMediaType").and_then(JsonValue::as_str)
+                == Some(IDENTIFIER_MEDIA_TYPE)
+            {
+                bs58::decode(encoded).into_vec().map_err(|_| {
+                    Error::Document(DocumentError::InvalidDocumentPropertyType(
+                        "identifier property must be base58 encoded",
+                    ))
+                })
+            } else {
+                base64::decode(encoded).map_err(|_| {
+                    Error::Document(DocumentError::InvalidDocumentPropertyType(
+                        "binary property must be base64 encoded",
+                    ))
+                })
+            }
+        }
+        _ => Err(Error::Document(DocumentError::InvalidDocumentPropertyType(
+            "binary property must be an array of bytes or an encoded string",
+        ))),
+    }
+}
+
+impl TryFrom<&DppDocument> for Document {
+    type Error = Error;
+
+    /// Converts a DPP document to a Drive document. The binary properties are taken from the
+    /// data contract of the document and system fields other than the ids are kept as properties.
+    fn try_from(document: &DppDocument) -> Result<Self, Self::Error> {
+        let binary_properties = document
+            .data_contract
+            .get_binary_properties(&document.document_type)
+            .map_err(ContractError::from)?;
+
+        let mut properties = match json_value_to_cbor_value(&document.data, "", binary_properties)?
+        {
+            Value::Map(map) => map
+                .into_iter()
+                .filter_map(|(key, value)| match key {
+                    Value::Text(key) => Some((key, value)),
+                    _ => None,
+                })
+                .collect::<BTreeMap<String, Value>>(),
+            Value::Null => BTreeMap::new(),
+            _ => {
+                return Err(Error::Document(DocumentError::InvalidDocumentPropertyType(
+                    "document data must be an object",
+                )))
+            }
+        };
+
+        properties.insert(
+            String::from("$type"),
+            Value::Text(document.document_type.clone()),
+        );
+        properties.insert(
+            String::from("$dataContractId"),
+            Value::Bytes(document.data_contract_id.to_buffer().to_vec()),
+        );
+        properties.insert(
+            String::from("$revision"),
+            Value::Integer(document.revision.into()),
+        );
+        if let Some(created_at) = document.created_at {
+            properties.insert(
+                String::from("$createdAt"),
+                Value::Integer(created_at.into()),
+            );
+        }
+        if let Some(updated_at) = document.updated_at {
+            properties.insert(
+                String::from("$updatedAt"),
+                Value::Integer(updated_at.into()),
+            );
+        }
+
+        Ok(Document {
+            id: document.id.to_buffer(),
+            properties,
+            owner_id: document.owner_id.to_buffer(),
+        })
+    }
+}
+
+impl TryFrom<(&Document, &Contract, &str)> for DppDocument {
+    type Error = Error;
+
+    /// Converts a Drive document of the given contract and document type to a DPP document.
+    /// Binary serializations don't keep the document type and the contract id, so they are
+    /// taken from the given contract and document type, which the document must match.
+    /// Binary properties become arrays of bytes like in documents decoded by DPP.
+    fn try_from(
+        (document, contract, document_type_name): (&Document, &Contract, &str),
+    ) -> Result<Self, Self::Error> {
+        contract.document_type_for_name(document_type_name)?;
+
+        let mut properties = document.properties.clone();
+
+        match properties.remove("$type") {
+            Some(Value::Text(document_type)) if document_type != document_type_name => {
+                return Err(Error::Document(DocumentError::DocumentContractMismatch(
+                    "document has another document type",
+                )))
+            }
+            Some(Value::Text(_)) | None => {}
+            Some(_) => {
+                return Err(Error::Document(DocumentError::InvalidDocumentPropertyType(
+                    "document type must be a string",
+                )))
+            }
+        }
+
+        match properties.remove("$dataContractId") {
+            Some(Value::Bytes(data_contract_id)) => {
+                let data_contract_id: [u8; 32] = data_contract_id
+                    .try_into()
+                    .map_err(|_| Error::Document(DocumentError::InvalidContractIdSize()))?;
+
+                if data_contract_id != contract.id.to_buffer() {
+                    return Err(Error::Document(DocumentError::DocumentContractMismatch(
+                        "document belongs to another contract",
+                    )));
+                }
+            }
+            None => {}
+            Some(_) => {
+                return Err(Error::Document(DocumentError::InvalidDocumentPropertyType(
+                    "document contract id must be an array of bytes",
+                )))
+            }
+        }
+
+        let missing_revision_error =
+            Error::Document(DocumentError::MissingDocumentProperty("$revision"));
+        let revision = document.revision()?.ok_or(missing_revision_error)?;
+        properties.remove("$revision");
+
+        let mut timestamp = |field_name: &str| -> Result<Option<i64>, Error> {
+            properties
+                .remove(field_name)
+                .map(|value| {
+                    timestamp_as_integer(&value).ok_or(Error::Document(
+                        DocumentError::InvalidDocumentPropertyType(
+                            "document timestamps must be whole numbers of milliseconds",
+                        ),
+                    ))
+                })
+                .transpose()
+        };
+
+        let created_at = timestamp("$createdAt")?;
+        let updated_at = timestamp("$updatedAt")?;
+
+        let data = properties
+            .iter()
+            .map(|(key, value)| Ok((key.clone(), cbor_value_to_json_value(value)?)))
+            .collect::<Result<serde_json::Map<String, JsonValue>, anyhow::Error>>()
+            .map_err(|_| {
+                Error::Document(DocumentError::InvalidDocumentPropertyType(
+                    "unable to convert document properties to JSON",
+                ))
+            })?;
+
+        Ok(DppDocument {
+            protocol_version: PROTOCOL_VERSION,
+            id: Identifier::new(document.id),
+            document_type: document_type_name.to_string(),
+            revision: revision.try_into().map_err(|_| {
+                Error::Document(DocumentError::InvalidDocumentPropertyType(
+                    "document revision must fit into 32 bits",
+                ))
+            })?,
+            data_contract_id: contract.id.clone(),
+            owner_id: Identifier::new(document.owner_id),
+            created_at,
+            updated_at,
+            data: JsonValue::Object(data),
+            data_contract: contract.clone(),
+            ..Default::default()
+        })
+    }
+}
+
 impl fmt::Display for Document {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "id:{} ", bs58::encode(self.id).into_string())?;
@@ -436,6 +907,188 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_drive_protobuf_encoding_round_trip() {
+        let dashpay_cbor = json_document_to_cbor(
+            "tests/supporting_files/contract/dashpay/dashpay-contract.json",
+            Some(1),
+        );
+        let contract = <Contract as DriveContractExt>::from_cbor(&dashpay_cbor, None).unwrap();
+
+        let document_type = contract
+            .document_type_for_name("contactRequest")
+            .expect("expected to get contact request document type");
+        let document = document_type.random_document(Some(3333));
+
+        let document_protobuf = document
+            .to_encoded(DriveEncoding::DriveProtobuf)
+            .expect("expected to encode the document in protobuf");
+        assert_eq!(
+            drive_protobuf_to_cbor(&document_protobuf, PROTOCOL_VERSION_PREFIX_LENGTH)
+                .expect("expected to convert protobuf to cbor"),
+            document.to_cbor()
+        );
+
+        let deserialized_document =
+            Document::from_encoded(&document_protobuf, None, None, DriveEncoding::DriveProtobuf)
+                .expect("expected to decode the document from protobuf");
+        let cbor_deserialized_document = Document::from_cbor(&document.to_cbor(), None, None)
+            .expect("expected to decode the document from cbor");
+        assert_eq!(deserialized_document, cbor_deserialized_document);
+    }
+
+    #[test]
+    fn test_drive_serialization_with_system_fields() {
+        let dashpay_cbor = json_document_to_cbor(
+            "tests/supporting_files/contract/dashpay/dashpay-contract.json",
+            Some(1),
+        );
+        let contract = <Contract as DriveContractExt>::from_cbor(&dashpay_cbor, None).unwrap();
+
+        // contact requests define $createdAt in their document type, $updatedAt is
+        // serialized with the other system fields
+        let document_type = contract
+            .document_type_for_name("contactRequest")
+            .expect("expected to get contact request document type");
+        let mut document = document_type.random_document(Some(3333));
+        document
+            .properties
+            .insert("$revision".to_string(), Value::Integer(3.into()));
+        document.properties.insert(
+            "$createdAt".to_string(),
+            Value::Integer(1627081806116i64.into()),
+        );
+        document.properties.insert(
+            "$updatedAt".to_string(),
+            Value::Integer(1627081806117i64.into()),
+        );
+
+        let serialized_document = document
+            .serialize(document_type)
+            .expect("expected to serialize");
+        assert_eq!(
+            &serialized_document[..SERIALIZED_DOCUMENT_MARKER.len()],
+            SERIALIZED_DOCUMENT_MARKER.as_slice()
+        );
+
+        let deserialized_document = document_type
+            .document_from_bytes(serialized_document.as_slice())
+            .expect("expected to deserialize a document");
+        assert_eq!(document, deserialized_document);
+        assert_eq!(
+            deserialized_document.properties.get("$createdAt"),
+            Some(&Value::Integer(1627081806116i64.into()))
+        );
+        assert_eq!(
+            deserialized_document.properties.get("$updatedAt"),
+            Some(&Value::Integer(1627081806117i64.into()))
+        );
+
+        let serialized_consumed_document = document
+            .serialize_consume(document_type)
+            .expect("expected to serialize");
+        assert_eq!(serialized_document, serialized_consumed_document);
+    }
+
+    #[test]
+    fn test_drive_deserialization_of_version_0() {
+        let dashpay_cbor = json_document_to_cbor(
+            "tests/supporting_files/contract/dashpay/dashpay-contract.json",
+            Some(1),
+        );
+        let contract = <Contract as DriveContractExt>::from_cbor(&dashpay_cbor, None).unwrap();
+
+        let document_type = contract
+            .document_type_for_name("contactRequest")
+            .expect("expected to get contact request document type");
+        let document = document_type.random_document(Some(3333));
+
+        // version 0 is the serialization from before versioning, without the marker and
+        // with the system fields of the document type among the other properties
+        let mut serialized_document: Vec<u8> = document.id.to_vec();
+        serialized_document.extend(document.owner_id.as_slice());
+        for (field_name, field) in document_type.properties.iter() {
+            if let Some(value) = document.properties.get(field_name) {
+                serialized_document.extend(
+                    field
+                        .document_type
+                        .encode_value_ref_with_size(value, field.required)
+                        .expect("expected to encode value"),
+                );
+            } else {
+                serialized_document.push(0);
+            }
+        }
+
+        let deserialized_document = document_type
+            .document_from_bytes(serialized_document.as_slice())
+            .expect("expected to deserialize a document");
+        assert_eq!(document, deserialized_document);
+
+        // the current version starts with the marker and the version
+        let mut header = SERIALIZED_DOCUMENT_MARKER.to_vec();
+        header.extend(DOCUMENT_SERIALIZATION_VERSION.encode_var_vec());
+        let current_serialized_document = document
+            .serialize(document_type)
+            .expect("expected to serialize");
+        assert_eq!(
+            &current_serialized_document[..header.len()],
+            header.as_slice()
+        );
+
+        let mut unknown_version_serialized_document = SERIALIZED_DOCUMENT_MARKER.to_vec();
+        unknown_version_serialized_document
+            .extend((DOCUMENT_SERIALIZATION_VERSION + 1).encode_var_vec());
+        unknown_version_serialized_document.extend(serialized_document);
+        assert!(matches!(
+            document_type.document_from_bytes(unknown_version_serialized_document.as_slice()),
+            Err(Error::Drive(DriveError::CorruptedSerialization(_)))
+        ));
+    }
+
+    #[test]
+    fn test_stored_document_format_detection() {
+        let dashpay_cbor = json_document_to_cbor(
+            "tests/supporting_files/contract/dashpay/dashpay-contract.json",
+            Some(1),
+        );
+        let contract = <Contract as DriveContractExt>::from_cbor(&dashpay_cbor, None).unwrap();
+
+        let document_type = contract
+            .document_type_for_name("contactRequest")
+            .expect("expected to get contact request document type");
+        let mut document = document_type.random_document(Some(3333));
+        // an id starting with a CBOR map header
+        document.id = [0xA1; 32];
+
+        let serialized_document = document
+            .serialize(document_type)
+            .expect("expected to serialize");
+        assert!(Document::is_binary_serialization(&serialized_document));
+        assert_eq!(
+            Document::from_stored_bytes(&serialized_document, document_type)
+                .expect("expected to read the binary document"),
+            document
+        );
+
+        let document_cbor = document.to_cbor();
+        assert!(!Document::is_binary_serialization(&document_cbor));
+        assert_eq!(
+            Document::from_stored_bytes(&document_cbor, document_type)
+                .expect("expected to read the cbor document"),
+            Document::from_cbor(&document_cbor, None, None)
+                .expect("expected to decode the document from cbor")
+        );
+
+        // version 0 serializations have no marker and are not CBOR
+        let version_0_serialized_document = serialized_document
+            [SERIALIZED_DOCUMENT_MARKER.len() + 1..serialized_document.len() - 1]
+            .to_vec();
+        assert!(Document::is_binary_serialization(
+            &version_0_serialized_document
+        ));
+    }
+
     #[test]
     fn test_document_cbor_serialization() {
         let dashpay_cbor = json_document_to_cbor(
@@ -473,4 +1126,195 @@ mod tests {
         let document_string = format!("{}", document);
         assert_eq!(document_string.as_str(), "id:2vq574DjKi7ZD8kJ6dMHxT5wu6ZKD2bW5xKAyKAGW7qZ owner_id:ChTEGXJcpyknkADUC5s6tAzvPqVG7x6Lo1Nr5mFtj2mk $createdAt:1627081806.116 $updatedAt:1575820087.909 avatarUrl:1DbW18RuyblDX7hxB38O[...(106)] displayName:rzhRkzY2L213txD6gR2S[...(21)] publicMessage:ixPGeedfb4oeyipRFe8y[...(57)] ")
     }
+
+    /// Returns a contact request of the dashpay contract with system fields set
+    fn contact_request_document(contract: &Contract) -> Document {
+        let properties = BTreeMap::from([
+            (
+                "$type".to_string(),
+                Value::Text("contactRequest".to_string()),
+            ),
+            (
+                "$dataContractId".to_string(),
+                Value::Bytes(contract.id.to_buffer().to_vec()),
+            ),
+            ("$revision".to_string(), Value::Integer(1.into())),
+            ("$createdAt".to_string(), Value::Integer(1000.into())),
+            ("toUserId".to_string(), Value::Bytes(vec![1; 32])),
+            ("encryptedPublicKey".to_string(), Value::Bytes(vec![2; 96])),
+            ("senderKeyIndex".to_string(), Value::Integer(0.into())),
+            ("recipientKeyIndex".to_string(), Value::Integer(1.into())),
+            ("accountReference".to_string(), Value::Integer(2.into())),
+        ]);
+
+        Document {
+            id: [3; 32],
+            properties,
+            owner_id: [4; 32],
+        }
+    }
+
+    #[test]
+    fn test_dpp_document_conversion_round_trip() {
+        let dashpay_cbor = json_document_to_cbor(
+            "tests/supporting_files/contract/dashpay/dashpay-contract.json",
+            Some(1),
+        );
+        let contract = <Contract as DriveContractExt>::from_cbor(&dashpay_cbor, None).unwrap();
+
+        let document = contact_request_document(&contract);
+
+        let dpp_document = DppDocument::try_from((&document, &contract, "contactRequest"))
+            .expect("expected to convert to a DPP document");
+        assert_eq!(dpp_document.id.to_buffer(), document.id);
+        assert_eq!(dpp_document.owner_id.to_buffer(), document.owner_id);
+        assert_eq!(dpp_document.data_contract_id, contract.id);
+        assert_eq!(dpp_document.document_type, "contactRequest");
+        assert_eq!(dpp_document.revision, 1);
+        assert_eq!(dpp_document.created_at, Some(1000));
+        assert_eq!(dpp_document.updated_at, None);
+        assert_eq!(
+            dpp_document.data["toUserId"],
+            JsonValue::from(vec![1u8; 32])
+        );
+        assert!(dpp_document.data.get("$type").is_none());
+        assert_eq!(dpp_document.data_contract, contract);
+
+        let converted_document =
+            Document::try_from(&dpp_document).expect("expected to convert to a Drive document");
+        assert_eq!(converted_document, document);
+    }
+
+    #[test]
+    fn test_dpp_document_conversion_of_deserialized_document() {
+        let dashpay_cbor = json_document_to_cbor(
+            "tests/supporting_files/contract/dashpay/dashpay-contract.json",
+            Some(1),
+        );
+        let contract = <Contract as DriveContractExt>::from_cbor(&dashpay_cbor, None).unwrap();
+
+        let document_type = contract
+            .document_type_for_name("contactRequest")
+            .expect("expected to get contact request document type");
+
+        let mut document = contact_request_document(&contract);
+        document.properties.insert(
+            "$createdAt".to_string(),
+            Value::Integer(1627081806116i64.into()),
+        );
+
+        let serialized_document = document
+            .serialize(document_type)
+            .expect("expected to serialize");
+
+        let deserialized_document = Document::from_bytes(&serialized_document, document_type)
+            .expect("expected to deserialize a document");
+
+        assert_eq!(
+            deserialized_document.properties.get("$createdAt"),
+            Some(&Value::Integer(1627081806116i64.into()))
+        );
+
+        assert!(deserialized_document.properties.get("$type").is_none());
+
+        let dpp_document =
+            DppDocument::try_from((&deserialized_document, &contract, "contactRequest"))
+                .expect("expected to convert to a DPP document");
+
+        assert_eq!(dpp_document.document_type, "contactRequest");
+        assert_eq!(dpp_document.data_contract_id, contract.id);
+        assert_eq!(dpp_document.created_at, Some(1627081806116));
+        assert_eq!(dpp_document.updated_at, None);
+    }
+
+    #[test]
+    fn test_dpp_document_conversion_rejects_fractional_timestamps() {
+        let dashpay_cbor = json_document_to_cbor(
+            "tests/supporting_files/contract/dashpay/dashpay-contract.json",
+            Some(1),
+        );
+        let contract = <Contract as DriveContractExt>::from_cbor(&dashpay_cbor, None).unwrap();
+
+        let mut document = contact_request_document(&contract);
+        document
+            .properties
+            .insert("$createdAt".to_string(), Value::Float(1000.5));
+
+        assert!(matches!(
+            DppDocument::try_from((&document, &contract, "contactRequest")),
+            Err(Error::Document(DocumentError::InvalidDocumentPropertyType(
+                _
+            )))
+        ));
+    }
+
+    #[test]
+    fn test_dpp_document_conversion_decodes_encoded_binary_properties() {
+        let dashpay_cbor = json_document_to_cbor(
+            "tests/supporting_files/contract/dashpay/dashpay-contract.json",
+            Some(1),
+        );
+        let contract = <Contract as DriveContractExt>::from_cbor(&dashpay_cbor, None).unwrap();
+
+        let document = contact_request_document(&contract);
+
+        let mut dpp_document = DppDocument::try_from((&document, &contract, "contactRequest"))
+            .expect("expected to convert to a DPP document");
+        dpp_document.data["toUserId"] = JsonValue::String(base64::encode([1u8; 32]));
+
+        let converted_document =
+            Document::try_from(&dpp_document).expect("expected to convert to a Drive document");
+        assert_eq!(converted_document, document);
+    }
+
+    #[test]
+    fn test_dpp_document_conversion_requires_document_type_in_contract() {
+        let dashpay_cbor = json_document_to_cbor(
+            "tests/supporting_files/contract/dashpay/dashpay-contract.json",
+            Some(1),
+        );
+        let contract = <Contract as DriveContractExt>::from_cbor(&dashpay_cbor, None).unwrap();
+
+        let mut dpp_document = DppDocument::try_from((
+            &contact_request_document(&contract),
+            &contract,
+            "contactRequest",
+        ))
+        .expect("expected to convert to a DPP document");
+        dpp_document.data_contract = Default::default();
+
+        assert!(matches!(
+            Document::try_from(&dpp_document),
+            Err(Error::Contract(ContractError::ProtocolError(_)))
+        ));
+    }
+
+    #[test]
+    fn test_dpp_document_conversion_requires_matching_contract_and_document_type() {
+        let dashpay_cbor = json_document_to_cbor(
+            "tests/supporting_files/contract/dashpay/dashpay-contract.json",
+            Some(1),
+        );
+        let contract = <Contract as DriveContractExt>::from_cbor(&dashpay_cbor, None).unwrap();
+
+        let document = contact_request_document(&contract);
+
+        assert!(matches!(
+            DppDocument::try_from((&document, &contract, "profile")),
+            Err(Error::Document(DocumentError::DocumentContractMismatch(_)))
+        ));
+
+        assert!(matches!(
+            DppDocument::try_from((&document, &contract, "unknownType")),
+            Err(Error::Contract(ContractError::DocumentTypeNotFound(_)))
+        ));
+
+        let mut other_contract = contract.clone();
+        other_contract.id = Identifier::new([5; 32]);
+
+        assert!(matches!(
+            DppDocument::try_from((&document, &other_contract, "contactRequest")),
+            Err(Error::Document(DocumentError::DocumentContractMismatch(_)))
+        ));
+    }
 }
@@ -0,0 +1,374 @@
+// MIT LICENSE
+//
+// Copyright (c) 2022 Dash Core Group
+//
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+//
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+//
+
+//! Asset Locks.
+//!
+//! This module defines functions within the Drive struct related to the outpoints
+//! of asset lock transactions which were already used to create or top up identities.
+//! Spent outpoints are stored as keys of the `SpentAssetLockTransactions` subtree.
+//!
+
+use std::collections::HashMap;
+
+use grovedb::batch::KeyInfoPath;
+use grovedb::EstimatedLayerCount::{EstimatedLevel, PotentiallyAtMaxElements};
+use grovedb::EstimatedLayerSizes::{AllItems, AllSubtrees};
+use grovedb::EstimatedSumTrees::NoSumTrees;
+use grovedb::{Element, EstimatedLayerInformation, PathQuery, Query, SizedQuery, TransactionArg};
+
+use crate::drive::block_info::BlockInfo;
+use crate::drive::grove_operations::BatchInsertApplyType;
+use crate::drive::grove_operations::DirectQueryType::{StatefulDirectQuery, StatelessDirectQuery};
+use crate::drive::grove_operations::QueryTarget::QueryTargetValue;
+use crate::drive::object_size_info::PathKeyElementInfo::PathFixedSizeKeyElement;
+use crate::drive::{Drive, RootTree};
+use crate::error::identity::IdentityError;
+use crate::error::query::QueryError;
+use crate::error::Error;
+use crate::fee::calculate_fee;
+use crate::fee::op::DriveOperation;
+use crate::fee::result::FeeResult;
+
+/// The size of a serialized outpoint, the transaction hash followed by the output index
+pub const ASSET_LOCK_OUTPOINT_SIZE: usize = 36;
+
+/// Returns the path to the spent asset lock outpoints.
+pub(crate) fn asset_lock_storage_path() -> [&'static [u8]; 1] {
+    [Into::<&[u8; 1]>::into(RootTree::SpentAssetLockTransactions)]
+}
+
+impl Drive {
+    /// Adds the estimation costs for inserting or checking a spent asset lock outpoint
+    fn add_estimation_costs_for_asset_lock_outpoints(
+        estimated_costs_only_with_layer_info: &mut HashMap<KeyInfoPath, EstimatedLayerInformation>,
+    ) {
+        estimated_costs_only_with_layer_info.insert(
+            KeyInfoPath::from_known_path([]),
+            EstimatedLayerInformation {
+                is_sum_tree: false,
+                estimated_layer_count: EstimatedLevel(0, false),
+                estimated_layer_sizes: AllSubtrees(1, NoSumTrees, None),
+            },
+        );
+
+        // outpoints are keys without values
+        estimated_costs_only_with_layer_info.insert(
+            KeyInfoPath::from_known_path(asset_lock_storage_path()),
+            EstimatedLayerInformation {
+                is_sum_tree: false,
+                estimated_layer_count: PotentiallyAtMaxElements,
+                estimated_layer_sizes: AllItems(ASSET_LOCK_OUTPOINT_SIZE as u8, 0, None),
+            },
+        );
+    }
+
+    /// Checks if the asset lock outpoint was already used.
+    /// Returns false if `apply` is false, as only the costs of the check are estimated.
+    pub(crate) fn has_asset_lock_outpoint_operations(
+        &self,
+        asset_lock_outpoint: &[u8; ASSET_LOCK_OUTPOINT_SIZE],
+        apply: bool,
+        transaction: TransactionArg,
+        drive_operations: &mut Vec<DriveOperation>,
+    ) -> Result<bool, Error> {
+        let direct_query_type = if apply {
+            StatefulDirectQuery
+        } else {
+            StatelessDirectQuery {
+                in_tree_using_sums: false,
+                query_target: QueryTargetValue(0),
+            }
+        };
+
+        self.grove_has_raw(
+            asset_lock_storage_path(),
+            asset_lock_outpoint.as_slice(),
+            direct_query_type,
+            transaction,
+            drive_operations,
+        )
+    }
+
+    /// Checks if the asset lock outpoint was already used.
+    pub fn has_asset_lock_outpoint(
+        &self,
+        asset_lock_outpoint: &[u8; ASSET_LOCK_OUTPOINT_SIZE],
+        transaction: TransactionArg,
+    ) -> Result<bool, Error> {
+        let mut drive_operations: Vec<DriveOperation> = vec![];
+        self.has_asset_lock_outpoint_operations(
+            asset_lock_outpoint,
+            true,
+            transaction,
+            &mut drive_operations,
+        )
+    }
+
+    /// Adds the operations to mark the asset lock outpoint as used.
+    /// Fails if the asset lock outpoint was already used.
+    pub fn add_insert_asset_lock_outpoint_operations(
+        &self,
+        asset_lock_outpoint: &[u8; ASSET_LOCK_OUTPOINT_SIZE],
+        estimated_costs_only_with_layer_info: &mut Option<
+            HashMap<KeyInfoPath, EstimatedLayerInformation>,
+        >,
+        transaction: TransactionArg,
+        drive_operations: &mut Vec<DriveOperation>,
+    ) -> Result<(), Error> {
+        let apply_type = if let Some(estimated_costs_only_with_layer_info) =
+            estimated_costs_only_with_layer_info
+        {
+            Self::add_estimation_costs_for_asset_lock_outpoints(
+                estimated_costs_only_with_layer_info,
+            );
+            BatchInsertApplyType::StatelessBatchInsert {
+                in_tree_using_sums: false,
+                target: QueryTargetValue(0),
+            }
+        } else {
+            BatchInsertApplyType::StatefulBatchInsert
+        };
+
+        let inserted = self.batch_insert_if_not_exists(
+            PathFixedSizeKeyElement((
+                asset_lock_storage_path(),
+                asset_lock_outpoint.as_slice(),
+                Element::new_item(vec![]),
+            )),
+            apply_type,
+            transaction,
+            drive_operations,
+        )?;
+
+        if !inserted {
+            return Err(Error::Identity(
+                IdentityError::AssetLockOutpointAlreadyUsed("asset lock outpoint was already used"),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Marks the asset lock outpoint as used.
+    pub fn insert_asset_lock_outpoint(
+        &self,
+        asset_lock_outpoint: &[u8; ASSET_LOCK_OUTPOINT_SIZE],
+        block_info: &BlockInfo,
+        apply: bool,
+        transaction: TransactionArg,
+    ) -> Result<FeeResult, Error> {
+        let mut estimated_costs_only_with_layer_info = if apply {
+            None::<HashMap<KeyInfoPath, EstimatedLayerInformation>>
+        } else {
+            Some(HashMap::new())
+        };
+
+        let mut batch_operations: Vec<DriveOperation> = vec![];
+
+        self.add_insert_asset_lock_outpoint_operations(
+            asset_lock_outpoint,
+            &mut estimated_costs_only_with_layer_info,
+            transaction,
+            &mut batch_operations,
+        )?;
+
+        let mut drive_operations: Vec<DriveOperation> = vec![];
+
+        self.apply_batch_drive_operations(
+            estimated_costs_only_with_layer_info,
+            transaction,
+            batch_operations,
+            &mut drive_operations,
+        )?;
+
+        calculate_fee(None, Some(drive_operations), &block_info.epoch)
+    }
+
+    /// The path query of the asset lock outpoint.
+    pub(crate) fn asset_lock_outpoint_path_query(
+        asset_lock_outpoint: &[u8; ASSET_LOCK_OUTPOINT_SIZE],
+    ) -> PathQuery {
+        let mut query = Query::new();
+        query.insert_key(asset_lock_outpoint.to_vec());
+
+        PathQuery::new(
+            vec![vec![RootTree::SpentAssetLockTransactions as u8]],
+            SizedQuery::new(query, Some(1), None),
+        )
+    }
+
+    /// Proves whether the asset lock outpoint was already used.
+    pub fn prove_asset_lock_outpoint(
+        &self,
+        asset_lock_outpoint: &[u8; ASSET_LOCK_OUTPOINT_SIZE],
+        transaction: TransactionArg,
+    ) -> Result<Vec<u8>, Error> {
+        let mut drive_operations: Vec<DriveOperation> = vec![];
+        self.grove_get_proved_path_query(
+            &Self::asset_lock_outpoint_path_query(asset_lock_outpoint),
+            transaction,
+            &mut drive_operations,
+        )
+    }
+
+    /// Verifies a proof of the asset lock outpoint against the expected root hash.
+    /// Returns whether the proof shows that the asset lock outpoint was already used.
+    pub fn verify_asset_lock_outpoint(
+        proof: &[u8],
+        asset_lock_outpoint: &[u8; ASSET_LOCK_OUTPOINT_SIZE],
+        expected_root_hash: [u8; 32],
+    ) -> Result<bool, Error> {
+        let proved_elements = Self::grove_verify_proved_path_query(
+            proof,
+            &Self::asset_lock_outpoint_path_query(asset_lock_outpoint),
+            expected_root_hash,
+        )?;
+
+        match proved_elements.as_slice() {
+            [] => Ok(false),
+            [(key, Element::Item(..))] if key.as_slice() == asset_lock_outpoint.as_slice() => {
+                Ok(true)
+            }
+            _ => Err(Error::Query(QueryError::InvalidProof(
+                "proof must contain only the asset lock outpoint",
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::helpers::setup::setup_drive_with_initial_state_structure;
+    use crate::drive::block_info::BlockInfo;
+    use crate::drive::Drive;
+    use crate::error::identity::IdentityError;
+    use crate::error::Error;
+
+    #[test]
+    fn test_insert_and_check_asset_lock_outpoint() {
+        let drive = setup_drive_with_initial_state_structure();
+
+        let transaction = drive.grove.start_transaction();
+
+        let outpoint = [1; 36];
+
+        assert!(!drive
+            .has_asset_lock_outpoint(&outpoint, Some(&transaction))
+            .expect("expected to check outpoint"));
+
+        drive
+            .insert_asset_lock_outpoint(&outpoint, &BlockInfo::default(), true, Some(&transaction))
+            .expect("expected to insert outpoint");
+
+        assert!(drive
+            .has_asset_lock_outpoint(&outpoint, Some(&transaction))
+            .expect("expected to check outpoint"));
+
+        assert!(!drive
+            .has_asset_lock_outpoint(&[2; 36], Some(&transaction))
+            .expect("expected to check outpoint"));
+
+        let root_hash = drive
+            .grove
+            .root_hash(Some(&transaction))
+            .unwrap()
+            .expect("expected to get root hash");
+
+        let proof = drive
+            .prove_asset_lock_outpoint(&outpoint, Some(&transaction))
+            .expect("expected to prove outpoint");
+
+        assert!(
+            Drive::verify_asset_lock_outpoint(&proof, &outpoint, root_hash)
+                .expect("expected to verify outpoint")
+        );
+
+        let absence_proof = drive
+            .prove_asset_lock_outpoint(&[2; 36], Some(&transaction))
+            .expect("expected to prove outpoint absence");
+
+        assert!(
+            !Drive::verify_asset_lock_outpoint(&absence_proof, &[2; 36], root_hash)
+                .expect("expected to verify outpoint absence")
+        );
+
+        assert!(Drive::verify_asset_lock_outpoint(&proof, &outpoint, [0; 32]).is_err());
+    }
+
+    #[test]
+    fn test_insert_asset_lock_outpoint_twice() {
+        let drive = setup_drive_with_initial_state_structure();
+
+        let transaction = drive.grove.start_transaction();
+
+        let outpoint = [1; 36];
+
+        drive
+            .insert_asset_lock_outpoint(&outpoint, &BlockInfo::default(), true, Some(&transaction))
+            .expect("expected to insert outpoint");
+
+        let result = drive.insert_asset_lock_outpoint(
+            &outpoint,
+            &BlockInfo::default(),
+            true,
+            Some(&transaction),
+        );
+
+        assert!(matches!(
+            result,
+            Err(Error::Identity(
+                IdentityError::AssetLockOutpointAlreadyUsed(_)
+            ))
+        ));
+
+        // the cost of a second insertion can still be estimated
+        drive
+            .insert_asset_lock_outpoint(&outpoint, &BlockInfo::default(), false, Some(&transaction))
+            .expect("expected to estimate outpoint insertion");
+    }
+
+    #[test]
+    fn test_estimated_asset_lock_outpoint_insertion_does_not_insert() {
+        let drive = setup_drive_with_initial_state_structure();
+
+        let transaction = drive.grove.start_transaction();
+
+        let outpoint = [1; 36];
+
+        let fee_result = drive
+            .insert_asset_lock_outpoint(&outpoint, &BlockInfo::default(), false, Some(&transaction))
+            .expect("expected to estimate outpoint insertion");
+
+        assert!(fee_result.storage_fee > 0);
+
+        assert!(!drive
+            .has_asset_lock_outpoint(&outpoint, Some(&transaction))
+            .expect("expected to check outpoint"));
+    }
+}
@@ -36,14 +36,19 @@ use crate::drive::object_size_info::DocumentInfo::{
 };
 use crate::drive::object_size_info::{DocumentAndContractInfo, OwnedDocumentInfo};
 use crate::drive::Drive;
+use crate::error::fee::FeeError;
+use crate::error::identity::IdentityError;
 use crate::error::Error;
 use crate::fee::calculate_fee;
+use crate::fee::credits::Credits;
 use crate::fee::op::DriveOperation;
 use crate::fee::result::FeeResult;
 use dpp::data_contract::extra::{DocumentType, DriveContractExt};
+use dpp::identity::{Identity, IdentityPublicKey, KeyID};
+use dpp::prelude::Revision;
 use grovedb::batch::KeyInfoPath;
 use grovedb::{EstimatedLayerInformation, TransactionArg};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 /// A converter that will get Drive Operations from High Level Operations
 pub trait DriveOperationConverter {
@@ -60,6 +65,7 @@ pub trait DriveOperationConverter {
 }
 
 /// Operations on Contracts
+#[derive(Clone)]
 pub enum ContractOperationType<'a> {
     /// Deserializes a contract from CBOR and applies it.
     ApplyContractCbor {
@@ -80,6 +86,11 @@ pub enum ContractOperationType<'a> {
         /// Storage flags for the contract
         storage_flags: Option<&'a StorageFlags>,
     },
+    /// Deletes a contract along with all its documents, refunding the removed storage.
+    DeleteContract {
+        /// The contract id
+        contract_id: [u8; 32],
+    },
 }
 
 impl DriveOperationConverter for ContractOperationType<'_> {
@@ -123,11 +134,19 @@ impl DriveOperationConverter for ContractOperationType<'_> {
                 storage_flags,
                 transaction,
             ),
+            ContractOperationType::DeleteContract { contract_id } => drive
+                .delete_contract_operations(
+                    contract_id,
+                    block_info,
+                    estimated_costs_only_with_layer_info,
+                    transaction,
+                ),
         }
     }
 }
 
 /// A wrapper for an update operation
+#[derive(Clone)]
 pub struct UpdateOperationInfo<'a> {
     /// The document to update
     document: &'a Document,
@@ -140,6 +159,7 @@ pub struct UpdateOperationInfo<'a> {
 }
 
 /// A wrapper for a document operation
+#[derive(Clone)]
 pub enum DocumentOperation<'a> {
     /// An add operation
     AddOperation {
@@ -153,6 +173,7 @@ pub enum DocumentOperation<'a> {
 }
 
 /// Document and contract info
+#[derive(Clone)]
 pub struct DocumentOperationsForContractDocumentType<'a> {
     /// Document info
     pub operations: Vec<DocumentOperation<'a>>,
@@ -163,6 +184,7 @@ pub struct DocumentOperationsForContractDocumentType<'a> {
 }
 
 /// Operations on Documents
+#[derive(Clone)]
 pub enum DocumentOperationType<'a> {
     /// Deserializes a document and a contract and adds the document to the contract.
     AddSerializedDocumentForSerializedContract {
@@ -418,6 +440,7 @@ impl DriveOperationConverter for DocumentOperationType<'_> {
                 };
                 drive.update_document_for_contract_operations(
                     document_and_contract_info,
+                    true,
                     block_info,
                     &mut None,
                     estimated_costs_only_with_layer_info,
@@ -448,6 +471,7 @@ impl DriveOperationConverter for DocumentOperationType<'_> {
                 };
                 drive.update_document_for_contract_operations(
                     document_and_contract_info,
+                    true,
                     block_info,
                     &mut None,
                     estimated_costs_only_with_layer_info,
@@ -477,6 +501,7 @@ impl DriveOperationConverter for DocumentOperationType<'_> {
                 };
                 drive.update_document_for_contract_operations(
                     document_and_contract_info,
+                    true,
                     block_info,
                     &mut None,
                     estimated_costs_only_with_layer_info,
@@ -542,6 +567,7 @@ impl DriveOperationConverter for DocumentOperationType<'_> {
                             };
                             let mut operations = drive.update_document_for_contract_operations(
                                 document_and_contract_info,
+                                true,
                                 block_info,
                                 &mut Some(&mut drive_operations),
                                 estimated_costs_only_with_layer_info,
@@ -556,45 +582,145 @@ impl DriveOperationConverter for DocumentOperationType<'_> {
         }
     }
 }
-//
-// /// Operations on Identities
-// pub enum IdentityOperationType<'a> {
-//     /// Inserts a new identity to the `Identities` subtree.
-//     InsertIdentity {
-//         /// The identity we wish to insert
-//         identity: Identity,
-//         /// Add storage flags (like epoch, owner id, etc)
-//         storage_flags: Option<&'a StorageFlags>,
-//     },
-// }
-//
-// impl DriveOperationConverter for IdentityOperationType<'_> {
-//     fn to_grove_db_operations(
-//         self,
-//         drive: &Drive,
-//         apply: bool,
-//         block_info: &BlockInfo,
-//         transaction: TransactionArg,
-//     ) -> Result<Vec<DriveOperation>, Error> {
-//         match self {
-//             IdentityOperationType::InsertIdentity {
-//                 identity,
-//                 storage_flags,
-//             } => {
-//                 drive.insert_identity(identity, block_info, apply, storage_flags, transaction)
-//             }
-//         }
-//     }
-// }
+
+/// Operations on Identities
+#[derive(Clone)]
+pub enum IdentityOperationType<'a> {
+    /// Inserts a new identity to the `Identities` subtree.
+    InsertIdentity {
+        /// The identity we wish to insert
+        identity: Identity,
+        /// Add storage flags (like epoch, owner id, etc)
+        storage_flags: Option<&'a StorageFlags>,
+    },
+    /// Adds balance to an identity.
+    /// Balance changes of the same identity in a batch are combined into one.
+    AddToIdentityBalance {
+        /// The identity id of the identity
+        identity_id: [u8; 32],
+        /// The added balance
+        added_balance: u64,
+    },
+    /// Removes balance from an identity.
+    /// Balance changes of the same identity in a batch are combined into one.
+    RemoveFromIdentityBalance {
+        /// The identity id of the identity
+        identity_id: [u8; 32],
+        /// The balance that will be removed from the identity
+        /// This needs to be verified in advance
+        balance_to_remove: u64,
+    },
+    /// Adds an array of keys to the identity.
+    AddNewKeysToIdentity {
+        /// The identity id of the identity
+        identity_id: [u8; 32],
+        /// The keys to be added
+        keys_to_add: Vec<IdentityPublicKey>,
+    },
+    /// Disables an array of keys of the identity at the block time.
+    DisableIdentityKeys {
+        /// The identity id of the identity
+        identity_id: [u8; 32],
+        /// The ids of the keys to be disabled
+        key_ids: Vec<KeyID>,
+    },
+    /// Updates the revision of an identity.
+    UpdateIdentityRevision {
+        /// The identity id of the identity
+        identity_id: [u8; 32],
+        /// The revision we are updating to
+        revision: Revision,
+    },
+}
+
+impl DriveOperationConverter for IdentityOperationType<'_> {
+    fn to_drive_operations(
+        self,
+        drive: &Drive,
+        estimated_costs_only_with_layer_info: &mut Option<
+            HashMap<KeyInfoPath, EstimatedLayerInformation>,
+        >,
+        block_info: &BlockInfo,
+        transaction: TransactionArg,
+    ) -> Result<Vec<DriveOperation>, Error> {
+        match self {
+            IdentityOperationType::InsertIdentity {
+                identity,
+                storage_flags,
+            } => drive.insert_identity_operations(
+                identity,
+                storage_flags,
+                estimated_costs_only_with_layer_info,
+                transaction,
+            ),
+            IdentityOperationType::AddToIdentityBalance {
+                identity_id,
+                added_balance,
+            } => drive.add_to_identity_balance_operations(
+                identity_id,
+                added_balance,
+                estimated_costs_only_with_layer_info,
+                transaction,
+            ),
+            IdentityOperationType::RemoveFromIdentityBalance {
+                identity_id,
+                balance_to_remove,
+            } => drive.remove_from_identity_balance_operations(
+                identity_id,
+                balance_to_remove,
+                estimated_costs_only_with_layer_info,
+                transaction,
+            ),
+            IdentityOperationType::AddNewKeysToIdentity {
+                identity_id,
+                keys_to_add,
+            } => {
+                let storage_flags =
+                    StorageFlags::new_single_epoch(block_info.epoch.index, Some(identity_id));
+                drive.add_new_keys_to_identity_operations(
+                    identity_id,
+                    keys_to_add,
+                    Some(&storage_flags),
+                    estimated_costs_only_with_layer_info,
+                    transaction,
+                )
+            }
+            IdentityOperationType::DisableIdentityKeys {
+                identity_id,
+                key_ids,
+            } => drive.disable_identity_keys_operations(
+                identity_id,
+                key_ids,
+                block_info.time_ms,
+                estimated_costs_only_with_layer_info,
+                transaction,
+            ),
+            IdentityOperationType::UpdateIdentityRevision {
+                identity_id,
+                revision,
+            } => {
+                let storage_flags =
+                    StorageFlags::new_single_epoch(block_info.epoch.index, Some(identity_id));
+                drive.update_identity_revision_operations(
+                    identity_id,
+                    revision,
+                    Some(&storage_flags),
+                    estimated_costs_only_with_layer_info,
+                )
+            }
+        }
+    }
+}
 
 /// All types of Drive Operations
+#[derive(Clone)]
 pub enum DriveOperationType<'a> {
     /// A contract operation
     ContractOperation(ContractOperationType<'a>),
     /// A document operation
     DocumentOperation(DocumentOperationType<'a>),
-    // /// An identity operation
-    // IdentityOperation(IdentityOperationType<'a>),
+    /// An identity operation
+    IdentityOperation(IdentityOperationType<'a>),
 }
 
 impl DriveOperationConverter for DriveOperationType<'_> {
@@ -623,20 +749,96 @@ impl DriveOperationConverter for DriveOperationType<'_> {
                     block_info,
                     transaction,
                 )
-            } // DriveOperationType::IdentityOperation(identity_operation_type) => {
-              //     identity_operation_type.to_grove_db_operations(
-              //         drive,
-              //         apply,
-              //         block_info,
-              //         transaction,
-              //     )
-              // }
+            }
+            DriveOperationType::IdentityOperation(identity_operation_type) => {
+                identity_operation_type.to_drive_operations(
+                    drive,
+                    estimated_costs_only_with_layer_info,
+                    block_info,
+                    transaction,
+                )
+            }
         }
     }
 }
 
+/// Combines the balance changes of each identity into a single operation placed where
+/// the first balance change of the identity was. Balance operations write the new balance
+/// computed from the balance stored before the batch, so several of them for the same
+/// identity would override each other.
+fn merge_identity_balance_operations(
+    operations: Vec<DriveOperationType>,
+) -> Result<Vec<DriveOperationType>, Error> {
+    let mut balance_changes: HashMap<[u8; 32], i128> = HashMap::new();
+
+    for operation in operations.iter() {
+        let (identity_id, change) = match operation {
+            DriveOperationType::IdentityOperation(
+                IdentityOperationType::AddToIdentityBalance {
+                    identity_id,
+                    added_balance,
+                },
+            ) => (identity_id, *added_balance as i128),
+            DriveOperationType::IdentityOperation(
+                IdentityOperationType::RemoveFromIdentityBalance {
+                    identity_id,
+                    balance_to_remove,
+                },
+            ) => (identity_id, -(*balance_to_remove as i128)),
+            _ => continue,
+        };
+
+        let balance_change = balance_changes.entry(*identity_id).or_default();
+        *balance_change = balance_change
+            .checked_add(change)
+            .ok_or(Error::Fee(FeeError::Overflow("balance change overflow")))?;
+    }
+
+    let mut merged_identity_ids = HashSet::new();
+    let mut merged_operations = Vec::with_capacity(operations.len());
+
+    for operation in operations {
+        let identity_id = match &operation {
+            DriveOperationType::IdentityOperation(
+                IdentityOperationType::AddToIdentityBalance { identity_id, .. }
+                | IdentityOperationType::RemoveFromIdentityBalance { identity_id, .. },
+            ) => *identity_id,
+            _ => {
+                merged_operations.push(operation);
+                continue;
+            }
+        };
+
+        if !merged_identity_ids.insert(identity_id) {
+            continue;
+        }
+
+        let balance_change = balance_changes[&identity_id];
+
+        let balance_operation = if balance_change >= 0 {
+            IdentityOperationType::AddToIdentityBalance {
+                identity_id,
+                added_balance: u64::try_from(balance_change)
+                    .map_err(|_| Error::Fee(FeeError::Overflow("added balance overflow")))?,
+            }
+        } else {
+            IdentityOperationType::RemoveFromIdentityBalance {
+                identity_id,
+                balance_to_remove: u64::try_from(-balance_change)
+                    .map_err(|_| Error::Fee(FeeError::Overflow("removed balance overflow")))?,
+            }
+        };
+
+        merged_operations.push(DriveOperationType::IdentityOperation(balance_operation));
+    }
+
+    Ok(merged_operations)
+}
+
 impl Drive {
-    /// We can apply multiple operations at once
+    /// We can apply multiple operations at once.
+    /// Balance changes of the same identity are combined into one balance update.
+    /// Deleted contracts are removed from cache once the operations are applied.
     pub fn apply_drive_operations(
         &self,
         operations: Vec<DriveOperationType>,
@@ -644,13 +846,22 @@ impl Drive {
         block_info: &BlockInfo,
         transaction: TransactionArg,
     ) -> Result<FeeResult, Error> {
+        let deleted_contract_ids: Vec<[u8; 32]> = operations
+            .iter()
+            .filter_map(|operation| match operation {
+                DriveOperationType::ContractOperation(ContractOperationType::DeleteContract {
+                    contract_id,
+                }) => Some(*contract_id),
+                _ => None,
+            })
+            .collect();
         let mut drive_operations = vec![];
         let mut estimated_costs_only_with_layer_info = if apply {
             None::<HashMap<KeyInfoPath, EstimatedLayerInformation>>
         } else {
             Some(HashMap::new())
         };
-        for drive_op in operations {
+        for drive_op in merge_identity_balance_operations(operations)? {
             drive_operations.append(&mut drive_op.to_drive_operations(
                 self,
                 &mut estimated_costs_only_with_layer_info,
@@ -665,8 +876,113 @@ impl Drive {
             drive_operations,
             &mut cost_operations,
         )?;
+        if apply && !deleted_contract_ids.is_empty() {
+            let mut drive_cache = self.write_cache()?;
+
+            for contract_id in deleted_contract_ids {
+                drive_cache
+                    .cached_contracts
+                    .remove(contract_id, transaction.is_some());
+            }
+        }
         calculate_fee(None, Some(cost_operations), &block_info.epoch)
     }
+
+    /// Applies the operations and charges their fees, with the `additional_fees` such as the
+    /// fees of validating a state transition, to the identity paying for them.
+    /// The `user_tip` is removed from the balance of the identity as well, it is not part of
+    /// the returned fees as it is paid to the block proposer instead of the fee pools.
+    /// Storage removed by the operations is refunded to the identities which stored it.
+    /// If `apply` is false, no balance is changed and only the estimated fees are returned.
+    /// Otherwise the operations are only applied if the identity can pay their estimated
+    /// fees and the tip, as applied operations are not reverted if it can't pay for them.
+    pub fn apply_drive_operations_paid_by_identity(
+        &self,
+        operations: Vec<DriveOperationType>,
+        identity_id: [u8; 32],
+        additional_fees: FeeResult,
+        user_tip: Credits,
+        apply: bool,
+        block_info: &BlockInfo,
+        transaction: TransactionArg,
+    ) -> Result<FeeResult, Error> {
+        if apply {
+            // Estimated fees are the most applying can cost, refunds can't pay for them
+            let mut estimated_fee_result =
+                self.apply_drive_operations(operations.clone(), false, block_info, transaction)?;
+            estimated_fee_result.checked_add_assign(additional_fees.clone())?;
+
+            let required_balance = u64::try_from(estimated_fee_result.balance_change()?)
+                .unwrap_or_default()
+                .checked_add(user_tip)
+                .ok_or(Error::Fee(FeeError::Overflow("fee overflow error")))?;
+
+            let balance = self.fetch_identity_balance(identity_id, transaction)?;
+
+            if balance < required_balance {
+                return Err(Error::Identity(IdentityError::IdentityInsufficientBalance(
+                    "identity can't pay the estimated fees of the operations",
+                )));
+            }
+        }
+
+        let mut fee_result =
+            self.apply_drive_operations(operations, apply, block_info, transaction)?;
+        fee_result.checked_add_assign(additional_fees)?;
+
+        if !apply {
+            return Ok(fee_result);
+        }
+
+        let to_pay = fee_result
+            .storage_fee
+            .checked_add(fee_result.processing_fee)
+            .and_then(|fees| fees.checked_add(user_tip))
+            .ok_or(Error::Fee(FeeError::Overflow("fee overflow error")))?;
+        let refunded = fee_result.fee_refunds.total_for_identifier(&identity_id)?;
+
+        let mut balance_operations = vec![];
+
+        if to_pay > refunded {
+            balance_operations.push(DriveOperationType::IdentityOperation(
+                IdentityOperationType::RemoveFromIdentityBalance {
+                    identity_id,
+                    balance_to_remove: to_pay - refunded,
+                },
+            ));
+        } else if refunded > to_pay {
+            balance_operations.push(DriveOperationType::IdentityOperation(
+                IdentityOperationType::AddToIdentityBalance {
+                    identity_id,
+                    added_balance: refunded - to_pay,
+                },
+            ));
+        }
+
+        for refunded_identity_id in fee_result.fee_refunds.0.keys() {
+            if refunded_identity_id == &identity_id {
+                continue;
+            }
+
+            let added_balance = fee_result
+                .fee_refunds
+                .total_for_identifier(refunded_identity_id)?;
+
+            if added_balance > 0 {
+                balance_operations.push(DriveOperationType::IdentityOperation(
+                    IdentityOperationType::AddToIdentityBalance {
+                        identity_id: *refunded_identity_id,
+                        added_balance,
+                    },
+                ));
+            }
+        }
+
+        // The balance updates are not charged, they have the same cost for every state transition
+        self.apply_drive_operations(balance_operations, true, block_info, transaction)?;
+
+        Ok(fee_result)
+    }
 }
 
 #[cfg(test)]
@@ -680,13 +996,21 @@ mod tests {
     use serde_json::json;
     use tempfile::TempDir;
 
-    use crate::common::{json_document_to_cbor, setup_contract};
+    use crate::common::helpers::identities::create_test_identity;
+    use crate::common::{
+        json_document_to_cbor, json_document_with_revision_to_cbor, setup_contract,
+    };
     use crate::drive::batch::drive_op_batch::DocumentOperation::{AddOperation, UpdateOperation};
     use crate::drive::batch::ContractOperationType::ApplyContractWithSerialization;
     use crate::drive::batch::DocumentOperationType::{
         AddSerializedDocumentForContract, MultipleDocumentOperationsForSameContractDocumentType,
     };
-    use crate::drive::batch::DriveOperationType::{ContractOperation, DocumentOperation};
+    use crate::drive::batch::DriveOperationType::{
+        ContractOperation, DocumentOperation, IdentityOperation,
+    };
+    use crate::drive::batch::IdentityOperationType::{
+        AddToIdentityBalance, RemoveFromIdentityBalance, UpdateIdentityRevision,
+    };
     use crate::drive::config::DriveConfig;
     use crate::drive::contract::contract_root_path;
     use crate::drive::flags::StorageFlags;
@@ -1240,13 +1564,15 @@ mod tests {
 
         drive_operations = vec![];
 
-        let person_serialized_document0 = json_document_to_cbor(
+        let person_serialized_document0 = json_document_with_revision_to_cbor(
             "tests/supporting_files/contract/family/person0-older.json",
+            1,
             Some(1),
         );
 
-        let person_serialized_document1 = json_document_to_cbor(
+        let person_serialized_document1 = json_document_with_revision_to_cbor(
             "tests/supporting_files/contract/family/person3-older.json",
+            1,
             Some(1),
         );
 
@@ -1476,13 +1802,15 @@ mod tests {
 
         drive_operations = vec![];
 
-        let person_serialized_document0 = json_document_to_cbor(
+        let person_serialized_document0 = json_document_with_revision_to_cbor(
             "tests/supporting_files/contract/family/person0-older.json",
+            1,
             Some(1),
         );
 
-        let person_serialized_document1 = json_document_to_cbor(
+        let person_serialized_document1 = json_document_with_revision_to_cbor(
             "tests/supporting_files/contract/family/person3.json",
+            1,
             Some(1),
         );
 
@@ -1601,4 +1929,354 @@ mod tests {
             .expect("expected to query");
         assert_eq!(docs.len(), 1);
     }
+
+    #[test]
+    fn test_add_dashpay_document_and_remove_identity_balance() {
+        let tmp_dir = TempDir::new().unwrap();
+        let drive: Drive = Drive::open(tmp_dir, None).expect("expected to open Drive successfully");
+
+        let db_transaction = drive.grove.start_transaction();
+
+        drive
+            .create_initial_state_structure(Some(&db_transaction))
+            .expect("expected to create root tree successfully");
+
+        let owner_id = rand::thread_rng().gen::<[u8; 32]>();
+
+        create_test_identity(&drive, owner_id, Some(&db_transaction));
+
+        drive
+            .apply_drive_operations(
+                vec![IdentityOperation(AddToIdentityBalance {
+                    identity_id: owner_id,
+                    added_balance: 1000,
+                })],
+                true,
+                &BlockInfo::default(),
+                Some(&db_transaction),
+            )
+            .expect("expected to add to identity balance");
+
+        let contract_cbor = json_document_to_cbor(
+            "tests/supporting_files/contract/dashpay/dashpay-contract-all-mutable.json",
+            Some(crate::drive::defaults::PROTOCOL_VERSION),
+        );
+        let contract = <Contract as DriveContractExt>::from_cbor(&contract_cbor, None)
+            .expect("contract should be deserialized");
+        let serialized_contract =
+            DriveContractExt::to_cbor(&contract).expect("contract should be serialized");
+
+        let dashpay_cr_serialized_document = json_document_to_cbor(
+            "tests/supporting_files/contract/dashpay/contact-request0.json",
+            Some(1),
+        );
+
+        let drive_operations = vec![
+            ContractOperation(ApplyContractWithSerialization {
+                contract: &contract,
+                serialized_contract,
+                storage_flags: None,
+            }),
+            DocumentOperation(AddSerializedDocumentForContract {
+                serialized_document: dashpay_cr_serialized_document.as_slice(),
+                contract: &contract,
+                document_type_name: "contactRequest",
+                owner_id: Some(owner_id),
+                override_document: false,
+                storage_flags: StorageFlags::optional_default_as_ref(),
+            }),
+            IdentityOperation(RemoveFromIdentityBalance {
+                identity_id: owner_id,
+                balance_to_remove: 400,
+            }),
+        ];
+
+        drive
+            .apply_drive_operations(
+                drive_operations,
+                true,
+                &BlockInfo::default(),
+                Some(&db_transaction),
+            )
+            .expect("expected to insert document and remove identity balance");
+
+        let (identity, _) = drive
+            .fetch_identity(
+                &owner_id,
+                crate::drive::defaults::PROTOCOL_VERSION,
+                Some(&db_transaction),
+            )
+            .expect("expected to fetch identity");
+
+        assert_eq!(identity.balance, 600);
+    }
+
+    #[test]
+    fn test_add_and_remove_identity_balance_in_one_batch() {
+        let tmp_dir = TempDir::new().unwrap();
+        let drive: Drive = Drive::open(tmp_dir, None).expect("expected to open Drive successfully");
+
+        let db_transaction = drive.grove.start_transaction();
+
+        drive
+            .create_initial_state_structure(Some(&db_transaction))
+            .expect("expected to create root tree successfully");
+
+        let identity_id = rand::thread_rng().gen::<[u8; 32]>();
+
+        create_test_identity(&drive, identity_id, Some(&db_transaction));
+
+        drive
+            .apply_drive_operations(
+                vec![
+                    IdentityOperation(AddToIdentityBalance {
+                        identity_id,
+                        added_balance: 1000,
+                    }),
+                    IdentityOperation(RemoveFromIdentityBalance {
+                        identity_id,
+                        balance_to_remove: 400,
+                    }),
+                ],
+                true,
+                &BlockInfo::default(),
+                Some(&db_transaction),
+            )
+            .expect("expected to change identity balance");
+
+        let balance = drive
+            .fetch_identity_balance(identity_id, Some(&db_transaction))
+            .expect("expected to fetch identity balance");
+
+        assert_eq!(balance, 600);
+
+        // Removing more than added in the same batch fails as a whole
+        drive
+            .apply_drive_operations(
+                vec![
+                    IdentityOperation(AddToIdentityBalance {
+                        identity_id,
+                        added_balance: 100,
+                    }),
+                    IdentityOperation(RemoveFromIdentityBalance {
+                        identity_id,
+                        balance_to_remove: 701,
+                    }),
+                ],
+                true,
+                &BlockInfo::default(),
+                Some(&db_transaction),
+            )
+            .expect_err("expected to not be able to remove more than the balance");
+
+        let balance = drive
+            .fetch_identity_balance(identity_id, Some(&db_transaction))
+            .expect("expected to fetch identity balance");
+
+        assert_eq!(balance, 600);
+    }
+
+    #[test]
+    fn test_apply_drive_operations_paid_by_identity() {
+        let tmp_dir = TempDir::new().unwrap();
+        let drive: Drive = Drive::open(tmp_dir, None).expect("expected to open Drive successfully");
+
+        let db_transaction = drive.grove.start_transaction();
+
+        drive
+            .create_initial_state_structure(Some(&db_transaction))
+            .expect("expected to create root tree successfully");
+
+        let owner_id = rand::thread_rng().gen::<[u8; 32]>();
+
+        create_test_identity(&drive, owner_id, Some(&db_transaction));
+
+        let initial_balance = 100_000_000_000;
+
+        drive
+            .apply_drive_operations(
+                vec![IdentityOperation(AddToIdentityBalance {
+                    identity_id: owner_id,
+                    added_balance: initial_balance,
+                })],
+                true,
+                &BlockInfo::default(),
+                Some(&db_transaction),
+            )
+            .expect("expected to add to identity balance");
+
+        let contract_cbor = json_document_to_cbor(
+            "tests/supporting_files/contract/dashpay/dashpay-contract-all-mutable.json",
+            Some(crate::drive::defaults::PROTOCOL_VERSION),
+        );
+        let contract = <Contract as DriveContractExt>::from_cbor(&contract_cbor, None)
+            .expect("contract should be deserialized");
+        let serialized_contract =
+            DriveContractExt::to_cbor(&contract).expect("contract should be serialized");
+
+        let dashpay_cr_serialized_document = json_document_to_cbor(
+            "tests/supporting_files/contract/dashpay/contact-request0.json",
+            Some(1),
+        );
+
+        let drive_operations = vec![
+            ContractOperation(ApplyContractWithSerialization {
+                contract: &contract,
+                serialized_contract,
+                storage_flags: None,
+            }),
+            DocumentOperation(AddSerializedDocumentForContract {
+                serialized_document: dashpay_cr_serialized_document.as_slice(),
+                contract: &contract,
+                document_type_name: "contactRequest",
+                owner_id: Some(owner_id),
+                override_document: false,
+                storage_flags: StorageFlags::optional_default_as_ref(),
+            }),
+        ];
+
+        let fee_result = drive
+            .apply_drive_operations_paid_by_identity(
+                drive_operations,
+                owner_id,
+                FeeResult::from_fees(0, 1000),
+                500,
+                true,
+                &BlockInfo::default(),
+                Some(&db_transaction),
+            )
+            .expect("expected to insert document and pay for it");
+
+        assert!(fee_result.storage_fee > 0);
+
+        let balance = drive
+            .fetch_identity_balance(owner_id, Some(&db_transaction))
+            .expect("expected to fetch identity balance");
+
+        assert_eq!(
+            balance,
+            initial_balance - fee_result.storage_fee - fee_result.processing_fee - 500
+        );
+    }
+
+    #[test]
+    fn test_apply_drive_operations_paid_by_identity_without_enough_balance() {
+        let tmp_dir = TempDir::new().unwrap();
+        let drive: Drive = Drive::open(tmp_dir, None).expect("expected to open Drive successfully");
+
+        let db_transaction = drive.grove.start_transaction();
+
+        drive
+            .create_initial_state_structure(Some(&db_transaction))
+            .expect("expected to create root tree successfully");
+
+        let owner_id = rand::thread_rng().gen::<[u8; 32]>();
+
+        create_test_identity(&drive, owner_id, Some(&db_transaction));
+
+        let initial_balance = drive
+            .fetch_identity_balance(owner_id, Some(&db_transaction))
+            .expect("expected to fetch identity balance");
+
+        let contract_cbor = json_document_to_cbor(
+            "tests/supporting_files/contract/dashpay/dashpay-contract-all-mutable.json",
+            Some(crate::drive::defaults::PROTOCOL_VERSION),
+        );
+        let contract = <Contract as DriveContractExt>::from_cbor(&contract_cbor, None)
+            .expect("contract should be deserialized");
+        let serialized_contract =
+            DriveContractExt::to_cbor(&contract).expect("contract should be serialized");
+
+        let drive_operations = vec![ContractOperation(ApplyContractWithSerialization {
+            contract: &contract,
+            serialized_contract,
+            storage_flags: None,
+        })];
+
+        let result = drive.apply_drive_operations_paid_by_identity(
+            drive_operations,
+            owner_id,
+            FeeResult::default(),
+            initial_balance + 1,
+            true,
+            &BlockInfo::default(),
+            Some(&db_transaction),
+        );
+
+        assert!(matches!(
+            result,
+            Err(Error::Identity(IdentityError::IdentityInsufficientBalance(
+                _
+            )))
+        ));
+
+        let (_, contract_fetch_info) = drive
+            .get_contract_with_fetch_info(contract.id.to_buffer(), None, Some(&db_transaction))
+            .expect("expected to fetch the contract");
+
+        assert!(contract_fetch_info.is_none());
+
+        let balance = drive
+            .fetch_identity_balance(owner_id, Some(&db_transaction))
+            .expect("expected to fetch identity balance");
+
+        assert_eq!(balance, initial_balance);
+    }
+
+    #[test]
+    fn test_remove_more_than_identity_balance_should_fail() {
+        let tmp_dir = TempDir::new().unwrap();
+        let drive: Drive = Drive::open(tmp_dir, None).expect("expected to open Drive successfully");
+
+        let db_transaction = drive.grove.start_transaction();
+
+        drive
+            .create_initial_state_structure(Some(&db_transaction))
+            .expect("expected to create root tree successfully");
+
+        let identity_id = rand::thread_rng().gen::<[u8; 32]>();
+
+        create_test_identity(&drive, identity_id, Some(&db_transaction));
+
+        drive
+            .apply_drive_operations(
+                vec![IdentityOperation(RemoveFromIdentityBalance {
+                    identity_id,
+                    balance_to_remove: 1,
+                })],
+                true,
+                &BlockInfo::default(),
+                Some(&db_transaction),
+            )
+            .expect_err("expected to not be able to remove more than the balance");
+    }
+
+    #[test]
+    fn test_estimated_costs_for_identity_operations() {
+        let tmp_dir = TempDir::new().unwrap();
+        let drive: Drive = Drive::open(tmp_dir, None).expect("expected to open Drive successfully");
+
+        let db_transaction = drive.grove.start_transaction();
+
+        drive
+            .create_initial_state_structure(Some(&db_transaction))
+            .expect("expected to create root tree successfully");
+
+        let identity_id = rand::thread_rng().gen::<[u8; 32]>();
+
+        // The identity doesn't exist, estimation should not need it
+        let fee_result = drive
+            .apply_drive_operations(
+                vec![IdentityOperation(UpdateIdentityRevision {
+                    identity_id,
+                    revision: 2,
+                })],
+                false,
+                &BlockInfo::default(),
+                Some(&db_transaction),
+            )
+            .expect("expected to estimate identity operations");
+
+        assert!(fee_result.processing_fee > 0);
+    }
 }
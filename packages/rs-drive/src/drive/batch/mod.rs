@@ -35,5 +35,7 @@
 /// Operation module
 pub mod drive_op_batch;
 mod grovedb_op_batch;
-pub use drive_op_batch::{ContractOperationType, DocumentOperationType, DriveOperationType};
+pub use drive_op_batch::{
+    ContractOperationType, DocumentOperationType, DriveOperationType, IdentityOperationType,
+};
 pub use grovedb_op_batch::GroveDbOpBatch;
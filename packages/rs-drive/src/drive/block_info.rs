@@ -1,7 +1,8 @@
+use crate::drive::defaults::PROTOCOL_VERSION;
 use crate::fee_pools::epochs::Epoch;
 
 /// Block information
-#[derive(Clone, Default)]
+#[derive(Clone)]
 pub struct BlockInfo {
     /// Block time in milliseconds
     pub time_ms: u64,
@@ -11,6 +12,20 @@ pub struct BlockInfo {
 
     /// Current fee epoch
     pub epoch: Epoch,
+
+    /// Protocol version of the block
+    pub protocol_version: u32,
+}
+
+impl Default for BlockInfo {
+    fn default() -> Self {
+        BlockInfo {
+            time_ms: 0,
+            height: 0,
+            epoch: Epoch::default(),
+            protocol_version: PROTOCOL_VERSION,
+        }
+    }
 }
 
 impl BlockInfo {
@@ -1,5 +1,6 @@
 use crate::drive::contract::ContractFetchInfo;
 use moka::sync::Cache;
+use std::collections::HashSet;
 use std::sync::Arc;
 
 /// Drive cache struct
@@ -14,6 +15,7 @@ pub struct DriveCache {
 pub struct DataContractCache {
     global_cache: Cache<[u8; 32], Arc<ContractFetchInfo>>,
     block_cache: Cache<[u8; 32], Arc<ContractFetchInfo>>,
+    block_removed_contract_ids: HashSet<[u8; 32]>,
 }
 
 impl DataContractCache {
@@ -22,6 +24,7 @@ impl DataContractCache {
         Self {
             global_cache: Cache::new(global_cache_max_capacity),
             block_cache: Cache::new(block_cache_max_capacity),
+            block_removed_contract_ids: HashSet::new(),
         }
     }
 
@@ -31,6 +34,8 @@ impl DataContractCache {
         let data_contract_id_bytes = fetch_info.contract.id().to_buffer();
 
         if is_block_cache {
+            self.block_removed_contract_ids
+                .remove(&data_contract_id_bytes);
             self.block_cache.insert(data_contract_id_bytes, fetch_info);
         } else {
             self.global_cache.insert(data_contract_id_bytes, fetch_info);
@@ -40,12 +45,17 @@ impl DataContractCache {
     /// Tries to get a data contract from black cache if present
     /// if block cache doesn't have the contract
     /// then it tries get the contract from global cache
+    /// unless the contract was removed within the block
     pub fn get(
         &self,
         contract_id: [u8; 32],
         is_block_cache: bool,
     ) -> Option<Arc<ContractFetchInfo>> {
         let maybe_fetch_info = if is_block_cache {
+            if self.block_removed_contract_ids.contains(&contract_id) {
+                return None;
+            }
+
             self.block_cache.get(&contract_id)
         } else {
             None
@@ -54,8 +64,25 @@ impl DataContractCache {
         maybe_fetch_info.or_else(|| self.global_cache.get(&contract_id))
     }
 
+    /// Removes a Data Contract from block cache and hides it from global cache
+    /// until the block cache is merged or cleared
+    /// otherwise it is removed from both caches
+    pub fn remove(&mut self, contract_id: [u8; 32], is_block_cache: bool) {
+        self.block_cache.invalidate(&contract_id);
+
+        if is_block_cache {
+            self.block_removed_contract_ids.insert(contract_id);
+        } else {
+            self.global_cache.invalidate(&contract_id);
+        }
+    }
+
     /// Merge block cache to global cache
     pub fn merge_block_cache(&mut self) {
+        for contract_id in self.block_removed_contract_ids.iter() {
+            self.global_cache.invalidate(contract_id);
+        }
+
         for (contract_id, fetch_info) in self.block_cache.iter() {
             self.global_cache.insert(*contract_id, fetch_info);
         }
@@ -64,6 +91,7 @@ impl DataContractCache {
     /// Clear block cache
     pub fn clear_block_cache(&mut self) {
         self.block_cache.invalidate_all();
+        self.block_removed_contract_ids.clear();
     }
 }
 
@@ -143,4 +171,51 @@ mod tests {
             assert_eq!(fetch_info_from_cache, fetch_info_block)
         }
     }
+
+    mod remove {
+        use super::*;
+
+        #[test]
+        fn test_remove_from_block_cache_hides_contract_until_merged() {
+            let mut data_contract_cache = DataContractCache::new(10, 10);
+
+            let fetch_info = Arc::new(ContractFetchInfo::default());
+
+            let contract_id = fetch_info.contract.id().to_buffer();
+
+            data_contract_cache.insert(Arc::clone(&fetch_info), false);
+
+            data_contract_cache.remove(contract_id, true);
+
+            assert!(data_contract_cache.get(contract_id, true).is_none());
+            assert!(data_contract_cache.get(contract_id, false).is_some());
+
+            data_contract_cache.merge_block_cache();
+            data_contract_cache.clear_block_cache();
+
+            assert!(data_contract_cache.get(contract_id, true).is_none());
+            assert!(data_contract_cache.get(contract_id, false).is_none());
+        }
+
+        #[test]
+        fn test_remove_from_block_cache_is_discarded_when_cleared() {
+            let mut data_contract_cache = DataContractCache::new(10, 10);
+
+            let fetch_info = Arc::new(ContractFetchInfo::default());
+
+            let contract_id = fetch_info.contract.id().to_buffer();
+
+            data_contract_cache.insert(Arc::clone(&fetch_info), false);
+
+            data_contract_cache.remove(contract_id, true);
+
+            data_contract_cache.clear_block_cache();
+
+            let fetch_info_from_cache = data_contract_cache
+                .get(contract_id, true)
+                .expect("should be present");
+
+            assert_eq!(fetch_info_from_cache, fetch_info)
+        }
+    }
 }
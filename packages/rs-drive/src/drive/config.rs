@@ -42,12 +42,7 @@ pub const DEFAULT_GROVE_HAS_RAW_ENABLED: bool = true;
 pub const DEFAULT_DATA_CONTRACTS_CACHE_SIZE: u64 = 500;
 
 /// Encoding for Drive
-pub enum DriveEncoding {
-    /// Drive CBOR
-    DriveCbor,
-    /// Drive protobuf
-    DriveProtobuf,
-}
+pub use dpp::data_contract::extra::DriveEncoding;
 
 /// Drive configuration struct
 pub struct DriveConfig {
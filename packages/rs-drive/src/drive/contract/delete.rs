@@ -0,0 +1,466 @@
+// MIT LICENSE
+//
+// Copyright (c) 2021 Dash Core Group
+//
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+//
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+//
+
+//! Contract Deletion.
+//!
+//! This module defines functions within the Drive struct related to deleting a contract
+//! along with all its document types and documents. Deletion is only allowed for
+//! contracts created with `canBeDeleted`. Removed bytes are refunded through the epochs
+//! and owners kept in the storage flags of every removed element.
+//!
+
+use std::collections::HashMap;
+
+use grovedb::batch::KeyInfoPath;
+use grovedb::query_result_type::QueryResultType::QueryKeyElementPairResultType;
+use grovedb::{Element, EstimatedLayerInformation, PathQuery, Query, SizedQuery, TransactionArg};
+
+use crate::contract::Contract;
+use crate::drive::batch::{ContractOperationType, DriveOperationType};
+use crate::drive::block_info::BlockInfo;
+use crate::drive::contract::{all_contracts_global_root_path, contract_root_path};
+use crate::drive::contract_documents_path;
+use crate::drive::defaults::{
+    CONTRACT_MAX_SERIALIZED_SIZE, EMPTY_TREE_STORAGE_SIZE,
+    ESTIMATED_CONTRACT_DELETION_DOCUMENTS_COUNT, MAX_CONTRACT_DELETION_ELEMENTS,
+};
+use crate::drive::grove_operations::BatchDeleteApplyType::{
+    StatefulBatchDelete, StatelessBatchDelete,
+};
+use crate::drive::Drive;
+use crate::error::document::DocumentError;
+use crate::error::drive::DriveError;
+use crate::error::Error;
+use crate::fee::op::DriveOperation;
+use crate::fee::result::FeeResult;
+use dpp::data_contract::extra::DriveContractExt;
+
+impl Drive {
+    /// Adds the operations deleting every element under the given path, deepest first,
+    /// as grovedb only deletes trees once they are empty.
+    /// Fails if more than `remaining_elements` elements would be deleted.
+    fn add_delete_subtree_contents_operations(
+        &self,
+        path: Vec<Vec<u8>>,
+        remaining_elements: &mut u16,
+        transaction: TransactionArg,
+        drive_operations: &mut Vec<DriveOperation>,
+    ) -> Result<(), Error> {
+        let mut query = Query::new();
+        query.insert_all();
+        // one more element than allowed is queried to know if there are too many
+        let path_query = PathQuery::new(
+            path.clone(),
+            SizedQuery::new(query, Some(remaining_elements.saturating_add(1)), None),
+        );
+
+        let (query_result, _) = self.grove_get_raw_path_query(
+            &path_query,
+            transaction,
+            QueryKeyElementPairResultType,
+            drive_operations,
+        )?;
+
+        let key_elements = query_result.to_key_elements();
+
+        *remaining_elements = u16::try_from(key_elements.len())
+            .ok()
+            .and_then(|elements_count| remaining_elements.checked_sub(elements_count))
+            .ok_or(Error::Drive(
+                DriveError::DeletingContractWithTooManyElements(
+                    "contract has too many documents to be deleted at once, delete documents first",
+                ),
+            ))?;
+
+        for (key, element) in key_elements {
+            let is_sum_tree = matches!(element, Element::SumTree(..));
+            let is_tree = is_sum_tree || matches!(element, Element::Tree(..));
+            if is_tree {
+                let mut subtree_path = path.clone();
+                subtree_path.push(key.clone());
+                self.add_delete_subtree_contents_operations(
+                    subtree_path,
+                    remaining_elements,
+                    transaction,
+                    drive_operations,
+                )?;
+            }
+
+            // the deletions of the subtree contents are in the current batch
+            // so the subtree is considered empty
+            self.batch_delete(
+                path.iter().map(|segment| segment.as_slice()),
+                key.as_slice(),
+                StatefulBatchDelete {
+                    is_known_to_be_subtree_with_sum: Some((is_tree, is_sum_tree)),
+                },
+                transaction,
+                drive_operations,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Adds the estimated operations deleting the contents of a contract.
+    /// The stored documents are unknown, so `ESTIMATED_CONTRACT_DELETION_DOCUMENTS_COUNT`
+    /// documents of an average size are removed from every document type.
+    fn add_estimated_delete_contract_contents_operations(
+        &self,
+        contract: &Contract,
+        estimated_costs_only_with_layer_info: &mut Option<
+            HashMap<KeyInfoPath, EstimatedLayerInformation>,
+        >,
+        transaction: TransactionArg,
+        drive_operations: &mut Vec<DriveOperation>,
+    ) -> Result<(), Error> {
+        if let Some(estimated_costs_only_with_layer_info) = estimated_costs_only_with_layer_info {
+            Self::add_estimation_costs_for_contract_insertion(
+                contract,
+                estimated_costs_only_with_layer_info,
+            );
+        }
+
+        let contract_id = contract.id.to_buffer();
+
+        for (document_type_name, document_type) in contract.document_types() {
+            for _ in 0..ESTIMATED_CONTRACT_DELETION_DOCUMENTS_COUNT {
+                self.add_estimated_delete_document_operations(
+                    contract,
+                    document_type,
+                    estimated_costs_only_with_layer_info,
+                    transaction,
+                    drive_operations,
+                )?;
+            }
+
+            self.batch_delete(
+                contract_documents_path(&contract_id),
+                document_type_name.as_bytes(),
+                StatelessBatchDelete {
+                    is_sum_tree: false,
+                    estimated_value_size: EMPTY_TREE_STORAGE_SIZE,
+                },
+                transaction,
+                drive_operations,
+            )?;
+        }
+
+        self.batch_delete(
+            contract_root_path(&contract_id),
+            &[1],
+            StatelessBatchDelete {
+                is_sum_tree: false,
+                estimated_value_size: EMPTY_TREE_STORAGE_SIZE,
+            },
+            transaction,
+            drive_operations,
+        )?;
+
+        // the contract history is not estimated, only the latest version is removed
+        self.batch_delete(
+            contract_root_path(&contract_id),
+            &[0],
+            StatelessBatchDelete {
+                is_sum_tree: false,
+                estimated_value_size: CONTRACT_MAX_SERIALIZED_SIZE as u32,
+            },
+            transaction,
+            drive_operations,
+        )
+    }
+
+    /// Gathers the operations to delete a contract with all its document types and
+    /// documents. Fails if the contract does not exist, can not be deleted or has more
+    /// than `MAX_CONTRACT_DELETION_ELEMENTS` elements. The deletion isn't staged over
+    /// several blocks, as the contract must be gone once the transition is applied,
+    /// so bigger contracts need documents to be deleted before the contract.
+    /// The contract is still fetched when only estimating costs, the contents are estimated.
+    pub(crate) fn delete_contract_operations(
+        &self,
+        contract_id: [u8; 32],
+        block_info: &BlockInfo,
+        estimated_costs_only_with_layer_info: &mut Option<
+            HashMap<KeyInfoPath, EstimatedLayerInformation>,
+        >,
+        transaction: TransactionArg,
+    ) -> Result<Vec<DriveOperation>, Error> {
+        let mut drive_operations: Vec<DriveOperation> = vec![];
+
+        let contract_fetch_info = self
+            .get_contract_with_fetch_info_and_add_to_operations(
+                contract_id,
+                Some(&block_info.epoch),
+                transaction,
+                &mut drive_operations,
+            )?
+            .ok_or(Error::Document(DocumentError::ContractNotFound()))?;
+
+        if !contract_fetch_info.contract.can_be_deleted() {
+            return Err(Error::Drive(
+                DriveError::DeletingContractThatCanNotBeDeleted(
+                    "contract was not created with canBeDeleted",
+                ),
+            ));
+        }
+
+        if estimated_costs_only_with_layer_info.is_some() {
+            self.add_estimated_delete_contract_contents_operations(
+                &contract_fetch_info.contract,
+                estimated_costs_only_with_layer_info,
+                transaction,
+                &mut drive_operations,
+            )?;
+
+            self.batch_delete(
+                all_contracts_global_root_path(),
+                contract_id.as_slice(),
+                StatelessBatchDelete {
+                    is_sum_tree: false,
+                    estimated_value_size: EMPTY_TREE_STORAGE_SIZE,
+                },
+                transaction,
+                &mut drive_operations,
+            )?;
+        } else {
+            let contract_root_path = contract_root_path(&contract_id)
+                .into_iter()
+                .map(|segment| segment.to_vec())
+                .collect();
+            let mut remaining_elements = MAX_CONTRACT_DELETION_ELEMENTS;
+            self.add_delete_subtree_contents_operations(
+                contract_root_path,
+                &mut remaining_elements,
+                transaction,
+                &mut drive_operations,
+            )?;
+
+            self.batch_delete(
+                all_contracts_global_root_path(),
+                contract_id.as_slice(),
+                StatefulBatchDelete {
+                    is_known_to_be_subtree_with_sum: Some((true, false)),
+                },
+                transaction,
+                &mut drive_operations,
+            )?;
+        }
+
+        Ok(drive_operations)
+    }
+
+    /// Deletes a contract with all its document types and documents.
+    /// The fee result refunds the removed storage to the identities that paid for it.
+    /// If `apply` is false, the contract is kept and only the estimated fees are returned.
+    pub fn delete_contract(
+        &self,
+        contract_id: [u8; 32],
+        block_info: BlockInfo,
+        apply: bool,
+        transaction: TransactionArg,
+    ) -> Result<FeeResult, Error> {
+        self.apply_drive_operations(
+            vec![DriveOperationType::ContractOperation(
+                ContractOperationType::DeleteContract { contract_id },
+            )],
+            apply,
+            &block_info,
+            transaction,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use crate::common::helpers::setup::setup_drive_with_initial_state_structure;
+    use crate::common::json_document_to_cbor;
+    use crate::contract::Contract;
+    use crate::drive::block_info::BlockInfo;
+    use crate::drive::flags::StorageFlags;
+    use crate::drive::Drive;
+    use crate::error::drive::DriveError;
+    use crate::error::Error;
+    use crate::fee::default_costs::STORAGE_DISK_USAGE_CREDIT_PER_BYTE;
+    use crate::fee_pools::epochs::Epoch;
+    use dpp::data_contract::extra::DriveContractExt;
+    use grovedb::TransactionArg;
+
+    fn apply_contract_with_owner(
+        drive: &Drive,
+        path: &str,
+        owner_id: [u8; 32],
+        transaction: TransactionArg,
+    ) -> Contract {
+        let contract_cbor = json_document_to_cbor(path, Some(1));
+        let contract = <Contract as DriveContractExt>::from_cbor(&contract_cbor, None)
+            .expect("expected to deserialize the contract");
+        drive
+            .apply_contract(
+                &contract,
+                contract_cbor,
+                BlockInfo::default(),
+                true,
+                Some(&StorageFlags::SingleEpochOwned(0, owner_id)),
+                transaction,
+            )
+            .expect("expected to apply contract successfully");
+        contract
+    }
+
+    #[test]
+    fn test_delete_contract_refunds_removed_storage() {
+        let drive = setup_drive_with_initial_state_structure();
+
+        let transaction = drive.grove.start_transaction();
+
+        let owner_id = rand::thread_rng().gen::<[u8; 32]>();
+
+        let contract = apply_contract_with_owner(
+            &drive,
+            "tests/supporting_files/contract/dashpay/dashpay-contract-can-be-deleted.json",
+            owner_id,
+            Some(&transaction),
+        );
+
+        let dashpay_profile_serialized_document = json_document_to_cbor(
+            "tests/supporting_files/contract/dashpay/profile0.json",
+            Some(1),
+        );
+
+        let fee_result = drive
+            .add_serialized_document_for_contract(
+                &dashpay_profile_serialized_document,
+                &contract,
+                "profile",
+                Some(owner_id),
+                false,
+                BlockInfo::default(),
+                true,
+                Some(&StorageFlags::SingleEpochOwned(0, owner_id)),
+                Some(&transaction),
+            )
+            .expect("expected to insert a document successfully");
+
+        let document_added_bytes = fee_result.storage_fee / STORAGE_DISK_USAGE_CREDIT_PER_BYTE;
+
+        let fee_result = drive
+            .delete_contract(
+                contract.id.to_buffer(),
+                BlockInfo::default_with_epoch(Epoch::new(3)),
+                true,
+                Some(&transaction),
+            )
+            .expect("expected to delete the contract");
+
+        let removed_credits = fee_result
+            .fee_refunds
+            .get(&owner_id)
+            .expect("expected refunds for the owner")
+            .get(&0)
+            .expect("expected refunds for the epoch the storage was paid in");
+
+        // the contract and its document types were removed along with the document
+        assert!(
+            removed_credits.to_unsigned() / STORAGE_DISK_USAGE_CREDIT_PER_BYTE
+                > document_added_bytes
+        );
+
+        let (_, contract_fetch_info) = drive
+            .get_contract_with_fetch_info(contract.id.to_buffer(), None, Some(&transaction))
+            .expect("expected to fetch the contract");
+
+        assert!(contract_fetch_info.is_none());
+    }
+
+    #[test]
+    fn test_delete_contract_that_can_not_be_deleted() {
+        let drive = setup_drive_with_initial_state_structure();
+
+        let transaction = drive.grove.start_transaction();
+
+        let contract = apply_contract_with_owner(
+            &drive,
+            "tests/supporting_files/contract/dashpay/dashpay-contract.json",
+            rand::thread_rng().gen::<[u8; 32]>(),
+            Some(&transaction),
+        );
+
+        let result = drive.delete_contract(
+            contract.id.to_buffer(),
+            BlockInfo::default(),
+            true,
+            Some(&transaction),
+        );
+
+        assert!(matches!(
+            result,
+            Err(Error::Drive(
+                DriveError::DeletingContractThatCanNotBeDeleted(_)
+            ))
+        ));
+
+        let (_, contract_fetch_info) = drive
+            .get_contract_with_fetch_info(contract.id.to_buffer(), None, Some(&transaction))
+            .expect("expected to fetch the contract");
+
+        assert!(contract_fetch_info.is_some());
+    }
+
+    #[test]
+    fn test_delete_contract_estimated_costs() {
+        let drive = setup_drive_with_initial_state_structure();
+
+        let transaction = drive.grove.start_transaction();
+
+        let contract = apply_contract_with_owner(
+            &drive,
+            "tests/supporting_files/contract/dashpay/dashpay-contract-can-be-deleted.json",
+            rand::thread_rng().gen::<[u8; 32]>(),
+            Some(&transaction),
+        );
+
+        let fee_result = drive
+            .delete_contract(
+                contract.id.to_buffer(),
+                BlockInfo::default(),
+                false,
+                Some(&transaction),
+            )
+            .expect("expected to estimate the contract deletion");
+
+        assert!(fee_result.processing_fee > 0);
+
+        let (_, contract_fetch_info) = drive
+            .get_contract_with_fetch_info(contract.id.to_buffer(), None, Some(&transaction))
+            .expect("expected to fetch the contract");
+
+        assert!(contract_fetch_info.is_some());
+    }
+}
@@ -0,0 +1,378 @@
+// MIT LICENSE
+//
+// Copyright (c) 2022 Dash Core Group
+//
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+//
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+//
+
+//! Contract History.
+//!
+//! This module implements functions in Drive fetching the versions of contracts keeping
+//! history, either the version at a given time or the versions stored since a given time,
+//! and the matching stateless functions verifying proofs of such versions.
+//!
+
+use std::collections::BTreeMap;
+
+use dpp::data_contract::extra::DriveContractExt;
+use grovedb::query_result_type::QueryResultType::QueryKeyElementPairResultType;
+use grovedb::{Element, PathQuery, Query, SizedQuery, TransactionArg};
+
+use crate::common::encode::{decode_unsigned_integer, encode_unsigned_integer};
+use crate::contract::Contract;
+use crate::drive::contract::contract_keeping_history_storage_path;
+use crate::drive::Drive;
+use crate::error::drive::DriveError;
+use crate::error::Error;
+use crate::fee::calculate_fee;
+use crate::fee::op::DriveOperation;
+use crate::fee::result::FeeResult;
+use crate::fee_pools::epochs::Epoch;
+
+/// Returns the path to the versions of a contract keeping history as a vector.
+fn contract_keeping_history_storage_path_vec(contract_id: [u8; 32]) -> Vec<Vec<u8>> {
+    contract_keeping_history_storage_path(&contract_id)
+        .into_iter()
+        .map(|a| a.to_vec())
+        .collect()
+}
+
+/// Returns the path query of the version of a contract stored at or before the given time.
+/// Versions are stored under the encoded time in milliseconds at which they were stored,
+/// next to the reference to the latest version at key 0 which is left out.
+fn contract_at_time_path_query(contract_id: [u8; 32], time_ms: u64) -> Result<PathQuery, Error> {
+    let mut query = Query::new_with_direction(false);
+    query.insert_range_inclusive(encode_unsigned_integer(0)?..=encode_unsigned_integer(time_ms)?);
+
+    Ok(PathQuery::new(
+        contract_keeping_history_storage_path_vec(contract_id),
+        SizedQuery::new(query, Some(1), None),
+    ))
+}
+
+/// Returns the path query of the versions of a contract stored at or after the given time,
+/// oldest first.
+fn contract_history_path_query(
+    contract_id: [u8; 32],
+    start_at_ms: u64,
+    limit: Option<u16>,
+) -> Result<PathQuery, Error> {
+    let mut query = Query::new();
+    query.insert_range_from(encode_unsigned_integer(start_at_ms)?..);
+
+    Ok(PathQuery::new(
+        contract_keeping_history_storage_path_vec(contract_id),
+        SizedQuery::new(query, limit, None),
+    ))
+}
+
+/// Converts the versions of a contract with their keys to the contracts by the time at
+/// which they were stored.
+fn contract_versions_from_elements(
+    elements: Vec<(Vec<u8>, Element)>,
+) -> Result<BTreeMap<u64, Contract>, Error> {
+    elements
+        .into_iter()
+        .map(|(key, element)| {
+            let time_ms = decode_unsigned_integer(key.as_slice())?;
+            if let Element::Item(stored_contract_bytes, _) = element {
+                let contract =
+                    <Contract as DriveContractExt>::from_cbor(&stored_contract_bytes, None)
+                        .map_err(Error::Contract)?;
+                Ok((time_ms, contract))
+            } else {
+                Err(Error::Drive(DriveError::CorruptedContractPath(
+                    "contract history did not refer to a contract element",
+                )))
+            }
+        })
+        .collect()
+}
+
+impl Drive {
+    /// Fetches the versions of a contract keeping history matching the path query.
+    /// Contracts which don't exist have no versions.
+    fn fetch_contract_versions_operations(
+        &self,
+        path_query: &PathQuery,
+        transaction: TransactionArg,
+        drive_operations: &mut Vec<DriveOperation>,
+    ) -> Result<BTreeMap<u64, Contract>, Error> {
+        let query_result = self.grove_get_raw_path_query(
+            path_query,
+            transaction,
+            QueryKeyElementPairResultType,
+            drive_operations,
+        );
+
+        match query_result {
+            Err(Error::GroveDB(
+                grovedb::Error::PathKeyNotFound(_)
+                | grovedb::Error::PathNotFound(_)
+                | grovedb::Error::PathParentLayerNotFound(_),
+            )) => Ok(BTreeMap::new()),
+            _ => {
+                let (query_result, _) = query_result?;
+                contract_versions_from_elements(query_result.to_key_elements())
+            }
+        }
+    }
+
+    /// Fetches the version of a contract keeping history which was the latest at the given
+    /// time in milliseconds. Returns `None` if the contract didn't exist at that time.
+    /// Contracts not keeping history only have their latest version, see `fetch_contract`.
+    pub fn fetch_contract_at_time(
+        &self,
+        contract_id: [u8; 32],
+        time_ms: u64,
+        epoch: Option<&Epoch>,
+        transaction: TransactionArg,
+    ) -> Result<(Option<FeeResult>, Option<Contract>), Error> {
+        let mut drive_operations: Vec<DriveOperation> = vec![];
+
+        let contract = self
+            .fetch_contract_versions_operations(
+                &contract_at_time_path_query(contract_id, time_ms)?,
+                transaction,
+                &mut drive_operations,
+            )?
+            .into_values()
+            .next();
+
+        let fee_result = epoch.map_or(Ok(None), |epoch| {
+            calculate_fee(None, Some(drive_operations), epoch).map(Some)
+        })?;
+        Ok((fee_result, contract))
+    }
+
+    /// Fetches the versions of a contract keeping history stored at or after the given time
+    /// in milliseconds, by the time at which they were stored, up to the limit if any.
+    pub fn fetch_contract_history(
+        &self,
+        contract_id: [u8; 32],
+        start_at_ms: u64,
+        limit: Option<u16>,
+        epoch: Option<&Epoch>,
+        transaction: TransactionArg,
+    ) -> Result<(Option<FeeResult>, BTreeMap<u64, Contract>), Error> {
+        let mut drive_operations: Vec<DriveOperation> = vec![];
+
+        let contracts = self.fetch_contract_versions_operations(
+            &contract_history_path_query(contract_id, start_at_ms, limit)?,
+            transaction,
+            &mut drive_operations,
+        )?;
+
+        let fee_result = epoch.map_or(Ok(None), |epoch| {
+            calculate_fee(None, Some(drive_operations), epoch).map(Some)
+        })?;
+        Ok((fee_result, contracts))
+    }
+
+    /// Proves the version of a contract keeping history which was the latest at the given
+    /// time in milliseconds, or that there was none.
+    pub fn prove_contract_at_time(
+        &self,
+        contract_id: [u8; 32],
+        time_ms: u64,
+        transaction: TransactionArg,
+    ) -> Result<Vec<u8>, Error> {
+        let mut drive_operations: Vec<DriveOperation> = vec![];
+        self.grove_get_proved_path_query(
+            &contract_at_time_path_query(contract_id, time_ms)?,
+            transaction,
+            &mut drive_operations,
+        )
+    }
+
+    /// Proves the versions of a contract keeping history stored at or after the given time
+    /// in milliseconds, up to the limit if any.
+    pub fn prove_contract_history(
+        &self,
+        contract_id: [u8; 32],
+        start_at_ms: u64,
+        limit: Option<u16>,
+        transaction: TransactionArg,
+    ) -> Result<Vec<u8>, Error> {
+        let mut drive_operations: Vec<DriveOperation> = vec![];
+        self.grove_get_proved_path_query(
+            &contract_history_path_query(contract_id, start_at_ms, limit)?,
+            transaction,
+            &mut drive_operations,
+        )
+    }
+
+    /// Verifies a proof of the version of a contract at the given time in milliseconds
+    /// against the expected root hash. Returns `None` if the proof shows that there was no
+    /// version at that time.
+    pub fn verify_contract_at_time(
+        proof: &[u8],
+        contract_id: [u8; 32],
+        time_ms: u64,
+        expected_root_hash: [u8; 32],
+    ) -> Result<Option<Contract>, Error> {
+        let proved_elements = Self::grove_verify_proved_path_query(
+            proof,
+            &contract_at_time_path_query(contract_id, time_ms)?,
+            expected_root_hash,
+        )?;
+
+        Ok(contract_versions_from_elements(proved_elements)?
+            .into_values()
+            .next())
+    }
+
+    /// Verifies a proof of the versions of a contract stored at or after the given time in
+    /// milliseconds against the expected root hash and returns them by the time at which
+    /// they were stored.
+    pub fn verify_contract_history(
+        proof: &[u8],
+        contract_id: [u8; 32],
+        start_at_ms: u64,
+        limit: Option<u16>,
+        expected_root_hash: [u8; 32],
+    ) -> Result<BTreeMap<u64, Contract>, Error> {
+        let proved_elements = Self::grove_verify_proved_path_query(
+            proof,
+            &contract_history_path_query(contract_id, start_at_ms, limit)?,
+            expected_root_hash,
+        )?;
+
+        contract_versions_from_elements(proved_elements)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::helpers::setup::setup_drive_with_initial_state_structure;
+    use crate::common::json_document_to_cbor;
+    use crate::contract::Contract;
+    use crate::drive::block_info::BlockInfo;
+    use crate::drive::flags::StorageFlags;
+    use crate::drive::Drive;
+    use dpp::data_contract::extra::DriveContractExt;
+
+    #[test]
+    fn test_fetch_and_prove_contract_history() {
+        let drive = setup_drive_with_initial_state_structure();
+
+        let contract_path =
+            "tests/supporting_files/contract/references/references_with_contract_history.json";
+        let contract_cbor = json_document_to_cbor(contract_path, Some(1));
+        let contract = <Contract as DriveContractExt>::from_cbor(&contract_cbor, None)
+            .expect("expected to deserialize the contract");
+
+        drive
+            .apply_contract(
+                &contract,
+                contract_cbor,
+                BlockInfo::default_with_time(1000),
+                true,
+                StorageFlags::optional_default_as_ref(),
+                None,
+            )
+            .expect("expected to apply contract successfully");
+
+        let mut updated_contract = contract.clone();
+        updated_contract.version = 2;
+        let updated_contract_cbor = updated_contract
+            .to_cbor()
+            .expect("expected to serialize the contract");
+
+        drive
+            .apply_contract(
+                &updated_contract,
+                updated_contract_cbor,
+                BlockInfo::default_with_time(2000),
+                true,
+                StorageFlags::optional_default_as_ref(),
+                None,
+            )
+            .expect("expected to update contract successfully");
+
+        let contract_id = *contract.id();
+
+        let (_, contract_at_time) = drive
+            .fetch_contract_at_time(contract_id, 1500, None, None)
+            .expect("expected to fetch the contract at a time");
+        assert_eq!(contract_at_time.map(|contract| contract.version), Some(1));
+
+        let (_, contract_at_time) = drive
+            .fetch_contract_at_time(contract_id, 500, None, None)
+            .expect("expected to fetch the contract at a time");
+        assert!(contract_at_time.is_none());
+
+        let latest_contract = drive
+            .fetch_contract(contract_id, None, None)
+            .unwrap()
+            .expect("expected to fetch the contract")
+            .expect("expected the contract to exist");
+        assert_eq!(latest_contract.contract.version, 2);
+
+        let (_, contract_history) = drive
+            .fetch_contract_history(contract_id, 0, None, None, None)
+            .expect("expected to fetch the contract history");
+        assert_eq!(
+            contract_history
+                .iter()
+                .map(|(time_ms, contract)| (*time_ms, contract.version))
+                .collect::<Vec<(u64, u32)>>(),
+            vec![(1000, 1), (2000, 2)]
+        );
+
+        let root_hash = drive
+            .grove
+            .root_hash(None)
+            .unwrap()
+            .expect("expected a root hash");
+
+        let proof = drive
+            .prove_contract_at_time(contract_id, 1500, None)
+            .expect("expected to prove the contract at a time");
+        let proved_contract =
+            Drive::verify_contract_at_time(proof.as_slice(), contract_id, 1500, root_hash)
+                .expect("expected to verify the contract at a time");
+        assert_eq!(proved_contract.map(|contract| contract.version), Some(1));
+
+        let proof = drive
+            .prove_contract_history(contract_id, 1500, Some(10), None)
+            .expect("expected to prove the contract history");
+        let proved_history = Drive::verify_contract_history(
+            proof.as_slice(),
+            contract_id,
+            1500,
+            Some(10),
+            root_hash,
+        )
+        .expect("expected to verify the contract history");
+        assert_eq!(
+            proved_history
+                .iter()
+                .map(|(time_ms, contract)| (*time_ms, contract.version))
+                .collect::<Vec<(u64, u32)>>(),
+            vec![(2000, 2)]
+        );
+    }
+}
@@ -32,7 +32,9 @@
 //! This module defines functions pertinent to Contracts stored in Drive.
 //!
 
+mod delete;
 mod estimation_costs;
+mod history;
 
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
@@ -45,7 +47,7 @@ use grovedb::batch::key_info::KeyInfo;
 use grovedb::batch::KeyInfoPath;
 use grovedb::reference_path::ReferencePathType::SiblingReference;
 
-use grovedb::{Element, EstimatedLayerInformation, TransactionArg};
+use grovedb::{Element, EstimatedLayerInformation, PathQuery, Query, SizedQuery, TransactionArg};
 
 use crate::contract::Contract;
 use crate::drive::batch::GroveDbOpBatch;
@@ -56,18 +58,19 @@ use crate::drive::flags::StorageFlags;
 use crate::drive::object_size_info::DriveKeyInfo::{Key, KeyRef};
 
 use crate::drive::grove_operations::QueryTarget::QueryTargetValue;
-use crate::drive::grove_operations::{BatchInsertTreeApplyType, DirectQueryType};
+use crate::drive::grove_operations::{BatchInsertTreeApplyType, DirectQueryType, QueryType};
 use crate::drive::object_size_info::PathKeyElementInfo::{
     PathFixedSizeKeyElement, PathKeyElementSize,
 };
 use crate::drive::object_size_info::PathKeyInfo::PathFixedSizeKeyRef;
 use crate::drive::{contract_documents_path, Drive, RootTree};
 use crate::error::drive::DriveError;
+use crate::error::query::QueryError;
 use crate::error::Error;
-use crate::fee::calculate_fee;
 use crate::fee::op::DriveOperation;
 use crate::fee::op::DriveOperation::{CalculatedCostOperation, PreCalculatedFeeResult};
 use crate::fee::result::FeeResult;
+use crate::fee::{calculate_fee, calculate_fee_without_multiplier};
 use crate::fee_pools::epochs::Epoch;
 
 /// The global root path for all contracts
@@ -106,6 +109,20 @@ pub(crate) fn contract_keeping_history_storage_time_reference_path(
     ]
 }
 
+/// Returns the path query of the contract with the given ID.
+/// The contract is queried from the contracts subtree so that the absence
+/// of the contract can be proved as well.
+fn contract_path_query(contract_id: [u8; 32]) -> PathQuery {
+    let mut query = Query::new();
+    query.insert_key(contract_id.to_vec());
+    query.set_subquery_key(vec![0]);
+
+    PathQuery::new(
+        vec![vec![RootTree::ContractDocuments as u8]],
+        SizedQuery::new(query, None, None),
+    )
+}
+
 /// Adds operations to the op batch relevant to initializing the contract's structure.
 /// Namely it inserts an empty tree at the contract's root path.
 pub fn add_init_contracts_structure_operations(batch: &mut GroveDbOpBatch) {
@@ -124,16 +141,19 @@ pub struct ContractFetchInfo {
     pub(crate) cost: OperationCost,
     /// The fee is updated every epoch based on operation costs
     /// Except if protocol version has changed in which case all the cache is cleared
+    /// It doesn't include the fee multiplier, which is applied when the fee is charged
     pub fee: Option<FeeResult>,
 }
 
 impl Drive {
     /// Adds a contract to storage.
+    /// The history tree of a contract keeping history is only inserted with its first version.
     fn add_contract_to_storage(
         &self,
         contract_element: Element,
         contract: &Contract,
         block_info: &BlockInfo,
+        is_first_version: bool,
         estimated_costs_only_with_layer_info: &mut Option<
             HashMap<KeyInfoPath, EstimatedLayerInformation>,
         >,
@@ -153,12 +173,14 @@ impl Drive {
                 );
             }
 
-            self.batch_insert_empty_tree(
-                contract_root_path,
-                KeyRef(&[0]),
-                storage_flags.as_ref(),
-                insert_operations,
-            )?;
+            if is_first_version {
+                self.batch_insert_empty_tree(
+                    contract_root_path,
+                    KeyRef(&[0]),
+                    storage_flags.as_ref(),
+                    insert_operations,
+                )?;
+            }
             let encoded_time = encode_unsigned_integer(block_info.time_ms)?;
             let contract_keeping_history_storage_path =
                 contract_keeping_history_storage_path(contract.id.as_bytes());
@@ -324,6 +346,7 @@ impl Drive {
             contract_element,
             contract,
             block_info,
+            true,
             estimated_costs_only_with_layer_info,
             &mut batch_operations,
         )?;
@@ -467,7 +490,7 @@ impl Drive {
                 "contract should exist",
             )))?;
 
-        let mut drive_cache = self.cache.borrow_mut();
+        let mut drive_cache = self.write_cache()?;
 
         drive_cache
             .cached_contracts
@@ -587,6 +610,7 @@ impl Drive {
             contract_element,
             contract,
             block_info,
+            false,
             estimated_costs_only_with_layer_info,
             &mut batch_operations,
         )?;
@@ -647,6 +671,16 @@ impl Drive {
                         index_cache.insert(index_bytes);
                     }
                 }
+
+                // existing documents must also be referenced by the indices added in this update
+                self.add_index_backfill_operations(
+                    contract,
+                    document_type,
+                    original_document_type,
+                    estimated_costs_only_with_layer_info,
+                    transaction,
+                    &mut batch_operations,
+                )?;
             } else {
                 // We can just insert this directly because the original document type already exists
                 self.batch_insert_empty_tree(
@@ -714,6 +748,30 @@ impl Drive {
         )
     }
 
+    /// Applies a contract serialized in the encoding set in the Drive config.
+    pub fn apply_serialized_contract(
+        &self,
+        serialized_contract: &[u8],
+        contract_id: Option<[u8; 32]>,
+        block_info: BlockInfo,
+        apply: bool,
+        storage_flags: Option<&StorageFlags>,
+        transaction: TransactionArg,
+    ) -> Result<FeeResult, Error> {
+        let contract_cbor = self
+            .serialization_to_cbor(serialized_contract)?
+            .into_owned();
+
+        self.apply_contract_cbor(
+            contract_cbor,
+            contract_id,
+            block_info,
+            apply,
+            storage_flags,
+            transaction,
+        )
+    }
+
     /// Returns the contract with fetch info and operations with the given ID.
     pub fn get_contract_with_fetch_info(
         &self,
@@ -743,7 +801,7 @@ impl Drive {
         transaction: TransactionArg,
         drive_operations: &mut Vec<DriveOperation>,
     ) -> Result<Option<Arc<ContractFetchInfo>>, Error> {
-        let mut cache = self.cache.borrow_mut();
+        let mut cache = self.write_cache()?;
 
         match cache
             .cached_contracts
@@ -772,9 +830,10 @@ impl Drive {
                     let fee = if let Some(known_fee) = &contract_fetch_info.fee {
                         known_fee.clone()
                     } else {
-                        // we need to calculate new fee
+                        // we need to calculate new fee, the fee multiplier of the epoch is
+                        // applied when the fee is charged
                         let op = vec![CalculatedCostOperation(contract_fetch_info.cost.clone())];
-                        let fee = calculate_fee(None, Some(op), epoch)?;
+                        let fee = calculate_fee_without_multiplier(op, epoch)?;
 
                         let updated_contract_fetch_info = Arc::new(ContractFetchInfo {
                             contract: contract_fetch_info.contract.clone(),
@@ -836,12 +895,12 @@ impl Drive {
         &self,
         contract_id: [u8; 32],
         transaction: TransactionArg,
-    ) -> Option<Arc<ContractFetchInfo>> {
-        self.cache
-            .borrow()
+    ) -> Result<Option<Arc<ContractFetchInfo>>, Error> {
+        Ok(self
+            .read_cache()?
             .cached_contracts
             .get(contract_id, transaction.is_some())
-            .map(|fetch_info| Arc::clone(&fetch_info))
+            .map(|fetch_info| Arc::clone(&fetch_info)))
     }
 
     /// Returns the contract with the given ID from storage and also inserts it in cache.
@@ -851,10 +910,23 @@ impl Drive {
         epoch: Option<&Epoch>,
         transaction: TransactionArg,
     ) -> CostResult<Option<Arc<ContractFetchInfo>>, Error> {
-        let CostContext { value, cost } =
+        let CostContext { value, mut cost } =
             self.grove
                 .get(contract_root_path(&contract_id), &[0], transaction);
 
+        // the latest version of a contract keeping history is referenced at key 0 of its history
+        let value = match value {
+            Ok(Element::Tree(..)) => self
+                .grove
+                .get(
+                    contract_keeping_history_storage_path(&contract_id),
+                    &[0],
+                    transaction,
+                )
+                .unwrap_add_cost(&mut cost),
+            value => value,
+        };
+
         match value {
             Ok(Element::Item(stored_contract_bytes, element_flag)) => {
                 let contract = cost_return_on_error_no_add!(
@@ -866,7 +938,7 @@ impl Drive {
                 let fee = if let Some(epoch) = epoch {
                     Some(cost_return_on_error_no_add!(
                         &cost,
-                        calculate_fee(None, Some(vec![drive_operation]), epoch)
+                        calculate_fee_without_multiplier(vec![drive_operation], epoch)
                     ))
                 } else {
                     None
@@ -898,6 +970,64 @@ impl Drive {
         }
     }
 
+    /// Proves the contract with the given ID, or its absence if it doesn't exist.
+    /// The latest version of a contract keeping history is proved from its history.
+    pub fn prove_contract(
+        &self,
+        contract_id: [u8; 32],
+        transaction: TransactionArg,
+    ) -> Result<Vec<u8>, Error> {
+        let keeps_history = matches!(
+            self.grove
+                .get(contract_root_path(&contract_id), &[0], transaction)
+                .unwrap(),
+            Ok(Element::Tree(..))
+        );
+
+        if keeps_history {
+            return self.prove_contract_at_time(contract_id, u64::MAX, transaction);
+        }
+
+        let mut drive_operations: Vec<DriveOperation> = vec![];
+        self.grove_get_proved_path_query(
+            &contract_path_query(contract_id),
+            transaction,
+            &mut drive_operations,
+        )
+    }
+
+    /// Verifies a proof of the contract with the given ID against the expected root hash.
+    /// Returns `None` if the proof shows that the contract doesn't exist.
+    pub fn verify_contract(
+        proof: &[u8],
+        contract_id: [u8; 32],
+        expected_root_hash: [u8; 32],
+    ) -> Result<Option<Contract>, Error> {
+        let proved_elements = Self::grove_verify_proved_path_query(
+            proof,
+            &contract_path_query(contract_id),
+            expected_root_hash,
+        );
+
+        match proved_elements.as_deref() {
+            Ok([]) => Ok(None),
+            Ok([(_, Element::Item(stored_contract_bytes, _))]) => Ok(Some(
+                <Contract as DriveContractExt>::from_cbor(stored_contract_bytes, None)
+                    .map_err(Error::Contract)?,
+            )),
+            // the latest version of a contract keeping history is proved from its history
+            Ok([(_, Element::Tree(..))]) | Err(_) => {
+                Self::verify_contract_at_time(proof, contract_id, u64::MAX, expected_root_hash)
+            }
+            Ok([_]) => Err(Error::Drive(DriveError::CorruptedContractPath(
+                "contract path did not refer to a contract element",
+            ))),
+            Ok(_) => Err(Error::Query(QueryError::InvalidProof(
+                "proof must contain only the contract",
+            ))),
+        }
+    }
+
     /// Applies a contract and returns the fee for applying.
     /// If the contract already exists, an update is applied, otherwise an insert.
     pub fn apply_contract(
@@ -968,13 +1098,28 @@ impl Drive {
         };
 
         // We can do a get direct because there are no references involved
-        if let Ok(Some(stored_element)) = self.grove_get_direct(
+        let stored_element = match self.grove_get_direct(
             contract_root_path(contract.id.as_bytes()),
             &[0],
             direct_query_type,
             transaction,
             &mut drive_operations,
         ) {
+            // the latest version of a contract keeping history is referenced in its history
+            Ok(Some(Element::Tree(..))) => self
+                .grove_get(
+                    contract_keeping_history_storage_path(contract.id.as_bytes()),
+                    &[0],
+                    QueryType::StatefulQuery,
+                    transaction,
+                    &mut drive_operations,
+                )
+                .ok()
+                .flatten(),
+            stored_element => stored_element.ok().flatten(),
+        };
+
+        if let Some(stored_element) = stored_element {
             already_exists = true;
             match stored_element {
                 Element::Item(stored_contract_bytes, _) => {
@@ -1031,6 +1176,7 @@ mod tests {
     use tempfile::TempDir;
 
     use super::*;
+    use crate::common::helpers::setup::setup_drive_with_initial_state_structure;
     use crate::common::json_document_to_cbor;
     use crate::contract::Contract;
     use crate::drive::flags::StorageFlags;
@@ -1038,6 +1184,8 @@ mod tests {
         DocumentAndContractInfo, DocumentInfo, OwnedDocumentInfo,
     };
     use crate::drive::Drive;
+    use crate::fee::apply_fee_multiplier;
+    use crate::fee::default_costs::LATEST_FEE_VERSION;
 
     fn setup_deep_nested_contract() -> (Drive, Contract, Vec<u8>) {
         // Todo: make TempDir based on _prefix
@@ -1209,6 +1357,93 @@ mod tests {
             .expect("expected to insert a document successfully");
     }
 
+    #[test]
+    fn test_prove_and_verify_contract() {
+        let (drive, contract, _contract_cbor) = setup_reference_contract();
+
+        let root_hash = drive
+            .grove
+            .root_hash(None)
+            .unwrap()
+            .expect("expected to get root hash");
+
+        let proof = drive
+            .prove_contract(contract.id.to_buffer(), None)
+            .expect("expected to prove contract");
+
+        let proved_contract =
+            Drive::verify_contract(proof.as_slice(), contract.id.to_buffer(), root_hash)
+                .expect("expected to verify contract");
+
+        assert_eq!(proved_contract, Some(contract));
+
+        let proof = drive
+            .prove_contract([0; 32], None)
+            .expect("expected to prove contract absence");
+
+        assert_eq!(
+            Drive::verify_contract(proof.as_slice(), [0; 32], root_hash)
+                .expect("expected to verify contract absence"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_prove_and_verify_contract_keeping_history() {
+        let drive = setup_drive_with_initial_state_structure();
+
+        let contract_cbor = json_document_to_cbor(
+            "tests/supporting_files/contract/references/references_with_contract_history.json",
+            Some(1),
+        );
+        let contract = <Contract as DriveContractExt>::from_cbor(&contract_cbor, None)
+            .expect("expected to deserialize the contract");
+
+        drive
+            .apply_contract(
+                &contract,
+                contract_cbor,
+                BlockInfo::default_with_time(1000),
+                true,
+                StorageFlags::optional_default_as_ref(),
+                None,
+            )
+            .expect("expected to apply contract successfully");
+
+        let mut updated_contract = contract.clone();
+        updated_contract.version = 2;
+        let updated_contract_cbor = updated_contract
+            .to_cbor()
+            .expect("expected to serialize the contract");
+
+        drive
+            .apply_contract(
+                &updated_contract,
+                updated_contract_cbor,
+                BlockInfo::default_with_time(2000),
+                true,
+                StorageFlags::optional_default_as_ref(),
+                None,
+            )
+            .expect("expected to update contract successfully");
+
+        let root_hash = drive
+            .grove
+            .root_hash(None)
+            .unwrap()
+            .expect("expected to get root hash");
+
+        let proof = drive
+            .prove_contract(contract.id.to_buffer(), None)
+            .expect("expected to prove contract");
+
+        let proved_contract =
+            Drive::verify_contract(proof.as_slice(), contract.id.to_buffer(), root_hash)
+                .expect("expected to verify contract");
+
+        assert_eq!(proved_contract, Some(updated_contract));
+    }
+
     #[test]
     fn test_create_reference_contract_without_apply() {
         let tmp_dir = TempDir::new().unwrap();
@@ -1381,5 +1616,39 @@ mod tests {
             assert!(fees.is_some());
             assert_eq!(fees.unwrap().processing_fee, 6000)
         }
+
+        #[test]
+        fn test_get_contract_fee_has_the_fee_multiplier_applied_once() {
+            let (drive, contract, _) = setup_reference_contract();
+
+            let doubled_fee_epoch = Epoch::new_with_fee_parameters(0, LATEST_FEE_VERSION, None, 2.0);
+
+            let (doubled_fee, _) = drive
+                .get_contract_with_fetch_info(
+                    contract.id().to_buffer(),
+                    Some(&doubled_fee_epoch),
+                    None,
+                )
+                .expect("should get contract");
+            let doubled_fee = doubled_fee.expect("should have a fee");
+
+            let (neutral_fee, _) = drive
+                .get_contract_with_fetch_info(contract.id().to_buffer(), Some(&Epoch::new(0)), None)
+                .expect("should get contract");
+            let neutral_fee = neutral_fee.expect("should have a fee");
+
+            // the cached fee doesn't keep the fee multiplier of the epoch it was fetched in
+            let cached_fetch_info = drive
+                .get_cached_contract_with_fetch_info(contract.id().to_buffer(), None)
+                .expect("should read the cache")
+                .expect("should be cached");
+            assert_eq!(cached_fetch_info.fee.as_ref(), Some(&neutral_fee));
+            assert!(neutral_fee.processing_fee > 0);
+            assert_eq!(
+                doubled_fee.processing_fee,
+                apply_fee_multiplier(neutral_fee.processing_fee, 2.0)
+                    .expect("should apply the fee multiplier")
+            );
+        }
     }
 }
@@ -54,6 +54,8 @@ pub const DEFAULT_HASH_SIZE: u32 = 32;
 pub const DEFAULT_HASH_SIZE_U8: u8 = 32;
 /// Default hash size as u16
 pub const DEFAULT_HASH_SIZE_U16: u16 = 32;
+/// Public key hash (hash160) size as u8
+pub const DEFAULT_HASH_160_SIZE_U8: u8 = 20;
 /// Some optimized document reference size
 pub const OPTIMIZED_DOCUMENT_REFERENCE: u16 = 34; // 1 + hops + DEFAULT_HASH_SIZE
 /// Default float size
@@ -85,5 +87,31 @@ pub const AVERAGE_UPDATE_BYTE_COUNT_REQUIRED_SIZE: u8 = 1;
 /// The estimated average document type name size
 pub const ESTIMATED_AVERAGE_DOCUMENT_TYPE_NAME_SIZE: u8 = 12;
 
+/// The estimated average serialized identity key size
+pub const ESTIMATED_AVERAGE_IDENTITY_KEY_SIZE: u32 = 100;
+
+/// The estimated average number of keys of an identity
+pub const ESTIMATED_AVERAGE_IDENTITY_KEYS_COUNT: u32 = 4;
+
+/// The estimated average number of identities sharing a non-unique public key hash
+pub const ESTIMATED_NON_UNIQUE_PUBLIC_KEY_HASH_IDENTITIES_COUNT: u32 = 2;
+
 /// The estimated average index name size
 pub const ESTIMATED_AVERAGE_INDEX_NAME_SIZE: u8 = 16;
+
+/// How many existing documents are read at once when adding them to new indices
+pub const INDEX_BACKFILL_PAGE_SIZE: u16 = 100;
+
+/// The max number of existing documents a contract update can add to new indices
+pub const MAX_INDEX_BACKFILL_DOCUMENTS_COUNT: u16 = 1000;
+
+/// The estimated number of existing documents added to new indices by a contract update
+pub const ESTIMATED_INDEX_BACKFILL_DOCUMENTS_COUNT: u16 = 100;
+
+/// The estimated number of documents of each document type removed along with their contract
+pub const ESTIMATED_CONTRACT_DELETION_DOCUMENTS_COUNT: u16 = 100;
+
+/// The maximum number of elements, documents and their index entries included,
+/// removed by a contract deletion. Contracts with more elements can't be deleted
+/// until their owner deletes enough documents with documents batch transitions.
+pub const MAX_CONTRACT_DELETION_ELEMENTS: u16 = 10000;
@@ -735,6 +735,62 @@ impl Drive {
         )?;
         Ok(batch_operations)
     }
+
+    /// Adds the estimated operations removing a document of an average size from the primary
+    /// storage and the indices of its document type. Unlike the deletion of a document by its
+    /// owner, it applies to immutable documents and documents keeping history as well, as it
+    /// is used when documents are removed along with their contract.
+    /// Nothing is added if costs are not estimated.
+    pub(crate) fn add_estimated_delete_document_operations(
+        &self,
+        contract: &Contract,
+        document_type: &DocumentType,
+        estimated_costs_only_with_layer_info: &mut Option<
+            HashMap<KeyInfoPath, EstimatedLayerInformation>,
+        >,
+        transaction: TransactionArg,
+        batch_operations: &mut Vec<DriveOperation>,
+    ) -> Result<(), Error> {
+        match estimated_costs_only_with_layer_info {
+            Some(estimated_costs_only_with_layer_info) => {
+                Self::add_estimation_costs_for_levels_up_to_contract_document_type_excluded(
+                    contract,
+                    estimated_costs_only_with_layer_info,
+                );
+            }
+            None => return Ok(()),
+        }
+
+        let contract_documents_primary_key_path =
+            contract_documents_primary_key_path(contract.id.as_bytes(), &document_type.name);
+
+        // the document id is only used for its size when estimating
+        self.remove_document_from_primary_storage(
+            [0; 32],
+            document_type,
+            contract_documents_primary_key_path,
+            estimated_costs_only_with_layer_info,
+            transaction,
+            batch_operations,
+        )?;
+
+        let document_and_contract_info = DocumentAndContractInfo {
+            owned_document_info: OwnedDocumentInfo {
+                document_info: DocumentEstimatedAverageSize(document_type.estimated_size() as u32),
+                owner_id: None,
+            },
+            contract,
+            document_type,
+        };
+
+        self.remove_indices_for_top_index_level_for_contract_operations(
+            &document_and_contract_info,
+            &None,
+            estimated_costs_only_with_layer_info,
+            transaction,
+            batch_operations,
+        )
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,179 @@
+// MIT LICENSE
+//
+// Copyright (c) 2022 Dash Core Group
+//
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+//
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+//
+
+//! Document History.
+//!
+//! This module implements functions in Drive listing every revision stored for a document
+//! of a document type keeping history, along with the time at which it was stored, and
+//! the matching stateless function verifying proofs of such revisions.
+//!
+
+use dpp::data_contract::extra::{DocumentType, DriveContractExt};
+use grovedb::query_result_type::QueryResultType::QueryKeyElementPairResultType;
+use grovedb::{Element, PathQuery, Query, SizedQuery, TransactionArg};
+
+use crate::common::encode::{decode_unsigned_integer, encode_unsigned_integer};
+use crate::contract::document::Document;
+use crate::contract::Contract;
+use crate::drive::block_info::BlockInfo;
+use crate::drive::Drive;
+use crate::error::drive::DriveError;
+use crate::error::query::QueryError;
+use crate::error::Error;
+use crate::fee::calculate_fee;
+use crate::fee::op::DriveOperation;
+
+/// Returns the path query of every revision of a document, oldest first.
+/// Revisions are stored under the encoded time in milliseconds at which they were stored,
+/// next to the reference to the latest revision at key 0 which is left out.
+fn document_history_path_query(
+    contract: &Contract,
+    document_type: &DocumentType,
+    document_id: [u8; 32],
+) -> Result<PathQuery, Error> {
+    if !document_type.documents_keep_history {
+        return Err(Error::Query(QueryError::InvalidDocumentType(
+            "document type does not keep history",
+        )));
+    }
+
+    let path = contract
+        .documents_with_history_primary_key_path(document_type.name.as_str(), &document_id)
+        .into_iter()
+        .map(|a| a.to_vec())
+        .collect::<Vec<Vec<u8>>>();
+
+    let mut query = Query::new();
+    query.insert_range_from(encode_unsigned_integer(0)?..);
+
+    Ok(PathQuery::new(path, SizedQuery::new(query, None, None)))
+}
+
+/// Converts the revisions of a document with their keys to the times at which they were
+/// stored along with the documents.
+fn document_history_from_elements(
+    elements: Vec<(Vec<u8>, Element)>,
+    document_type: &DocumentType,
+) -> Result<Vec<(u64, Document)>, Error> {
+    elements
+        .into_iter()
+        .map(|(key, element)| {
+            let time_ms = decode_unsigned_integer(key.as_slice())?;
+            if let Element::Item(serialized_document, _) = element {
+                let document =
+                    Document::from_stored_bytes(serialized_document.as_slice(), document_type)?;
+                Ok((time_ms, document))
+            } else {
+                Err(Error::Drive(DriveError::CorruptedDocumentNotItem(
+                    "document revision must be an item",
+                )))
+            }
+        })
+        .collect()
+}
+
+impl Drive {
+    /// Fetches every revision of a document of a document type keeping history, oldest
+    /// first, along with the time in milliseconds at which each revision was stored.
+    /// Returns the revisions and the processing fee.
+    pub fn fetch_document_history(
+        &self,
+        contract: &Contract,
+        document_type_name: &str,
+        document_id: [u8; 32],
+        block_info: Option<BlockInfo>,
+        transaction: TransactionArg,
+    ) -> Result<(Vec<(u64, Document)>, u64), Error> {
+        let mut drive_operations: Vec<DriveOperation> = vec![];
+        let document_type = contract.document_type_for_name(document_type_name)?;
+        let path_query = document_history_path_query(contract, document_type, document_id)?;
+
+        let (query_result, _) = self.grove_get_raw_path_query(
+            &path_query,
+            transaction,
+            QueryKeyElementPairResultType,
+            &mut drive_operations,
+        )?;
+        let revisions =
+            document_history_from_elements(query_result.to_key_elements(), document_type)?;
+
+        let cost = if let Some(block_info) = block_info {
+            let fee_result = calculate_fee(None, Some(drive_operations), &block_info.epoch)?;
+            fee_result.processing_fee
+        } else {
+            0
+        };
+        Ok((revisions, cost))
+    }
+
+    /// Proves every revision of a document of a document type keeping history.
+    /// Returns the proof and the processing fee.
+    pub fn prove_document_history(
+        &self,
+        contract: &Contract,
+        document_type_name: &str,
+        document_id: [u8; 32],
+        block_info: Option<BlockInfo>,
+        transaction: TransactionArg,
+    ) -> Result<(Vec<u8>, u64), Error> {
+        let mut drive_operations: Vec<DriveOperation> = vec![];
+        let document_type = contract.document_type_for_name(document_type_name)?;
+        let path_query = document_history_path_query(contract, document_type, document_id)?;
+
+        let proof =
+            self.grove_get_proved_path_query(&path_query, transaction, &mut drive_operations)?;
+
+        let cost = if let Some(block_info) = block_info {
+            let fee_result = calculate_fee(None, Some(drive_operations), &block_info.epoch)?;
+            fee_result.processing_fee
+        } else {
+            0
+        };
+        Ok((proof, cost))
+    }
+
+    /// Verifies a proof of every revision of a document against the expected root hash and
+    /// returns the revisions, oldest first, along with the time in milliseconds at which
+    /// each was stored. Doesn't need access to Drive.
+    pub fn verify_document_history(
+        proof: &[u8],
+        contract: &Contract,
+        document_type_name: &str,
+        document_id: [u8; 32],
+        expected_root_hash: [u8; 32],
+    ) -> Result<Vec<(u64, Document)>, Error> {
+        let document_type = contract.document_type_for_name(document_type_name)?;
+        let path_query = document_history_path_query(contract, document_type, document_id)?;
+
+        let proved_elements =
+            Self::grove_verify_proved_path_query(proof, &path_query, expected_root_hash)?;
+
+        document_history_from_elements(proved_elements, document_type)
+    }
+}
@@ -0,0 +1,513 @@
+// MIT LICENSE
+//
+// Copyright (c) 2022 Dash Core Group
+//
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+//
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+//
+
+//! Index Backfill.
+//!
+//! This module defines functions within the Drive struct related to adding the existing
+//! documents of a document type to the indices a contract update adds to it.
+//! The backfill happens inline in the batch of the contract update, so that the update
+//! pays for it and new indices are never partially populated. Cost estimations of
+//! contract updates include the backfill of an estimated number of documents, applying
+//! them pays for the documents actually added. Updates adding indices to more than
+//! `MAX_INDEX_BACKFILL_DOCUMENTS_COUNT` existing documents are rejected.
+//!
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use dpp::data_contract::extra::{DocumentType, DriveContractExt, Index};
+use grovedb::batch::key_info::KeyInfo;
+use grovedb::batch::{KeyInfoPath, Op};
+use grovedb::query_result_type::QueryResultType::QueryKeyElementPairResultType;
+use grovedb::{Element, EstimatedLayerInformation, PathQuery, Query, SizedQuery, TransactionArg};
+
+use crate::contract::document::Document;
+use crate::contract::Contract;
+use crate::drive::defaults::{
+    ESTIMATED_INDEX_BACKFILL_DOCUMENTS_COUNT, INDEX_BACKFILL_PAGE_SIZE,
+    MAX_INDEX_BACKFILL_DOCUMENTS_COUNT,
+};
+use crate::drive::document::contract_documents_keeping_history_primary_key_path_for_document_id;
+use crate::drive::flags::StorageFlags;
+use crate::drive::grove_operations::QueryType::StatefulQuery;
+use crate::drive::object_size_info::DocumentInfo::{
+    DocumentEstimatedAverageSize, DocumentRefWithoutSerialization,
+};
+use crate::drive::object_size_info::{DocumentAndContractInfo, OwnedDocumentInfo};
+use crate::drive::Drive;
+use crate::error::drive::DriveError;
+use crate::error::Error;
+use crate::fee::op::DriveOperation;
+use crate::fee::op::DriveOperation::GroveOperation;
+
+/// Returns the indices of a document type which its original version did not have.
+fn added_indices(
+    document_type: &DocumentType,
+    original_document_type: &DocumentType,
+) -> Vec<Index> {
+    document_type
+        .indices
+        .iter()
+        .filter(|index| {
+            !original_document_type
+                .indices
+                .iter()
+                .any(|original_index| original_index.properties == index.properties)
+        })
+        .cloned()
+        .collect()
+}
+
+/// Returns the path and key at which the operation inserts a reference, if it does.
+fn reference_insertion_position(operation: &DriveOperation) -> Option<(KeyInfoPath, KeyInfo)> {
+    let GroveOperation(grove_op) = operation else {
+        return None;
+    };
+    if let Op::Insert {
+        element: Element::Reference(..),
+    } = grove_op.op
+    {
+        Some((grove_op.path.clone(), grove_op.key.clone()))
+    } else {
+        None
+    }
+}
+
+impl Drive {
+    /// Fetches a page of the latest revisions of the documents of a document type with their
+    /// storage flags, ordered by id and starting after the given document id.
+    fn fetch_documents_page_for_backfill(
+        &self,
+        contract: &Contract,
+        document_type: &DocumentType,
+        start_after: Option<[u8; 32]>,
+        transaction: TransactionArg,
+        drive_operations: &mut Vec<DriveOperation>,
+    ) -> Result<Vec<(Document, Option<StorageFlags>)>, Error> {
+        let path = contract
+            .documents_primary_key_path(document_type.name.as_str())
+            .into_iter()
+            .map(|a| a.to_vec())
+            .collect::<Vec<Vec<u8>>>();
+
+        let mut query = Query::new();
+        if let Some(start_after) = start_after {
+            query.insert_range_after(start_after.to_vec()..);
+        } else {
+            query.insert_all();
+        }
+
+        let path_query = PathQuery::new(
+            path,
+            SizedQuery::new(query, Some(INDEX_BACKFILL_PAGE_SIZE), None),
+        );
+
+        let (query_result, _) = self.grove_get_raw_path_query(
+            &path_query,
+            transaction,
+            QueryKeyElementPairResultType,
+            drive_operations,
+        )?;
+
+        query_result
+            .to_key_elements()
+            .into_iter()
+            .map(|(document_id, element)| {
+                let element = if document_type.documents_keep_history {
+                    // the latest revision is referenced at key 0 under the document id
+                    self.grove_get(
+                        contract_documents_keeping_history_primary_key_path_for_document_id(
+                            contract.id.as_bytes(),
+                            document_type.name.as_str(),
+                            document_id.as_slice(),
+                        ),
+                        &[0],
+                        StatefulQuery,
+                        transaction,
+                        drive_operations,
+                    )?
+                    .ok_or(Error::Drive(
+                        DriveError::CorruptedDocumentPath(
+                            "document keeping history must reference its latest revision",
+                        ),
+                    ))?
+                } else {
+                    element
+                };
+                if let Element::Item(serialized_document, element_flags) = element {
+                    Ok((
+                        Document::from_stored_bytes(serialized_document.as_slice(), document_type)?,
+                        StorageFlags::from_some_element_flags_ref(&element_flags)?,
+                    ))
+                } else {
+                    Err(Error::Drive(DriveError::CorruptedDocumentNotItem(
+                        "document must be an item",
+                    )))
+                }
+            })
+            .collect()
+    }
+
+    /// Adds the operations inserting the existing documents of a document type into the
+    /// indices the contract update adds to it, with the storage flags of each document.
+    /// Returns how many documents were added.
+    /// Existing documents are unknown when only estimating costs, so the operations of
+    /// `ESTIMATED_INDEX_BACKFILL_DOCUMENTS_COUNT` documents of the maximum size are added then.
+    pub(crate) fn add_index_backfill_operations(
+        &self,
+        contract: &Contract,
+        document_type: &DocumentType,
+        original_document_type: &DocumentType,
+        estimated_costs_only_with_layer_info: &mut Option<
+            HashMap<KeyInfoPath, EstimatedLayerInformation>,
+        >,
+        transaction: TransactionArg,
+        batch_operations: &mut Vec<DriveOperation>,
+    ) -> Result<u64, Error> {
+        let new_indices = added_indices(document_type, original_document_type);
+        if new_indices.is_empty() {
+            return Ok(0);
+        }
+
+        // only the new indices are walked, the existing ones already reference the documents
+        let mut backfill_document_type = document_type.clone();
+        backfill_document_type.index_structure = DocumentType::build_index_structure(&new_indices);
+
+        if estimated_costs_only_with_layer_info.is_some() {
+            let document_and_contract_info = DocumentAndContractInfo {
+                owned_document_info: OwnedDocumentInfo {
+                    document_info: DocumentEstimatedAverageSize(document_type.max_size() as u32),
+                    owner_id: None,
+                },
+                contract,
+                document_type: &backfill_document_type,
+            };
+
+            for _ in 0..ESTIMATED_INDEX_BACKFILL_DOCUMENTS_COUNT {
+                self.add_indices_for_top_index_level_for_contract_operations(
+                    &document_and_contract_info,
+                    &mut None,
+                    estimated_costs_only_with_layer_info,
+                    transaction,
+                    batch_operations,
+                )?;
+            }
+
+            return Ok(ESTIMATED_INDEX_BACKFILL_DOCUMENTS_COUNT as u64);
+        }
+
+        let mut backfill_operations: Vec<DriveOperation> = vec![];
+        let mut inserted_references: HashSet<(KeyInfoPath, KeyInfo)> = HashSet::new();
+        let mut backfilled_count: u64 = 0;
+        let mut start_after = None;
+        loop {
+            let documents = self.fetch_documents_page_for_backfill(
+                contract,
+                document_type,
+                start_after,
+                transaction,
+                batch_operations,
+            )?;
+
+            if backfilled_count + documents.len() as u64 > MAX_INDEX_BACKFILL_DOCUMENTS_COUNT as u64
+            {
+                return Err(Error::Drive(
+                    DriveError::AddingIndicesToTooManyExistingDocuments(
+                        "contract updates can only add indices to 1000 existing documents",
+                    ),
+                ));
+            }
+
+            for (document, document_storage_flags) in &documents {
+                // the references of the new indices belong to the owner of the document,
+                // not to the owner of the contract
+                let document_and_contract_info = DocumentAndContractInfo {
+                    owned_document_info: OwnedDocumentInfo {
+                        document_info: DocumentRefWithoutSerialization((
+                            document,
+                            document_storage_flags.as_ref(),
+                        )),
+                        owner_id: None,
+                    },
+                    contract,
+                    document_type: &backfill_document_type,
+                };
+
+                let mut document_operations: Vec<DriveOperation> = vec![];
+                self.add_indices_for_top_index_level_for_contract_operations(
+                    &document_and_contract_info,
+                    &mut Some(&mut backfill_operations),
+                    &mut None,
+                    transaction,
+                    &mut document_operations,
+                )?;
+
+                // two existing documents sharing values of a new unique index would
+                // otherwise silently override each other's reference
+                for position in document_operations
+                    .iter()
+                    .filter_map(reference_insertion_position)
+                {
+                    if !inserted_references.insert(position) {
+                        return Err(Error::Drive(
+                            DriveError::AddingUniqueIndexViolatedByExistingDocuments(
+                                "existing documents share the values of a new unique index",
+                            ),
+                        ));
+                    }
+                }
+
+                backfill_operations.append(&mut document_operations);
+                backfilled_count += 1;
+            }
+
+            if documents.len() < INDEX_BACKFILL_PAGE_SIZE as usize {
+                break;
+            }
+            start_after = documents.last().map(|(document, _)| document.id);
+        }
+
+        batch_operations.append(&mut backfill_operations);
+        Ok(backfilled_count)
+    }
+
+    /// Counts, for every document type getting new indices from a contract update, the
+    /// existing documents that applying the update will add to those indices.
+    /// Cost estimations of contract updates only include the backfill of an estimated number
+    /// of documents, so this can be used to gauge it beforehand.
+    pub fn index_backfill_document_counts(
+        &self,
+        contract: &Contract,
+        original_contract: &Contract,
+        transaction: TransactionArg,
+    ) -> Result<BTreeMap<String, u64>, Error> {
+        let mut drive_operations: Vec<DriveOperation> = vec![];
+        let mut counts = BTreeMap::new();
+        for (type_key, document_type) in contract.document_types() {
+            let Some(original_document_type) = original_contract.document_types().get(type_key)
+            else {
+                continue;
+            };
+            if added_indices(document_type, original_document_type).is_empty() {
+                continue;
+            }
+
+            let mut count: u64 = 0;
+            let mut start_after = None;
+            loop {
+                let documents = self.fetch_documents_page_for_backfill(
+                    original_contract,
+                    original_document_type,
+                    start_after,
+                    transaction,
+                    &mut drive_operations,
+                )?;
+                count += documents.len() as u64;
+                if documents.len() < INDEX_BACKFILL_PAGE_SIZE as usize {
+                    break;
+                }
+                start_after = documents.last().map(|(document, _)| document.id);
+            }
+            counts.insert(type_key.clone(), count);
+        }
+        Ok(counts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::common::helpers::setup::setup_drive_with_initial_state_structure;
+    use crate::common::{json_document_to_cbor, setup_contract};
+    use crate::drive::block_info::BlockInfo;
+
+    #[test]
+    fn test_estimated_index_backfill_operations() {
+        let drive = setup_drive_with_initial_state_structure();
+
+        let original_contract = <Contract as DriveContractExt>::from_cbor(
+            &json_document_to_cbor(
+                "tests/supporting_files/contract/family/family-contract-only-first-name-index.json",
+                Some(1),
+            ),
+            None,
+        )
+        .expect("expected to deserialize the original contract");
+        let contract = <Contract as DriveContractExt>::from_cbor(
+            &json_document_to_cbor(
+                "tests/supporting_files/contract/family/family-contract.json",
+                Some(1),
+            ),
+            None,
+        )
+        .expect("expected to deserialize the contract");
+
+        let document_type = contract
+            .document_type_for_name("person")
+            .expect("expected a person document type");
+        let original_document_type = original_contract
+            .document_type_for_name("person")
+            .expect("expected a person document type");
+
+        let mut batch_operations = vec![];
+        let backfilled_count = drive
+            .add_index_backfill_operations(
+                &contract,
+                document_type,
+                original_document_type,
+                &mut Some(HashMap::new()),
+                None,
+                &mut batch_operations,
+            )
+            .expect("expected to estimate the index backfill");
+
+        assert_eq!(
+            backfilled_count,
+            ESTIMATED_INDEX_BACKFILL_DOCUMENTS_COUNT as u64
+        );
+        assert!(!batch_operations.is_empty());
+
+        let mut batch_operations = vec![];
+        let backfilled_count = drive
+            .add_index_backfill_operations(
+                &contract,
+                document_type,
+                document_type,
+                &mut Some(HashMap::new()),
+                None,
+                &mut batch_operations,
+            )
+            .expect("expected to estimate the index backfill");
+
+        assert_eq!(backfilled_count, 0);
+        assert!(batch_operations.is_empty());
+    }
+
+    #[test]
+    fn test_index_backfill_of_binary_documents_keeps_owner_storage_flags() {
+        let drive = setup_drive_with_initial_state_structure();
+
+        let original_contract = setup_contract(
+            &drive,
+            "tests/supporting_files/contract/family/family-contract-only-first-name-index.json",
+            None,
+            None,
+        );
+        let contract = <Contract as DriveContractExt>::from_cbor(
+            &json_document_to_cbor(
+                "tests/supporting_files/contract/family/family-contract.json",
+                Some(1),
+            ),
+            None,
+        )
+        .expect("expected to deserialize the contract");
+
+        let document_type = contract
+            .document_type_for_name("person")
+            .expect("expected a person document type");
+        let original_document_type = original_contract
+            .document_type_for_name("person")
+            .expect("expected a person document type");
+
+        let mut owner_storage_flags = vec![];
+        for (person_path, owner_id) in [
+            (
+                "tests/supporting_files/contract/family/person0.json",
+                [1; 32],
+            ),
+            (
+                "tests/supporting_files/contract/family/person1.json",
+                [2; 32],
+            ),
+        ] {
+            let document =
+                Document::from_cbor(&json_document_to_cbor(person_path, Some(1)), None, None)
+                    .expect("expected to deserialize the document");
+            let storage_flags = StorageFlags::SingleEpochOwned(0, owner_id);
+
+            // documents without serialization are stored in the binary serialization
+            drive
+                .add_document_for_contract(
+                    DocumentAndContractInfo {
+                        owned_document_info: OwnedDocumentInfo {
+                            document_info: DocumentRefWithoutSerialization((
+                                &document,
+                                Some(&storage_flags),
+                            )),
+                            owner_id: None,
+                        },
+                        contract: &original_contract,
+                        document_type: original_document_type,
+                    },
+                    false,
+                    BlockInfo::default(),
+                    true,
+                    None,
+                )
+                .expect("expected to insert a document");
+
+            owner_storage_flags.push(storage_flags.to_some_element_flags());
+        }
+
+        let mut batch_operations = vec![];
+        let backfilled_count = drive
+            .add_index_backfill_operations(
+                &contract,
+                document_type,
+                original_document_type,
+                &mut None,
+                None,
+                &mut batch_operations,
+            )
+            .expect("expected to backfill the new indices");
+
+        assert_eq!(backfilled_count, 2);
+
+        let reference_flags = batch_operations
+            .iter()
+            .filter_map(|operation| match operation {
+                GroveOperation(grove_op) => match &grove_op.op {
+                    Op::Insert {
+                        element: Element::Reference(_, _, flags),
+                    } => Some(flags.clone()),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        assert!(!reference_flags.is_empty());
+        assert!(reference_flags
+            .iter()
+            .all(|flags| owner_storage_flags.contains(flags)));
+        assert!(owner_storage_flags
+            .iter()
+            .all(|flags| reference_flags.contains(flags)));
+    }
+}
@@ -399,6 +399,7 @@ impl Drive {
     }
 
     /// Deserializes a document and a contract and adds the document to the contract.
+    /// Both are expected in the encoding set in the Drive config.
     pub fn add_serialized_document_for_serialized_contract(
         &self,
         serialized_document: &[u8],
@@ -411,9 +412,15 @@ impl Drive {
         storage_flags: Option<&StorageFlags>,
         transaction: TransactionArg,
     ) -> Result<FeeResult, Error> {
-        let contract = <Contract as DriveContractExt>::from_cbor(serialized_contract, None)?;
+        let contract = <Contract as DriveContractExt>::deserialize(
+            serialized_contract,
+            None,
+            self.config.encoding,
+        )?;
 
-        let document = Document::from_cbor(serialized_document, None, owner_id)?;
+        let serialized_document = self.serialization_to_cbor(serialized_document)?;
+
+        let document = Document::from_cbor(&serialized_document, None, owner_id)?;
 
         let document_info =
             DocumentRefAndSerialization((&document, serialized_document, storage_flags));
@@ -437,6 +444,7 @@ impl Drive {
     }
 
     /// Deserializes a document and adds it to a contract.
+    /// The document is expected in the encoding set in the Drive config.
     pub fn add_serialized_document_for_contract(
         &self,
         serialized_document: &[u8],
@@ -449,10 +457,12 @@ impl Drive {
         storage_flags: Option<&StorageFlags>,
         transaction: TransactionArg,
     ) -> Result<FeeResult, Error> {
-        let document = Document::from_cbor(serialized_document, None, owner_id)?;
+        let serialized_document = self.serialization_to_cbor(serialized_document)?;
+
+        let document = Document::from_cbor(&serialized_document, None, owner_id)?;
 
         let document_info =
-            DocumentRefAndSerialization((&document, serialized_document, storage_flags));
+            DocumentRefAndSerialization((&document, &serialized_document, storage_flags));
 
         let document_type = contract.document_type_for_name(document_type_name)?;
 
@@ -473,6 +483,7 @@ impl Drive {
     }
 
     /// Deserializes a document and adds it to a contract by id.
+    /// The document is expected in the encoding set in the Drive config.
     pub fn add_serialized_document_for_contract_id(
         &self,
         serialized_document: &[u8],
@@ -498,10 +509,12 @@ impl Drive {
 
         let contract = &contract_fetch_info.contract;
 
-        let document = Document::from_cbor(serialized_document, None, owner_id)?;
+        let serialized_document = self.serialization_to_cbor(serialized_document)?;
+
+        let document = Document::from_cbor(&serialized_document, None, owner_id)?;
 
         let document_info =
-            DocumentRefAndSerialization((&document, serialized_document, storage_flags));
+            DocumentRefAndSerialization((&document, &serialized_document, storage_flags));
 
         let document_type = contract.document_type_for_name(document_type_name)?;
 
@@ -928,7 +941,7 @@ impl Drive {
     }
 
     /// Adds indices for the top index level and calls for lower levels.
-    fn add_indices_for_top_index_level_for_contract_operations(
+    pub(super) fn add_indices_for_top_index_level_for_contract_operations(
         &self,
         document_and_contract_info: &DocumentAndContractInfo,
         previous_batch_operations: &mut Option<&mut Vec<DriveOperation>>,
@@ -1134,8 +1147,10 @@ impl Drive {
                 &mut batch_operations,
             )?
         {
+            // overriding replaces the stored document whatever its revision
             let update_operations = self.update_document_for_contract_operations(
                 document_and_contract_info,
+                false,
                 block_info,
                 previous_batch_operations,
                 estimated_costs_only_with_layer_info,
@@ -45,6 +45,8 @@ use grovedb::Element;
 
 mod delete;
 mod estimation_costs;
+mod history;
+mod index_backfill;
 mod insert;
 mod update;
 
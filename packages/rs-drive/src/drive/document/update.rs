@@ -72,9 +72,31 @@ use crate::drive::grove_operations::{
 };
 use crate::fee::result::FeeResult;
 use dpp::data_contract::extra::DriveContractExt;
+use dpp::document::document_transition::INITIAL_REVISION;
+use dpp::prelude::Revision;
+
+/// Verifies that the revision of an updated document directly follows the stored one.
+/// Updated documents must have a revision, documents stored without a revision
+/// may only be updated to the initial revision.
+fn verify_revision_follows(old_document: &Document, document: &Document) -> Result<(), Error> {
+    match (old_document.revision()?, document.revision()?) {
+        (_, None) => Err(Error::Drive(DriveError::UpdatingDocumentWithWrongRevision(
+            "updated document must have a revision",
+        ))),
+        // documents stored without a revision take the initial revision on their first update
+        (None, Some(revision)) if revision == Revision::from(INITIAL_REVISION) => Ok(()),
+        (Some(old_revision), Some(revision)) if old_revision.checked_add(1) == Some(revision) => {
+            Ok(())
+        }
+        _ => Err(Error::Drive(DriveError::UpdatingDocumentWithWrongRevision(
+            "document revision must directly follow the stored revision",
+        ))),
+    }
+}
 
 impl Drive {
     /// Updates a serialized document given a contract CBOR and returns the associated fee.
+    /// The document is expected in the encoding set in the Drive config.
     pub fn update_document_for_contract_cbor(
         &self,
         serialized_document: &[u8],
@@ -88,11 +110,13 @@ impl Drive {
     ) -> Result<FeeResult, Error> {
         let contract = <Contract as DriveContractExt>::from_cbor(contract_cbor, None)?;
 
-        let document = Document::from_cbor(serialized_document, None, owner_id)?;
+        let serialized_document = self.serialization_to_cbor(serialized_document)?;
+
+        let document = Document::from_cbor(&serialized_document, None, owner_id)?;
 
         self.update_document_for_contract(
             &document,
-            serialized_document,
+            &serialized_document,
             &contract,
             document_type,
             owner_id,
@@ -104,6 +128,7 @@ impl Drive {
     }
 
     /// Updates a serialized document given a contract id and returns the associated fee.
+    /// The document is expected in the encoding set in the Drive config.
     pub fn update_document_for_contract_id(
         &self,
         serialized_document: &[u8],
@@ -133,10 +158,12 @@ impl Drive {
 
         let contract = &contract_fetch_info.contract;
 
-        let document = Document::from_cbor(serialized_document, None, owner_id)?;
+        let serialized_document = self.serialization_to_cbor(serialized_document)?;
+
+        let document = Document::from_cbor(&serialized_document, None, owner_id)?;
 
         let document_info =
-            DocumentRefAndSerialization((&document, serialized_document, storage_flags));
+            DocumentRefAndSerialization((&document, &serialized_document, storage_flags));
 
         let document_type = contract.document_type_for_name(document_type)?;
 
@@ -161,6 +188,7 @@ impl Drive {
     }
 
     /// Updates a serialized document and returns the associated fee.
+    /// The document is expected in the encoding set in the Drive config.
     pub fn update_serialized_document_for_contract(
         &self,
         serialized_document: &[u8],
@@ -172,11 +200,13 @@ impl Drive {
         storage_flags: Option<&StorageFlags>,
         transaction: TransactionArg,
     ) -> Result<FeeResult, Error> {
-        let document = Document::from_cbor(serialized_document, None, owner_id)?;
+        let serialized_document = self.serialization_to_cbor(serialized_document)?;
+
+        let document = Document::from_cbor(&serialized_document, None, owner_id)?;
 
         self.update_document_for_contract(
             &document,
-            serialized_document,
+            &serialized_document,
             contract,
             document_type,
             owner_id,
@@ -243,6 +273,7 @@ impl Drive {
     ) -> Result<(), Error> {
         let batch_operations = self.update_document_for_contract_operations(
             document_and_contract_info,
+            true,
             block_info,
             &mut None,
             &mut estimated_costs_only_with_layer_info,
@@ -257,9 +288,12 @@ impl Drive {
     }
 
     /// Gathers operations for updating a document.
+    /// If `verify_revision` is true the updated document revision must directly follow
+    /// the stored one, otherwise the stored document is overridden whatever its revision.
     pub(crate) fn update_document_for_contract_operations(
         &self,
         document_and_contract_info: DocumentAndContractInfo,
+        verify_revision: bool,
         block_info: &BlockInfo,
         previous_batch_operations: &mut Option<&mut Vec<DriveOperation>>,
         estimated_costs_only_with_layer_info: &mut Option<
@@ -295,121 +329,186 @@ impl Drive {
         let document_type = document_and_contract_info.document_type;
         let owner_id = document_and_contract_info.owned_document_info.owner_id;
 
-        if let DocumentRefAndSerialization((document, _serialized_document, storage_flags)) =
-            document_and_contract_info.owned_document_info.document_info
-        {
-            // we need to construct the path for documents on the contract
-            // the path is
-            //  * Document and Contract root tree
-            //  * Contract ID recovered from document
-            //  * 0 to signify Documents and not Contract
-            let contract_document_type_path =
-                contract_document_type_path(contract.id.as_bytes(), document_type.name.as_str());
-
-            let contract_documents_primary_key_path = contract_documents_primary_key_path(
-                contract.id.as_bytes(),
-                document_type.name.as_str(),
-            );
+        let document_info = &document_and_contract_info.owned_document_info.document_info;
+        // only estimated sizes don't have a document and they are added above
+        let document = document_info.get_borrowed_document().ok_or(Error::Drive(
+            DriveError::CorruptedCodeExecution("updated document must be known"),
+        ))?;
+        let storage_flags = document_info.get_storage_flags_ref();
+
+        // we need to construct the path for documents on the contract
+        // the path is
+        //  * Document and Contract root tree
+        //  * Contract ID recovered from document
+        //  * 0 to signify Documents and not Contract
+        let contract_document_type_path =
+            contract_document_type_path(contract.id.as_bytes(), document_type.name.as_str());
+
+        let contract_documents_primary_key_path = contract_documents_primary_key_path(
+            contract.id.as_bytes(),
+            document_type.name.as_str(),
+        );
 
-            let document_reference = make_document_reference(
-                document,
-                document_and_contract_info.document_type,
-                storage_flags,
-            );
+        let document_reference = make_document_reference(
+            document,
+            document_and_contract_info.document_type,
+            storage_flags,
+        );
 
-            // next we need to get the old document from storage
-            let old_document_element = if document_type.documents_keep_history {
-                let contract_documents_keeping_history_primary_key_path_for_document_id =
-                    contract_documents_keeping_history_primary_key_path_for_document_id(
-                        contract.id.as_bytes(),
-                        document_type.name.as_str(),
-                        document.id.as_slice(),
-                    );
-                // When keeping document history the 0 is a reference that points to the current value
-                // O is just on one byte, so we have at most one hop of size 1 (1 byte)
-                self.grove_get(
-                    contract_documents_keeping_history_primary_key_path_for_document_id,
-                    &[0],
-                    QueryType::StatefulQuery,
-                    transaction,
-                    &mut batch_operations,
-                )?
-            } else {
-                self.grove_get_direct(
-                    contract_documents_primary_key_path,
+        // next we need to get the old document from storage
+        let old_document_element = if document_type.documents_keep_history {
+            let contract_documents_keeping_history_primary_key_path_for_document_id =
+                contract_documents_keeping_history_primary_key_path_for_document_id(
+                    contract.id.as_bytes(),
+                    document_type.name.as_str(),
                     document.id.as_slice(),
-                    DirectQueryType::StatefulDirectQuery,
-                    transaction,
-                    &mut batch_operations,
-                )?
-            };
-
-            // we need to store the document for it's primary key
-            // we should be overriding if the document_type does not have history enabled
-            self.add_document_to_primary_storage(
-                &document_and_contract_info,
-                block_info,
-                true,
-                estimated_costs_only_with_layer_info,
+                );
+            // When keeping document history the 0 is a reference that points to the current value
+            // O is just on one byte, so we have at most one hop of size 1 (1 byte)
+            self.grove_get(
+                contract_documents_keeping_history_primary_key_path_for_document_id,
+                &[0],
+                QueryType::StatefulQuery,
                 transaction,
                 &mut batch_operations,
-            )?;
-
-            let old_document_info = if let Some(old_document_element) = old_document_element {
-                if let Element::Item(old_serialized_document, element_flags) = old_document_element
-                {
-                    let document =
-                        Document::from_cbor(old_serialized_document.as_slice(), None, owner_id)?;
-                    Ok(DocumentWithoutSerialization((
-                        document,
-                        StorageFlags::from_some_element_flags_ref(&element_flags)?,
-                    )))
-                } else {
-                    Err(Error::Drive(DriveError::CorruptedDocumentNotItem(
-                        "old document is not an item",
-                    )))
-                }?
+            )?
+        } else {
+            self.grove_get_direct(
+                contract_documents_primary_key_path,
+                document.id.as_slice(),
+                DirectQueryType::StatefulDirectQuery,
+                transaction,
+                &mut batch_operations,
+            )?
+        };
+
+        // we need to store the document for it's primary key
+        // we should be overriding if the document_type does not have history enabled
+        self.add_document_to_primary_storage(
+            &document_and_contract_info,
+            block_info,
+            true,
+            estimated_costs_only_with_layer_info,
+            transaction,
+            &mut batch_operations,
+        )?;
+
+        let old_document_info = if let Some(old_document_element) = old_document_element {
+            if let Element::Item(old_serialized_document, element_flags) = old_document_element {
+                let document =
+                    Document::from_stored_bytes(old_serialized_document.as_slice(), document_type)?;
+                Ok(DocumentWithoutSerialization((
+                    document,
+                    StorageFlags::from_some_element_flags_ref(&element_flags)?,
+                )))
             } else {
-                return Err(Error::Drive(DriveError::UpdatingDocumentThatDoesNotExist(
-                    "document being updated does not exist",
-                )));
+                Err(Error::Drive(DriveError::CorruptedDocumentNotItem(
+                    "old document is not an item",
+                )))
+            }?
+        } else {
+            return Err(Error::Drive(DriveError::UpdatingDocumentThatDoesNotExist(
+                "document being updated does not exist",
+            )));
+        };
+
+        // a revision can only replace the one it was made from,
+        // so that concurrent writers can not override each other
+        if verify_revision {
+            if let Some(old_document) = old_document_info.get_borrowed_document() {
+                verify_revision_follows(old_document, document)?;
+            }
+        }
+
+        let mut batch_insertion_cache: HashSet<Vec<Vec<u8>>> = HashSet::new();
+        // fourth we need to store a reference to the document for each index
+        for index in &document_type.indices {
+            // at this point the contract path is to the contract documents
+            // for each index the top index component will already have been added
+            // when the contract itself was created
+            let mut index_path: Vec<Vec<u8>> = contract_document_type_path
+                .iter()
+                .map(|&x| Vec::from(x))
+                .collect();
+            let top_index_property = index.properties.get(0).ok_or(Error::Drive(
+                DriveError::CorruptedContractIndexes("invalid contract indices"),
+            ))?;
+            index_path.push(Vec::from(top_index_property.name.as_bytes()));
+
+            // with the example of the dashpay contract's first index
+            // the index path is now something like Contracts/ContractID/Documents(1)/$ownerId
+            let document_top_field = document
+                .get_raw_for_document_type(&top_index_property.name, document_type, owner_id)?
+                .unwrap_or_default();
+
+            let old_document_top_field = old_document_info
+                .get_raw_for_document_type(&top_index_property.name, document_type, owner_id, None)?
+                .unwrap_or_default();
+
+            // if we are not applying that means we are trying to get worst case costs
+            // which would entail a change on every index
+            let mut change_occurred_on_index = match &old_document_top_field {
+                DriveKeyInfo::Key(k) => &document_top_field != k,
+                DriveKeyInfo::KeyRef(k) => document_top_field.as_slice() != *k,
+                DriveKeyInfo::KeySize(_) => {
+                    // we should assume true in this worst case cost scenario
+                    true
+                }
             };
 
-            let mut batch_insertion_cache: HashSet<Vec<Vec<u8>>> = HashSet::new();
-            // fourth we need to store a reference to the document for each index
-            for index in &document_type.indices {
-                // at this point the contract path is to the contract documents
-                // for each index the top index component will already have been added
-                // when the contract itself was created
-                let mut index_path: Vec<Vec<u8>> = contract_document_type_path
-                    .iter()
-                    .map(|&x| Vec::from(x))
-                    .collect();
-                let top_index_property = index.properties.get(0).ok_or(Error::Drive(
+            if change_occurred_on_index {
+                // here we are inserting an empty tree that will have a subtree of all other index properties
+                let mut qualified_path = index_path.clone();
+                qualified_path.push(document_top_field.clone());
+
+                if !batch_insertion_cache.contains(&qualified_path) {
+                    let inserted = self.batch_insert_empty_tree_if_not_exists(
+                        PathKeyInfo::PathKeyRef::<0>((
+                            index_path.clone(),
+                            document_top_field.as_slice(),
+                        )),
+                        storage_flags,
+                        BatchInsertTreeApplyType::StatefulBatchInsert,
+                        transaction,
+                        previous_batch_operations,
+                        &mut batch_operations,
+                    )?;
+                    if inserted {
+                        batch_insertion_cache.insert(qualified_path);
+                    }
+                }
+            }
+
+            let mut all_fields_null = document_top_field.is_empty();
+
+            let mut old_index_path: Vec<DriveKeyInfo> = index_path
+                .iter()
+                .map(|path_item| DriveKeyInfo::Key(path_item.clone()))
+                .collect();
+            // we push the actual value of the index path
+            index_path.push(document_top_field);
+            // the index path is now something like Contracts/ContractID/Documents(1)/$ownerId/<ownerId>
+
+            old_index_path.push(old_document_top_field);
+
+            for i in 1..index.properties.len() {
+                let index_property = index.properties.get(i).ok_or(Error::Drive(
                     DriveError::CorruptedContractIndexes("invalid contract indices"),
                 ))?;
-                index_path.push(Vec::from(top_index_property.name.as_bytes()));
 
-                // with the example of the dashpay contract's first index
-                // the index path is now something like Contracts/ContractID/Documents(1)/$ownerId
-                let document_top_field = document
-                    .get_raw_for_document_type(&top_index_property.name, document_type, owner_id)?
+                let document_index_field = document
+                    .get_raw_for_document_type(&index_property.name, document_type, owner_id)?
                     .unwrap_or_default();
 
-                let old_document_top_field = old_document_info
-                    .get_raw_for_document_type(
-                        &top_index_property.name,
-                        document_type,
-                        owner_id,
-                        None,
-                    )?
+                let old_document_index_field = old_document_info
+                    .get_raw_for_document_type(&index_property.name, document_type, owner_id, None)?
                     .unwrap_or_default();
 
                 // if we are not applying that means we are trying to get worst case costs
                 // which would entail a change on every index
-                let mut change_occurred_on_index = match &old_document_top_field {
-                    DriveKeyInfo::Key(k) => &document_top_field != k,
-                    DriveKeyInfo::KeyRef(k) => document_top_field.as_slice() != *k,
+                change_occurred_on_index |= match &old_document_index_field {
+                    DriveKeyInfo::Key(k) => &document_index_field != k,
+                    DriveKeyInfo::KeyRef(k) => document_index_field != *k,
                     DriveKeyInfo::KeySize(_) => {
                         // we should assume true in this worst case cost scenario
                         true
@@ -418,14 +517,15 @@ impl Drive {
 
                 if change_occurred_on_index {
                     // here we are inserting an empty tree that will have a subtree of all other index properties
+
                     let mut qualified_path = index_path.clone();
-                    qualified_path.push(document_top_field.clone());
+                    qualified_path.push(index_property.name.as_bytes().to_vec());
 
                     if !batch_insertion_cache.contains(&qualified_path) {
                         let inserted = self.batch_insert_empty_tree_if_not_exists(
                             PathKeyInfo::PathKeyRef::<0>((
                                 index_path.clone(),
-                                document_top_field.as_slice(),
+                                index_property.name.as_bytes(),
                             )),
                             storage_flags,
                             BatchInsertTreeApplyType::StatefulBatchInsert,
@@ -439,196 +539,129 @@ impl Drive {
                     }
                 }
 
-                let mut all_fields_null = document_top_field.is_empty();
-
-                let mut old_index_path: Vec<DriveKeyInfo> = index_path
-                    .iter()
-                    .map(|path_item| DriveKeyInfo::Key(path_item.clone()))
-                    .collect();
-                // we push the actual value of the index path
-                index_path.push(document_top_field);
-                // the index path is now something like Contracts/ContractID/Documents(1)/$ownerId/<ownerId>
-
-                old_index_path.push(old_document_top_field);
-
-                for i in 1..index.properties.len() {
-                    let index_property = index.properties.get(i).ok_or(Error::Drive(
-                        DriveError::CorruptedContractIndexes("invalid contract indices"),
-                    ))?;
-
-                    let document_index_field = document
-                        .get_raw_for_document_type(&index_property.name, document_type, owner_id)?
-                        .unwrap_or_default();
-
-                    let old_document_index_field = old_document_info
-                        .get_raw_for_document_type(
-                            &index_property.name,
-                            document_type,
-                            owner_id,
-                            None,
-                        )?
-                        .unwrap_or_default();
-
-                    // if we are not applying that means we are trying to get worst case costs
-                    // which would entail a change on every index
-                    change_occurred_on_index |= match &old_document_index_field {
-                        DriveKeyInfo::Key(k) => &document_index_field != k,
-                        DriveKeyInfo::KeyRef(k) => document_index_field != *k,
-                        DriveKeyInfo::KeySize(_) => {
-                            // we should assume true in this worst case cost scenario
-                            true
-                        }
-                    };
-
-                    if change_occurred_on_index {
-                        // here we are inserting an empty tree that will have a subtree of all other index properties
-
-                        let mut qualified_path = index_path.clone();
-                        qualified_path.push(index_property.name.as_bytes().to_vec());
-
-                        if !batch_insertion_cache.contains(&qualified_path) {
-                            let inserted = self.batch_insert_empty_tree_if_not_exists(
-                                PathKeyInfo::PathKeyRef::<0>((
-                                    index_path.clone(),
-                                    index_property.name.as_bytes(),
-                                )),
-                                storage_flags,
-                                BatchInsertTreeApplyType::StatefulBatchInsert,
-                                transaction,
-                                previous_batch_operations,
-                                &mut batch_operations,
-                            )?;
-                            if inserted {
-                                batch_insertion_cache.insert(qualified_path);
-                            }
-                        }
-                    }
+                index_path.push(Vec::from(index_property.name.as_bytes()));
+                old_index_path.push(DriveKeyInfo::Key(Vec::from(index_property.name.as_bytes())));
 
-                    index_path.push(Vec::from(index_property.name.as_bytes()));
-                    old_index_path
-                        .push(DriveKeyInfo::Key(Vec::from(index_property.name.as_bytes())));
-
-                    // Iteration 1. the index path is now something like Contracts/ContractID/Documents(1)/$ownerId/<ownerId>/toUserId
-                    // Iteration 2. the index path is now something like Contracts/ContractID/Documents(1)/$ownerId/<ownerId>/toUserId/<ToUserId>/accountReference
-
-                    if change_occurred_on_index {
-                        // here we are inserting an empty tree that will have a subtree of all other index properties
-
-                        let mut qualified_path = index_path.clone();
-                        qualified_path.push(document_index_field.clone());
-
-                        if !batch_insertion_cache.contains(&qualified_path) {
-                            let inserted = self.batch_insert_empty_tree_if_not_exists(
-                                PathKeyInfo::PathKeyRef::<0>((
-                                    index_path.clone(),
-                                    document_index_field.as_slice(),
-                                )),
-                                storage_flags,
-                                BatchInsertTreeApplyType::StatefulBatchInsert,
-                                transaction,
-                                previous_batch_operations,
-                                &mut batch_operations,
-                            )?;
-                            if inserted {
-                                batch_insertion_cache.insert(qualified_path);
-                            }
-                        }
-                    }
-
-                    all_fields_null &= document_index_field.is_empty();
-
-                    // we push the actual value of the index path, both for the new and the old
-                    index_path.push(document_index_field);
-                    old_index_path.push(old_document_index_field);
-                    // Iteration 1. the index path is now something like Contracts/ContractID/Documents(1)/$ownerId/<ownerId>/toUserId/<ToUserId>/
-                    // Iteration 2. the index path is now something like Contracts/ContractID/Documents(1)/$ownerId/<ownerId>/toUserId/<ToUserId>/accountReference/<accountReference>
-                }
+                // Iteration 1. the index path is now something like Contracts/ContractID/Documents(1)/$ownerId/<ownerId>/toUserId
+                // Iteration 2. the index path is now something like Contracts/ContractID/Documents(1)/$ownerId/<ownerId>/toUserId/<ToUserId>/accountReference
 
                 if change_occurred_on_index {
-                    // we first need to delete the old values
-                    // unique indexes will be stored under key "0"
-                    // non unique indices should have a tree at key "0" that has all elements based off of primary key
-
-                    let mut key_info_path = KeyInfoPath::from_vec(
-                        old_index_path
-                            .into_iter()
-                            .map(|key_info| match key_info {
-                                Key(key) => KnownKey(key),
-                                KeyRef(key_ref) => KnownKey(key_ref.to_vec()),
-                                KeySize(key_info) => key_info,
-                            })
-                            .collect::<Vec<KeyInfo>>(),
-                    );
-
-                    if !index.unique {
-                        key_info_path.push(KnownKey(vec![0]));
-
-                        // here we should return an error if the element already exists
-                        self.batch_delete_up_tree_while_empty(
-                            key_info_path,
-                            document.id.as_slice(),
-                            Some(CONTRACT_DOCUMENTS_PATH_HEIGHT),
-                            BatchDeleteUpTreeApplyType::StatefulBatchDelete {
-                                is_known_to_be_subtree_with_sum: Some((false, false)),
-                            },
-                            transaction,
-                            &previous_batch_operations,
-                            &mut batch_operations,
-                        )?;
-                    } else {
-                        // here we should return an error if the element already exists
-                        self.batch_delete_up_tree_while_empty(
-                            key_info_path,
-                            &[0],
-                            Some(CONTRACT_DOCUMENTS_PATH_HEIGHT),
-                            BatchDeleteUpTreeApplyType::StatefulBatchDelete {
-                                is_known_to_be_subtree_with_sum: Some((false, false)),
-                            },
-                            transaction,
-                            &previous_batch_operations,
-                            &mut batch_operations,
-                        )?;
-                    }
+                    // here we are inserting an empty tree that will have a subtree of all other index properties
 
-                    // unique indexes will be stored under key "0"
-                    // non unique indices should have a tree at key "0" that has all elements based off of primary key
-                    if !index.unique || all_fields_null {
-                        // here we are inserting an empty tree that will have a subtree of all other index properties
-                        self.batch_insert_empty_tree_if_not_exists(
-                            PathKeyInfo::PathKeyRef::<0>((index_path.clone(), &[0])),
+                    let mut qualified_path = index_path.clone();
+                    qualified_path.push(document_index_field.clone());
+
+                    if !batch_insertion_cache.contains(&qualified_path) {
+                        let inserted = self.batch_insert_empty_tree_if_not_exists(
+                            PathKeyInfo::PathKeyRef::<0>((
+                                index_path.clone(),
+                                document_index_field.as_slice(),
+                            )),
                             storage_flags,
                             BatchInsertTreeApplyType::StatefulBatchInsert,
                             transaction,
                             previous_batch_operations,
                             &mut batch_operations,
                         )?;
-                        index_path.push(vec![0]);
-
-                        // here we should return an error if the element already exists
-                        self.batch_insert(
-                            PathKeyElement::<0>((
-                                index_path,
-                                document.id.as_slice(),
-                                document_reference.clone(),
-                            )),
-                            &mut batch_operations,
-                        )?;
-                    } else {
-                        // in one update you can't insert an element twice, so need to check the cache
-                        // here we should return an error if the element already exists
-                        let inserted = self.batch_insert_if_not_exists(
-                            PathKeyElement::<0>((index_path, &[0], document_reference.clone())),
-                            BatchInsertApplyType::StatefulBatchInsert,
-                            transaction,
-                            &mut batch_operations,
-                        )?;
-                        if !inserted {
-                            return Err(Error::Drive(DriveError::CorruptedContractIndexes(
-                                "index already exists",
-                            )));
+                        if inserted {
+                            batch_insertion_cache.insert(qualified_path);
                         }
                     }
                 }
+
+                all_fields_null &= document_index_field.is_empty();
+
+                // we push the actual value of the index path, both for the new and the old
+                index_path.push(document_index_field);
+                old_index_path.push(old_document_index_field);
+                // Iteration 1. the index path is now something like Contracts/ContractID/Documents(1)/$ownerId/<ownerId>/toUserId/<ToUserId>/
+                // Iteration 2. the index path is now something like Contracts/ContractID/Documents(1)/$ownerId/<ownerId>/toUserId/<ToUserId>/accountReference/<accountReference>
+            }
+
+            if change_occurred_on_index {
+                // we first need to delete the old values
+                // unique indexes will be stored under key "0"
+                // non unique indices should have a tree at key "0" that has all elements based off of primary key
+
+                let mut key_info_path = KeyInfoPath::from_vec(
+                    old_index_path
+                        .into_iter()
+                        .map(|key_info| match key_info {
+                            Key(key) => KnownKey(key),
+                            KeyRef(key_ref) => KnownKey(key_ref.to_vec()),
+                            KeySize(key_info) => key_info,
+                        })
+                        .collect::<Vec<KeyInfo>>(),
+                );
+
+                if !index.unique {
+                    key_info_path.push(KnownKey(vec![0]));
+
+                    // here we should return an error if the element already exists
+                    self.batch_delete_up_tree_while_empty(
+                        key_info_path,
+                        document.id.as_slice(),
+                        Some(CONTRACT_DOCUMENTS_PATH_HEIGHT),
+                        BatchDeleteUpTreeApplyType::StatefulBatchDelete {
+                            is_known_to_be_subtree_with_sum: Some((false, false)),
+                        },
+                        transaction,
+                        &previous_batch_operations,
+                        &mut batch_operations,
+                    )?;
+                } else {
+                    // here we should return an error if the element already exists
+                    self.batch_delete_up_tree_while_empty(
+                        key_info_path,
+                        &[0],
+                        Some(CONTRACT_DOCUMENTS_PATH_HEIGHT),
+                        BatchDeleteUpTreeApplyType::StatefulBatchDelete {
+                            is_known_to_be_subtree_with_sum: Some((false, false)),
+                        },
+                        transaction,
+                        &previous_batch_operations,
+                        &mut batch_operations,
+                    )?;
+                }
+
+                // unique indexes will be stored under key "0"
+                // non unique indices should have a tree at key "0" that has all elements based off of primary key
+                if !index.unique || all_fields_null {
+                    // here we are inserting an empty tree that will have a subtree of all other index properties
+                    self.batch_insert_empty_tree_if_not_exists(
+                        PathKeyInfo::PathKeyRef::<0>((index_path.clone(), &[0])),
+                        storage_flags,
+                        BatchInsertTreeApplyType::StatefulBatchInsert,
+                        transaction,
+                        previous_batch_operations,
+                        &mut batch_operations,
+                    )?;
+                    index_path.push(vec![0]);
+
+                    // here we should return an error if the element already exists
+                    self.batch_insert(
+                        PathKeyElement::<0>((
+                            index_path,
+                            document.id.as_slice(),
+                            document_reference.clone(),
+                        )),
+                        &mut batch_operations,
+                    )?;
+                } else {
+                    // in one update you can't insert an element twice, so need to check the cache
+                    // here we should return an error if the element already exists
+                    let inserted = self.batch_insert_if_not_exists(
+                        PathKeyElement::<0>((index_path, &[0], document_reference.clone())),
+                        BatchInsertApplyType::StatefulBatchInsert,
+                        transaction,
+                        &mut batch_operations,
+                    )?;
+                    if !inserted {
+                        return Err(Error::Drive(DriveError::CorruptedContractIndexes(
+                            "index already exists",
+                        )));
+                    }
+                }
             }
         }
         Ok(batch_operations)
@@ -665,7 +698,10 @@ mod tests {
     use crate::fee::default_costs::STORAGE_DISK_USAGE_CREDIT_PER_BYTE;
     use crate::query::DriveQuery;
     use crate::{
-        common::{json_document_to_cbor, setup_contract, value_to_cbor},
+        common::{
+            json_document_to_cbor, json_document_with_revision_to_cbor, setup_contract,
+            value_to_cbor,
+        },
         drive::test_utils::TestEntropyGenerator,
     };
 
@@ -1285,15 +1321,18 @@ mod tests {
             Some(&db_transaction),
         );
 
-        let dashpay_profile_serialized_document = json_document_to_cbor(
+        let dashpay_profile_serialized_document = json_document_with_revision_to_cbor(
             "tests/supporting_files/contract/dashpay/profile0.json",
+            1,
             Some(1),
         );
 
-        let dashpay_profile_updated_public_message_serialized_document = json_document_to_cbor(
-            "tests/supporting_files/contract/dashpay/profile0-updated-public-message.json",
-            Some(1),
-        );
+        let dashpay_profile_updated_public_message_serialized_document =
+            json_document_with_revision_to_cbor(
+                "tests/supporting_files/contract/dashpay/profile0-updated-public-message.json",
+                2,
+                Some(1),
+            );
 
         let random_owner_id = rand::thread_rng().gen::<[u8; 32]>();
         drive
@@ -1324,6 +1363,276 @@ mod tests {
             .expect("expected to update a document with history successfully");
     }
 
+    #[test]
+    fn test_update_dashpay_profile_checks_revision() {
+        let tmp_dir = TempDir::new().unwrap();
+        let drive: Drive = Drive::open(tmp_dir, None).expect("expected to open Drive successfully");
+
+        let db_transaction = drive.grove.start_transaction();
+
+        drive
+            .create_initial_state_structure(Some(&db_transaction))
+            .expect("expected to create root tree successfully");
+
+        let contract = setup_contract(
+            &drive,
+            "tests/supporting_files/contract/dashpay/dashpay-contract.json",
+            None,
+            Some(&db_transaction),
+        );
+
+        let profile_with_revision = |revision: u64| {
+            let file = std::fs::File::open("tests/supporting_files/contract/dashpay/profile0.json")
+                .expect("expected to open the profile");
+            let mut profile: Value =
+                serde_json::from_reader(file).expect("expected a valid json profile");
+            profile["$revision"] = json!(revision);
+            value_to_cbor(profile, Some(defaults::PROTOCOL_VERSION))
+        };
+
+        let random_owner_id = rand::thread_rng().gen::<[u8; 32]>();
+        drive
+            .add_serialized_document_for_contract(
+                &profile_with_revision(1),
+                &contract,
+                "profile",
+                Some(random_owner_id),
+                false,
+                BlockInfo::default(),
+                true,
+                StorageFlags::optional_default_as_ref(),
+                Some(&db_transaction),
+            )
+            .expect("expected to insert a document successfully");
+
+        let update_to_revision = |revision: u64| {
+            drive.update_serialized_document_for_contract(
+                &profile_with_revision(revision),
+                &contract,
+                "profile",
+                Some(random_owner_id),
+                BlockInfo::default(),
+                true,
+                StorageFlags::optional_default_as_ref(),
+                Some(&db_transaction),
+            )
+        };
+
+        assert!(matches!(
+            update_to_revision(3),
+            Err(Error::Drive(DriveError::UpdatingDocumentWithWrongRevision(
+                _
+            )))
+        ));
+
+        update_to_revision(2).expect("expected to update the document to the next revision");
+
+        // a second writer starting from the first revision can not override the update
+        assert!(matches!(
+            update_to_revision(2),
+            Err(Error::Drive(DriveError::UpdatingDocumentWithWrongRevision(
+                _
+            )))
+        ));
+
+        let document_type = contract
+            .document_type_for_name("profile")
+            .expect("expected to get the profile document type");
+        let query_cbor = value_to_cbor(json!({ "where": [] }), None);
+        let (results, _, _) = drive
+            .query_documents_from_contract(
+                &contract,
+                document_type,
+                query_cbor.as_slice(),
+                None,
+                Some(&db_transaction),
+            )
+            .expect("expected to query the profiles");
+        let document = Document::from_cbor(results[0].as_slice(), None, None)
+            .expect("expected to deserialize the profile");
+        assert_eq!(
+            document.revision().expect("expected a valid revision"),
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn test_update_dashpay_profile_stored_without_revision() {
+        let tmp_dir = TempDir::new().unwrap();
+        let drive: Drive = Drive::open(tmp_dir, None).expect("expected to open Drive successfully");
+
+        let db_transaction = drive.grove.start_transaction();
+
+        drive
+            .create_initial_state_structure(Some(&db_transaction))
+            .expect("expected to create root tree successfully");
+
+        let contract = setup_contract(
+            &drive,
+            "tests/supporting_files/contract/dashpay/dashpay-contract.json",
+            None,
+            Some(&db_transaction),
+        );
+
+        let profile_with_revision = |revision: Option<u64>| {
+            let file = std::fs::File::open("tests/supporting_files/contract/dashpay/profile0.json")
+                .expect("expected to open the profile");
+            let mut profile: Value =
+                serde_json::from_reader(file).expect("expected a valid json profile");
+            if let Some(revision) = revision {
+                profile["$revision"] = json!(revision);
+            }
+            value_to_cbor(profile, Some(defaults::PROTOCOL_VERSION))
+        };
+
+        let random_owner_id = rand::thread_rng().gen::<[u8; 32]>();
+        drive
+            .add_serialized_document_for_contract(
+                &profile_with_revision(None),
+                &contract,
+                "profile",
+                Some(random_owner_id),
+                false,
+                BlockInfo::default(),
+                true,
+                StorageFlags::optional_default_as_ref(),
+                Some(&db_transaction),
+            )
+            .expect("expected to insert a document successfully");
+
+        let update_to_revision = |revision: Option<u64>| {
+            drive.update_serialized_document_for_contract(
+                &profile_with_revision(revision),
+                &contract,
+                "profile",
+                Some(random_owner_id),
+                BlockInfo::default(),
+                true,
+                StorageFlags::optional_default_as_ref(),
+                Some(&db_transaction),
+            )
+        };
+
+        // updated documents must have a revision
+        assert!(matches!(
+            update_to_revision(None),
+            Err(Error::Drive(DriveError::UpdatingDocumentWithWrongRevision(
+                _
+            )))
+        ));
+
+        assert!(matches!(
+            update_to_revision(Some(2)),
+            Err(Error::Drive(DriveError::UpdatingDocumentWithWrongRevision(
+                _
+            )))
+        ));
+
+        update_to_revision(Some(1))
+            .expect("expected to update the document to the initial revision");
+
+        update_to_revision(Some(2)).expect("expected to update the document to the next revision");
+    }
+
+    #[test]
+    fn test_update_dashpay_profile_without_serialization_checks_revision() {
+        let tmp_dir = TempDir::new().unwrap();
+        let drive: Drive = Drive::open(tmp_dir, None).expect("expected to open Drive successfully");
+
+        let db_transaction = drive.grove.start_transaction();
+
+        drive
+            .create_initial_state_structure(Some(&db_transaction))
+            .expect("expected to create root tree successfully");
+
+        let contract = setup_contract(
+            &drive,
+            "tests/supporting_files/contract/dashpay/dashpay-contract.json",
+            None,
+            Some(&db_transaction),
+        );
+
+        let profile_with_revision = |revision: u64| {
+            json_document_with_revision_to_cbor(
+                "tests/supporting_files/contract/dashpay/profile0.json",
+                revision,
+                Some(defaults::PROTOCOL_VERSION),
+            )
+        };
+
+        let random_owner_id = rand::thread_rng().gen::<[u8; 32]>();
+        drive
+            .add_serialized_document_for_contract(
+                &profile_with_revision(1),
+                &contract,
+                "profile",
+                Some(random_owner_id),
+                false,
+                BlockInfo::default(),
+                true,
+                StorageFlags::optional_default_as_ref(),
+                Some(&db_transaction),
+            )
+            .expect("expected to insert a document successfully");
+
+        let document_type = contract
+            .document_type_for_name("profile")
+            .expect("expected to get the profile document type");
+
+        let update_to_revision = |revision: u64| {
+            let document = Document::from_cbor(
+                profile_with_revision(revision).as_slice(),
+                None,
+                Some(random_owner_id),
+            )
+            .expect("expected to deserialize the profile");
+
+            drive.update_document_for_contract_apply_and_add_to_operations(
+                DocumentAndContractInfo {
+                    owned_document_info: OwnedDocumentInfo {
+                        document_info: DocumentWithoutSerialization((
+                            document,
+                            StorageFlags::optional_default(),
+                        )),
+                        owner_id: Some(random_owner_id),
+                    },
+                    contract: &contract,
+                    document_type,
+                },
+                &BlockInfo::default(),
+                None,
+                Some(&db_transaction),
+                &mut vec![],
+            )
+        };
+
+        assert!(matches!(
+            update_to_revision(1),
+            Err(Error::Drive(DriveError::UpdatingDocumentWithWrongRevision(
+                _
+            )))
+        ));
+
+        update_to_revision(2).expect("expected to update the document to the next revision");
+
+        let query_cbor = value_to_cbor(json!({ "where": [] }), None);
+        let (results, _, _) = drive
+            .query_documents_from_contract(
+                &contract,
+                document_type,
+                query_cbor.as_slice(),
+                None,
+                Some(&db_transaction),
+            )
+            .expect("expected to query the profiles");
+        let document = Document::from_cbor(results[0].as_slice(), None, None)
+            .expect("expected to deserialize the profile");
+        assert_eq!(
+            document.revision().expect("expected a valid revision"),
+            Some(2)
+        );
+    }
+
     fn test_fees_for_update_document(using_history: bool, using_transaction: bool) {
         let config = DriveConfig {
             batching_enabled: true,
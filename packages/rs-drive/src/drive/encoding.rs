@@ -0,0 +1,86 @@
+// MIT LICENSE
+//
+// Copyright (c) 2021 Dash Core Group
+//
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+//
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+//
+
+//! Drive Encoding.
+//!
+//! This module converts the contracts and documents sent to and returned by Drive between
+//! the encoding set in the Drive config and CBOR, which is what Drive stores contracts and
+//! serialized documents in. Queried documents stored with the binary serialization of their
+//! document type are decoded with it.
+//!
+
+use std::borrow::Cow;
+
+use dpp::data_contract::extra::{
+    cbor_to_drive_protobuf, drive_protobuf_to_cbor, DocumentType, DriveEncoding,
+    PROTOCOL_VERSION_PREFIX_LENGTH,
+};
+
+use crate::contract::document::Document;
+use crate::drive::Drive;
+use crate::error::Error;
+
+impl Drive {
+    /// Converts a contract or document serialized in the configured encoding to CBOR.
+    pub fn serialization_to_cbor<'a>(&self, serialized: &'a [u8]) -> Result<Cow<'a, [u8]>, Error> {
+        match self.config.encoding {
+            DriveEncoding::DriveCbor => Ok(Cow::Borrowed(serialized)),
+            DriveEncoding::DriveProtobuf => Ok(Cow::Owned(drive_protobuf_to_cbor(
+                serialized,
+                PROTOCOL_VERSION_PREFIX_LENGTH,
+            )?)),
+        }
+    }
+
+    /// Converts a CBOR serialized contract or document to the configured encoding.
+    pub fn serialization_from_cbor(&self, cbor: Vec<u8>) -> Result<Vec<u8>, Error> {
+        match self.config.encoding {
+            DriveEncoding::DriveCbor => Ok(cbor),
+            DriveEncoding::DriveProtobuf => Ok(cbor_to_drive_protobuf(
+                &cbor,
+                PROTOCOL_VERSION_PREFIX_LENGTH,
+            )?),
+        }
+    }
+
+    /// Converts a stored document to the configured encoding. Documents inserted with their
+    /// serialization are stored in CBOR, the others with the binary serialization of their
+    /// document type, which is decoded with it.
+    pub(crate) fn stored_document_to_encoding(
+        &self,
+        stored_document: Vec<u8>,
+        document_type: &DocumentType,
+    ) -> Result<Vec<u8>, Error> {
+        if Document::is_binary_serialization(&stored_document) {
+            Document::from_bytes(&stored_document, document_type)?.to_encoded(self.config.encoding)
+        } else {
+            self.serialization_from_cbor(stored_document)
+        }
+    }
+}
@@ -38,7 +38,8 @@ use crate::drive::Drive;
 use crate::error::drive::DriveError;
 use crate::error::Error;
 use crate::fee::credits::{Creditable, Credits};
-use crate::fee::get_overflow_error;
+use crate::fee::default_costs::{FeeSchedule, FeeVersion, INITIAL_FEE_VERSION};
+use crate::fee::{get_overflow_error, NEUTRAL_FEE_MULTIPLIER};
 use crate::fee_pools::epochs::Epoch;
 
 use crate::fee_pools::epochs::epoch_key_constants;
@@ -63,7 +64,7 @@ impl Drive {
         let Element::SumItem(item, _) = element else {
             return Err(Error::Drive(DriveError::UnexpectedElementType(
                 "epochs storage fee must be an item",
-            )))
+            )));
         };
 
         Ok(item.to_unsigned())
@@ -88,14 +89,16 @@ impl Drive {
         let Element::SumItem(credits, _) = element else {
             return Err(Error::Drive(DriveError::UnexpectedElementType(
                 "epochs processing fee must be an item",
-            )))
+            )));
         };
 
         Ok(credits.to_unsigned())
     }
 
     /// Gets the Fee Multiplier for the Epoch.
-    pub(crate) fn get_epoch_fee_multiplier(
+    /// Epochs started before the fee version was stored didn't apply their stored
+    /// fee multiplier to processing fees, so they use the neutral fee multiplier.
+    pub fn get_epoch_fee_multiplier(
         &self,
         epoch_tree: &Epoch,
         transaction: TransactionArg,
@@ -113,16 +116,107 @@ impl Drive {
         let Element::Item(encoded_multiplier, _) = element else {
             return Err(Error::Drive(DriveError::UnexpectedElementType(
                 "epochs multiplier must be an item",
-            )))
+            )));
         };
 
-        Ok(f64::from_be_bytes(
-            encoded_multiplier.as_slice().try_into().map_err(|_| {
+        let multiplier =
+            f64::from_be_bytes(encoded_multiplier.as_slice().try_into().map_err(|_| {
                 Error::Drive(DriveError::CorruptedSerialization(
                     "epochs multiplier must be f64",
                 ))
+            })?);
+
+        if self
+            .get_epoch_stored_fee_version(epoch_tree, transaction)?
+            .is_none()
+        {
+            return Ok(NEUTRAL_FEE_MULTIPLIER);
+        }
+
+        Ok(multiplier)
+    }
+
+    /// Gets the version of the fee schedule used by the Epoch.
+    /// Epochs started before the fee version was stored use the initial fee schedule.
+    pub fn get_epoch_fee_version(
+        &self,
+        epoch_tree: &Epoch,
+        transaction: TransactionArg,
+    ) -> Result<FeeVersion, Error> {
+        Ok(self
+            .get_epoch_stored_fee_version(epoch_tree, transaction)?
+            .unwrap_or(INITIAL_FEE_VERSION))
+    }
+
+    /// Gets the version of the fee schedule stored when the Epoch was started if there is one.
+    fn get_epoch_stored_fee_version(
+        &self,
+        epoch_tree: &Epoch,
+        transaction: TransactionArg,
+    ) -> Result<Option<FeeVersion>, Error> {
+        let element = match self
+            .grove
+            .get(
+                epoch_tree.get_path(),
+                epoch_key_constants::KEY_FEE_VERSION.as_slice(),
+                transaction,
+            )
+            .unwrap()
+        {
+            Ok(element) => element,
+            Err(grovedb::Error::PathKeyNotFound(_)) => return Ok(None),
+            Err(e) => return Err(Error::GroveDB(e)),
+        };
+
+        let Element::Item(encoded_fee_version, _) = element else {
+            return Err(Error::Drive(DriveError::UnexpectedElementType(
+                "epochs fee version must be an item",
+            )));
+        };
+
+        Ok(Some(FeeVersion::from_be_bytes(
+            encoded_fee_version.as_slice().try_into().map_err(|_| {
+                Error::Drive(DriveError::CorruptedSerialization(
+                    "epochs fee version must be u32",
+                ))
             })?,
-        ))
+        )))
+    }
+
+    /// Gets the fee schedule activated by governance for the Epoch if there is one.
+    /// Epochs without one use the fee schedule of their fee version.
+    pub fn get_epoch_fee_schedule(
+        &self,
+        epoch_tree: &Epoch,
+        transaction: TransactionArg,
+    ) -> Result<Option<FeeSchedule>, Error> {
+        let element = match self
+            .grove
+            .get(
+                epoch_tree.get_path(),
+                epoch_key_constants::KEY_FEE_SCHEDULE.as_slice(),
+                transaction,
+            )
+            .unwrap()
+        {
+            Ok(element) => element,
+            Err(grovedb::Error::PathKeyNotFound(_)) => return Ok(None),
+            Err(e) => return Err(Error::GroveDB(e)),
+        };
+
+        let Element::Item(encoded_fee_schedule, _) = element else {
+            return Err(Error::Drive(DriveError::UnexpectedElementType(
+                "epochs fee schedule must be an item",
+            )));
+        };
+
+        let fee_schedule = FeeSchedule::from_be_bytes(&encoded_fee_schedule).map_err(|_| {
+            Error::Drive(DriveError::CorruptedSerialization(
+                "epochs fee schedule must be 9 u64s",
+            ))
+        })?;
+
+        Ok(Some(fee_schedule))
     }
 
     /// Gets the total credits to be distributed for the Epoch.
@@ -149,6 +243,7 @@ mod tests {
 
     use crate::common::helpers::setup::setup_drive_with_initial_state_structure;
     use crate::drive::batch::GroveDbOpBatch;
+    use crate::fee::DEFAULT_ORIGINAL_FEE_MULTIPLIER;
     use crate::fee_pools::epochs_root_tree_key_constants::KEY_STORAGE_FEE_POOL;
 
     mod get_epoch_storage_credits_for_distribution {
@@ -338,5 +433,204 @@ mod tests {
 
             assert_eq!(stored_multiplier, multiplier);
         }
+
+        #[test]
+        fn test_neutral_multiplier_if_fee_version_is_not_set() {
+            let drive = setup_drive_with_initial_state_structure();
+            let transaction = drive.grove.start_transaction();
+
+            let epoch = Epoch::new(0);
+
+            // Epochs started before fee multipliers were applied stored the original multiplier
+            drive
+                .grove
+                .insert(
+                    epoch.get_path(),
+                    epoch_key_constants::KEY_FEE_MULTIPLIER.as_slice(),
+                    Element::Item(DEFAULT_ORIGINAL_FEE_MULTIPLIER.to_be_bytes().to_vec(), None),
+                    None,
+                    Some(&transaction),
+                )
+                .unwrap()
+                .expect("should insert multiplier");
+
+            let multiplier = drive
+                .get_epoch_fee_multiplier(&epoch, Some(&transaction))
+                .expect("should get multiplier");
+
+            assert_eq!(multiplier, NEUTRAL_FEE_MULTIPLIER);
+        }
+    }
+
+    mod fee_version {
+        use super::*;
+
+        #[test]
+        fn test_error_if_epoch_tree_is_not_initiated() {
+            let drive = setup_drive_with_initial_state_structure();
+            let transaction = drive.grove.start_transaction();
+
+            let epoch = Epoch::new(7000);
+
+            let result = drive.get_epoch_fee_version(&epoch, Some(&transaction));
+
+            assert!(matches!(
+                result,
+                Err(Error::GroveDB(grovedb::Error::PathParentLayerNotFound(_)))
+            ));
+        }
+
+        #[test]
+        fn test_error_if_value_has_invalid_length() {
+            let drive = setup_drive_with_initial_state_structure();
+            let transaction = drive.grove.start_transaction();
+
+            let epoch = Epoch::new(0);
+
+            drive
+                .grove
+                .insert(
+                    epoch.get_path(),
+                    epoch_key_constants::KEY_FEE_VERSION.as_slice(),
+                    Element::Item(u128::MAX.to_be_bytes().to_vec(), None),
+                    None,
+                    Some(&transaction),
+                )
+                .unwrap()
+                .expect("should insert invalid data");
+
+            let result = drive.get_epoch_fee_version(&epoch, Some(&transaction));
+
+            assert!(matches!(
+                result,
+                Err(Error::Drive(DriveError::CorruptedSerialization(_)))
+            ));
+        }
+
+        #[test]
+        fn test_initial_fee_version_if_value_is_not_set() {
+            let drive = setup_drive_with_initial_state_structure();
+            let transaction = drive.grove.start_transaction();
+
+            // Epochs started before fee versions were stored don't have the key
+            let epoch = Epoch::new(0);
+
+            let fee_version = drive
+                .get_epoch_fee_version(&epoch, Some(&transaction))
+                .expect("should get fee version");
+
+            assert_eq!(fee_version, INITIAL_FEE_VERSION);
+        }
+
+        #[test]
+        fn test_value_is_set() {
+            let drive = setup_drive_with_initial_state_structure();
+            let transaction = drive.grove.start_transaction();
+
+            let epoch = Epoch::new_with_fee_parameters(0, 1, None, 1.0);
+
+            let mut batch = GroveDbOpBatch::new();
+
+            epoch
+                .add_init_empty_operations(&mut batch)
+                .expect("should add empty epoch operations");
+
+            epoch.add_init_current_operations(1.0, 1, 1, &mut batch);
+
+            drive
+                .grove_apply_batch(batch, false, Some(&transaction))
+                .expect("should apply batch");
+
+            let stored_fee_version = drive
+                .get_epoch_fee_version(&epoch, Some(&transaction))
+                .expect("should get fee version");
+
+            assert_eq!(stored_fee_version, 1);
+
+            let stored_epoch = Epoch::new_with_fee_parameters(0, stored_fee_version, None, 1.0);
+
+            assert!(stored_epoch.fee_schedule().is_ok());
+        }
+    }
+
+    mod fee_schedule {
+        use super::*;
+        use crate::fee::default_costs::FEE_SCHEDULE_V1;
+
+        #[test]
+        fn test_error_if_value_has_invalid_length() {
+            let drive = setup_drive_with_initial_state_structure();
+            let transaction = drive.grove.start_transaction();
+
+            let epoch = Epoch::new(0);
+
+            drive
+                .grove
+                .insert(
+                    epoch.get_path(),
+                    epoch_key_constants::KEY_FEE_SCHEDULE.as_slice(),
+                    Element::Item(u128::MAX.to_be_bytes().to_vec(), None),
+                    None,
+                    Some(&transaction),
+                )
+                .unwrap()
+                .expect("should insert invalid data");
+
+            let result = drive.get_epoch_fee_schedule(&epoch, Some(&transaction));
+
+            assert!(matches!(
+                result,
+                Err(Error::Drive(DriveError::CorruptedSerialization(_)))
+            ));
+        }
+
+        #[test]
+        fn test_none_if_value_is_not_set() {
+            let drive = setup_drive_with_initial_state_structure();
+            let transaction = drive.grove.start_transaction();
+
+            let epoch = Epoch::new(0);
+
+            let fee_schedule = drive
+                .get_epoch_fee_schedule(&epoch, Some(&transaction))
+                .expect("should get fee schedule");
+
+            assert_eq!(fee_schedule, None);
+        }
+
+        #[test]
+        fn test_value_is_set() {
+            let drive = setup_drive_with_initial_state_structure();
+            let transaction = drive.grove.start_transaction();
+
+            let fee_schedule = FeeSchedule {
+                storage_seek_cost: FEE_SCHEDULE_V1.storage_seek_cost * 2,
+                ..FEE_SCHEDULE_V1
+            };
+
+            let epoch = Epoch::new_with_fee_parameters(1, 1, Some(fee_schedule), 1.0);
+
+            let mut batch = GroveDbOpBatch::new();
+
+            epoch
+                .add_init_empty_operations(&mut batch)
+                .expect("should add empty epoch operations");
+
+            epoch.add_init_current_operations(1.0, 1, 1, &mut batch);
+
+            drive
+                .grove_apply_batch(batch, false, Some(&transaction))
+                .expect("should apply batch");
+
+            let stored_fee_schedule = drive
+                .get_epoch_fee_schedule(&epoch, Some(&transaction))
+                .expect("should get fee schedule");
+
+            assert_eq!(stored_fee_schedule, Some(fee_schedule));
+            assert_eq!(
+                epoch.fee_schedule().expect("should get fee schedule"),
+                &fee_schedule
+            );
+        }
     }
 }
@@ -46,6 +46,7 @@ use itertools::Itertools;
 /// Epochs module
 pub mod epochs;
 pub mod pending_epoch_updates;
+pub mod prove;
 pub mod storage_fee_distribution_pool;
 pub mod unpaid_epoch;
 
@@ -0,0 +1,490 @@
+// MIT LICENSE
+//
+// Copyright (c) 2021 Dash Core Group
+//
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+//
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+//
+
+//! Prove Fee Pools.
+//!
+//! This module implements functions in Drive for proving the fee pools, the values
+//! stored for an epoch and its block proposers, and the matching stateless functions
+//! verifying such proofs against a platform state root hash.
+//!
+
+use grovedb::{Element, PathQuery, Query, SizedQuery, TransactionArg};
+
+use crate::drive::fee_pools::pools_vec_path;
+use crate::drive::Drive;
+use crate::error::query::QueryError;
+use crate::error::Error;
+use crate::fee::credits::{Creditable, Credits};
+use crate::fee::default_costs::{FeeSchedule, FeeVersion, INITIAL_FEE_VERSION};
+use crate::fee::epoch::EpochIndex;
+use crate::fee::op::DriveOperation;
+use crate::fee::NEUTRAL_FEE_MULTIPLIER;
+use crate::fee_pools::epochs::epoch_key_constants::{
+    KEY_FEE_MULTIPLIER, KEY_FEE_SCHEDULE, KEY_FEE_VERSION, KEY_POOL_PROCESSING_FEES,
+    KEY_POOL_STORAGE_FEES, KEY_START_BLOCK_HEIGHT, KEY_START_TIME,
+};
+use crate::fee_pools::epochs::Epoch;
+use crate::fee_pools::epochs_root_tree_key_constants::{
+    KEY_STORAGE_FEE_POOL, KEY_UNPAID_EPOCH_INDEX,
+};
+
+/// The values stored for an epoch, as proved.
+/// Values which aren't stored for the epoch are `None`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ProvedEpoch {
+    /// The time at which the epoch started in milliseconds
+    pub start_time: Option<u64>,
+    /// The height of the first block of the epoch
+    pub start_block_height: Option<u64>,
+    /// The storage credits to be distributed for the epoch
+    pub storage_credits_for_distribution: Option<Credits>,
+    /// The processing credits to be distributed for the epoch
+    pub processing_credits_for_distribution: Option<Credits>,
+    /// The fee multiplier applied to processing fees during the epoch
+    pub fee_multiplier: Option<f64>,
+    /// The version of the fee schedule used during the epoch
+    pub fee_version: Option<FeeVersion>,
+    /// The fee schedule activated by governance for the epoch
+    pub fee_schedule: Option<FeeSchedule>,
+}
+
+/// The values stored in the root of the fee pools, as proved.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ProvedPools {
+    /// The aggregate storage fee distribution pool
+    pub storage_fee_pool: Credits,
+    /// The index of the oldest epoch which wasn't paid yet
+    pub unpaid_epoch_index: Option<EpochIndex>,
+}
+
+/// Returns the path query of the values stored for an epoch.
+/// The proposers tree isn't part of it, see `epoch_proposers_path_query`.
+fn epoch_path_query(epoch: &Epoch) -> PathQuery {
+    let mut query = Query::new();
+    query.insert_key(KEY_POOL_PROCESSING_FEES.to_vec());
+    query.insert_key(KEY_POOL_STORAGE_FEES.to_vec());
+    query.insert_key(KEY_START_TIME.to_vec());
+    query.insert_key(KEY_START_BLOCK_HEIGHT.to_vec());
+    query.insert_key(KEY_FEE_MULTIPLIER.to_vec());
+    query.insert_key(KEY_FEE_VERSION.to_vec());
+    query.insert_key(KEY_FEE_SCHEDULE.to_vec());
+
+    PathQuery::new(epoch.get_vec_path(), SizedQuery::new(query, None, None))
+}
+
+/// Returns the path query of the block proposers of an epoch.
+fn epoch_proposers_path_query(epoch: &Epoch, limit: u16) -> PathQuery {
+    let mut query = Query::new();
+    query.insert_all();
+
+    PathQuery::new(
+        epoch.get_proposers_vec_path(),
+        SizedQuery::new(query, Some(limit), None),
+    )
+}
+
+/// Returns the path query of the values stored in the root of the fee pools.
+fn pools_path_query() -> PathQuery {
+    let mut query = Query::new();
+    query.insert_key(KEY_STORAGE_FEE_POOL.to_vec());
+    query.insert_key(KEY_UNPAID_EPOCH_INDEX.to_vec());
+
+    PathQuery::new(pools_vec_path(), SizedQuery::new(query, None, None))
+}
+
+/// Decodes a proved big endian u64 item.
+fn decode_proved_u64(element: Element, error: &'static str) -> Result<u64, Error> {
+    let Element::Item(value, _) = element else {
+        return Err(Error::Query(QueryError::InvalidProof(error)));
+    };
+
+    Ok(u64::from_be_bytes(value.as_slice().try_into().map_err(
+        |_| Error::Query(QueryError::InvalidProof(error)),
+    )?))
+}
+
+/// Decodes a proved sum item holding credits.
+fn decode_proved_credits(element: Element, error: &'static str) -> Result<Credits, Error> {
+    let Element::SumItem(value, _) = element else {
+        return Err(Error::Query(QueryError::InvalidProof(error)));
+    };
+
+    Ok(value.to_unsigned())
+}
+
+/// Reconstructs the values stored for an epoch from its proved elements.
+/// Returns `None` if nothing was proved, meaning nothing is stored for the epoch.
+fn epoch_from_proved_elements(
+    proved_elements: Vec<(Vec<u8>, Element)>,
+) -> Result<Option<ProvedEpoch>, Error> {
+    if proved_elements.is_empty() {
+        return Ok(None);
+    }
+
+    let mut proved_epoch = ProvedEpoch::default();
+
+    for (key, element) in proved_elements {
+        match key.as_slice() {
+            key if key == KEY_POOL_PROCESSING_FEES => {
+                proved_epoch.processing_credits_for_distribution = Some(decode_proved_credits(
+                    element,
+                    "proved epoch processing fees must be a sum item",
+                )?);
+            }
+            key if key == KEY_POOL_STORAGE_FEES => {
+                proved_epoch.storage_credits_for_distribution = Some(decode_proved_credits(
+                    element,
+                    "proved epoch storage fees must be a sum item",
+                )?);
+            }
+            key if key == KEY_START_TIME => {
+                proved_epoch.start_time = Some(decode_proved_u64(
+                    element,
+                    "proved epoch start time must be an u64 item",
+                )?);
+            }
+            key if key == KEY_START_BLOCK_HEIGHT => {
+                proved_epoch.start_block_height = Some(decode_proved_u64(
+                    element,
+                    "proved epoch start block height must be an u64 item",
+                )?);
+            }
+            key if key == KEY_FEE_MULTIPLIER => {
+                let Element::Item(value, _) = element else {
+                    return Err(Error::Query(QueryError::InvalidProof(
+                        "proved epoch fee multiplier must be an item",
+                    )));
+                };
+
+                proved_epoch.fee_multiplier = Some(f64::from_be_bytes(
+                    value.as_slice().try_into().map_err(|_| {
+                        Error::Query(QueryError::InvalidProof(
+                            "proved epoch fee multiplier must be f64",
+                        ))
+                    })?,
+                ));
+            }
+            key if key == KEY_FEE_VERSION => {
+                let Element::Item(value, _) = element else {
+                    return Err(Error::Query(QueryError::InvalidProof(
+                        "proved epoch fee version must be an item",
+                    )));
+                };
+
+                proved_epoch.fee_version = Some(FeeVersion::from_be_bytes(
+                    value.as_slice().try_into().map_err(|_| {
+                        Error::Query(QueryError::InvalidProof(
+                            "proved epoch fee version must be u32",
+                        ))
+                    })?,
+                ));
+            }
+            key if key == KEY_FEE_SCHEDULE => {
+                let Element::Item(value, _) = element else {
+                    return Err(Error::Query(QueryError::InvalidProof(
+                        "proved epoch fee schedule must be an item",
+                    )));
+                };
+
+                proved_epoch.fee_schedule =
+                    Some(FeeSchedule::from_be_bytes(&value).map_err(|_| {
+                        Error::Query(QueryError::InvalidProof(
+                            "proved epoch fee schedule must be 9 u64s",
+                        ))
+                    })?);
+            }
+            _ => {
+                return Err(Error::Query(QueryError::InvalidProof(
+                    "proof contains an unexpected epoch element",
+                )));
+            }
+        }
+    }
+
+    // Epochs started before the fee version was stored use the initial fee schedule
+    // and didn't apply their stored fee multiplier, same as `get_epoch_fee_multiplier`
+    if proved_epoch.fee_multiplier.is_some() && proved_epoch.fee_version.is_none() {
+        proved_epoch.fee_multiplier = Some(NEUTRAL_FEE_MULTIPLIER);
+        proved_epoch.fee_version = Some(INITIAL_FEE_VERSION);
+    }
+
+    Ok(Some(proved_epoch))
+}
+
+impl Drive {
+    /// Proves the values stored for an epoch: its start time and block height,
+    /// its credits for distribution, fee multiplier, fee version and fee schedule.
+    pub fn prove_epoch(
+        &self,
+        epoch: &Epoch,
+        transaction: TransactionArg,
+    ) -> Result<Vec<u8>, Error> {
+        let mut drive_operations: Vec<DriveOperation> = vec![];
+        self.grove_get_proved_path_query(
+            &epoch_path_query(epoch),
+            transaction,
+            &mut drive_operations,
+        )
+    }
+
+    /// Verifies a proof of the values stored for an epoch against the expected root hash.
+    /// Returns `None` if nothing is stored for the epoch.
+    pub fn verify_epoch(
+        proof: &[u8],
+        epoch: &Epoch,
+        expected_root_hash: [u8; 32],
+    ) -> Result<Option<ProvedEpoch>, Error> {
+        let proved_elements = Self::grove_verify_proved_path_query(
+            proof,
+            &epoch_path_query(epoch),
+            expected_root_hash,
+        )?;
+
+        epoch_from_proved_elements(proved_elements)
+    }
+
+    /// Proves up to `limit` block proposers of an epoch with their block count.
+    pub fn prove_epoch_proposers(
+        &self,
+        epoch: &Epoch,
+        limit: u16,
+        transaction: TransactionArg,
+    ) -> Result<Vec<u8>, Error> {
+        let mut drive_operations: Vec<DriveOperation> = vec![];
+        self.grove_get_proved_path_query(
+            &epoch_proposers_path_query(epoch, limit),
+            transaction,
+            &mut drive_operations,
+        )
+    }
+
+    /// Verifies a proof of the block proposers of an epoch against the expected root hash
+    /// and returns their pro tx hashes with their block count.
+    pub fn verify_epoch_proposers(
+        proof: &[u8],
+        epoch: &Epoch,
+        limit: u16,
+        expected_root_hash: [u8; 32],
+    ) -> Result<Vec<(Vec<u8>, u64)>, Error> {
+        let proved_elements = Self::grove_verify_proved_path_query(
+            proof,
+            &epoch_proposers_path_query(epoch, limit),
+            expected_root_hash,
+        )?;
+
+        proved_elements
+            .into_iter()
+            .map(|(pro_tx_hash, element)| {
+                let block_count = decode_proved_u64(
+                    element,
+                    "proved epoch proposer block count must be an u64 item",
+                )?;
+
+                Ok((pro_tx_hash, block_count))
+            })
+            .collect()
+    }
+
+    /// Proves the aggregate storage fee distribution pool and the unpaid epoch index.
+    pub fn prove_pools(&self, transaction: TransactionArg) -> Result<Vec<u8>, Error> {
+        let mut drive_operations: Vec<DriveOperation> = vec![];
+        self.grove_get_proved_path_query(&pools_path_query(), transaction, &mut drive_operations)
+    }
+
+    /// Verifies a proof of the aggregate storage fee distribution pool and
+    /// the unpaid epoch index against the expected root hash.
+    pub fn verify_pools(proof: &[u8], expected_root_hash: [u8; 32]) -> Result<ProvedPools, Error> {
+        let proved_elements =
+            Self::grove_verify_proved_path_query(proof, &pools_path_query(), expected_root_hash)?;
+
+        let mut proved_pools = ProvedPools::default();
+
+        for (key, element) in proved_elements {
+            match key.as_slice() {
+                key if key == KEY_STORAGE_FEE_POOL => {
+                    proved_pools.storage_fee_pool = decode_proved_credits(
+                        element,
+                        "proved storage fee pool must be a sum item",
+                    )?;
+                }
+                key if key == KEY_UNPAID_EPOCH_INDEX => {
+                    let Element::Item(value, _) = element else {
+                        return Err(Error::Query(QueryError::InvalidProof(
+                            "proved unpaid epoch index must be an item",
+                        )));
+                    };
+
+                    proved_pools.unpaid_epoch_index = Some(EpochIndex::from_be_bytes(
+                        value.as_slice().try_into().map_err(|_| {
+                            Error::Query(QueryError::InvalidProof(
+                                "proved unpaid epoch index must be u16",
+                            ))
+                        })?,
+                    ));
+                }
+                _ => {
+                    return Err(Error::Query(QueryError::InvalidProof(
+                        "proof contains an unexpected pools element",
+                    )));
+                }
+            }
+        }
+
+        Ok(proved_pools)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::common::helpers::setup::setup_drive_with_initial_state_structure;
+    use crate::drive::batch::GroveDbOpBatch;
+    use crate::fee::default_costs::{FEE_SCHEDULE_V1, LATEST_FEE_VERSION};
+
+    #[test]
+    fn test_prove_and_verify_epoch_and_proposers() {
+        let drive = setup_drive_with_initial_state_structure();
+
+        let fee_schedule = FeeSchedule {
+            sha256_cost: FEE_SCHEDULE_V1.sha256_cost * 2,
+            ..FEE_SCHEDULE_V1
+        };
+
+        let epoch = Epoch::new_with_fee_parameters(0, LATEST_FEE_VERSION, Some(fee_schedule), 1.5);
+
+        let mut batch = GroveDbOpBatch::new();
+        epoch.add_init_current_operations(1.5, 1, 1000, &mut batch);
+        batch.push(epoch.update_proposer_block_count_operation(&[1; 32], 3));
+        batch.push(epoch.update_proposer_block_count_operation(&[2; 32], 5));
+
+        drive
+            .grove_apply_batch(batch, false, None)
+            .expect("expected to apply batch");
+
+        let root_hash = drive
+            .grove
+            .root_hash(None)
+            .unwrap()
+            .expect("expected a root hash");
+
+        let proof = drive
+            .prove_epoch(&epoch, None)
+            .expect("expected to prove the epoch");
+        let proved_epoch = Drive::verify_epoch(proof.as_slice(), &epoch, root_hash)
+            .expect("expected to verify the epoch")
+            .expect("expected the epoch to be stored");
+
+        assert_eq!(
+            proved_epoch,
+            ProvedEpoch {
+                start_time: Some(1000),
+                start_block_height: Some(1),
+                storage_credits_for_distribution: Some(0),
+                processing_credits_for_distribution: None,
+                fee_multiplier: Some(1.5),
+                fee_version: Some(
+                    drive
+                        .get_epoch_fee_version(&epoch, None)
+                        .expect("expected to get the fee version")
+                ),
+                fee_schedule: Some(fee_schedule),
+            }
+        );
+
+        assert!(Drive::verify_epoch(proof.as_slice(), &epoch, [0; 32]).is_err());
+
+        let next_epoch = Epoch::new(1);
+        let proof = drive
+            .prove_epoch(&next_epoch, None)
+            .expect("expected to prove the epoch");
+        let proved_epoch = Drive::verify_epoch(proof.as_slice(), &next_epoch, root_hash)
+            .expect("expected to verify the epoch")
+            .expect("expected the epoch to be stored");
+
+        assert_eq!(
+            proved_epoch,
+            ProvedEpoch {
+                storage_credits_for_distribution: Some(0),
+                ..Default::default()
+            }
+        );
+
+        let proof = drive
+            .prove_epoch_proposers(&epoch, 10, None)
+            .expect("expected to prove the epoch proposers");
+        let proved_proposers =
+            Drive::verify_epoch_proposers(proof.as_slice(), &epoch, 10, root_hash)
+                .expect("expected to verify the epoch proposers");
+
+        assert_eq!(
+            proved_proposers,
+            drive
+                .get_epoch_proposers(&epoch, 10, None)
+                .expect("expected to get the epoch proposers")
+        );
+        assert_eq!(
+            proved_proposers,
+            vec![([1; 32].to_vec(), 3), ([2; 32].to_vec(), 5)]
+        );
+    }
+
+    #[test]
+    fn test_prove_and_verify_pools() {
+        let drive = setup_drive_with_initial_state_structure();
+
+        let root_hash = drive
+            .grove
+            .root_hash(None)
+            .unwrap()
+            .expect("expected a root hash");
+
+        let proof = drive
+            .prove_pools(None)
+            .expect("expected to prove the pools");
+        let proved_pools =
+            Drive::verify_pools(proof.as_slice(), root_hash).expect("expected to verify the pools");
+
+        assert_eq!(
+            proved_pools,
+            ProvedPools {
+                storage_fee_pool: drive
+                    .get_aggregate_storage_fees_from_distribution_pool(None)
+                    .expect("expected to get the storage fee pool"),
+                unpaid_epoch_index: Some(
+                    drive
+                        .get_unpaid_epoch_index(None)
+                        .expect("expected to get the unpaid epoch index")
+                ),
+            }
+        );
+
+        assert!(Drive::verify_pools(proof.as_slice(), [0; 32]).is_err());
+    }
+}
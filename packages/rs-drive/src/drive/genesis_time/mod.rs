@@ -47,7 +47,7 @@ impl Drive {
     /// Returns the genesis time. Checks cache first, then storage.
     pub fn get_genesis_time(&self, transaction: TransactionArg) -> Result<Option<u64>, Error> {
         // let's first check the cache
-        let mut cache = self.cache.borrow_mut();
+        let mut cache = self.write_cache()?;
         match cache.genesis_time_ms {
             None => {
                 let genesis_time_ms = self.fetch_genesis_time(transaction)?;
@@ -98,7 +98,7 @@ impl Drive {
         genesis_time_ms: u64,
         transaction: TransactionArg,
     ) -> Result<(), Error> {
-        self.cache.borrow_mut().genesis_time_ms = Some(genesis_time_ms);
+        self.write_cache()?.genesis_time_ms = Some(genesis_time_ms);
 
         let op = update_genesis_time_operation(genesis_time_ms);
 
@@ -191,7 +191,7 @@ mod tests {
                 None => assert!(false, "should be present"),
             }
 
-            let cache = drive.cache.borrow();
+            let cache = drive.read_cache().expect("expected to read cache");
 
             match cache.genesis_time_ms {
                 Some(stored_genesis_time_ms) => assert_eq!(stored_genesis_time_ms, genesis_time_ms),
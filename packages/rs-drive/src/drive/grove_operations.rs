@@ -53,6 +53,7 @@ use crate::drive::object_size_info::PathKeyInfo::{
 use crate::drive::object_size_info::{DriveKeyInfo, PathKeyElementInfo, PathKeyInfo};
 use crate::drive::Drive;
 use crate::error::drive::DriveError;
+use crate::error::query::QueryError;
 use crate::error::Error;
 use crate::fee::op::DriveOperation;
 use crate::fee::op::DriveOperation::{CalculatedCostOperation, GroveOperation};
@@ -139,6 +140,7 @@ impl BatchInsertTreeApplyType {
     }
 }
 
+#[derive(Clone, Copy)]
 pub enum BatchInsertApplyType {
     StatelessBatchInsert {
         in_tree_using_sums: bool,
@@ -414,6 +416,36 @@ impl Drive {
         value.map_err(Error::GroveDB)
     }
 
+    /// Verifies a groveDB proof of a path query against the expected root hash.
+    /// Doesn't need access to storage, returns the proved keys with their elements.
+    pub(crate) fn grove_verify_proved_path_query(
+        proof: &[u8],
+        path_query: &PathQuery,
+        expected_root_hash: [u8; 32],
+    ) -> Result<Vec<(Vec<u8>, Element)>, Error> {
+        let (root_hash, proved_key_values) =
+            GroveDb::verify_query(proof, path_query).map_err(Error::GroveDB)?;
+
+        if root_hash != expected_root_hash {
+            return Err(Error::Query(QueryError::InvalidProof(
+                "proof root hash does not match the expected root hash",
+            )));
+        }
+
+        proved_key_values
+            .into_iter()
+            .map(|proved_key_value| {
+                let element =
+                    Element::deserialize(proved_key_value.value.as_slice()).map_err(|_| {
+                        Error::Query(QueryError::InvalidProof(
+                            "proof contains an invalid element",
+                        ))
+                    })?;
+                Ok((proved_key_value.key, element))
+            })
+            .collect()
+    }
+
     /// Gets the return value and the cost of a groveDB `has_raw` operation.
     /// Pushes the cost to `drive_operations` and returns the return value.
     pub(crate) fn grove_has_raw<'p, P>(
@@ -0,0 +1,164 @@
+use crate::drive::defaults::{
+    DEFAULT_HASH_160_SIZE_U8, DEFAULT_HASH_SIZE, DEFAULT_HASH_SIZE_U8,
+    ESTIMATED_AVERAGE_IDENTITY_KEYS_COUNT, ESTIMATED_AVERAGE_IDENTITY_KEY_SIZE,
+    ESTIMATED_NON_UNIQUE_PUBLIC_KEY_HASH_IDENTITIES_COUNT,
+};
+use crate::drive::flags::StorageFlags;
+use crate::drive::identity::public_key_hashes::{
+    non_unique_public_key_hash_identities_path, non_unique_public_key_hashes_path,
+    public_key_hashes_root_path, unique_public_key_hashes_path,
+};
+use crate::drive::identity::{identities_root_path, identity_keys_path, identity_path};
+use crate::drive::Drive;
+
+use grovedb::batch::KeyInfoPath;
+use grovedb::EstimatedLayerCount::{ApproximateElements, EstimatedLevel, PotentiallyAtMaxElements};
+use grovedb::EstimatedLayerInformation;
+use grovedb::EstimatedLayerSizes::{AllItems, AllSubtrees, Mix};
+use grovedb::EstimatedSumTrees::{AllSumTrees, NoSumTrees};
+use std::collections::HashMap;
+
+impl Drive {
+    /// Adds the estimation costs for the levels up to the `Identities` subtree
+    pub(super) fn add_estimation_costs_for_levels_up_to_identities(
+        estimated_costs_only_with_layer_info: &mut HashMap<KeyInfoPath, EstimatedLayerInformation>,
+    ) {
+        // we have constructed the top layer so the identities tree is at the top
+        estimated_costs_only_with_layer_info.insert(
+            KeyInfoPath::from_known_path([]),
+            EstimatedLayerInformation {
+                is_sum_tree: false,
+                estimated_layer_count: EstimatedLevel(0, false),
+                estimated_layer_sizes: AllSubtrees(1, NoSumTrees, None),
+            },
+        );
+
+        // we then need to insert the identities layer, every identity is a sum tree
+        estimated_costs_only_with_layer_info.insert(
+            KeyInfoPath::from_known_path(identities_root_path()),
+            EstimatedLayerInformation {
+                is_sum_tree: false,
+                estimated_layer_count: PotentiallyAtMaxElements,
+                estimated_layer_sizes: AllSubtrees(
+                    DEFAULT_HASH_SIZE_U8,
+                    AllSumTrees,
+                    Some(StorageFlags::approximate_size(true, None)),
+                ),
+            },
+        );
+    }
+
+    /// Adds the estimation costs for changing the balance or the revision of an identity
+    pub(super) fn add_estimation_costs_for_identity(
+        identity_id: &[u8],
+        estimated_costs_only_with_layer_info: &mut HashMap<KeyInfoPath, EstimatedLayerInformation>,
+    ) {
+        Self::add_estimation_costs_for_levels_up_to_identities(
+            estimated_costs_only_with_layer_info,
+        );
+
+        let flags_size = Some(StorageFlags::approximate_size(true, None));
+
+        // the identity sum tree holds the balance, the revision and the keys subtree
+        estimated_costs_only_with_layer_info.insert(
+            KeyInfoPath::from_known_path(identity_path(identity_id)),
+            EstimatedLayerInformation {
+                is_sum_tree: true,
+                estimated_layer_count: EstimatedLevel(1, false),
+                estimated_layer_sizes: Mix {
+                    subtrees_size: Some((1, NoSumTrees, flags_size, 1)),
+                    items_size: Some((1, 8, flags_size, 2)),
+                    references_size: None,
+                },
+            },
+        );
+    }
+
+    /// Adds the estimation costs for changing the public keys of an identity
+    pub(super) fn add_estimation_costs_for_identity_keys(
+        identity_id: &[u8],
+        estimated_costs_only_with_layer_info: &mut HashMap<KeyInfoPath, EstimatedLayerInformation>,
+    ) {
+        Self::add_estimation_costs_for_identity(identity_id, estimated_costs_only_with_layer_info);
+
+        estimated_costs_only_with_layer_info.insert(
+            KeyInfoPath::from_known_path(identity_keys_path(identity_id)),
+            EstimatedLayerInformation {
+                is_sum_tree: false,
+                estimated_layer_count: ApproximateElements(ESTIMATED_AVERAGE_IDENTITY_KEYS_COUNT),
+                estimated_layer_sizes: AllItems(
+                    8,
+                    ESTIMATED_AVERAGE_IDENTITY_KEY_SIZE,
+                    Some(StorageFlags::approximate_size(true, None)),
+                ),
+            },
+        );
+    }
+
+    /// Adds the estimation costs for mapping public key hashes to identities
+    pub(super) fn add_estimation_costs_for_public_key_hashes(
+        public_key_hash: &[u8],
+        estimated_costs_only_with_layer_info: &mut HashMap<KeyInfoPath, EstimatedLayerInformation>,
+    ) {
+        let flags_size = Some(StorageFlags::approximate_size(true, None));
+
+        estimated_costs_only_with_layer_info.insert(
+            KeyInfoPath::from_known_path([]),
+            EstimatedLayerInformation {
+                is_sum_tree: false,
+                estimated_layer_count: EstimatedLevel(0, false),
+                estimated_layer_sizes: AllSubtrees(1, NoSumTrees, None),
+            },
+        );
+
+        // the public key hashes tree only holds the unique and the non-unique subtrees
+        estimated_costs_only_with_layer_info.insert(
+            KeyInfoPath::from_known_path(public_key_hashes_root_path()),
+            EstimatedLayerInformation {
+                is_sum_tree: false,
+                estimated_layer_count: EstimatedLevel(1, false),
+                estimated_layer_sizes: AllSubtrees(1, NoSumTrees, None),
+            },
+        );
+
+        estimated_costs_only_with_layer_info.insert(
+            KeyInfoPath::from_known_path(unique_public_key_hashes_path()),
+            EstimatedLayerInformation {
+                is_sum_tree: false,
+                estimated_layer_count: PotentiallyAtMaxElements,
+                estimated_layer_sizes: AllItems(
+                    DEFAULT_HASH_160_SIZE_U8,
+                    DEFAULT_HASH_SIZE,
+                    flags_size,
+                ),
+            },
+        );
+
+        estimated_costs_only_with_layer_info.insert(
+            KeyInfoPath::from_known_path(non_unique_public_key_hashes_path()),
+            EstimatedLayerInformation {
+                is_sum_tree: false,
+                estimated_layer_count: PotentiallyAtMaxElements,
+                estimated_layer_sizes: AllSubtrees(
+                    DEFAULT_HASH_160_SIZE_U8,
+                    NoSumTrees,
+                    flags_size,
+                ),
+            },
+        );
+
+        // the identities sharing a non-unique public key hash are keys with empty values
+        estimated_costs_only_with_layer_info.insert(
+            KeyInfoPath::from_known_path(non_unique_public_key_hash_identities_path(
+                public_key_hash,
+            )),
+            EstimatedLayerInformation {
+                is_sum_tree: false,
+                estimated_layer_count: ApproximateElements(
+                    ESTIMATED_NON_UNIQUE_PUBLIC_KEY_HASH_IDENTITIES_COUNT,
+                ),
+                estimated_layer_sizes: AllItems(DEFAULT_HASH_SIZE_U8, 0, flags_size),
+            },
+        );
+    }
+}
@@ -0,0 +1,363 @@
+// MIT LICENSE
+//
+// Copyright (c) 2022 Dash Core Group
+//
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+//
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+//
+
+//! Fetch Identities.
+//!
+//! This module implements functions in Drive for fetching identities, or only
+//! their balance, revision or public keys, from the `Identities` subtree.
+//!
+
+use dpp::identifier::Identifier;
+use dpp::identity::{Identity, IdentityPublicKey};
+use dpp::prelude::Revision;
+use grovedb::query_result_type::QueryResultType::QueryElementResultType;
+use grovedb::{Element, PathQuery, Query, SizedQuery, TransactionArg};
+
+use crate::drive::flags::StorageFlags;
+use crate::drive::grove_operations::DirectQueryType::{StatefulDirectQuery, StatelessDirectQuery};
+use crate::drive::grove_operations::QueryTarget::QueryTargetValue;
+use crate::drive::identity::{
+    deserialize_identity_key, identities_root_path, identity_keys_path, identity_path,
+    IDENTITY_BALANCE_KEY, IDENTITY_REVISION_KEY,
+};
+use crate::drive::Drive;
+use crate::error::drive::DriveError;
+use crate::error::identity::IdentityError;
+use crate::error::Error;
+use crate::fee::calculate_fee;
+use crate::fee::credits::{Creditable, Credits};
+use crate::fee::op::DriveOperation;
+use crate::fee::result::FeeResult;
+use crate::fee_pools::epochs::Epoch;
+
+impl Drive {
+    /// Fetches the balance of an identity with the storage flags of the balance.
+    /// Returns `None` if `apply` is false, as only the costs of fetching are estimated.
+    pub(crate) fn fetch_identity_balance_operations(
+        &self,
+        identity_id: &[u8],
+        apply: bool,
+        transaction: TransactionArg,
+        drive_operations: &mut Vec<DriveOperation>,
+    ) -> Result<Option<(Credits, Option<StorageFlags>)>, Error> {
+        let direct_query_type = if apply {
+            StatefulDirectQuery
+        } else {
+            StatelessDirectQuery {
+                in_tree_using_sums: true,
+                query_target: QueryTargetValue(8),
+            }
+        };
+
+        match self.grove_get_direct(
+            identity_path(identity_id),
+            IDENTITY_BALANCE_KEY.as_slice(),
+            direct_query_type,
+            transaction,
+            drive_operations,
+        )? {
+            Some(Element::SumItem(balance, element_flags)) => Ok(Some((
+                balance.to_unsigned(),
+                StorageFlags::from_some_element_flags_ref(&element_flags)?,
+            ))),
+            None => Ok(None),
+            _ => Err(Error::Drive(DriveError::UnexpectedElementType(
+                "identity balance must be a sum item",
+            ))),
+        }
+    }
+
+    /// Fetches the balance of an identity.
+    pub fn fetch_identity_balance(
+        &self,
+        identity_id: [u8; 32],
+        transaction: TransactionArg,
+    ) -> Result<Credits, Error> {
+        let mut drive_operations: Vec<DriveOperation> = vec![];
+        self.fetch_identity_balance_operations(
+            identity_id.as_slice(),
+            true,
+            transaction,
+            &mut drive_operations,
+        )?
+        .map(|(balance, _)| balance)
+        .ok_or(Error::Drive(DriveError::CorruptedCodeExecution(
+            "stateful fetch must return a balance",
+        )))
+    }
+
+    /// Fetches the revision of an identity.
+    pub(crate) fn fetch_identity_revision_operations(
+        &self,
+        identity_id: [u8; 32],
+        transaction: TransactionArg,
+        drive_operations: &mut Vec<DriveOperation>,
+    ) -> Result<Revision, Error> {
+        let element = match self.grove_get_direct(
+            identity_path(identity_id.as_slice()),
+            IDENTITY_REVISION_KEY.as_slice(),
+            StatefulDirectQuery,
+            transaction,
+            drive_operations,
+        ) {
+            Err(Error::GroveDB(grovedb::Error::PathKeyNotFound(_))) => None,
+            result => result?,
+        };
+
+        let encoded_revision = match element {
+            Some(Element::Item(encoded_revision, _)) => encoded_revision,
+            Some(_) => {
+                return Err(Error::Drive(DriveError::UnexpectedElementType(
+                    "identity revision must be an item",
+                )))
+            }
+            None => {
+                return Err(Error::Identity(IdentityError::CorruptedIdentity(
+                    "identity must have a revision",
+                )))
+            }
+        };
+
+        Ok(Revision::from_be_bytes(
+            encoded_revision.as_slice().try_into().map_err(|_| {
+                Error::Drive(DriveError::CorruptedSerialization(
+                    "identity revision must be u64",
+                ))
+            })?,
+        ))
+    }
+
+    /// Fetches the revision of an identity.
+    pub fn fetch_identity_revision(
+        &self,
+        identity_id: [u8; 32],
+        transaction: TransactionArg,
+    ) -> Result<Revision, Error> {
+        let mut drive_operations: Vec<DriveOperation> = vec![];
+        self.fetch_identity_revision_operations(identity_id, transaction, &mut drive_operations)
+    }
+
+    /// Fetches all public keys of an identity ordered by key ID.
+    pub(crate) fn fetch_identity_keys_operations(
+        &self,
+        identity_id: [u8; 32],
+        transaction: TransactionArg,
+        drive_operations: &mut Vec<DriveOperation>,
+    ) -> Result<Vec<IdentityPublicKey>, Error> {
+        let mut query = Query::new();
+        query.insert_all();
+
+        let path_query = PathQuery::new(
+            identity_keys_path(identity_id.as_slice())
+                .into_iter()
+                .map(Vec::from)
+                .collect(),
+            SizedQuery::new(query, None, None),
+        );
+
+        let (result_items, _) = self.grove_get_raw_path_query(
+            &path_query,
+            transaction,
+            QueryElementResultType,
+            drive_operations,
+        )?;
+
+        result_items
+            .to_elements()
+            .into_iter()
+            .map(|element| {
+                if let Element::Item(key_bytes, _) = element {
+                    deserialize_identity_key(key_bytes.as_slice())
+                } else {
+                    Err(Error::Drive(DriveError::UnexpectedElementType(
+                        "identity key must be an item",
+                    )))
+                }
+            })
+            .collect()
+    }
+
+    /// Fetches all public keys of an identity ordered by key ID.
+    pub fn fetch_identity_keys(
+        &self,
+        identity_id: [u8; 32],
+        transaction: TransactionArg,
+    ) -> Result<Vec<IdentityPublicKey>, Error> {
+        let mut drive_operations: Vec<DriveOperation> = vec![];
+        self.fetch_identity_keys_operations(identity_id, transaction, &mut drive_operations)
+    }
+
+    /// Given an identity, fetches the identity with its flags from storage.
+    /// Identities are stored without a protocol version, the identity is built
+    /// for the requested protocol version.
+    pub(crate) fn fetch_identity_operations(
+        &self,
+        id: &[u8],
+        protocol_version: u32,
+        transaction: TransactionArg,
+        drive_operations: &mut Vec<DriveOperation>,
+    ) -> Result<(Identity, Option<StorageFlags>), Error> {
+        let identity_id: [u8; 32] = id.try_into().map_err(|_| {
+            Error::Identity(IdentityError::InvalidIdentityStructure(
+                "identity id must be 32 bytes",
+            ))
+        })?;
+
+        // the flags of the identity are the flags of its subtree
+        let element = match self.grove_get_direct(
+            identities_root_path(),
+            id,
+            StatefulDirectQuery,
+            transaction,
+            drive_operations,
+        ) {
+            Err(Error::GroveDB(grovedb::Error::PathKeyNotFound(_))) => {
+                return Err(Error::Identity(IdentityError::IdentityNotFound(
+                    "identity does not exist",
+                )))
+            }
+            result => result?,
+        };
+
+        let Some(Element::SumTree(_, _, element_flags)) = &element else {
+            return Err(Error::Drive(DriveError::UnexpectedElementType(
+                "identity must be a sum tree",
+            )));
+        };
+
+        let balance = match self.fetch_identity_balance_operations(
+            identity_id.as_slice(),
+            true,
+            transaction,
+            drive_operations,
+        ) {
+            Err(Error::GroveDB(grovedb::Error::PathKeyNotFound(_))) => None,
+            result => result?,
+        }
+        .map(|(balance, _)| balance)
+        .ok_or(Error::Identity(IdentityError::CorruptedIdentity(
+            "identity must have a balance",
+        )))?;
+
+        let identity = Identity {
+            protocol_version,
+            id: Identifier::new(identity_id),
+            public_keys: self.fetch_identity_keys_operations(
+                identity_id,
+                transaction,
+                drive_operations,
+            )?,
+            balance,
+            revision: self.fetch_identity_revision_operations(
+                identity_id,
+                transaction,
+                drive_operations,
+            )?,
+            asset_lock_proof: None,
+            metadata: None,
+        };
+
+        Ok((
+            identity,
+            StorageFlags::from_some_element_flags_ref(element_flags)?,
+        ))
+    }
+
+    /// Given an identity, fetches the identity with its flags from storage
+    /// for the requested protocol version.
+    pub fn fetch_identity(
+        &self,
+        id: &[u8],
+        protocol_version: u32,
+        transaction: TransactionArg,
+    ) -> Result<(Identity, Option<StorageFlags>), Error> {
+        let mut drive_operations: Vec<DriveOperation> = vec![];
+        self.fetch_identity_operations(id, protocol_version, transaction, &mut drive_operations)
+    }
+
+    /// Given an identity, fetches the identity with its flags from storage and returns
+    /// the fees of reading it in the given epoch. The identity is built for the requested
+    /// protocol version.
+    /// The identity is `None` if it doesn't exist, reading that it doesn't exist is charged.
+    pub fn fetch_identity_with_fees(
+        &self,
+        id: &[u8],
+        protocol_version: u32,
+        epoch: &Epoch,
+        transaction: TransactionArg,
+    ) -> Result<(Option<(Identity, Option<StorageFlags>)>, FeeResult), Error> {
+        let mut drive_operations: Vec<DriveOperation> = vec![];
+        let maybe_identity = match self.fetch_identity_operations(
+            id,
+            protocol_version,
+            transaction,
+            &mut drive_operations,
+        ) {
+            Ok(identity_with_flags) => Some(identity_with_flags),
+            Err(Error::Identity(IdentityError::IdentityNotFound(_))) => None,
+            Err(e) => return Err(e),
+        };
+        let fee_result = calculate_fee(None, Some(drive_operations), epoch)?;
+        Ok((maybe_identity, fee_result))
+    }
+
+    /// Given a vector of identities, fetches the identities from storage
+    /// for the requested protocol version.
+    pub fn fetch_identities(
+        &self,
+        ids: &Vec<[u8; 32]>,
+        protocol_version: u32,
+        transaction: TransactionArg,
+    ) -> Result<Vec<Identity>, Error> {
+        Ok(self
+            .fetch_identities_with_flags(ids, protocol_version, transaction)?
+            .into_iter()
+            .map(|(identity, _)| identity)
+            .collect())
+    }
+
+    /// Given a vector of identities, fetches the identities with their flags from storage
+    /// for the requested protocol version. Identities which don't exist are skipped.
+    pub fn fetch_identities_with_flags(
+        &self,
+        ids: &Vec<[u8; 32]>,
+        protocol_version: u32,
+        transaction: TransactionArg,
+    ) -> Result<Vec<(Identity, Option<StorageFlags>)>, Error> {
+        ids.iter()
+            .filter_map(
+                |id| match self.fetch_identity(id, protocol_version, transaction) {
+                    Ok(identity_with_flags) => Some(Ok(identity_with_flags)),
+                    Err(Error::Identity(IdentityError::IdentityNotFound(_))) => None,
+                    Err(e) => Some(Err(e)),
+                },
+            )
+            .collect()
+    }
+}
@@ -32,9 +32,8 @@
 //! fetching identities from the subtree.
 //!
 
-use dpp::identity::Identity;
+use dpp::identity::{Identity, IdentityPublicKey, KeyID};
 use grovedb::batch::KeyInfoPath;
-use grovedb::query_result_type::QueryResultType::QueryElementResultType;
 use grovedb::{
     Element, EstimatedLayerInformation, PathQuery, Query, QueryItem, SizedQuery, TransactionArg,
 };
@@ -43,55 +42,291 @@ use std::collections::HashMap;
 use crate::drive::batch::GroveDbOpBatch;
 use crate::drive::block_info::BlockInfo;
 use crate::drive::flags::StorageFlags;
+use crate::drive::identity::public_key_hashes::{is_unique_key_type, public_key_hash};
+use crate::drive::object_size_info::DriveKeyInfo::KeyRef;
+use crate::drive::object_size_info::PathKeyElementInfo::PathFixedSizeKeyElement;
 use crate::drive::{Drive, RootTree};
-use crate::error::drive::DriveError;
 use crate::error::identity::IdentityError;
 use crate::error::Error;
 use crate::fee::calculate_fee;
+use crate::fee::credits::{Creditable, Credits};
 use crate::fee::op::DriveOperation;
 use crate::fee::result::FeeResult;
 
+mod estimation_costs;
+mod fetch;
+mod prove;
+pub mod public_key_hashes;
+mod update;
 pub mod withdrawal_queue;
 
-const IDENTITY_KEY: [u8; 1] = [0];
+/// The key of the balance sum item in the identity subtree
+pub(crate) const IDENTITY_BALANCE_KEY: [u8; 1] = [0];
+/// The key of the revision item in the identity subtree
+pub(crate) const IDENTITY_REVISION_KEY: [u8; 1] = [1];
+/// The key of the public keys subtree in the identity subtree
+pub(crate) const IDENTITY_KEYS_KEY: [u8; 1] = [2];
+
+/// Returns the path to the `Identities` subtree.
+pub(crate) fn identities_root_path() -> [&'static [u8]; 1] {
+    [Into::<&[u8; 1]>::into(RootTree::Identities)]
+}
+
+/// Takes an identity ID and returns the identity's subtree path.
+pub(crate) fn identity_path(identity_id: &[u8]) -> [&[u8]; 2] {
+    [Into::<&[u8; 1]>::into(RootTree::Identities), identity_id]
+}
+
+/// Takes an identity ID and returns the path to the identity's public keys.
+pub(crate) fn identity_keys_path(identity_id: &[u8]) -> [&[u8]; 3] {
+    [
+        Into::<&[u8; 1]>::into(RootTree::Identities),
+        identity_id,
+        &IDENTITY_KEYS_KEY,
+    ]
+}
+
+/// Returns the path query of the whole subtrees of the given identities.
+pub(crate) fn full_identities_path_query(identity_ids: &[[u8; 32]]) -> PathQuery {
+    let mut keys_query = Query::new();
+    keys_query.insert_all();
+
+    let mut identity_query = Query::new();
+    identity_query.insert_all();
+    identity_query.add_conditional_subquery(
+        QueryItem::Key(IDENTITY_KEYS_KEY.to_vec()),
+        None,
+        Some(keys_query),
+    );
+
+    let mut query = Query::new();
+    for identity_id in identity_ids {
+        query.insert_key(identity_id.to_vec());
+    }
+    query.set_subquery(identity_query);
+
+    PathQuery::new(
+        vec![vec![RootTree::Identities as u8]],
+        SizedQuery::new(query, None, None),
+    )
+}
+
+/// Takes an identity ID and returns the identity's subtree path as a vector.
+fn identity_path_vec(identity_id: &[u8]) -> Vec<Vec<u8>> {
+    vec![vec![RootTree::Identities as u8], identity_id.to_vec()]
+}
+
+/// Encodes a key ID to be used as a key in the identity's public keys subtree.
+fn identity_key_tree_key(key_id: KeyID) -> [u8; 8] {
+    key_id.to_be_bytes()
+}
+
+/// Serializes an identity public key for storage.
+fn serialize_identity_key(key: &IdentityPublicKey) -> Result<Vec<u8>, Error> {
+    let mut buffer: Vec<u8> = Vec::new();
+    ciborium::ser::into_writer(&key.to_cbor_value(), &mut buffer).map_err(|_| {
+        Error::Identity(IdentityError::IdentitySerialization(
+            "failed to serialize identity key to CBOR",
+        ))
+    })?;
+    Ok(buffer)
+}
+
+/// Deserializes an identity public key from storage.
+fn deserialize_identity_key(bytes: &[u8]) -> Result<IdentityPublicKey, Error> {
+    let value: ciborium::value::Value = ciborium::de::from_reader(bytes).map_err(|_| {
+        Error::Identity(IdentityError::IdentitySerialization(
+            "failed to de-serialize identity key from CBOR",
+        ))
+    })?;
+    IdentityPublicKey::from_cbor_value(&value).map_err(|_| {
+        Error::Identity(IdentityError::IdentitySerialization(
+            "failed to de-serialize identity key from CBOR",
+        ))
+    })
+}
 
 impl Drive {
+    /// Returns the operation which sets the balance of an identity.
+    fn set_identity_balance_operation(
+        identity_id: &[u8],
+        balance: Credits,
+        storage_flags: Option<&StorageFlags>,
+    ) -> Result<DriveOperation, Error> {
+        Ok(DriveOperation::for_known_path_key_element(
+            identity_path_vec(identity_id),
+            IDENTITY_BALANCE_KEY.to_vec(),
+            Element::new_sum_item_with_flags(
+                balance.to_signed()?,
+                StorageFlags::map_to_some_element_flags(storage_flags),
+            ),
+        ))
+    }
+
+    /// Returns the operation which sets the revision of an identity.
+    fn set_identity_revision_operation(
+        identity_id: &[u8],
+        revision: u64,
+        storage_flags: Option<&StorageFlags>,
+    ) -> DriveOperation {
+        DriveOperation::for_known_path_key_element(
+            identity_path_vec(identity_id),
+            IDENTITY_REVISION_KEY.to_vec(),
+            Element::Item(
+                revision.to_be_bytes().to_vec(),
+                StorageFlags::map_to_some_element_flags(storage_flags),
+            ),
+        )
+    }
+
+    /// Pushes the operations inserting or replacing the given keys in the identity's
+    /// public keys subtree.
+    fn set_identity_keys_operations<'a>(
+        &self,
+        identity_id: &[u8],
+        keys: impl IntoIterator<Item = &'a IdentityPublicKey>,
+        storage_flags: Option<&StorageFlags>,
+        drive_operations: &mut Vec<DriveOperation>,
+    ) -> Result<(), Error> {
+        for key in keys {
+            let key_id_bytes = identity_key_tree_key(key.id);
+            self.batch_insert(
+                PathFixedSizeKeyElement((
+                    identity_keys_path(identity_id),
+                    key_id_bytes.as_slice(),
+                    Element::Item(
+                        serialize_identity_key(key)?,
+                        StorageFlags::map_to_some_element_flags(storage_flags),
+                    ),
+                )),
+                drive_operations,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// The operations for inserting a new identity in the `Identities` subtree.
+    ///
+    /// Every identity has its own sum subtree holding the balance as a sum item,
+    /// the revision as an item and a subtree of public keys keyed by key ID.
+    /// The hashes of its enabled public keys are mapped to the identity.
+    pub(crate) fn insert_identity_operations(
+        &self,
+        identity: Identity,
+        storage_flags: Option<&StorageFlags>,
+        estimated_costs_only_with_layer_info: &mut Option<
+            HashMap<KeyInfoPath, EstimatedLayerInformation>,
+        >,
+        transaction: TransactionArg,
+    ) -> Result<Vec<DriveOperation>, Error> {
+        let mut batch_operations: Vec<DriveOperation> = vec![];
+
+        let identity_id = identity.id.as_bytes();
+
+        if let Some(estimated_costs_only_with_layer_info) = estimated_costs_only_with_layer_info {
+            Self::add_estimation_costs_for_identity_keys(
+                identity_id,
+                estimated_costs_only_with_layer_info,
+            );
+        }
+
+        batch_operations.push(DriveOperation::for_known_path_key_empty_sum_tree(
+            vec![vec![RootTree::Identities as u8]],
+            identity_id.to_vec(),
+            storage_flags,
+        ));
+
+        batch_operations.push(Self::set_identity_balance_operation(
+            identity_id,
+            identity.balance,
+            storage_flags,
+        )?);
+
+        batch_operations.push(Self::set_identity_revision_operation(
+            identity_id,
+            identity.revision,
+            storage_flags,
+        ));
+
+        self.batch_insert_empty_tree(
+            identity_path(identity_id),
+            KeyRef(&IDENTITY_KEYS_KEY),
+            storage_flags,
+            &mut batch_operations,
+        )?;
+
+        self.set_identity_keys_operations(
+            identity_id,
+            identity.public_keys.iter(),
+            storage_flags,
+            &mut batch_operations,
+        )?;
+
+        self.insert_public_key_hash_references_to_identity_operations(
+            &identity,
+            storage_flags,
+            estimated_costs_only_with_layer_info,
+            transaction,
+            &mut batch_operations,
+        )?;
+
+        Ok(batch_operations)
+    }
+
+    /// Pushes the operations mapping the hashes of the enabled public keys of an identity
+    /// to the identity, and removing the mappings of its disabled public keys.
+    fn insert_public_key_hash_references_to_identity_operations(
+        &self,
+        identity: &Identity,
+        storage_flags: Option<&StorageFlags>,
+        estimated_costs_only_with_layer_info: &mut Option<
+            HashMap<KeyInfoPath, EstimatedLayerInformation>,
+        >,
+        transaction: TransactionArg,
+        drive_operations: &mut Vec<DriveOperation>,
+    ) -> Result<(), Error> {
+        let identity_id = identity.id.to_buffer();
+
+        for key in identity.public_keys.iter() {
+            if key.is_disabled() {
+                self.remove_public_key_hash_reference_to_identity_operations(
+                    identity_id,
+                    public_key_hash(key)?.as_slice(),
+                    is_unique_key_type(key.key_type),
+                    estimated_costs_only_with_layer_info,
+                    transaction,
+                    drive_operations,
+                )?;
+            } else {
+                self.insert_public_key_hash_reference_to_identity_operations(
+                    identity_id,
+                    key,
+                    storage_flags,
+                    estimated_costs_only_with_layer_info,
+                    transaction,
+                    drive_operations,
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
     /// Adds operations to the op batch to insert a new identity in the `Identities` subtree
-    /// with its own empty subtree.
+    /// with its own subtree.
     pub fn add_insert_identity_operations(
         &self,
         identity: Identity,
         storage_flags: Option<&StorageFlags>,
         batch: &mut GroveDbOpBatch,
+        transaction: TransactionArg,
     ) -> Result<(), Error> {
-        // Serialize identity
-        let identity_bytes = identity.to_buffer().map_err(|_| {
-            Error::Identity(IdentityError::IdentitySerialization(
-                "failed to serialize identity to CBOR",
-            ))
-        })?;
-
-        // Adds an operation to the op batch which inserts an empty subtree with flags
-        // at the key of the given identity in the `Identities` subtree.
-        batch.add_insert_empty_tree_with_flags(
-            vec![vec![RootTree::Identities as u8]],
-            identity.id.buffer.to_vec(),
-            storage_flags,
-        );
+        let drive_operations =
+            self.insert_identity_operations(identity, storage_flags, &mut None, transaction)?;
 
-        // Adds an operation to the op batch which inserts the serialized identity
-        // in the `IDENTITY_KEY` key of the new subtree that was just created.
-        batch.add_insert(
-            vec![
-                vec![RootTree::Identities as u8],
-                identity.id.buffer.to_vec(),
-            ],
-            IDENTITY_KEY.to_vec(),
-            Element::Item(
-                identity_bytes,
-                StorageFlags::map_to_some_element_flags(storage_flags),
-            ),
-        );
+        batch.append(&mut DriveOperation::grovedb_operations_batch(
+            &drive_operations,
+        ));
 
         Ok(())
     }
@@ -105,132 +340,150 @@ impl Drive {
         storage_flags: Option<&StorageFlags>,
         transaction: TransactionArg,
     ) -> Result<FeeResult, Error> {
-        let mut batch = GroveDbOpBatch::new();
-        let estimated_costs_only_with_layer_info = if apply {
+        let mut estimated_costs_only_with_layer_info = if apply {
             None::<HashMap<KeyInfoPath, EstimatedLayerInformation>>
         } else {
             Some(HashMap::new())
         };
 
-        self.add_insert_identity_operations(identity, storage_flags, &mut batch)?;
+        let batch_operations = self.insert_identity_operations(
+            identity,
+            storage_flags,
+            &mut estimated_costs_only_with_layer_info,
+            transaction,
+        )?;
 
         let mut drive_operations: Vec<DriveOperation> = vec![];
 
-        self.apply_batch_grovedb_operations(
+        self.apply_batch_drive_operations(
             estimated_costs_only_with_layer_info,
             transaction,
-            batch,
+            batch_operations,
             &mut drive_operations,
         )?;
 
         calculate_fee(None, Some(drive_operations), &block_info.epoch)
     }
 
-    /// Given an identity, fetches the identity with its flags from storage.
-    pub fn fetch_identity(
+    /// The operations for replacing the balance, revision and public keys of an
+    /// already existing identity. Keys are never removed from an identity, they are
+    /// disabled instead, so every key of the given identity is inserted or replaced.
+    /// The public key hash mappings of the identity are updated accordingly.
+    pub(crate) fn update_identity_operations(
         &self,
-        id: &[u8],
+        identity: &Identity,
+        storage_flags: Option<&StorageFlags>,
+        estimated_costs_only_with_layer_info: &mut Option<
+            HashMap<KeyInfoPath, EstimatedLayerInformation>,
+        >,
         transaction: TransactionArg,
-    ) -> Result<(Identity, Option<StorageFlags>), Error> {
-        // get element from GroveDB
-        let element = self
-            .grove
-            .get(
-                [Into::<&[u8; 1]>::into(RootTree::Identities).as_slice(), id],
-                &IDENTITY_KEY,
-                transaction,
-            )
-            .unwrap()
-            .map_err(Error::GroveDB)?;
-
-        // extract identity from element and deserialize the identity
-        if let Element::Item(identity_cbor, element_flags) = &element {
-            let identity = Identity::from_buffer(identity_cbor.as_slice()).map_err(|_| {
-                Error::Identity(IdentityError::IdentitySerialization(
-                    "failed to de-serialize identity from CBOR",
-                ))
-            })?;
-
-            Ok((
-                identity,
-                StorageFlags::from_some_element_flags_ref(element_flags)?,
-            ))
-        } else {
-            Err(Error::Drive(DriveError::CorruptedIdentityNotItem(
-                "identity must be an item",
-            )))
+    ) -> Result<Vec<DriveOperation>, Error> {
+        let mut batch_operations: Vec<DriveOperation> = vec![];
+
+        let identity_id = identity.id.as_bytes();
+
+        if let Some(estimated_costs_only_with_layer_info) = estimated_costs_only_with_layer_info {
+            Self::add_estimation_costs_for_identity_keys(
+                identity_id,
+                estimated_costs_only_with_layer_info,
+            );
         }
+
+        batch_operations.push(Self::set_identity_balance_operation(
+            identity_id,
+            identity.balance,
+            storage_flags,
+        )?);
+
+        batch_operations.push(Self::set_identity_revision_operation(
+            identity_id,
+            identity.revision,
+            storage_flags,
+        ));
+
+        self.set_identity_keys_operations(
+            identity_id,
+            identity.public_keys.iter(),
+            storage_flags,
+            &mut batch_operations,
+        )?;
+
+        self.insert_public_key_hash_references_to_identity_operations(
+            identity,
+            storage_flags,
+            estimated_costs_only_with_layer_info,
+            transaction,
+            &mut batch_operations,
+        )?;
+
+        Ok(batch_operations)
     }
 
-    /// Given a vector of identities, fetches the identities from storage.
-    pub fn fetch_identities(
+    /// Adds operations to the op batch to replace the balance, revision and public keys
+    /// of an already existing identity.
+    pub fn add_update_identity_operations(
         &self,
-        ids: &Vec<[u8; 32]>,
+        identity: &Identity,
+        storage_flags: Option<&StorageFlags>,
+        batch: &mut GroveDbOpBatch,
         transaction: TransactionArg,
-    ) -> Result<Vec<Identity>, Error> {
-        Ok(self
-            .fetch_identities_with_flags(ids, transaction)?
-            .into_iter()
-            .map(|(identity, _)| identity)
-            .collect())
+    ) -> Result<(), Error> {
+        let drive_operations =
+            self.update_identity_operations(identity, storage_flags, &mut None, transaction)?;
+
+        batch.append(&mut DriveOperation::grovedb_operations_batch(
+            &drive_operations,
+        ));
+
+        Ok(())
     }
 
-    /// Given a vector of identities, fetches the identities with their flags from storage.
-    pub fn fetch_identities_with_flags(
+    /// Replaces an existing identity in the `Identities` subtree.
+    pub fn update_identity(
         &self,
-        ids: &Vec<[u8; 32]>,
+        identity: &Identity,
+        block_info: BlockInfo,
+        apply: bool,
+        storage_flags: Option<&StorageFlags>,
         transaction: TransactionArg,
-    ) -> Result<Vec<(Identity, Option<StorageFlags>)>, Error> {
-        let mut query = Query::new();
-        query.set_subquery_key(IDENTITY_KEY.to_vec());
-        for id in ids {
-            query.insert_item(QueryItem::Key(id.to_vec()));
-        }
-        let path_query = PathQuery {
-            path: vec![vec![RootTree::Identities as u8]],
-            query: SizedQuery {
-                query,
-                limit: None,
-                offset: None,
-            },
+    ) -> Result<FeeResult, Error> {
+        let mut estimated_costs_only_with_layer_info = if apply {
+            None::<HashMap<KeyInfoPath, EstimatedLayerInformation>>
+        } else {
+            Some(HashMap::new())
         };
-        let (result_items, _) = self
-            .grove
-            .query_raw(&path_query, QueryElementResultType, transaction)
-            .unwrap()
-            .map_err(Error::GroveDB)?;
-
-        result_items
-            .to_elements()
-            .into_iter()
-            .map(|element| {
-                if let Element::Item(identity_cbor, element_flags) = &element {
-                    let identity =
-                        Identity::from_buffer(identity_cbor.as_slice()).map_err(|_| {
-                            Error::Identity(IdentityError::IdentitySerialization(
-                                "failed to de-serialize identity from CBOR",
-                            ))
-                        })?;
-
-                    Ok((
-                        identity,
-                        StorageFlags::from_some_element_flags_ref(element_flags)?,
-                    ))
-                } else {
-                    Err(Error::Drive(DriveError::CorruptedIdentityNotItem(
-                        "identity must be an item",
-                    )))
-                }
-            })
-            .collect()
+
+        let batch_operations = self.update_identity_operations(
+            identity,
+            storage_flags,
+            &mut estimated_costs_only_with_layer_info,
+            transaction,
+        )?;
+
+        let mut drive_operations: Vec<DriveOperation> = vec![];
+
+        self.apply_batch_drive_operations(
+            estimated_costs_only_with_layer_info,
+            transaction,
+            batch_operations,
+            &mut drive_operations,
+        )?;
+
+        calculate_fee(None, Some(drive_operations), &block_info.epoch)
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::common::helpers::identities::create_test_identity;
     use crate::common::helpers::setup::setup_drive;
     use crate::drive::block_info::BlockInfo;
+    use crate::drive::defaults::PROTOCOL_VERSION;
     use crate::drive::flags::StorageFlags;
+    use crate::drive::identity::{identity_path, IDENTITY_REVISION_KEY};
+    use crate::error::identity::IdentityError;
+    use crate::error::Error;
+    use crate::fee::op::DriveOperation;
     use dpp::identity::Identity;
 
     #[test]
@@ -259,7 +512,11 @@ mod tests {
             .expect("expected to insert identity");
 
         let (fetched_identity, _) = drive
-            .fetch_identity(&identity.id.buffer, Some(&transaction))
+            .fetch_identity(
+                &identity.id.buffer,
+                identity.protocol_version,
+                Some(&transaction),
+            )
             .expect("should fetch an identity");
 
         assert_eq!(
@@ -267,4 +524,114 @@ mod tests {
             identity.to_buffer().expect("should serialize")
         );
     }
+
+    #[test]
+    fn test_fetch_identity_balance_revision_and_keys() {
+        let drive = setup_drive(None);
+
+        let transaction = drive.grove.start_transaction();
+
+        drive
+            .create_initial_state_structure(Some(&transaction))
+            .expect("expected to create root tree successfully");
+
+        let identity = create_test_identity(&drive, [1; 32], Some(&transaction));
+
+        let balance = drive
+            .fetch_identity_balance([1; 32], Some(&transaction))
+            .expect("expected to fetch identity balance");
+
+        assert_eq!(balance, identity.balance);
+
+        let revision = drive
+            .fetch_identity_revision([1; 32], Some(&transaction))
+            .expect("expected to fetch identity revision");
+
+        assert_eq!(revision, identity.revision);
+
+        let keys = drive
+            .fetch_identity_keys([1; 32], Some(&transaction))
+            .expect("expected to fetch identity keys");
+
+        assert_eq!(keys, identity.public_keys);
+    }
+
+    #[test]
+    fn test_add_to_identity_balance() {
+        let drive = setup_drive(None);
+
+        let transaction = drive.grove.start_transaction();
+
+        drive
+            .create_initial_state_structure(Some(&transaction))
+            .expect("expected to create root tree successfully");
+
+        let identity = create_test_identity(&drive, [1; 32], Some(&transaction));
+
+        let drive_operations = drive
+            .add_to_identity_balance_operations([1; 32], 300, &mut None, Some(&transaction))
+            .expect("expected to get operations");
+
+        drive
+            .grove_apply_batch(
+                DriveOperation::grovedb_operations_batch(&drive_operations),
+                false,
+                Some(&transaction),
+            )
+            .expect("expected to apply operations");
+
+        let (fetched_identity, _) = drive
+            .fetch_identity(&[1; 32], PROTOCOL_VERSION, Some(&transaction))
+            .expect("should fetch an identity");
+
+        assert_eq!(fetched_identity.balance, identity.balance + 300);
+        assert_eq!(fetched_identity.public_keys, identity.public_keys);
+    }
+
+    #[test]
+    fn test_fetch_identity_which_does_not_exist() {
+        let drive = setup_drive(None);
+
+        let transaction = drive.grove.start_transaction();
+
+        drive
+            .create_initial_state_structure(Some(&transaction))
+            .expect("expected to create root tree successfully");
+
+        let result = drive.fetch_identity(&[1; 32], PROTOCOL_VERSION, Some(&transaction));
+
+        assert!(matches!(
+            result,
+            Err(Error::Identity(IdentityError::IdentityNotFound(_)))
+        ));
+    }
+
+    #[test]
+    fn test_fetch_identity_without_revision() {
+        let drive = setup_drive(None);
+
+        let transaction = drive.grove.start_transaction();
+
+        drive
+            .create_initial_state_structure(Some(&transaction))
+            .expect("expected to create root tree successfully");
+
+        create_test_identity(&drive, [1; 32], Some(&transaction));
+
+        drive
+            .grove_delete(
+                identity_path(&[1; 32]),
+                IDENTITY_REVISION_KEY.as_slice(),
+                Some(&transaction),
+                &mut vec![],
+            )
+            .expect("expected to delete the revision");
+
+        let result = drive.fetch_identity(&[1; 32], PROTOCOL_VERSION, Some(&transaction));
+
+        assert!(matches!(
+            result,
+            Err(Error::Identity(IdentityError::CorruptedIdentity(_)))
+        ));
+    }
 }
@@ -0,0 +1,334 @@
+// MIT LICENSE
+//
+// Copyright (c) 2022 Dash Core Group
+//
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+//
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+//
+
+//! Prove Identities.
+//!
+//! This module implements functions in Drive for proving identities, or only their
+//! balance, and the matching stateless functions verifying such proofs against a
+//! platform state root hash.
+//!
+
+use std::collections::BTreeMap;
+
+use dpp::identifier::Identifier;
+use dpp::identity::Identity;
+use dpp::prelude::Revision;
+use grovedb::{Element, PathQuery, Query, SizedQuery, TransactionArg};
+
+use crate::drive::defaults::PROTOCOL_VERSION;
+use crate::drive::identity::{
+    deserialize_identity_key, full_identities_path_query, IDENTITY_BALANCE_KEY,
+    IDENTITY_REVISION_KEY,
+};
+use crate::drive::{Drive, RootTree};
+use crate::error::query::QueryError;
+use crate::error::Error;
+use crate::fee::credits::{Creditable, Credits};
+use crate::fee::op::DriveOperation;
+
+/// Returns the path query of the balance of an identity.
+/// The identity is queried from the `Identities` subtree so that the absence
+/// of the identity can be proved as well.
+fn identity_balance_path_query(identity_id: [u8; 32]) -> PathQuery {
+    let mut query = Query::new();
+    query.insert_key(identity_id.to_vec());
+    query.set_subquery_key(IDENTITY_BALANCE_KEY.to_vec());
+
+    PathQuery::new(
+        vec![vec![RootTree::Identities as u8]],
+        SizedQuery::new(query, None, None),
+    )
+}
+
+/// Reconstructs an identity from the proved elements of its subtree.
+/// Returns `None` if nothing was proved, meaning the identity doesn't exist.
+fn identity_from_proved_elements(
+    identity_id: [u8; 32],
+    proved_elements: Vec<(Vec<u8>, Element)>,
+) -> Result<Option<Identity>, Error> {
+    if proved_elements.is_empty() {
+        return Ok(None);
+    }
+
+    let mut balance = None;
+    let mut revision = None;
+    let mut public_keys = vec![];
+
+    for (key, element) in proved_elements {
+        match element {
+            Element::SumItem(value, _) if key == IDENTITY_BALANCE_KEY => {
+                balance = Some(value.to_unsigned());
+            }
+            Element::Item(value, _) if key == IDENTITY_REVISION_KEY => {
+                revision = Some(Revision::from_be_bytes(
+                    value.as_slice().try_into().map_err(|_| {
+                        Error::Query(QueryError::InvalidProof(
+                            "proved identity revision must be u64",
+                        ))
+                    })?,
+                ));
+            }
+            Element::Item(value, _) => {
+                public_keys.push(deserialize_identity_key(value.as_slice())?);
+            }
+            _ => {
+                return Err(Error::Query(QueryError::InvalidProof(
+                    "proof contains an unexpected identity element",
+                )));
+            }
+        }
+    }
+
+    let (Some(balance), Some(revision)) = (balance, revision) else {
+        return Err(Error::Query(QueryError::InvalidProof(
+            "proved identity must have a balance and a revision",
+        )));
+    };
+
+    Ok(Some(Identity {
+        protocol_version: PROTOCOL_VERSION,
+        id: Identifier::new(identity_id),
+        public_keys,
+        balance,
+        revision,
+        asset_lock_proof: None,
+        metadata: None,
+    }))
+}
+
+impl Drive {
+    /// Proves an identity with its balance, revision and public keys,
+    /// or its absence if it doesn't exist.
+    pub fn prove_identity(
+        &self,
+        identity_id: [u8; 32],
+        transaction: TransactionArg,
+    ) -> Result<Vec<u8>, Error> {
+        let mut drive_operations: Vec<DriveOperation> = vec![];
+        self.grove_get_proved_path_query(
+            &full_identities_path_query(&[identity_id]),
+            transaction,
+            &mut drive_operations,
+        )
+    }
+
+    /// Proves each of the given identities.
+    /// Proved elements don't carry their path, so identities are proved one by one
+    /// to be able to tell which of them don't exist.
+    pub fn prove_identities(
+        &self,
+        identity_ids: &[[u8; 32]],
+        transaction: TransactionArg,
+    ) -> Result<BTreeMap<[u8; 32], Vec<u8>>, Error> {
+        identity_ids
+            .iter()
+            .map(|identity_id| {
+                Ok((
+                    *identity_id,
+                    self.prove_identity(*identity_id, transaction)?,
+                ))
+            })
+            .collect()
+    }
+
+    /// Proves the balance of an identity, or its absence if the identity doesn't exist.
+    pub fn prove_identity_balance(
+        &self,
+        identity_id: [u8; 32],
+        transaction: TransactionArg,
+    ) -> Result<Vec<u8>, Error> {
+        let mut drive_operations: Vec<DriveOperation> = vec![];
+        self.grove_get_proved_path_query(
+            &identity_balance_path_query(identity_id),
+            transaction,
+            &mut drive_operations,
+        )
+    }
+
+    /// Verifies a proof of an identity against the expected root hash.
+    /// Returns `None` if the proof shows that the identity doesn't exist.
+    pub fn verify_identity(
+        proof: &[u8],
+        identity_id: [u8; 32],
+        expected_root_hash: [u8; 32],
+    ) -> Result<Option<Identity>, Error> {
+        let proved_elements = Self::grove_verify_proved_path_query(
+            proof,
+            &full_identities_path_query(&[identity_id]),
+            expected_root_hash,
+        )?;
+
+        identity_from_proved_elements(identity_id, proved_elements)
+    }
+
+    /// Verifies the proofs of identities against the expected root hash.
+    /// Identities which the proofs show don't exist are mapped to `None`.
+    pub fn verify_identities(
+        proofs: &BTreeMap<[u8; 32], Vec<u8>>,
+        expected_root_hash: [u8; 32],
+    ) -> Result<BTreeMap<[u8; 32], Option<Identity>>, Error> {
+        proofs
+            .iter()
+            .map(|(identity_id, proof)| {
+                Ok((
+                    *identity_id,
+                    Self::verify_identity(proof, *identity_id, expected_root_hash)?,
+                ))
+            })
+            .collect()
+    }
+
+    /// Verifies a proof of the balance of an identity against the expected root hash.
+    /// Returns `None` if the proof shows that the identity doesn't exist.
+    pub fn verify_identity_balance(
+        proof: &[u8],
+        identity_id: [u8; 32],
+        expected_root_hash: [u8; 32],
+    ) -> Result<Option<Credits>, Error> {
+        let proved_elements = Self::grove_verify_proved_path_query(
+            proof,
+            &identity_balance_path_query(identity_id),
+            expected_root_hash,
+        )?;
+
+        match proved_elements.as_slice() {
+            [] => Ok(None),
+            [(_, Element::SumItem(balance, _))] => Ok(Some(balance.to_unsigned())),
+            _ => Err(Error::Query(QueryError::InvalidProof(
+                "proof must contain only the identity balance",
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use crate::common::helpers::identities::create_test_identity;
+    use crate::common::helpers::setup::setup_drive_with_initial_state_structure;
+    use crate::drive::Drive;
+
+    #[test]
+    fn test_prove_and_verify_identity() {
+        let drive = setup_drive_with_initial_state_structure();
+
+        let transaction = drive.grove.start_transaction();
+
+        let identity = create_test_identity(&drive, [1; 32], Some(&transaction));
+
+        let root_hash = drive
+            .grove
+            .root_hash(Some(&transaction))
+            .unwrap()
+            .expect("expected to get root hash");
+
+        let proof = drive
+            .prove_identity([1; 32], Some(&transaction))
+            .expect("expected to prove identity");
+
+        let proved_identity = Drive::verify_identity(proof.as_slice(), [1; 32], root_hash)
+            .expect("expected to verify identity")
+            .expect("expected identity to exist");
+
+        assert_eq!(proved_identity.id, identity.id);
+        assert_eq!(proved_identity.balance, identity.balance);
+        assert_eq!(proved_identity.revision, identity.revision);
+        assert_eq!(
+            proved_identity
+                .public_keys
+                .iter()
+                .map(|key| key.id)
+                .collect::<Vec<_>>(),
+            vec![1]
+        );
+
+        assert!(Drive::verify_identity(proof.as_slice(), [1; 32], [0; 32]).is_err());
+
+        let proof = drive
+            .prove_identity([2; 32], Some(&transaction))
+            .expect("expected to prove identity absence");
+
+        assert!(Drive::verify_identity(proof.as_slice(), [2; 32], root_hash)
+            .expect("expected to verify identity absence")
+            .is_none());
+    }
+
+    #[test]
+    fn test_prove_and_verify_identities_and_balance() {
+        let drive = setup_drive_with_initial_state_structure();
+
+        let transaction = drive.grove.start_transaction();
+
+        create_test_identity(&drive, [1; 32], Some(&transaction));
+        create_test_identity(&drive, [3; 32], Some(&transaction));
+
+        let root_hash = drive
+            .grove
+            .root_hash(Some(&transaction))
+            .unwrap()
+            .expect("expected to get root hash");
+
+        let proofs = drive
+            .prove_identities(&[[1; 32], [2; 32], [3; 32]], Some(&transaction))
+            .expect("expected to prove identities");
+
+        let proved_identities: BTreeMap<[u8; 32], Option<u64>> =
+            Drive::verify_identities(&proofs, root_hash)
+                .expect("expected to verify identities")
+                .into_iter()
+                .map(|(id, identity)| (id, identity.map(|identity| identity.balance)))
+                .collect();
+
+        assert_eq!(
+            proved_identities,
+            BTreeMap::from([([1; 32], Some(0)), ([2; 32], None), ([3; 32], Some(0))])
+        );
+
+        let proof = drive
+            .prove_identity_balance([3; 32], Some(&transaction))
+            .expect("expected to prove identity balance");
+
+        assert_eq!(
+            Drive::verify_identity_balance(proof.as_slice(), [3; 32], root_hash)
+                .expect("expected to verify identity balance"),
+            Some(0)
+        );
+
+        let proof = drive
+            .prove_identity_balance([2; 32], Some(&transaction))
+            .expect("expected to prove identity balance absence");
+
+        assert_eq!(
+            Drive::verify_identity_balance(proof.as_slice(), [2; 32], root_hash)
+                .expect("expected to verify identity balance absence"),
+            None
+        );
+    }
+}
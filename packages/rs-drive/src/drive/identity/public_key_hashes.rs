@@ -0,0 +1,971 @@
+// MIT LICENSE
+//
+// Copyright (c) 2022 Dash Core Group
+//
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+//
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+//
+
+//! Public Key Hashes to Identities.
+//!
+//! This module maintains the `PublicKeyHashesToIdentities` subtree, mapping the hashes
+//! of identity public keys to the identities they belong to. Hashes of keys which can
+//! only belong to a single identity (ECDSA and BLS keys) are stored in the unique subtree
+//! as items holding the identity ID. Hashes which can be shared (hash160 and script hash
+//! keys) are stored in the non-unique subtree as trees of identity IDs.
+//!
+
+use std::collections::{BTreeMap, HashMap};
+
+use dpp::identity::{Identity, IdentityPublicKey, KeyType};
+use grovedb::batch::key_info::KeyInfo;
+use grovedb::batch::{GroveDbOp, KeyInfoPath, Op};
+use grovedb::query_result_type::QueryResultType::{
+    QueryKeyElementPairResultType, QueryPathKeyElementTrioResultType,
+};
+use grovedb::{
+    Element, EstimatedLayerInformation, PathQuery, Query, QueryItem, SizedQuery, TransactionArg,
+};
+
+use crate::drive::batch::GroveDbOpBatch;
+use crate::drive::defaults::DEFAULT_HASH_SIZE;
+use crate::drive::flags::StorageFlags;
+use crate::drive::grove_operations::DirectQueryType::{StatefulDirectQuery, StatelessDirectQuery};
+use crate::drive::grove_operations::QueryTarget::QueryTargetValue;
+use crate::drive::grove_operations::{
+    BatchDeleteApplyType, BatchInsertApplyType, BatchInsertTreeApplyType,
+};
+use crate::drive::identity::full_identities_path_query;
+use crate::drive::object_size_info::PathKeyElementInfo::PathFixedSizeKeyElement;
+use crate::drive::object_size_info::PathKeyInfo::PathFixedSizeKeyRef;
+use crate::drive::{Drive, RootTree};
+use crate::error::drive::DriveError;
+use crate::error::identity::IdentityError;
+use crate::error::query::QueryError;
+use crate::error::Error;
+use crate::fee::op::DriveOperation;
+
+/// The key of the subtree of public key hashes belonging to a single identity
+pub const UNIQUE_PUBLIC_KEY_HASHES_KEY: [u8; 1] = [0];
+/// The key of the subtree of public key hashes which can be shared by identities
+pub const NON_UNIQUE_PUBLIC_KEY_HASHES_KEY: [u8; 1] = [1];
+
+/// Returns the path to the `PublicKeyHashesToIdentities` subtree.
+pub(crate) fn public_key_hashes_root_path() -> [&'static [u8]; 1] {
+    [Into::<&[u8; 1]>::into(
+        RootTree::PublicKeyHashesToIdentities,
+    )]
+}
+
+/// Returns the path to the unique public key hashes.
+pub(crate) fn unique_public_key_hashes_path() -> [&'static [u8]; 2] {
+    [
+        Into::<&[u8; 1]>::into(RootTree::PublicKeyHashesToIdentities),
+        &UNIQUE_PUBLIC_KEY_HASHES_KEY,
+    ]
+}
+
+/// Returns the path to the non-unique public key hashes.
+pub(crate) fn non_unique_public_key_hashes_path() -> [&'static [u8]; 2] {
+    [
+        Into::<&[u8; 1]>::into(RootTree::PublicKeyHashesToIdentities),
+        &NON_UNIQUE_PUBLIC_KEY_HASHES_KEY,
+    ]
+}
+
+/// Takes a non-unique public key hash and returns the path to the identities sharing it.
+pub(crate) fn non_unique_public_key_hash_identities_path(public_key_hash: &[u8]) -> [&[u8]; 3] {
+    [
+        Into::<&[u8; 1]>::into(RootTree::PublicKeyHashesToIdentities),
+        &NON_UNIQUE_PUBLIC_KEY_HASHES_KEY,
+        public_key_hash,
+    ]
+}
+
+/// Returns true if a public key of the given type can only belong to a single identity.
+pub(crate) fn is_unique_key_type(key_type: KeyType) -> bool {
+    matches!(key_type, KeyType::ECDSA_SECP256K1 | KeyType::BLS12_381)
+}
+
+/// Returns the hash under which a public key is mapped to its identity.
+pub(crate) fn public_key_hash(key: &IdentityPublicKey) -> Result<Vec<u8>, Error> {
+    key.hash().map_err(|_| {
+        Error::Identity(IdentityError::InvalidIdentityStructure(
+            "identity public key can't be hashed",
+        ))
+    })
+}
+
+/// Converts a stored identity ID to a fixed size array.
+fn identity_id_from_bytes(identity_id: &[u8]) -> Result<[u8; 32], Error> {
+    identity_id.try_into().map_err(|_| {
+        Error::Drive(DriveError::CorruptedSerialization(
+            "public key hash must reference a 32 byte identity id",
+        ))
+    })
+}
+
+/// Checks that the operations of a batch don't map a unique public key hash to two
+/// identities. Mappings are checked against the state when their operations are gathered,
+/// which doesn't contain the mappings inserted earlier in the same batch.
+pub(crate) fn verify_unique_public_key_hashes_in_batch(
+    batch_operations: &[DriveOperation],
+) -> Result<(), Error> {
+    let unique_public_key_hashes_path =
+        KeyInfoPath::from_known_path(unique_public_key_hashes_path());
+
+    let mut identity_ids_by_public_key_hash: HashMap<&KeyInfo, &Vec<u8>> = HashMap::new();
+
+    let inserted_identity_ids = batch_operations
+        .iter()
+        .filter_map(|operation| match operation {
+            DriveOperation::GroveOperation(GroveDbOp {
+                path,
+                key,
+                op:
+                    Op::Insert {
+                        element: Element::Item(identity_id, _),
+                    },
+                ..
+            }) if path == &unique_public_key_hashes_path => Some((key, identity_id)),
+            _ => None,
+        });
+
+    for (public_key_hash, identity_id) in inserted_identity_ids {
+        if let Some(other_identity_id) =
+            identity_ids_by_public_key_hash.insert(public_key_hash, identity_id)
+        {
+            if other_identity_id != identity_id {
+                return Err(Error::Identity(
+                    IdentityError::UniquePublicKeyAlreadyExists(
+                        "unique public key is added to two identities in the same batch",
+                    ),
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads the identity IDs a public key hash is mapped to from the elements proved by the
+/// path query of `identity_ids_by_public_key_hashes_path_query` for this public key hash.
+fn identity_ids_from_proved_elements(
+    public_key_hash: &[u8],
+    proved_elements: Vec<(Vec<u8>, Element)>,
+) -> Result<Vec<[u8; 32]>, Error> {
+    proved_elements
+        .into_iter()
+        .map(|(key, element)| match element {
+            // a unique public key hash references its identity with the item
+            Element::Item(identity_id, _) if key == public_key_hash => {
+                identity_id_from_bytes(identity_id.as_slice())
+            }
+            // the identities sharing a non-unique public key hash are the keys of its tree
+            Element::Item(..) => identity_id_from_bytes(key.as_slice()),
+            _ => Err(Error::Query(QueryError::InvalidProof(
+                "proof must contain only the identity ids of the public key hash",
+            ))),
+        })
+        .collect()
+}
+
+/// Add operations for creating the initial public key hashes structure
+pub fn add_initial_public_key_hashes_structure_operations(batch: &mut GroveDbOpBatch) {
+    batch.add_insert_empty_tree(vec![], vec![RootTree::PublicKeyHashesToIdentities as u8]);
+
+    batch.add_insert_empty_tree(
+        vec![vec![RootTree::PublicKeyHashesToIdentities as u8]],
+        UNIQUE_PUBLIC_KEY_HASHES_KEY.to_vec(),
+    );
+
+    batch.add_insert_empty_tree(
+        vec![vec![RootTree::PublicKeyHashesToIdentities as u8]],
+        NON_UNIQUE_PUBLIC_KEY_HASHES_KEY.to_vec(),
+    );
+}
+
+impl Drive {
+    /// Pushes the operations mapping the hash of a public key to the identity it belongs to.
+    /// A mapping which already exists for the identity is kept as it is, while a unique
+    /// public key hash belonging to another identity is an error.
+    pub(crate) fn insert_public_key_hash_reference_to_identity_operations(
+        &self,
+        identity_id: [u8; 32],
+        key: &IdentityPublicKey,
+        storage_flags: Option<&StorageFlags>,
+        estimated_costs_only_with_layer_info: &mut Option<
+            HashMap<KeyInfoPath, EstimatedLayerInformation>,
+        >,
+        transaction: TransactionArg,
+        drive_operations: &mut Vec<DriveOperation>,
+    ) -> Result<(), Error> {
+        let public_key_hash = public_key_hash(key)?;
+
+        if let Some(estimated_costs_only_with_layer_info) = estimated_costs_only_with_layer_info {
+            Self::add_estimation_costs_for_public_key_hashes(
+                public_key_hash.as_slice(),
+                estimated_costs_only_with_layer_info,
+            );
+        }
+
+        let is_estimating = estimated_costs_only_with_layer_info.is_some();
+
+        if is_unique_key_type(key.key_type) {
+            let direct_query_type = if is_estimating {
+                StatelessDirectQuery {
+                    in_tree_using_sums: false,
+                    query_target: QueryTargetValue(DEFAULT_HASH_SIZE),
+                }
+            } else {
+                StatefulDirectQuery
+            };
+
+            let existing_element = match self.grove_get_direct(
+                unique_public_key_hashes_path(),
+                public_key_hash.as_slice(),
+                direct_query_type,
+                transaction,
+                drive_operations,
+            ) {
+                Ok(element) => element,
+                Err(Error::GroveDB(grovedb::Error::PathKeyNotFound(_))) => None,
+                Err(e) => return Err(e),
+            };
+
+            match existing_element {
+                Some(Element::Item(existing_identity_id, _)) => {
+                    if existing_identity_id.as_slice() != identity_id.as_slice() {
+                        return Err(Error::Identity(
+                            IdentityError::UniquePublicKeyAlreadyExists(
+                                "unique public key already belongs to another identity",
+                            ),
+                        ));
+                    }
+                    Ok(())
+                }
+                Some(_) => Err(Error::Drive(DriveError::UnexpectedElementType(
+                    "unique public key hash must reference an identity with an item",
+                ))),
+                None => self.batch_insert(
+                    PathFixedSizeKeyElement((
+                        unique_public_key_hashes_path(),
+                        public_key_hash.as_slice(),
+                        Element::Item(
+                            identity_id.to_vec(),
+                            StorageFlags::map_to_some_element_flags(storage_flags),
+                        ),
+                    )),
+                    drive_operations,
+                ),
+            }
+        } else {
+            let (tree_apply_type, apply_type) = if is_estimating {
+                (
+                    BatchInsertTreeApplyType::StatelessBatchInsert {
+                        in_tree_using_sums: false,
+                        is_sum_tree: false,
+                        flags_len: storage_flags
+                            .map(|s| s.serialized_size())
+                            .unwrap_or_default(),
+                    },
+                    BatchInsertApplyType::StatelessBatchInsert {
+                        in_tree_using_sums: false,
+                        target: QueryTargetValue(0),
+                    },
+                )
+            } else {
+                (
+                    BatchInsertTreeApplyType::StatefulBatchInsert,
+                    BatchInsertApplyType::StatefulBatchInsert,
+                )
+            };
+
+            let inserted_tree = self.batch_insert_empty_tree_if_not_exists(
+                PathFixedSizeKeyRef((
+                    non_unique_public_key_hashes_path(),
+                    public_key_hash.as_slice(),
+                )),
+                storage_flags,
+                tree_apply_type,
+                transaction,
+                &mut None,
+                drive_operations,
+            )?;
+
+            let path_key_element_info = PathFixedSizeKeyElement((
+                non_unique_public_key_hash_identities_path(public_key_hash.as_slice()),
+                identity_id.as_slice(),
+                Element::Item(
+                    vec![],
+                    StorageFlags::map_to_some_element_flags(storage_flags),
+                ),
+            ));
+
+            // a new tree can't already reference the identity
+            if inserted_tree && !is_estimating {
+                self.batch_insert(path_key_element_info, drive_operations)
+            } else {
+                self.batch_insert_if_not_exists(
+                    path_key_element_info,
+                    apply_type,
+                    transaction,
+                    drive_operations,
+                )
+                .map(|_| ())
+            }
+        }
+    }
+
+    /// Pushes the operations removing the mapping of a public key hash to the identity
+    /// it belongs to. Nothing is removed if the public key hash isn't mapped to the identity.
+    /// Emptied trees of non-unique public key hashes are kept.
+    pub(crate) fn remove_public_key_hash_reference_to_identity_operations(
+        &self,
+        identity_id: [u8; 32],
+        public_key_hash: &[u8],
+        is_unique: bool,
+        estimated_costs_only_with_layer_info: &mut Option<
+            HashMap<KeyInfoPath, EstimatedLayerInformation>,
+        >,
+        transaction: TransactionArg,
+        drive_operations: &mut Vec<DriveOperation>,
+    ) -> Result<(), Error> {
+        if let Some(estimated_costs_only_with_layer_info) = estimated_costs_only_with_layer_info {
+            Self::add_estimation_costs_for_public_key_hashes(
+                public_key_hash,
+                estimated_costs_only_with_layer_info,
+            );
+        }
+
+        let is_estimating = estimated_costs_only_with_layer_info.is_some();
+
+        let direct_query_type = if is_estimating {
+            StatelessDirectQuery {
+                in_tree_using_sums: false,
+                query_target: QueryTargetValue(if is_unique { DEFAULT_HASH_SIZE } else { 0 }),
+            }
+        } else {
+            StatefulDirectQuery
+        };
+
+        if is_unique {
+            let existing_element = match self.grove_get_direct(
+                unique_public_key_hashes_path(),
+                public_key_hash,
+                direct_query_type,
+                transaction,
+                drive_operations,
+            ) {
+                Ok(element) => element,
+                Err(Error::GroveDB(grovedb::Error::PathKeyNotFound(_))) => return Ok(()),
+                Err(e) => return Err(e),
+            };
+
+            let is_referenced = match existing_element {
+                Some(Element::Item(existing_identity_id, _)) => {
+                    existing_identity_id.as_slice() == identity_id.as_slice()
+                }
+                Some(_) => {
+                    return Err(Error::Drive(DriveError::UnexpectedElementType(
+                        "unique public key hash must reference an identity with an item",
+                    )))
+                }
+                None => is_estimating,
+            };
+
+            if is_referenced {
+                self.batch_delete(
+                    unique_public_key_hashes_path(),
+                    public_key_hash,
+                    Self::public_key_hash_delete_apply_type(is_estimating, DEFAULT_HASH_SIZE),
+                    transaction,
+                    drive_operations,
+                )?;
+            }
+        } else {
+            let is_referenced = match self.grove_has_raw(
+                non_unique_public_key_hash_identities_path(public_key_hash),
+                identity_id.as_slice(),
+                direct_query_type,
+                transaction,
+                drive_operations,
+            ) {
+                Ok(is_referenced) => is_referenced,
+                Err(Error::GroveDB(
+                    grovedb::Error::PathNotFound(_) | grovedb::Error::PathParentLayerNotFound(_),
+                )) => false,
+                Err(e) => return Err(e),
+            };
+
+            if is_referenced || is_estimating {
+                self.batch_delete(
+                    non_unique_public_key_hash_identities_path(public_key_hash),
+                    identity_id.as_slice(),
+                    Self::public_key_hash_delete_apply_type(is_estimating, 0),
+                    transaction,
+                    drive_operations,
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The apply type for deleting items of the public key hashes subtree.
+    fn public_key_hash_delete_apply_type(
+        is_estimating: bool,
+        estimated_value_size: u32,
+    ) -> BatchDeleteApplyType {
+        if is_estimating {
+            BatchDeleteApplyType::StatelessBatchDelete {
+                is_sum_tree: false,
+                estimated_value_size,
+            }
+        } else {
+            BatchDeleteApplyType::StatefulBatchDelete {
+                is_known_to_be_subtree_with_sum: Some((false, false)),
+            }
+        }
+    }
+
+    /// Fetches the IDs of the identities the given public key hashes are mapped to.
+    /// Every public key hash is returned, with no identity IDs if it isn't mapped.
+    pub fn fetch_identity_ids_by_public_key_hashes(
+        &self,
+        public_key_hashes: &[Vec<u8>],
+        transaction: TransactionArg,
+    ) -> Result<BTreeMap<Vec<u8>, Vec<[u8; 32]>>, Error> {
+        let mut identity_ids_by_public_key_hash: BTreeMap<Vec<u8>, Vec<[u8; 32]>> =
+            public_key_hashes
+                .iter()
+                .map(|public_key_hash| (public_key_hash.clone(), vec![]))
+                .collect();
+
+        if public_key_hashes.is_empty() {
+            return Ok(identity_ids_by_public_key_hash);
+        }
+
+        let mut drive_operations: Vec<DriveOperation> = vec![];
+
+        let (unique_results, _) = self.grove_get_raw_path_query(
+            &PathQuery::new(
+                unique_public_key_hashes_path()
+                    .into_iter()
+                    .map(Vec::from)
+                    .collect(),
+                SizedQuery::new(Self::public_key_hashes_query(public_key_hashes), None, None),
+            ),
+            transaction,
+            QueryKeyElementPairResultType,
+            &mut drive_operations,
+        )?;
+
+        for (public_key_hash, element) in unique_results.to_key_elements() {
+            let Element::Item(identity_id, _) = element else {
+                return Err(Error::Drive(DriveError::UnexpectedElementType(
+                    "unique public key hash must reference an identity with an item",
+                )));
+            };
+            identity_ids_by_public_key_hash
+                .entry(public_key_hash)
+                .or_default()
+                .push(identity_id_from_bytes(identity_id.as_slice())?);
+        }
+
+        let mut non_unique_query = Self::public_key_hashes_query(public_key_hashes);
+        let mut identities_query = Query::new();
+        identities_query.insert_all();
+        non_unique_query.set_subquery(identities_query);
+
+        let (non_unique_results, _) = self.grove_get_raw_path_query(
+            &PathQuery::new(
+                non_unique_public_key_hashes_path()
+                    .into_iter()
+                    .map(Vec::from)
+                    .collect(),
+                SizedQuery::new(non_unique_query, None, None),
+            ),
+            transaction,
+            QueryPathKeyElementTrioResultType,
+            &mut drive_operations,
+        )?;
+
+        for (path, identity_id, _) in non_unique_results.to_path_key_elements() {
+            let Some(public_key_hash) = path.last() else {
+                return Err(Error::Drive(DriveError::CorruptedCodeExecution(
+                    "non-unique public key hash identities must have a path",
+                )));
+            };
+            identity_ids_by_public_key_hash
+                .entry(public_key_hash.clone())
+                .or_default()
+                .push(identity_id_from_bytes(identity_id.as_slice())?);
+        }
+
+        Ok(identity_ids_by_public_key_hash)
+    }
+
+    /// Fetches the identities the given public key hashes are mapped to, for the requested
+    /// protocol version. Every public key hash is returned, with no identities if it isn't mapped.
+    pub fn fetch_identities_by_public_key_hashes(
+        &self,
+        public_key_hashes: &[Vec<u8>],
+        protocol_version: u32,
+        transaction: TransactionArg,
+    ) -> Result<BTreeMap<Vec<u8>, Vec<Identity>>, Error> {
+        self.fetch_identity_ids_by_public_key_hashes(public_key_hashes, transaction)?
+            .into_iter()
+            .map(|(public_key_hash, identity_ids)| {
+                let identities = identity_ids
+                    .iter()
+                    .map(|identity_id| {
+                        self.fetch_identity(identity_id, protocol_version, transaction)
+                            .map(|(identity, _)| identity)
+                    })
+                    .collect::<Result<Vec<Identity>, Error>>()?;
+                Ok((public_key_hash, identities))
+            })
+            .collect()
+    }
+
+    /// Proves the IDs of the identities a public key hash is mapped to,
+    /// or that it isn't mapped.
+    pub fn prove_identity_ids_by_public_key_hash(
+        &self,
+        public_key_hash: &[u8],
+        transaction: TransactionArg,
+    ) -> Result<Vec<u8>, Error> {
+        let mut drive_operations: Vec<DriveOperation> = vec![];
+        self.grove_get_proved_path_query(
+            &Self::identity_ids_by_public_key_hashes_path_query(&[public_key_hash.to_vec()]),
+            transaction,
+            &mut drive_operations,
+        )
+    }
+
+    /// Proves the IDs of the identities each of the given public key hashes is mapped to.
+    /// Proved elements don't carry their path, so public key hashes are proved one by one
+    /// to be able to tell which of them the identities sharing a public key hash belong to.
+    pub fn prove_identity_ids_by_public_key_hashes(
+        &self,
+        public_key_hashes: &[Vec<u8>],
+        transaction: TransactionArg,
+    ) -> Result<BTreeMap<Vec<u8>, Vec<u8>>, Error> {
+        public_key_hashes
+            .iter()
+            .map(|public_key_hash| {
+                Ok((
+                    public_key_hash.clone(),
+                    self.prove_identity_ids_by_public_key_hash(public_key_hash, transaction)?,
+                ))
+            })
+            .collect()
+    }
+
+    /// Proves the identities the given public key hashes are mapped to.
+    /// Returns the proofs of the identity IDs each public key hash is mapped to and
+    /// the proofs of the identities with these IDs.
+    pub fn prove_identities_by_public_key_hashes(
+        &self,
+        public_key_hashes: &[Vec<u8>],
+        transaction: TransactionArg,
+    ) -> Result<(BTreeMap<Vec<u8>, Vec<u8>>, BTreeMap<[u8; 32], Vec<u8>>), Error> {
+        let identity_ids_proofs =
+            self.prove_identity_ids_by_public_key_hashes(public_key_hashes, transaction)?;
+
+        let identity_ids: Vec<[u8; 32]> = self
+            .fetch_identity_ids_by_public_key_hashes(public_key_hashes, transaction)?
+            .into_values()
+            .flatten()
+            .collect();
+
+        let identities_proofs = self.prove_identities(identity_ids.as_slice(), transaction)?;
+
+        Ok((identity_ids_proofs, identities_proofs))
+    }
+
+    /// Verifies a proof of the IDs of the identities a public key hash is mapped to
+    /// against the expected root hash. Returns no identity IDs if the proof shows that
+    /// the public key hash isn't mapped.
+    pub fn verify_identity_ids_by_public_key_hash(
+        proof: &[u8],
+        public_key_hash: &[u8],
+        expected_root_hash: [u8; 32],
+    ) -> Result<Vec<[u8; 32]>, Error> {
+        let proved_elements = Self::grove_verify_proved_path_query(
+            proof,
+            &Self::identity_ids_by_public_key_hashes_path_query(&[public_key_hash.to_vec()]),
+            expected_root_hash,
+        )?;
+
+        identity_ids_from_proved_elements(public_key_hash, proved_elements)
+    }
+
+    /// Verifies the proofs of the identity IDs public key hashes are mapped to against
+    /// the expected root hash.
+    pub fn verify_identity_ids_by_public_key_hashes(
+        proofs: &BTreeMap<Vec<u8>, Vec<u8>>,
+        expected_root_hash: [u8; 32],
+    ) -> Result<BTreeMap<Vec<u8>, Vec<[u8; 32]>>, Error> {
+        proofs
+            .iter()
+            .map(|(public_key_hash, proof)| {
+                Ok((
+                    public_key_hash.clone(),
+                    Self::verify_identity_ids_by_public_key_hash(
+                        proof,
+                        public_key_hash,
+                        expected_root_hash,
+                    )?,
+                ))
+            })
+            .collect()
+    }
+
+    /// Verifies the proofs returned by `prove_identities_by_public_key_hashes` against the
+    /// expected root hash. Every identity a public key hash is mapped to must be proved.
+    pub fn verify_identities_by_public_key_hashes(
+        identity_ids_proofs: &BTreeMap<Vec<u8>, Vec<u8>>,
+        identities_proofs: &BTreeMap<[u8; 32], Vec<u8>>,
+        expected_root_hash: [u8; 32],
+    ) -> Result<BTreeMap<Vec<u8>, Vec<Identity>>, Error> {
+        Self::verify_identity_ids_by_public_key_hashes(identity_ids_proofs, expected_root_hash)?
+            .into_iter()
+            .map(|(public_key_hash, identity_ids)| {
+                let identities = identity_ids
+                    .iter()
+                    .map(|identity_id| {
+                        let proof = identities_proofs.get(identity_id).ok_or(Error::Query(
+                            QueryError::InvalidProof(
+                                "proof of an identity mapped to a public key hash is missing",
+                            ),
+                        ))?;
+                        Self::verify_identity(proof, *identity_id, expected_root_hash)?.ok_or(
+                            Error::Query(QueryError::InvalidProof(
+                                "identity mapped to a public key hash must exist",
+                            )),
+                        )
+                    })
+                    .collect::<Result<Vec<Identity>, Error>>()?;
+                Ok((public_key_hash, identities))
+            })
+            .collect()
+    }
+
+    /// A query of the given public key hashes.
+    fn public_key_hashes_query(public_key_hashes: &[Vec<u8>]) -> Query {
+        let mut query = Query::new();
+        for public_key_hash in public_key_hashes {
+            query.insert_key(public_key_hash.clone());
+        }
+        query
+    }
+
+    /// The path query of the identity IDs the given public key hashes are mapped to,
+    /// in both the unique and the non-unique subtrees.
+    pub(crate) fn identity_ids_by_public_key_hashes_path_query(
+        public_key_hashes: &[Vec<u8>],
+    ) -> PathQuery {
+        let unique_query = Self::public_key_hashes_query(public_key_hashes);
+
+        let mut non_unique_query = Self::public_key_hashes_query(public_key_hashes);
+        let mut identities_query = Query::new();
+        identities_query.insert_all();
+        non_unique_query.set_subquery(identities_query);
+
+        let mut query = Query::new();
+        query.insert_key(UNIQUE_PUBLIC_KEY_HASHES_KEY.to_vec());
+        query.insert_key(NON_UNIQUE_PUBLIC_KEY_HASHES_KEY.to_vec());
+        query.add_conditional_subquery(
+            QueryItem::Key(UNIQUE_PUBLIC_KEY_HASHES_KEY.to_vec()),
+            None,
+            Some(unique_query),
+        );
+        query.add_conditional_subquery(
+            QueryItem::Key(NON_UNIQUE_PUBLIC_KEY_HASHES_KEY.to_vec()),
+            None,
+            Some(non_unique_query),
+        );
+
+        PathQuery::new(
+            public_key_hashes_root_path()
+                .into_iter()
+                .map(Vec::from)
+                .collect(),
+            SizedQuery::new(query, None, None),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::helpers::identities::create_test_identity;
+    use std::collections::BTreeMap;
+
+    use crate::common::helpers::setup::setup_drive_with_initial_state_structure;
+    use crate::drive::batch::{DriveOperationType, IdentityOperationType};
+    use crate::drive::block_info::BlockInfo;
+    use crate::drive::defaults::PROTOCOL_VERSION;
+    use crate::drive::flags::StorageFlags;
+    use crate::drive::Drive;
+    use crate::error::identity::IdentityError;
+    use crate::error::Error;
+    use dpp::identifier::Identifier;
+    use dpp::identity::{Identity, IdentityPublicKey, KeyType, Purpose, SecurityLevel};
+
+    fn identity_with_key(id: [u8; 32], key_type: KeyType, data: Vec<u8>) -> Identity {
+        Identity {
+            protocol_version: 1,
+            id: Identifier::new(id),
+            public_keys: vec![IdentityPublicKey {
+                id: 0,
+                purpose: Purpose::AUTHENTICATION,
+                security_level: SecurityLevel::MASTER,
+                key_type,
+                data,
+                read_only: false,
+                disabled_at: None,
+                signature: vec![],
+            }],
+            balance: 0,
+            revision: 0,
+            asset_lock_proof: None,
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn test_fetch_identity_ids_by_unique_public_key_hashes() {
+        let drive = setup_drive_with_initial_state_structure();
+
+        let transaction = drive.grove.start_transaction();
+
+        let identity = create_test_identity(&drive, [1; 32], Some(&transaction));
+
+        let public_key_hash = identity.public_keys[0]
+            .hash()
+            .expect("expected to hash the public key");
+
+        let identity_ids = drive
+            .fetch_identity_ids_by_public_key_hashes(
+                &[public_key_hash.clone(), vec![7; 20]],
+                Some(&transaction),
+            )
+            .expect("expected to fetch identity ids");
+
+        assert_eq!(identity_ids.get(&public_key_hash), Some(&vec![[1; 32]]));
+        assert_eq!(identity_ids.get(&vec![7; 20]), Some(&vec![]));
+
+        let identities = drive
+            .fetch_identities_by_public_key_hashes(
+                &[public_key_hash.clone()],
+                PROTOCOL_VERSION,
+                Some(&transaction),
+            )
+            .expect("expected to fetch identities");
+
+        let fetched_identity_ids: Vec<Identifier> = identities
+            .get(&public_key_hash)
+            .expect("expected the public key hash to be returned")
+            .iter()
+            .map(|identity| identity.id.clone())
+            .collect();
+
+        assert_eq!(fetched_identity_ids, vec![identity.id]);
+    }
+
+    #[test]
+    fn test_unique_public_key_can_not_belong_to_two_identities() {
+        let drive = setup_drive_with_initial_state_structure();
+
+        let transaction = drive.grove.start_transaction();
+
+        for id in [[1; 32], [2; 32]] {
+            let result = drive.insert_identity(
+                identity_with_key(id, KeyType::ECDSA_SECP256K1, vec![3; 33]),
+                BlockInfo::default(),
+                true,
+                StorageFlags::optional_default_as_ref(),
+                Some(&transaction),
+            );
+
+            if id == [1; 32] {
+                result.expect("expected to insert identity");
+            } else {
+                assert!(matches!(
+                    result,
+                    Err(Error::Identity(
+                        IdentityError::UniquePublicKeyAlreadyExists(_)
+                    ))
+                ));
+            }
+        }
+    }
+
+    #[test]
+    fn test_fetch_identity_ids_by_non_unique_public_key_hashes() {
+        let drive = setup_drive_with_initial_state_structure();
+
+        let transaction = drive.grove.start_transaction();
+
+        for id in [[1; 32], [2; 32]] {
+            drive
+                .insert_identity(
+                    identity_with_key(id, KeyType::ECDSA_HASH160, vec![4; 20]),
+                    BlockInfo::default(),
+                    true,
+                    StorageFlags::optional_default_as_ref(),
+                    Some(&transaction),
+                )
+                .expect("expected to insert identity");
+        }
+
+        let identity_ids = drive
+            .fetch_identity_ids_by_public_key_hashes(&[vec![4; 20]], Some(&transaction))
+            .expect("expected to fetch identity ids");
+
+        assert_eq!(
+            identity_ids.get(&vec![4; 20]),
+            Some(&vec![[1; 32], [2; 32]])
+        );
+
+        let root_hash = drive
+            .grove
+            .root_hash(Some(&transaction))
+            .unwrap()
+            .expect("expected to get root hash");
+
+        let proofs = drive
+            .prove_identity_ids_by_public_key_hashes(
+                &[vec![4; 20], vec![7; 20]],
+                Some(&transaction),
+            )
+            .expect("expected to prove identity ids");
+
+        let proved_identity_ids =
+            Drive::verify_identity_ids_by_public_key_hashes(&proofs, root_hash)
+                .expect("expected to verify identity ids");
+
+        assert_eq!(
+            proved_identity_ids,
+            BTreeMap::from([(vec![4; 20], vec![[1; 32], [2; 32]]), (vec![7; 20], vec![]),])
+        );
+    }
+
+    #[test]
+    fn test_prove_and_verify_identities_by_unique_public_key_hashes() {
+        let drive = setup_drive_with_initial_state_structure();
+
+        let transaction = drive.grove.start_transaction();
+
+        let identity = create_test_identity(&drive, [1; 32], Some(&transaction));
+
+        let public_key_hash = identity.public_keys[0]
+            .hash()
+            .expect("expected to hash the public key");
+
+        let root_hash = drive
+            .grove
+            .root_hash(Some(&transaction))
+            .unwrap()
+            .expect("expected to get root hash");
+
+        let (identity_ids_proofs, identities_proofs) = drive
+            .prove_identities_by_public_key_hashes(
+                &[public_key_hash.clone(), vec![7; 20]],
+                Some(&transaction),
+            )
+            .expect("expected to prove identities");
+
+        let proved_identity_ids =
+            Drive::verify_identity_ids_by_public_key_hashes(&identity_ids_proofs, root_hash)
+                .expect("expected to verify identity ids");
+
+        assert_eq!(
+            proved_identity_ids.get(&public_key_hash),
+            Some(&vec![[1; 32]])
+        );
+        assert_eq!(proved_identity_ids.get(&vec![7; 20]), Some(&vec![]));
+
+        let proved_identities = Drive::verify_identities_by_public_key_hashes(
+            &identity_ids_proofs,
+            &identities_proofs,
+            root_hash,
+        )
+        .expect("expected to verify identities");
+
+        let proved_identity_ids: Vec<Identifier> = proved_identities
+            .get(&public_key_hash)
+            .expect("expected the public key hash to be proved")
+            .iter()
+            .map(|identity| identity.id.clone())
+            .collect();
+
+        assert_eq!(proved_identity_ids, vec![identity.id]);
+        assert_eq!(proved_identities.get(&vec![7; 20]), Some(&vec![]));
+    }
+
+    #[test]
+    fn test_unique_public_key_can_not_be_added_to_two_identities_in_one_batch() {
+        let drive = setup_drive_with_initial_state_structure();
+
+        let transaction = drive.grove.start_transaction();
+
+        let operations = [[1; 32], [2; 32]]
+            .into_iter()
+            .map(|id| {
+                DriveOperationType::IdentityOperation(IdentityOperationType::InsertIdentity {
+                    identity: identity_with_key(id, KeyType::ECDSA_SECP256K1, vec![3; 33]),
+                    storage_flags: StorageFlags::optional_default_as_ref(),
+                })
+            })
+            .collect();
+
+        let result = drive.apply_drive_operations(
+            operations,
+            true,
+            &BlockInfo::default(),
+            Some(&transaction),
+        );
+
+        assert!(matches!(
+            result,
+            Err(Error::Identity(
+                IdentityError::UniquePublicKeyAlreadyExists(_)
+            ))
+        ));
+
+        let public_key_hash = identity_with_key([1; 32], KeyType::ECDSA_SECP256K1, vec![3; 33])
+            .public_keys[0]
+            .hash()
+            .expect("expected to hash the public key");
+
+        let identity_ids = drive
+            .fetch_identity_ids_by_public_key_hashes(&[public_key_hash.clone()], Some(&transaction))
+            .expect("expected to fetch identity ids");
+
+        assert_eq!(identity_ids.get(&public_key_hash), Some(&vec![]));
+    }
+}
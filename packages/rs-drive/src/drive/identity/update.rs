@@ -0,0 +1,355 @@
+// MIT LICENSE
+//
+// Copyright (c) 2022 Dash Core Group
+//
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+//
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+//
+
+//! Update Identities.
+//!
+//! This module implements the operations changing the balance, keys and revision
+//! of identities which are already stored in the `Identities` subtree.
+//!
+
+use dpp::identity::{IdentityPublicKey, KeyID, TimestampMillis};
+use dpp::prelude::Revision;
+use grovedb::batch::KeyInfoPath;
+use grovedb::{Element, EstimatedLayerInformation, TransactionArg};
+use std::collections::HashMap;
+
+use crate::drive::defaults::{DEFAULT_HASH_160_SIZE_U8, ESTIMATED_AVERAGE_IDENTITY_KEY_SIZE};
+use crate::drive::flags::StorageFlags;
+use crate::drive::grove_operations::BatchInsertApplyType;
+use crate::drive::grove_operations::DirectQueryType::{StatefulDirectQuery, StatelessDirectQuery};
+use crate::drive::grove_operations::QueryTarget::QueryTargetValue;
+use crate::drive::identity::public_key_hashes::{is_unique_key_type, public_key_hash};
+use crate::drive::identity::{
+    deserialize_identity_key, identity_key_tree_key, identity_keys_path, serialize_identity_key,
+};
+use crate::drive::object_size_info::PathKeyElementInfo::PathFixedSizeKeyElement;
+use crate::drive::Drive;
+use crate::error::drive::DriveError;
+use crate::error::fee::FeeError;
+use crate::error::identity::IdentityError;
+use crate::error::Error;
+use crate::fee::credits::Credits;
+use crate::fee::op::DriveOperation;
+
+/// Maps GroveDB errors of a missing identity to an identity not found error.
+fn map_identity_not_found_error(error: Error) -> Error {
+    match error {
+        Error::GroveDB(
+            grovedb::Error::PathKeyNotFound(_)
+            | grovedb::Error::PathNotFound(_)
+            | grovedb::Error::PathParentLayerNotFound(_),
+        ) => Error::Identity(IdentityError::IdentityNotFound(
+            "identity to update does not exist",
+        )),
+        e => e,
+    }
+}
+
+impl Drive {
+    /// The operations for changing the balance of an identity with the result of `update`.
+    /// The storage flags of the balance are kept as they are.
+    fn update_identity_balance_operations<F>(
+        &self,
+        identity_id: [u8; 32],
+        estimated_costs_only_with_layer_info: &mut Option<
+            HashMap<KeyInfoPath, EstimatedLayerInformation>,
+        >,
+        transaction: TransactionArg,
+        update: F,
+    ) -> Result<Vec<DriveOperation>, Error>
+    where
+        F: FnOnce(Credits) -> Result<Credits, Error>,
+    {
+        let mut drive_operations: Vec<DriveOperation> = vec![];
+
+        if let Some(estimated_costs_only_with_layer_info) = estimated_costs_only_with_layer_info {
+            Self::add_estimation_costs_for_identity(
+                identity_id.as_slice(),
+                estimated_costs_only_with_layer_info,
+            );
+        }
+
+        let previous_balance = self
+            .fetch_identity_balance_operations(
+                identity_id.as_slice(),
+                estimated_costs_only_with_layer_info.is_none(),
+                transaction,
+                &mut drive_operations,
+            )
+            .map_err(map_identity_not_found_error)?;
+
+        // When estimating costs the balance is unknown, a sum item always has the same size
+        let (new_balance, storage_flags) = match previous_balance {
+            Some((balance, storage_flags)) => (update(balance)?, storage_flags),
+            None => (0, StorageFlags::optional_default()),
+        };
+
+        drive_operations.push(Self::set_identity_balance_operation(
+            identity_id.as_slice(),
+            new_balance,
+            storage_flags.as_ref(),
+        )?);
+
+        Ok(drive_operations)
+    }
+
+    /// The operations for adding credits to the balance of an identity.
+    pub fn add_to_identity_balance_operations(
+        &self,
+        identity_id: [u8; 32],
+        added_balance: Credits,
+        estimated_costs_only_with_layer_info: &mut Option<
+            HashMap<KeyInfoPath, EstimatedLayerInformation>,
+        >,
+        transaction: TransactionArg,
+    ) -> Result<Vec<DriveOperation>, Error> {
+        self.update_identity_balance_operations(
+            identity_id,
+            estimated_costs_only_with_layer_info,
+            transaction,
+            |balance| {
+                balance
+                    .checked_add(added_balance)
+                    .ok_or(Error::Fee(FeeError::Overflow("identity balance overflow")))
+            },
+        )
+    }
+
+    /// The operations for removing credits from the balance of an identity.
+    /// Fails if the identity doesn't have enough credits.
+    pub fn remove_from_identity_balance_operations(
+        &self,
+        identity_id: [u8; 32],
+        balance_to_remove: Credits,
+        estimated_costs_only_with_layer_info: &mut Option<
+            HashMap<KeyInfoPath, EstimatedLayerInformation>,
+        >,
+        transaction: TransactionArg,
+    ) -> Result<Vec<DriveOperation>, Error> {
+        self.update_identity_balance_operations(
+            identity_id,
+            estimated_costs_only_with_layer_info,
+            transaction,
+            |balance| {
+                balance
+                    .checked_sub(balance_to_remove)
+                    .ok_or(Error::Identity(IdentityError::IdentityInsufficientBalance(
+                        "identity does not have enough balance",
+                    )))
+            },
+        )
+    }
+
+    /// The operations for adding new public keys to an identity.
+    /// Fails if the identity already has a key with the same ID.
+    pub(crate) fn add_new_keys_to_identity_operations(
+        &self,
+        identity_id: [u8; 32],
+        keys_to_add: Vec<IdentityPublicKey>,
+        storage_flags: Option<&StorageFlags>,
+        estimated_costs_only_with_layer_info: &mut Option<
+            HashMap<KeyInfoPath, EstimatedLayerInformation>,
+        >,
+        transaction: TransactionArg,
+    ) -> Result<Vec<DriveOperation>, Error> {
+        let mut drive_operations: Vec<DriveOperation> = vec![];
+
+        let apply_type = if let Some(estimated_costs_only_with_layer_info) =
+            estimated_costs_only_with_layer_info
+        {
+            Self::add_estimation_costs_for_identity_keys(
+                identity_id.as_slice(),
+                estimated_costs_only_with_layer_info,
+            );
+            BatchInsertApplyType::StatelessBatchInsert {
+                in_tree_using_sums: false,
+                target: QueryTargetValue(ESTIMATED_AVERAGE_IDENTITY_KEY_SIZE),
+            }
+        } else {
+            BatchInsertApplyType::StatefulBatchInsert
+        };
+
+        for key in keys_to_add {
+            let key_id_bytes = identity_key_tree_key(key.id);
+            let inserted = self
+                .batch_insert_if_not_exists(
+                    PathFixedSizeKeyElement((
+                        identity_keys_path(identity_id.as_slice()),
+                        key_id_bytes.as_slice(),
+                        Element::Item(
+                            serialize_identity_key(&key)?,
+                            StorageFlags::map_to_some_element_flags(storage_flags),
+                        ),
+                    )),
+                    apply_type,
+                    transaction,
+                    &mut drive_operations,
+                )
+                .map_err(map_identity_not_found_error)?;
+
+            if !inserted {
+                return Err(Error::Identity(IdentityError::IdentityKeyAlreadyExists(
+                    "identity already has a key with this id",
+                )));
+            }
+
+            if !key.is_disabled() {
+                self.insert_public_key_hash_reference_to_identity_operations(
+                    identity_id,
+                    &key,
+                    storage_flags,
+                    estimated_costs_only_with_layer_info,
+                    transaction,
+                    &mut drive_operations,
+                )?;
+            }
+        }
+
+        Ok(drive_operations)
+    }
+
+    /// The operations for disabling public keys of an identity at the given time.
+    /// The public key hashes of disabled keys are no longer mapped to the identity.
+    pub(crate) fn disable_identity_keys_operations(
+        &self,
+        identity_id: [u8; 32],
+        key_ids: Vec<KeyID>,
+        disable_at: TimestampMillis,
+        estimated_costs_only_with_layer_info: &mut Option<
+            HashMap<KeyInfoPath, EstimatedLayerInformation>,
+        >,
+        transaction: TransactionArg,
+    ) -> Result<Vec<DriveOperation>, Error> {
+        let mut drive_operations: Vec<DriveOperation> = vec![];
+
+        let direct_query_type = if let Some(estimated_costs_only_with_layer_info) =
+            estimated_costs_only_with_layer_info
+        {
+            Self::add_estimation_costs_for_identity_keys(
+                identity_id.as_slice(),
+                estimated_costs_only_with_layer_info,
+            );
+            StatelessDirectQuery {
+                in_tree_using_sums: false,
+                query_target: QueryTargetValue(ESTIMATED_AVERAGE_IDENTITY_KEY_SIZE),
+            }
+        } else {
+            StatefulDirectQuery
+        };
+
+        for key_id in key_ids {
+            let key_id_bytes = identity_key_tree_key(key_id);
+
+            let element = self
+                .grove_get_direct(
+                    identity_keys_path(identity_id.as_slice()),
+                    key_id_bytes.as_slice(),
+                    direct_query_type,
+                    transaction,
+                    &mut drive_operations,
+                )
+                .map_err(|e| match e {
+                    Error::GroveDB(grovedb::Error::PathKeyNotFound(_)) => Error::Identity(
+                        IdentityError::IdentityKeyNotFound("key to disable does not exist"),
+                    ),
+                    e => map_identity_not_found_error(e),
+                })?;
+
+            // When estimating costs the key is unknown, so a unique key of average size is replaced
+            let (key_bytes, element_flags, public_key_hash, is_unique) = match element {
+                Some(Element::Item(key_bytes, element_flags)) => {
+                    let mut key = deserialize_identity_key(key_bytes.as_slice())?;
+                    key.disabled_at = Some(disable_at);
+                    (
+                        serialize_identity_key(&key)?,
+                        element_flags,
+                        public_key_hash(&key)?,
+                        is_unique_key_type(key.key_type),
+                    )
+                }
+                None => (
+                    vec![0; ESTIMATED_AVERAGE_IDENTITY_KEY_SIZE as usize],
+                    StorageFlags::map_to_some_element_flags(StorageFlags::optional_default_as_ref()),
+                    vec![0; DEFAULT_HASH_160_SIZE_U8 as usize],
+                    true,
+                ),
+                _ => {
+                    return Err(Error::Drive(DriveError::UnexpectedElementType(
+                        "identity key must be an item",
+                    )))
+                }
+            };
+
+            self.batch_insert(
+                PathFixedSizeKeyElement((
+                    identity_keys_path(identity_id.as_slice()),
+                    key_id_bytes.as_slice(),
+                    Element::Item(key_bytes, element_flags),
+                )),
+                &mut drive_operations,
+            )?;
+
+            self.remove_public_key_hash_reference_to_identity_operations(
+                identity_id,
+                public_key_hash.as_slice(),
+                is_unique,
+                estimated_costs_only_with_layer_info,
+                transaction,
+                &mut drive_operations,
+            )?;
+        }
+
+        Ok(drive_operations)
+    }
+
+    /// The operations for setting the revision of an identity.
+    /// Only the revision item is replaced, so the identity doesn't need to be fetched.
+    pub(crate) fn update_identity_revision_operations(
+        &self,
+        identity_id: [u8; 32],
+        revision: Revision,
+        storage_flags: Option<&StorageFlags>,
+        estimated_costs_only_with_layer_info: &mut Option<
+            HashMap<KeyInfoPath, EstimatedLayerInformation>,
+        >,
+    ) -> Result<Vec<DriveOperation>, Error> {
+        if let Some(estimated_costs_only_with_layer_info) = estimated_costs_only_with_layer_info {
+            Self::add_estimation_costs_for_identity(
+                identity_id.as_slice(),
+                estimated_costs_only_with_layer_info,
+            );
+        }
+
+        Ok(vec![Self::set_identity_revision_operation(
+            identity_id.as_slice(),
+            revision,
+            storage_flags,
+        )])
+    }
+}
@@ -30,17 +30,27 @@
 //! This module defines functions within the Drive struct related to withdrawal transaction (AssetUnlock)
 //!
 
+use std::collections::HashMap;
 use std::ops::RangeFull;
 
+use grovedb::batch::KeyInfoPath;
 use grovedb::query_result_type::QueryResultType::QueryKeyElementPairResultType;
-use grovedb::{Element, PathQuery, Query, QueryItem, SizedQuery, TransactionArg};
+use grovedb::EstimatedLayerCount::{EstimatedLevel, PotentiallyAtMaxElements};
+use grovedb::EstimatedLayerSizes::{AllItems, AllSubtrees, Mix};
+use grovedb::EstimatedSumTrees::NoSumTrees;
+use grovedb::{
+    Element, EstimatedLayerInformation, PathQuery, Query, QueryItem, SizedQuery, TransactionArg,
+};
 
 use crate::drive::batch::GroveDbOpBatch;
+use crate::drive::block_info::BlockInfo;
 use crate::drive::grove_operations::BatchDeleteApplyType;
 use crate::drive::{Drive, RootTree};
 use crate::error::drive::DriveError;
 use crate::error::Error;
+use crate::fee::calculate_fee;
 use crate::fee::op::DriveOperation;
+use crate::fee::result::FeeResult;
 
 /// constant id for transaction counter
 pub const WITHDRAWAL_TRANSACTIONS_COUNTER_ID: [u8; 1] = [0];
@@ -135,6 +145,93 @@ impl Drive {
         }
     }
 
+    /// Adds the estimation costs for enqueuing a withdrawal transaction of the given size
+    fn add_estimation_costs_for_withdrawal_queue(
+        transaction_size: u32,
+        estimated_costs_only_with_layer_info: &mut HashMap<KeyInfoPath, EstimatedLayerInformation>,
+    ) {
+        estimated_costs_only_with_layer_info.insert(
+            KeyInfoPath::from_known_path([]),
+            EstimatedLayerInformation {
+                is_sum_tree: false,
+                estimated_layer_count: EstimatedLevel(0, false),
+                estimated_layer_sizes: AllSubtrees(1, NoSumTrees, None),
+            },
+        );
+
+        // the counter and the queue
+        estimated_costs_only_with_layer_info.insert(
+            KeyInfoPath::from_known_path([Into::<&[u8; 1]>::into(
+                RootTree::WithdrawalTransactions,
+            )]),
+            EstimatedLayerInformation {
+                is_sum_tree: false,
+                estimated_layer_count: EstimatedLevel(1, false),
+                estimated_layer_sizes: Mix {
+                    subtrees_size: Some((1, NoSumTrees, None, 1)),
+                    items_size: Some((1, 8, None, 1)),
+                    references_size: None,
+                },
+            },
+        );
+
+        // withdrawal transactions are keyed by their u64 index
+        estimated_costs_only_with_layer_info.insert(
+            KeyInfoPath::from_known_path([
+                Into::<&[u8; 1]>::into(RootTree::WithdrawalTransactions).as_slice(),
+                &WITHDRAWAL_TRANSACTIONS_QUEUE_ID,
+            ]),
+            EstimatedLayerInformation {
+                is_sum_tree: false,
+                estimated_layer_count: PotentiallyAtMaxElements,
+                estimated_layer_sizes: AllItems(8, transaction_size, None),
+            },
+        );
+    }
+
+    /// Enqueues a withdrawal transaction at the given index and makes it the latest index.
+    pub fn enqueue_withdrawal_transaction(
+        &self,
+        index: u64,
+        transaction_bytes: Vec<u8>,
+        block_info: &BlockInfo,
+        apply: bool,
+        transaction: TransactionArg,
+    ) -> Result<FeeResult, Error> {
+        let estimated_costs_only_with_layer_info = if apply {
+            None
+        } else {
+            let mut estimated_costs_only_with_layer_info = HashMap::new();
+            Self::add_estimation_costs_for_withdrawal_queue(
+                transaction_bytes.len() as u32,
+                &mut estimated_costs_only_with_layer_info,
+            );
+            Some(estimated_costs_only_with_layer_info)
+        };
+
+        let mut batch = GroveDbOpBatch::new();
+
+        let index_bytes = index.to_be_bytes().to_vec();
+
+        self.add_enqueue_withdrawal_transaction_operations(
+            &mut batch,
+            vec![(index_bytes.clone(), transaction_bytes)],
+        );
+
+        self.add_update_withdrawal_index_counter_operation(&mut batch, index_bytes);
+
+        let mut drive_operations: Vec<DriveOperation> = vec![];
+
+        self.apply_batch_grovedb_operations(
+            estimated_costs_only_with_layer_info,
+            transaction,
+            batch,
+            &mut drive_operations,
+        )?;
+
+        calculate_fee(None, Some(drive_operations), &block_info.epoch)
+    }
+
     /// Get specified amount of withdrawal transactions from the DB
     pub fn dequeue_withdrawal_transactions(
         &self,
@@ -37,6 +37,7 @@ use crate::error::Error;
 use crate::fee_pools::add_create_fee_pool_trees_operations;
 use grovedb::TransactionArg;
 
+use super::identity::public_key_hashes::add_initial_public_key_hashes_structure_operations;
 use super::identity::withdrawal_queue::add_initial_withdrawal_state_structure_operations;
 
 impl Drive {
@@ -48,7 +49,7 @@ impl Drive {
 
         add_init_contracts_structure_operations(&mut batch);
 
-        batch.add_insert_empty_tree(vec![], vec![RootTree::PublicKeyHashesToIdentities as u8]);
+        add_initial_public_key_hashes_structure_operations(&mut batch);
 
         batch.add_insert_empty_tree(vec![], vec![RootTree::SpentAssetLockTransactions as u8]);
 
@@ -27,9 +27,9 @@
 // DEALINGS IN THE SOFTWARE.
 //
 
-use std::cell::RefCell;
 use std::collections::HashMap;
 use std::path::Path;
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
 
 use grovedb::batch::KeyInfoPath;
 use grovedb::{EstimatedLayerInformation, GroveDb, Transaction, TransactionArg};
@@ -40,10 +40,14 @@ use object_size_info::DocumentInfo::DocumentEstimatedAverageSize;
 use crate::contract::Contract;
 use crate::drive::batch::GroveDbOpBatch;
 use crate::drive::config::DriveConfig;
+use crate::drive::identity::public_key_hashes::verify_unique_public_key_hashes_in_batch;
+use crate::error::drive::DriveError;
 use crate::error::Error;
 use crate::fee::op::DriveOperation;
 use crate::fee::op::DriveOperation::GroveOperation;
 
+/// Asset lock module
+pub mod asset_lock;
 /// Batch module
 pub mod batch;
 /// Block info module
@@ -56,6 +60,8 @@ pub mod contract;
 pub mod defaults;
 /// Document module
 pub mod document;
+/// Encoding module
+pub mod encoding;
 mod estimation_costs;
 /// Fee pools module
 pub mod fee_pools;
@@ -68,6 +74,8 @@ pub mod identity;
 pub mod initialization;
 pub mod object_size_info;
 pub mod query;
+/// DPP state repository module
+pub mod state_repository;
 #[cfg(test)]
 mod test_utils;
 
@@ -85,7 +93,7 @@ pub struct Drive {
     /// Drive config
     pub config: DriveConfig,
     /// Drive Cache
-    pub cache: RefCell<DriveCache>,
+    pub cache: RwLock<DriveCache>,
 }
 
 /// Keys for the root tree.
@@ -159,7 +167,7 @@ impl Drive {
                 Ok(Drive {
                     grove,
                     config,
-                    cache: RefCell::new(DriveCache {
+                    cache: RwLock::new(DriveCache {
                         cached_contracts: DataContractCache::new(
                             data_contracts_global_cache_size,
                             data_contracts_block_cache_size,
@@ -172,6 +180,26 @@ impl Drive {
         }
     }
 
+    /// Returns the cache to read from.
+    /// Fails if a thread panicked while writing to the cache.
+    pub fn read_cache(&self) -> Result<RwLockReadGuard<DriveCache>, Error> {
+        self.cache.read().map_err(|_| {
+            Error::Drive(DriveError::CorruptedCacheState(
+                "cache lock is poisoned by a panic while writing to it",
+            ))
+        })
+    }
+
+    /// Returns the cache to write to.
+    /// Fails if a thread panicked while writing to the cache.
+    pub fn write_cache(&self) -> Result<RwLockWriteGuard<DriveCache>, Error> {
+        self.cache.write().map_err(|_| {
+            Error::Drive(DriveError::CorruptedCacheState(
+                "cache lock is poisoned by a panic while writing to it",
+            ))
+        })
+    }
+
     /// Commits a transaction.
     pub fn commit_transaction(&self, transaction: Transaction) -> Result<(), Error> {
         self.grove
@@ -208,6 +236,7 @@ impl Drive {
     }
 
     /// Applies a batch of Drive operations to groveDB.
+    /// Fails if the operations map a unique public key hash to two identities.
     fn apply_batch_drive_operations(
         &self,
         estimated_costs_only_with_layer_info: Option<
@@ -217,6 +246,7 @@ impl Drive {
         batch_operations: Vec<DriveOperation>,
         drive_operations: &mut Vec<DriveOperation>,
     ) -> Result<(), Error> {
+        verify_unique_public_key_hashes_in_batch(&batch_operations)?;
         let grove_db_operations = DriveOperation::grovedb_operations_batch(&batch_operations);
         self.apply_batch_grovedb_operations(
             estimated_costs_only_with_layer_info,
@@ -40,6 +40,7 @@ use crate::error::query::QueryError;
 use crate::error::Error;
 use crate::fee::calculate_fee;
 use crate::fee::op::DriveOperation;
+use crate::query::aggregate::{Aggregate, AggregateResult};
 use crate::query::DriveQuery;
 use dpp::data_contract::extra::DocumentType;
 
@@ -49,9 +50,10 @@ use dpp::data_contract::extra::DriveContractExt;
 
 impl Drive {
     /// Performs and returns the result of the specified query along with skipped items and the cost.
+    /// The query and the returned documents are in the encoding set in the Drive config.
     pub fn query_documents(
         &self,
-        query_cbor: &[u8],
+        serialized_query: &[u8],
         contract_id: [u8; 32],
         document_type_name: &str,
         epoch: Option<&Epoch>,
@@ -74,7 +76,7 @@ impl Drive {
         let (items, skipped) = self.query_documents_from_contract_internal(
             &contract.contract,
             document_type,
-            query_cbor,
+            serialized_query,
             transaction,
             &mut drive_operations,
         )?;
@@ -88,12 +90,106 @@ impl Drive {
         Ok((items, skipped, cost))
     }
 
+    /// Performs the specified aggregate over the documents matched by the query and returns
+    /// its result along with the cost. The limit and offset of the query are ignored.
+    pub fn query_documents_aggregate(
+        &self,
+        serialized_query: &[u8],
+        contract_id: [u8; 32],
+        document_type_name: &str,
+        aggregate: &Aggregate,
+        epoch: Option<&Epoch>,
+        transaction: TransactionArg,
+    ) -> Result<(AggregateResult, u64), Error> {
+        let mut drive_operations: Vec<DriveOperation> = vec![];
+        let contract = self
+            .get_contract_with_fetch_info_and_add_to_operations(
+                contract_id,
+                epoch,
+                transaction,
+                &mut drive_operations,
+            )?
+            .ok_or(Error::Query(QueryError::ContractNotFound(
+                "contract not found",
+            )))?;
+        let document_type = contract
+            .contract
+            .document_type_for_name(document_type_name)?;
+        let query = DriveQuery::from_encoded(
+            serialized_query,
+            &contract.contract,
+            document_type,
+            self.config.encoding,
+        )?;
+        let result = query.execute_aggregate_no_proof_internal(
+            aggregate,
+            self,
+            transaction,
+            &mut drive_operations,
+        )?;
+        let cost = if let Some(epoch) = epoch {
+            let fee_result = calculate_fee(None, Some(drive_operations), epoch)?;
+            fee_result.processing_fee
+        } else {
+            0
+        };
+
+        Ok((result, cost))
+    }
+
+    /// Performs the specified aggregate over the documents matched by the query and returns
+    /// the proof of the aggregated documents along with the cost.
+    pub fn query_documents_aggregate_as_grove_proof(
+        &self,
+        serialized_query: &[u8],
+        contract_id: [u8; 32],
+        document_type_name: &str,
+        aggregate: &Aggregate,
+        epoch: Option<&Epoch>,
+        transaction: TransactionArg,
+    ) -> Result<(Vec<u8>, u64), Error> {
+        let mut drive_operations: Vec<DriveOperation> = vec![];
+        let contract = self
+            .get_contract_with_fetch_info_and_add_to_operations(
+                contract_id,
+                epoch,
+                transaction,
+                &mut drive_operations,
+            )?
+            .ok_or(Error::Query(QueryError::ContractNotFound(
+                "contract not found",
+            )))?;
+        let document_type = contract
+            .contract
+            .document_type_for_name(document_type_name)?;
+        let query = DriveQuery::from_encoded(
+            serialized_query,
+            &contract.contract,
+            document_type,
+            self.config.encoding,
+        )?;
+        let proof = query.execute_aggregate_with_proof_internal(
+            aggregate,
+            self,
+            transaction,
+            &mut drive_operations,
+        )?;
+        let cost = if let Some(epoch) = epoch {
+            let fee_result = calculate_fee(None, Some(drive_operations), epoch)?;
+            fee_result.processing_fee
+        } else {
+            0
+        };
+
+        Ok((proof, cost))
+    }
+
     /// Performs and returns the result of the specified query along with skipped items and the cost.
     pub fn query_documents_from_contract_cbor(
         &self,
         contract_cbor: &[u8],
         document_type_name: String,
-        query_cbor: &[u8],
+        serialized_query: &[u8],
         block_info: Option<BlockInfo>,
         transaction: TransactionArg,
     ) -> Result<(Vec<Vec<u8>>, u16, u64), Error> {
@@ -105,7 +201,7 @@ impl Drive {
         let (items, skipped) = self.query_documents_from_contract_internal(
             &contract,
             document_type,
-            query_cbor,
+            serialized_query,
             transaction,
             &mut drive_operations,
         )?;
@@ -123,7 +219,7 @@ impl Drive {
         &self,
         contract: &Contract,
         document_type: &DocumentType,
-        query_cbor: &[u8],
+        serialized_query: &[u8],
         block_info: Option<BlockInfo>,
         transaction: TransactionArg,
     ) -> Result<(Vec<Vec<u8>>, u16, u64), Error> {
@@ -131,7 +227,7 @@ impl Drive {
         let (items, skipped) = self.query_documents_from_contract_internal(
             contract,
             document_type,
-            query_cbor,
+            serialized_query,
             transaction,
             &mut drive_operations,
         )?;
@@ -149,20 +245,31 @@ impl Drive {
         &self,
         contract: &Contract,
         document_type: &DocumentType,
-        query_cbor: &[u8],
+        serialized_query: &[u8],
         transaction: TransactionArg,
         drive_operations: &mut Vec<DriveOperation>,
     ) -> Result<(Vec<Vec<u8>>, u16), Error> {
-        let query = DriveQuery::from_cbor(query_cbor, contract, document_type)?;
+        let query = DriveQuery::from_encoded(
+            serialized_query,
+            contract,
+            document_type,
+            self.config.encoding,
+        )?;
 
-        query.execute_no_proof_internal(self, transaction, drive_operations)
+        let (items, skipped) =
+            query.execute_no_proof_internal(self, transaction, drive_operations)?;
+        let items = items
+            .into_iter()
+            .map(|item| self.stored_document_to_encoding(item, document_type))
+            .collect::<Result<Vec<Vec<u8>>, Error>>()?;
+        Ok((items, skipped))
     }
 
     /// Performs and returns the result of the specified query along with the fee.
     /// Proof is generated.
     pub fn query_documents_as_grove_proof(
         &self,
-        query_cbor: &[u8],
+        serialized_query: &[u8],
         contract_id: [u8; 32],
         document_type_name: &str,
         block_info: Option<BlockInfo>,
@@ -186,7 +293,7 @@ impl Drive {
         let items = self.query_documents_from_contract_as_grove_proof_internal(
             &contract.contract,
             document_type,
-            query_cbor,
+            serialized_query,
             transaction,
             &mut drive_operations,
         )?;
@@ -205,7 +312,7 @@ impl Drive {
         &self,
         contract_cbor: &[u8],
         document_type_name: String,
-        query_cbor: &[u8],
+        serialized_query: &[u8],
         block_info: Option<BlockInfo>,
         transaction: TransactionArg,
     ) -> Result<(Vec<u8>, u64), Error> {
@@ -217,7 +324,7 @@ impl Drive {
         let items = self.query_documents_from_contract_as_grove_proof_internal(
             &contract,
             document_type,
-            query_cbor,
+            serialized_query,
             transaction,
             &mut drive_operations,
         )?;
@@ -236,7 +343,7 @@ impl Drive {
         &self,
         contract: &Contract,
         document_type: &DocumentType,
-        query_cbor: &[u8],
+        serialized_query: &[u8],
         block_info: Option<BlockInfo>,
         transaction: TransactionArg,
     ) -> Result<(Vec<u8>, u64), Error> {
@@ -245,7 +352,7 @@ impl Drive {
         let items = self.query_documents_from_contract_as_grove_proof_internal(
             contract,
             document_type,
-            query_cbor,
+            serialized_query,
             transaction,
             &mut drive_operations,
         )?;
@@ -264,11 +371,16 @@ impl Drive {
         &self,
         contract: &Contract,
         document_type: &DocumentType,
-        query_cbor: &[u8],
+        serialized_query: &[u8],
         transaction: TransactionArg,
         drive_operations: &mut Vec<DriveOperation>,
     ) -> Result<Vec<u8>, Error> {
-        let query = DriveQuery::from_cbor(query_cbor, contract, document_type)?;
+        let query = DriveQuery::from_encoded(
+            serialized_query,
+            contract,
+            document_type,
+            self.config.encoding,
+        )?;
 
         query.execute_with_proof_internal(self, transaction, drive_operations)
     }
@@ -278,7 +390,7 @@ impl Drive {
         &self,
         contract: &Contract,
         document_type: &DocumentType,
-        query_cbor: &[u8],
+        serialized_query: &[u8],
         block_info: Option<BlockInfo>,
         transaction: TransactionArg,
     ) -> Result<([u8; 32], Vec<Vec<u8>>, u64), Error> {
@@ -288,7 +400,7 @@ impl Drive {
             .query_documents_from_contract_as_grove_proof_only_get_elements_internal(
                 contract,
                 document_type,
-                query_cbor,
+                serialized_query,
                 transaction,
                 &mut drive_operations,
             )?;
@@ -306,12 +418,26 @@ impl Drive {
         &self,
         contract: &Contract,
         document_type: &DocumentType,
-        query_cbor: &[u8],
+        serialized_query: &[u8],
         transaction: TransactionArg,
         drive_operations: &mut Vec<DriveOperation>,
     ) -> Result<([u8; 32], Vec<Vec<u8>>), Error> {
-        let query = DriveQuery::from_cbor(query_cbor, contract, document_type)?;
+        let query = DriveQuery::from_encoded(
+            serialized_query,
+            contract,
+            document_type,
+            self.config.encoding,
+        )?;
 
-        query.execute_with_proof_only_get_elements_internal(self, transaction, drive_operations)
+        let (root_hash, items) = query.execute_with_proof_only_get_elements_internal(
+            self,
+            transaction,
+            drive_operations,
+        )?;
+        let items = items
+            .into_iter()
+            .map(|item| self.stored_document_to_encoding(item, document_type))
+            .collect::<Result<Vec<Vec<u8>>, Error>>()?;
+        Ok((root_hash, items))
     }
 }
@@ -0,0 +1,989 @@
+// MIT LICENSE
+//
+// Copyright (c) 2022 Dash Core Group
+//
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+//
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+//
+
+//! Drive State Repository.
+//!
+//! This module implements DPP's `StateRepositoryLike` on top of Drive, so that
+//! state transitions can be validated and applied against Drive state without
+//! going through JS. Core chain and Platform chain data which Drive doesn't store
+//! is provided by an implementation of `ChainDataProviderLike`.
+//!
+
+use std::convert::Infallible;
+
+use anyhow::{anyhow, Result as AnyResult};
+use async_trait::async_trait;
+use dpp::dashcore::InstantLock;
+use dpp::data_contract::extra::DriveContractExt;
+use dpp::prelude::{DataContract, Document, Identifier, Identity};
+use dpp::state_repository::StateRepositoryLike;
+use dpp::state_transition::fee::operations::{Operation, PreCalculatedOperation};
+use dpp::state_transition::state_transition_execution_context::StateTransitionExecutionContext;
+use grovedb::TransactionArg;
+use serde_json::Value as JsonValue;
+
+use crate::common::value_to_cbor;
+use crate::contract::document::Document as DriveDocument;
+use crate::drive::asset_lock::ASSET_LOCK_OUTPOINT_SIZE;
+use crate::drive::block_info::BlockInfo;
+use crate::drive::flags::StorageFlags;
+use crate::drive::Drive;
+use crate::error::Error;
+use crate::fee::result::FeeResult;
+
+/// Provides the chain data state transitions are validated against which is not stored
+/// in Drive, such as Core transactions and the Platform block headers.
+pub trait ChainDataProviderLike: Send + Sync {
+    /// Fetches the Core transaction with the given hash and the height of its block,
+    /// `None` if Core doesn't know the transaction.
+    fn fetch_transaction(&self, id: &str) -> AnyResult<Option<JsonValue>>;
+
+    /// Verifies the signature of an instant lock with the quorums known to Core.
+    fn verify_instant_lock(&self, instant_lock: &InstantLock) -> AnyResult<bool>;
+
+    /// Fetches the simplified masternode list store of Core.
+    fn fetch_sml_store(&self) -> AnyResult<JsonValue>;
+
+    /// Fetches the header of the latest Platform block.
+    fn fetch_latest_platform_block_header(&self) -> AnyResult<JsonValue>;
+}
+
+/// An implementation of DPP's `StateRepositoryLike` backed by Drive.
+///
+/// All reads and writes are performed within the given transaction. Writes are only
+/// applied to state if the execution context is not in dry run mode, otherwise only
+/// their estimated costs are added to the execution context.
+pub struct DriveStateRepository<'a> {
+    /// Drive
+    drive: &'a Drive,
+    /// Provides the chain data which is not stored in Drive
+    chain_data_provider: &'a dyn ChainDataProviderLike,
+    /// The block the state transitions are executed in
+    block_info: BlockInfo,
+    /// The transaction used for all reads and writes
+    transaction: TransactionArg<'a, 'a>,
+}
+
+impl<'a> DriveStateRepository<'a> {
+    /// Creates a state repository for the given Drive, chain data provider, block and
+    /// transaction.
+    pub fn new(
+        drive: &'a Drive,
+        chain_data_provider: &'a dyn ChainDataProviderLike,
+        block_info: BlockInfo,
+        transaction: TransactionArg<'a, 'a>,
+    ) -> Self {
+        DriveStateRepository {
+            drive,
+            chain_data_provider,
+            block_info,
+            transaction,
+        }
+    }
+
+    /// Storage flags for data owned by the given identity in the current epoch.
+    fn storage_flags_for_owner(&self, owner_id: &Identifier) -> StorageFlags {
+        StorageFlags::new_single_epoch(self.block_info.epoch.index, Some(owner_id.to_buffer()))
+    }
+
+    /// Adds the fees Drive calculated for an operation, refunds included, to the
+    /// execution context.
+    fn add_fee_result_to_execution_context(
+        fee_result: FeeResult,
+        execution_context: &StateTransitionExecutionContext,
+    ) {
+        execution_context.add_operation(Operation::PreCalculated(
+            PreCalculatedOperation::from_fee_result(fee_result),
+        ));
+    }
+}
+
+/// Converts a serialized outpoint given by DPP to an asset lock outpoint.
+fn to_asset_lock_outpoint(out_point_buffer: &[u8]) -> AnyResult<[u8; ASSET_LOCK_OUTPOINT_SIZE]> {
+    out_point_buffer.try_into().map_err(|_| {
+        anyhow!(
+            "asset lock outpoint must be {} bytes",
+            ASSET_LOCK_OUTPOINT_SIZE
+        )
+    })
+}
+
+#[async_trait]
+impl<'a> StateRepositoryLike for DriveStateRepository<'a> {
+    type ConversionError = Infallible;
+    type FetchDataContract = DataContract;
+
+    async fn fetch_data_contract(
+        &self,
+        data_contract_id: &Identifier,
+        execution_context: &StateTransitionExecutionContext,
+    ) -> AnyResult<Option<Self::FetchDataContract>> {
+        let (fee_result, contract_fetch_info) = self.drive.get_contract_with_fetch_info(
+            data_contract_id.to_buffer(),
+            Some(&self.block_info.epoch),
+            self.transaction,
+        )?;
+
+        if let Some(fee_result) = fee_result {
+            Self::add_fee_result_to_execution_context(fee_result, execution_context);
+        }
+
+        Ok(contract_fetch_info.map(|fetch_info| fetch_info.contract.clone()))
+    }
+
+    async fn store_data_contract(
+        &self,
+        data_contract: DataContract,
+        execution_context: &StateTransitionExecutionContext,
+    ) -> AnyResult<()> {
+        let serialized_contract = DriveContractExt::to_cbor(&data_contract)?;
+
+        let storage_flags = self.storage_flags_for_owner(&data_contract.owner_id);
+
+        let fee_result = self.drive.apply_contract(
+            &data_contract,
+            serialized_contract,
+            self.block_info.clone(),
+            !execution_context.is_dry_run(),
+            Some(&storage_flags),
+            self.transaction,
+        )?;
+
+        Self::add_fee_result_to_execution_context(fee_result, execution_context);
+
+        Ok(())
+    }
+
+    async fn delete_data_contract(
+        &self,
+        data_contract_id: &Identifier,
+        execution_context: &StateTransitionExecutionContext,
+    ) -> AnyResult<()> {
+        let fee_result = self.drive.delete_contract(
+            data_contract_id.to_buffer(),
+            self.block_info.clone(),
+            !execution_context.is_dry_run(),
+            self.transaction,
+        )?;
+
+        Self::add_fee_result_to_execution_context(fee_result, execution_context);
+
+        Ok(())
+    }
+
+    async fn fetch_documents<T>(
+        &self,
+        contract_id: &Identifier,
+        data_contract_type: &str,
+        where_query: JsonValue,
+        execution_context: &StateTransitionExecutionContext,
+    ) -> AnyResult<Vec<T>>
+    where
+        T: for<'de> serde::de::Deserialize<'de> + 'static,
+    {
+        let query_cbor = value_to_cbor(where_query, None);
+
+        let (items, _, processing_fee) = self.drive.query_documents(
+            query_cbor.as_slice(),
+            contract_id.to_buffer(),
+            data_contract_type,
+            Some(&self.block_info.epoch),
+            self.transaction,
+        )?;
+
+        Self::add_fee_result_to_execution_context(
+            FeeResult::from_fees(0, processing_fee),
+            execution_context,
+        );
+
+        let (fee_result, contract_fetch_info) = self.drive.get_contract_with_fetch_info(
+            contract_id.to_buffer(),
+            Some(&self.block_info.epoch),
+            self.transaction,
+        )?;
+
+        if let Some(fee_result) = fee_result {
+            Self::add_fee_result_to_execution_context(fee_result, execution_context);
+        }
+
+        let contract_fetch_info = contract_fetch_info
+            .ok_or_else(|| anyhow!("contract of the queried documents must exist"))?;
+        let contract = &contract_fetch_info.contract;
+
+        let document_type = contract.document_type_for_name(data_contract_type)?;
+
+        items
+            .into_iter()
+            .map(|item| {
+                let document = DriveDocument::from_stored_bytes(&item, document_type)?;
+                let document = Document::try_from((&document, contract, data_contract_type))?;
+
+                Ok(serde_json::from_value(document.to_json()?)?)
+            })
+            .collect()
+    }
+
+    async fn create_document(
+        &self,
+        document: &Document,
+        execution_context: &StateTransitionExecutionContext,
+    ) -> AnyResult<()> {
+        let serialized_document = document.to_buffer()?;
+
+        let storage_flags = self.storage_flags_for_owner(&document.owner_id);
+
+        let fee_result = self.drive.add_serialized_document_for_contract_id(
+            serialized_document.as_slice(),
+            document.data_contract_id.to_buffer(),
+            document.document_type.as_str(),
+            Some(document.owner_id.to_buffer()),
+            false,
+            self.block_info.clone(),
+            !execution_context.is_dry_run(),
+            Some(&storage_flags),
+            self.transaction,
+        )?;
+
+        Self::add_fee_result_to_execution_context(fee_result, execution_context);
+
+        Ok(())
+    }
+
+    async fn update_document(
+        &self,
+        document: &Document,
+        execution_context: &StateTransitionExecutionContext,
+    ) -> AnyResult<()> {
+        let serialized_document = document.to_buffer()?;
+
+        let storage_flags = self.storage_flags_for_owner(&document.owner_id);
+
+        let fee_result = self.drive.update_document_for_contract_id(
+            serialized_document.as_slice(),
+            document.data_contract_id.to_buffer(),
+            document.document_type.as_str(),
+            Some(document.owner_id.to_buffer()),
+            self.block_info.clone(),
+            !execution_context.is_dry_run(),
+            Some(&storage_flags),
+            self.transaction,
+        )?;
+
+        Self::add_fee_result_to_execution_context(fee_result, execution_context);
+
+        Ok(())
+    }
+
+    async fn remove_document(
+        &self,
+        data_contract: &DataContract,
+        data_contract_type: &str,
+        document_id: &Identifier,
+        execution_context: &StateTransitionExecutionContext,
+    ) -> AnyResult<()> {
+        let fee_result = self.drive.delete_document_for_contract(
+            document_id.to_buffer(),
+            data_contract,
+            data_contract_type,
+            None,
+            self.block_info.clone(),
+            !execution_context.is_dry_run(),
+            self.transaction,
+        )?;
+
+        Self::add_fee_result_to_execution_context(fee_result, execution_context);
+
+        Ok(())
+    }
+
+    async fn fetch_transaction<T>(
+        &self,
+        id: &str,
+        _execution_context: &StateTransitionExecutionContext,
+    ) -> AnyResult<Option<T>>
+    where
+        T: for<'de> serde::de::Deserialize<'de> + 'static,
+    {
+        self.chain_data_provider
+            .fetch_transaction(id)?
+            .map(|transaction| Ok(serde_json::from_value(transaction)?))
+            .transpose()
+    }
+
+    async fn fetch_identity<T>(
+        &self,
+        id: &Identifier,
+        execution_context: &StateTransitionExecutionContext,
+    ) -> AnyResult<Option<T>>
+    where
+        T: for<'de> serde::de::Deserialize<'de> + 'static,
+    {
+        let (maybe_identity, fee_result) = self.drive.fetch_identity_with_fees(
+            id.as_bytes(),
+            self.block_info.protocol_version,
+            &self.block_info.epoch,
+            self.transaction,
+        )?;
+
+        Self::add_fee_result_to_execution_context(fee_result, execution_context);
+
+        maybe_identity
+            .map(|(identity, _)| Ok(serde_json::from_value(serde_json::to_value(identity)?)?))
+            .transpose()
+    }
+
+    /// Drive maps the public key hashes of an identity to the identity whenever its keys
+    /// are written by `create_identity` and `update_identity`, so there is nothing to store.
+    async fn store_identity_public_key_hashes(
+        &self,
+        _identity_id: &Identifier,
+        _public_key_hashes: Vec<Vec<u8>>,
+        _execution_context: &StateTransitionExecutionContext,
+    ) -> AnyResult<()> {
+        Ok(())
+    }
+
+    /// Returns an identity for every public key hash, or null if the hash isn't mapped
+    /// to any identity.
+    async fn fetch_identity_by_public_key_hashes<T>(
+        &self,
+        public_key_hashed: Vec<Vec<u8>>,
+    ) -> AnyResult<Vec<T>>
+    where
+        T: for<'de> serde::de::Deserialize<'de> + 'static,
+    {
+        let identities_by_public_key_hash = self.drive.fetch_identities_by_public_key_hashes(
+            public_key_hashed.as_slice(),
+            self.block_info.protocol_version,
+            self.transaction,
+        )?;
+
+        public_key_hashed
+            .iter()
+            .map(|public_key_hash| {
+                let identity = identities_by_public_key_hash
+                    .get(public_key_hash)
+                    .and_then(|identities| identities.first().cloned());
+                Ok(serde_json::from_value(serde_json::to_value(identity)?)?)
+            })
+            .collect()
+    }
+
+    async fn fetch_latest_platform_block_header<T>(&self) -> AnyResult<T>
+    where
+        T: for<'de> serde::de::Deserialize<'de> + 'static,
+    {
+        Ok(serde_json::from_value(
+            self.chain_data_provider
+                .fetch_latest_platform_block_header()?,
+        )?)
+    }
+
+    async fn verify_instant_lock(
+        &self,
+        instant_lock: &InstantLock,
+        _execution_context: &StateTransitionExecutionContext,
+    ) -> AnyResult<bool> {
+        self.chain_data_provider.verify_instant_lock(instant_lock)
+    }
+
+    async fn is_asset_lock_transaction_out_point_already_used(
+        &self,
+        out_point_buffer: &[u8],
+        _execution_context: &StateTransitionExecutionContext,
+    ) -> AnyResult<bool> {
+        Ok(self.drive.has_asset_lock_outpoint(
+            &to_asset_lock_outpoint(out_point_buffer)?,
+            self.transaction,
+        )?)
+    }
+
+    async fn mark_asset_lock_transaction_out_point_as_used(
+        &self,
+        out_point_buffer: &[u8],
+        execution_context: &StateTransitionExecutionContext,
+    ) -> AnyResult<()> {
+        let fee_result = self.drive.insert_asset_lock_outpoint(
+            &to_asset_lock_outpoint(out_point_buffer)?,
+            &self.block_info,
+            !execution_context.is_dry_run(),
+            self.transaction,
+        )?;
+
+        Self::add_fee_result_to_execution_context(fee_result, execution_context);
+
+        Ok(())
+    }
+
+    async fn fetch_sml_store<T>(&self) -> AnyResult<T>
+    where
+        T: for<'de> serde::de::Deserialize<'de> + 'static,
+    {
+        Ok(serde_json::from_value(
+            self.chain_data_provider.fetch_sml_store()?,
+        )?)
+    }
+
+    async fn create_identity(
+        &self,
+        identity: &Identity,
+        execution_context: &StateTransitionExecutionContext,
+    ) -> AnyResult<()> {
+        let storage_flags = self.storage_flags_for_owner(&identity.id);
+
+        let fee_result = self.drive.insert_identity(
+            identity.clone(),
+            self.block_info.clone(),
+            !execution_context.is_dry_run(),
+            Some(&storage_flags),
+            self.transaction,
+        )?;
+
+        Self::add_fee_result_to_execution_context(fee_result, execution_context);
+
+        Ok(())
+    }
+
+    async fn update_identity(
+        &self,
+        identity: &Identity,
+        execution_context: &StateTransitionExecutionContext,
+    ) -> AnyResult<()> {
+        let storage_flags = self.storage_flags_for_owner(&identity.id);
+
+        let fee_result = self.drive.update_identity(
+            identity,
+            self.block_info.clone(),
+            !execution_context.is_dry_run(),
+            Some(&storage_flags),
+            self.transaction,
+        )?;
+
+        Self::add_fee_result_to_execution_context(fee_result, execution_context);
+
+        Ok(())
+    }
+
+    async fn fetch_latest_withdrawal_transaction_index(&self) -> AnyResult<u64> {
+        Ok(self
+            .drive
+            .fetch_latest_withdrawal_transaction_index(self.transaction)?)
+    }
+
+    async fn enqueue_withdrawal_transaction(
+        &self,
+        index: u64,
+        transaction_bytes: Vec<u8>,
+        execution_context: &StateTransitionExecutionContext,
+    ) -> AnyResult<()> {
+        let fee_result = self.drive.enqueue_withdrawal_transaction(
+            index,
+            transaction_bytes,
+            &self.block_info,
+            !execution_context.is_dry_run(),
+            self.transaction,
+        )?;
+
+        Self::add_fee_result_to_execution_context(fee_result, execution_context);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::helpers::setup::setup_drive_with_initial_state_structure;
+    use crate::common::setup_contract;
+    use crate::drive::object_size_info::DocumentInfo::DocumentRefWithoutSerialization;
+    use crate::drive::object_size_info::{DocumentAndContractInfo, OwnedDocumentInfo};
+    use crate::fee::default_costs::FeeSchedule;
+    use crate::fee::result::refunds::FeeRefunds;
+    use ciborium::value::Value;
+    use dpp::state_transition::fee::calculate_operations_fees;
+    use futures::executor::block_on;
+    use std::collections::BTreeMap;
+
+    /// Provides fixed chain data without a masternode list
+    struct TestChainDataProvider;
+
+    impl ChainDataProviderLike for TestChainDataProvider {
+        fn fetch_transaction(&self, id: &str) -> AnyResult<Option<JsonValue>> {
+            Ok(Some(serde_json::json!({ "data": id, "height": 42 })))
+        }
+
+        fn verify_instant_lock(&self, _instant_lock: &InstantLock) -> AnyResult<bool> {
+            Ok(true)
+        }
+
+        fn fetch_sml_store(&self) -> AnyResult<JsonValue> {
+            Err(anyhow!("no masternode list in tests"))
+        }
+
+        fn fetch_latest_platform_block_header(&self) -> AnyResult<JsonValue> {
+            Ok(serde_json::json!({ "height": 7 }))
+        }
+    }
+
+    fn test_identity() -> Identity {
+        let identity_bytes = hex::decode("01000000a462696458203012c19b98ec0033addb36cd64b7f510670f2a351a4304b5f6994144286efdac6762616c616e636500687265766973696f6e006a7075626c69634b65797381a6626964006464617461582102abb64674c5df796559eb3cf92a84525cc1a6068e7ad9d4ff48a1f0b179ae29e164747970650067707572706f73650068726561644f6e6c79f46d73656375726974794c6576656c00").expect("expected to decode identity hex");
+
+        Identity::from_buffer(identity_bytes.as_slice())
+            .expect("expected to deserialize an identity")
+    }
+
+    #[test]
+    fn test_create_and_fetch_identity() {
+        let drive = setup_drive_with_initial_state_structure();
+        let transaction = drive.grove.start_transaction();
+
+        let state_repository = DriveStateRepository::new(
+            &drive,
+            &TestChainDataProvider,
+            BlockInfo::default(),
+            Some(&transaction),
+        );
+        let execution_context = StateTransitionExecutionContext::default();
+
+        let identity = test_identity();
+
+        block_on(state_repository.create_identity(&identity, &execution_context))
+            .expect("expected to create identity");
+
+        let fetched_identity: Identity =
+            block_on(state_repository.fetch_identity(&identity.id, &execution_context))
+                .expect("expected to fetch identity")
+                .expect("expected identity to exist");
+
+        assert_eq!(
+            fetched_identity.to_buffer().expect("should serialize"),
+            identity.to_buffer().expect("should serialize")
+        );
+
+        assert!(!execution_context.get_operations().is_empty());
+    }
+
+    #[test]
+    fn test_fetch_identity_adds_read_operations() {
+        let drive = setup_drive_with_initial_state_structure();
+        let transaction = drive.grove.start_transaction();
+
+        let state_repository = DriveStateRepository::new(
+            &drive,
+            &TestChainDataProvider,
+            BlockInfo::default(),
+            Some(&transaction),
+        );
+
+        let identity = test_identity();
+
+        let execution_context = StateTransitionExecutionContext::default();
+
+        let fetched_identity: Option<Identity> =
+            block_on(state_repository.fetch_identity(&identity.id, &execution_context))
+                .expect("expected to fetch identity");
+
+        assert!(fetched_identity.is_none());
+        assert!(!execution_context.get_operations().is_empty());
+
+        block_on(state_repository.create_identity(&identity, &execution_context))
+            .expect("expected to create identity");
+
+        let execution_context = StateTransitionExecutionContext::default();
+
+        let fetched_identity: Option<Identity> =
+            block_on(state_repository.fetch_identity(&identity.id, &execution_context))
+                .expect("expected to fetch identity");
+
+        assert!(fetched_identity.is_some());
+        assert!(!execution_context.get_operations().is_empty());
+    }
+
+    #[test]
+    fn test_chain_data_is_fetched_from_the_provider() {
+        let drive = setup_drive_with_initial_state_structure();
+        let transaction = drive.grove.start_transaction();
+
+        let state_repository = DriveStateRepository::new(
+            &drive,
+            &TestChainDataProvider,
+            BlockInfo::default(),
+            Some(&transaction),
+        );
+        let execution_context = StateTransitionExecutionContext::default();
+
+        let transaction_data: Option<JsonValue> =
+            block_on(state_repository.fetch_transaction("abcd", &execution_context))
+                .expect("expected to fetch transaction");
+
+        assert_eq!(
+            transaction_data,
+            Some(serde_json::json!({ "data": "abcd", "height": 42 }))
+        );
+
+        let block_header: JsonValue =
+            block_on(state_repository.fetch_latest_platform_block_header())
+                .expect("expected to fetch block header");
+
+        assert_eq!(block_header, serde_json::json!({ "height": 7 }));
+
+        block_on(state_repository.fetch_sml_store::<JsonValue>())
+            .expect_err("expected provider error");
+    }
+
+    #[test]
+    fn test_fee_results_keep_refunds_in_execution_context() {
+        let fee_result = FeeResult {
+            storage_fee: 100,
+            processing_fee: 10,
+            fee_refunds: FeeRefunds::from_storage_removal([([1; 32], [(0, 3), (2, 4)])])
+                .expect("expected refunds"),
+            removed_bytes_from_system: 5,
+        };
+
+        let execution_context = StateTransitionExecutionContext::default();
+        DriveStateRepository::add_fee_result_to_execution_context(
+            fee_result.clone(),
+            &execution_context,
+        );
+
+        // Drive fees already have the fee multiplier of the epoch applied
+        let calculated_fee_result = calculate_operations_fees(
+            execution_context.get_operations(),
+            FeeSchedule::latest().expect("expected latest fee schedule"),
+            2.0,
+        )
+        .expect("expected to calculate fees");
+
+        assert_eq!(calculated_fee_result, fee_result);
+    }
+
+    #[test]
+    fn test_fetch_documents_converts_stored_documents() {
+        let drive = setup_drive_with_initial_state_structure();
+        let transaction = drive.grove.start_transaction();
+
+        let contract = setup_contract(
+            &drive,
+            "tests/supporting_files/contract/dashpay/dashpay-contract.json",
+            None,
+            Some(&transaction),
+        );
+
+        let document_type = contract
+            .document_type_for_name("profile")
+            .expect("expected to get profile document type");
+
+        // the binary serialization doesn't keep the document type and the contract id
+        let document = DriveDocument {
+            id: [1; 32],
+            properties: BTreeMap::from([
+                ("$revision".to_string(), Value::Integer(2.into())),
+                ("$createdAt".to_string(), Value::Integer(1000.into())),
+                ("$updatedAt".to_string(), Value::Integer(2000.into())),
+                ("displayName".to_string(), Value::Text("sam".to_string())),
+            ]),
+            owner_id: [2; 32],
+        };
+
+        drive
+            .add_document_for_contract(
+                DocumentAndContractInfo {
+                    owned_document_info: OwnedDocumentInfo {
+                        document_info: DocumentRefWithoutSerialization((&document, None)),
+                        owner_id: Some(document.owner_id),
+                    },
+                    contract: &contract,
+                    document_type,
+                },
+                false,
+                BlockInfo::default(),
+                true,
+                Some(&transaction),
+            )
+            .expect("expected to add the document");
+
+        let state_repository = DriveStateRepository::new(
+            &drive,
+            &TestChainDataProvider,
+            BlockInfo::default(),
+            Some(&transaction),
+        );
+        let execution_context = StateTransitionExecutionContext::default();
+
+        let documents: Vec<Document> = block_on(state_repository.fetch_documents(
+            &contract.id,
+            "profile",
+            serde_json::json!({}),
+            &execution_context,
+        ))
+        .expect("expected to fetch documents");
+
+        assert_eq!(documents.len(), 1);
+        assert_eq!(documents[0].id.to_buffer(), document.id);
+        assert_eq!(documents[0].owner_id.to_buffer(), document.owner_id);
+        assert_eq!(documents[0].document_type, "profile");
+        assert_eq!(documents[0].data_contract_id, contract.id);
+        assert_eq!(documents[0].revision, 2);
+        assert_eq!(documents[0].created_at, Some(1000));
+        assert_eq!(documents[0].updated_at, Some(2000));
+        assert_eq!(documents[0].data["displayName"], serde_json::json!("sam"));
+        assert!(!execution_context.get_operations().is_empty());
+    }
+
+    #[test]
+    fn test_fetch_non_existent_identity_returns_none() {
+        let drive = setup_drive_with_initial_state_structure();
+        let transaction = drive.grove.start_transaction();
+
+        let state_repository = DriveStateRepository::new(
+            &drive,
+            &TestChainDataProvider,
+            BlockInfo::default(),
+            Some(&transaction),
+        );
+        let execution_context = StateTransitionExecutionContext::default();
+
+        let fetched_identity: Option<Identity> = block_on(
+            state_repository.fetch_identity(&Identifier::new([1; 32]), &execution_context),
+        )
+        .expect("expected to fetch identity");
+
+        assert!(fetched_identity.is_none());
+    }
+
+    #[test]
+    fn test_dry_run_does_not_create_identity() {
+        let drive = setup_drive_with_initial_state_structure();
+        let transaction = drive.grove.start_transaction();
+
+        let state_repository = DriveStateRepository::new(
+            &drive,
+            &TestChainDataProvider,
+            BlockInfo::default(),
+            Some(&transaction),
+        );
+        let execution_context = StateTransitionExecutionContext::default();
+        execution_context.enable_dry_run();
+
+        let identity = test_identity();
+
+        block_on(state_repository.create_identity(&identity, &execution_context))
+            .expect("expected to estimate identity creation");
+
+        let fetched_identity: Option<Identity> =
+            block_on(state_repository.fetch_identity(&identity.id, &execution_context))
+                .expect("expected to fetch identity");
+
+        assert!(fetched_identity.is_none());
+        assert!(!execution_context.get_operations().is_empty());
+    }
+
+    #[test]
+    fn test_fetch_identity_by_public_key_hashes() {
+        let drive = setup_drive_with_initial_state_structure();
+        let transaction = drive.grove.start_transaction();
+
+        let state_repository = DriveStateRepository::new(
+            &drive,
+            &TestChainDataProvider,
+            BlockInfo::default(),
+            Some(&transaction),
+        );
+        let execution_context = StateTransitionExecutionContext::default();
+
+        let identity = test_identity();
+
+        block_on(state_repository.create_identity(&identity, &execution_context))
+            .expect("expected to create identity");
+
+        let public_key_hash = identity.public_keys[0]
+            .hash()
+            .expect("expected to hash the public key");
+
+        let identities: Vec<Option<Identity>> = block_on(
+            state_repository
+                .fetch_identity_by_public_key_hashes(vec![public_key_hash, vec![1; 20]]),
+        )
+        .expect("expected to fetch identities");
+
+        assert_eq!(identities.len(), 2);
+        assert_eq!(
+            identities[0]
+                .as_ref()
+                .expect("expected identity to exist")
+                .id,
+            identity.id
+        );
+        assert!(identities[1].is_none());
+    }
+
+    #[test]
+    fn test_mark_asset_lock_transaction_out_point_as_used() {
+        let drive = setup_drive_with_initial_state_structure();
+        let transaction = drive.grove.start_transaction();
+
+        let state_repository = DriveStateRepository::new(
+            &drive,
+            &TestChainDataProvider,
+            BlockInfo::default(),
+            Some(&transaction),
+        );
+        let execution_context = StateTransitionExecutionContext::default();
+
+        let out_point = [1; 36];
+
+        let is_used = block_on(
+            state_repository
+                .is_asset_lock_transaction_out_point_already_used(&out_point, &execution_context),
+        )
+        .expect("expected to check out point");
+
+        assert!(!is_used);
+
+        block_on(
+            state_repository
+                .mark_asset_lock_transaction_out_point_as_used(&out_point, &execution_context),
+        )
+        .expect("expected to mark out point as used");
+
+        let is_used = block_on(
+            state_repository
+                .is_asset_lock_transaction_out_point_already_used(&out_point, &execution_context),
+        )
+        .expect("expected to check out point");
+
+        assert!(is_used);
+    }
+
+    #[test]
+    fn test_enqueue_withdrawal_transaction_updates_index() {
+        let drive = setup_drive_with_initial_state_structure();
+        let transaction = drive.grove.start_transaction();
+
+        let state_repository = DriveStateRepository::new(
+            &drive,
+            &TestChainDataProvider,
+            BlockInfo::default(),
+            Some(&transaction),
+        );
+        let execution_context = StateTransitionExecutionContext::default();
+
+        block_on(state_repository.enqueue_withdrawal_transaction(
+            5,
+            vec![1; 32],
+            &execution_context,
+        ))
+        .expect("expected to enqueue withdrawal transaction");
+
+        let index = block_on(state_repository.fetch_latest_withdrawal_transaction_index())
+            .expect("expected to fetch index");
+
+        assert_eq!(index, 5);
+
+        let withdrawals = drive
+            .dequeue_withdrawal_transactions(16, Some(&transaction))
+            .expect("expected to dequeue withdrawals");
+
+        assert_eq!(
+            withdrawals,
+            vec![(5u64.to_be_bytes().to_vec(), vec![1; 32])]
+        );
+    }
+
+    #[test]
+    fn test_dry_run_does_not_mark_asset_lock_transaction_out_point_as_used() {
+        let drive = setup_drive_with_initial_state_structure();
+        let transaction = drive.grove.start_transaction();
+
+        let state_repository = DriveStateRepository::new(
+            &drive,
+            &TestChainDataProvider,
+            BlockInfo::default(),
+            Some(&transaction),
+        );
+        let execution_context = StateTransitionExecutionContext::default();
+        execution_context.enable_dry_run();
+
+        let out_point = [1; 36];
+
+        block_on(
+            state_repository
+                .mark_asset_lock_transaction_out_point_as_used(&out_point, &execution_context),
+        )
+        .expect("expected to estimate marking the out point as used");
+
+        assert!(!execution_context.get_operations().is_empty());
+
+        let is_used = block_on(
+            state_repository.is_asset_lock_transaction_out_point_already_used(
+                &out_point,
+                &StateTransitionExecutionContext::default(),
+            ),
+        )
+        .expect("expected to check out point");
+
+        assert!(!is_used);
+    }
+
+    #[test]
+    fn test_dry_run_does_not_enqueue_withdrawal_transaction() {
+        let drive = setup_drive_with_initial_state_structure();
+        let transaction = drive.grove.start_transaction();
+
+        let state_repository = DriveStateRepository::new(
+            &drive,
+            &TestChainDataProvider,
+            BlockInfo::default(),
+            Some(&transaction),
+        );
+        let execution_context = StateTransitionExecutionContext::default();
+        execution_context.enable_dry_run();
+
+        block_on(state_repository.enqueue_withdrawal_transaction(
+            5,
+            vec![1; 32],
+            &execution_context,
+        ))
+        .expect("expected to estimate enqueuing a withdrawal transaction");
+
+        assert!(!execution_context.get_operations().is_empty());
+
+        let index = block_on(state_repository.fetch_latest_withdrawal_transaction_index())
+            .expect("expected to fetch index");
+
+        assert_eq!(index, 0);
+
+        let withdrawals = drive
+            .dequeue_withdrawal_transactions(16, Some(&transaction))
+            .expect("expected to dequeue withdrawals");
+
+        assert!(withdrawals.is_empty());
+    }
+}
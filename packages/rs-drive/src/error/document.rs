@@ -13,4 +13,7 @@ pub enum DocumentError {
     /// Error
     #[error("contact with specified identifier is not found")]
     ContractNotFound(),
+    /// Error
+    #[error("document does not match its contract error: {0}")]
+    DocumentContractMismatch(&'static str),
 }
@@ -35,6 +35,9 @@ pub enum DriveError {
     /// Error
     #[error("updating document that did not already exist error: {0}")]
     UpdatingDocumentThatDoesNotExist(&'static str),
+    /// Error
+    #[error("updating document with wrong revision error: {0}")]
+    UpdatingDocumentWithWrongRevision(&'static str),
 
     /// Error
     #[error("changing contract to readonly error: {0}")]
@@ -49,11 +52,23 @@ pub enum DriveError {
     #[error("changing contract documents mutability default error: {0}")]
     ChangingContractDocumentsMutabilityDefault(&'static str),
     /// Error
+    #[error("deleting contract that can not be deleted error: {0}")]
+    DeletingContractThatCanNotBeDeleted(&'static str),
+    /// Error
+    #[error("deleting contract with too many elements error: {0}")]
+    DeletingContractWithTooManyElements(&'static str),
+    /// Error
     #[error("changing document type mutability error: {0}")]
     ChangingDocumentTypeMutability(&'static str),
     /// Error
     #[error("changing document type keeps history error: {0}")]
     ChangingDocumentTypeKeepsHistory(&'static str),
+    /// Error
+    #[error("adding unique index violated by existing documents error: {0}")]
+    AddingUniqueIndexViolatedByExistingDocuments(&'static str),
+    /// Error
+    #[error("adding indices to too many existing documents error: {0}")]
+    AddingIndicesToTooManyExistingDocuments(&'static str),
 
     /// Error
     #[error("corrupted contract path error: {0}")]
@@ -100,6 +115,10 @@ pub enum DriveError {
     #[error("corrupted genesis time invalid item length error: {0}")]
     CorruptedGenesisTimeInvalidItemLength(String),
 
+    /// Error
+    #[error("corrupted cache state error: {0}")]
+    CorruptedCacheState(&'static str),
+
     /// Error
     #[error("batch is empty")]
     BatchIsEmpty(),
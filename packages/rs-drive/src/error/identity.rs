@@ -20,4 +20,32 @@ pub enum IdentityError {
     /// Identity serialization error
     #[error("identity serialization error: {0}")]
     IdentitySerialization(&'static str),
+
+    /// Identity not found error
+    #[error("identity not found: {0}")]
+    IdentityNotFound(&'static str),
+
+    /// Corrupted identity error, an identity is missing part of its state
+    #[error("corrupted identity: {0}")]
+    CorruptedIdentity(&'static str),
+
+    /// Identity insufficient balance error
+    #[error("identity insufficient balance: {0}")]
+    IdentityInsufficientBalance(&'static str),
+
+    /// Identity key not found error
+    #[error("identity key not found: {0}")]
+    IdentityKeyNotFound(&'static str),
+
+    /// Identity key already exists error
+    #[error("identity key already exists: {0}")]
+    IdentityKeyAlreadyExists(&'static str),
+
+    /// Unique public key already exists error
+    #[error("unique public key already exists: {0}")]
+    UniquePublicKeyAlreadyExists(&'static str),
+
+    /// Asset lock outpoint already used error
+    #[error("asset lock outpoint already used: {0}")]
+    AssetLockOutpointAlreadyUsed(&'static str),
 }
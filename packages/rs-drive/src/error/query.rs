@@ -1,3 +1,20 @@
+use std::fmt;
+
+/// Position in the source of a SQL query, lines and columns start at 1
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SqlPosition {
+    /// Line
+    pub line: u64,
+    /// Column
+    pub column: u64,
+}
+
+impl fmt::Display for SqlPosition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
 /// Query errors
 #[derive(Debug, thiserror::Error)]
 pub enum QueryError {
@@ -7,6 +24,14 @@ pub enum QueryError {
     /// Invalid SQL error
     #[error("invalid sql error: {0}")]
     InvalidSQL(&'static str),
+    /// Error in a SQL query at a position of its source
+    #[error("{error} at {position}")]
+    InvalidSQLAt {
+        /// The error
+        error: Box<QueryError>,
+        /// Position of the part of the query causing the error
+        position: SqlPosition,
+    },
 
     /// Contract not found error
     #[error("contract not found error: {0}")]
@@ -111,4 +136,30 @@ pub enum QueryError {
     /// Starts with illegal string error
     #[error("starts with illegal string error: {0}")]
     StartsWithIllegalString(&'static str),
+
+    /// Invalid proof error
+    #[error("invalid proof error: {0}")]
+    InvalidProof(&'static str),
+
+    /// Invalid or clause error
+    #[error("invalid or clause error: {0}")]
+    InvalidOrClause(&'static str),
+
+    /// Invalid aggregate error
+    #[error("invalid aggregate error: {0}")]
+    InvalidAggregate(&'static str),
+    /// Too many documents to aggregate error
+    #[error("too many documents to aggregate error: {0}")]
+    TooManyDocumentsToAggregate(&'static str),
+    /// Too many documents to query at a block time error
+    #[error("too many documents to query at a block time error: {0}")]
+    TooManyDocumentsAtBlockTime(&'static str),
+
+    /// Invalid continuation token error
+    #[error("invalid continuation token error: {0}")]
+    InvalidContinuationToken(&'static str),
+
+    /// Incomplete query error, the query can be continued page by page
+    #[error("incomplete query error: {0}")]
+    IncompleteQuery(&'static str),
 }
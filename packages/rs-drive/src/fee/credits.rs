@@ -45,8 +45,7 @@ use crate::fee::get_overflow_error;
 use integer_encoding::VarInt;
 use rust_decimal::Decimal;
 
-/// Credits type
-pub type Credits = u64;
+pub use dpp::state_transition::fee::Credits;
 
 /// Signed Credits type is used for internal computations and total credits
 /// balance verification
@@ -29,18 +29,15 @@
 
 //! Fee costs
 //!
-//! Fee costs for Drive (GroveDB) operations
+//! Fee costs for Drive (GroveDB) operations, defined in DPP so both calculate
+//! the same fees. Costs are versioned in fee schedules and every epoch uses the
+//! fee schedule it was started with.
 //!
 
-/// Storage disk usage credit per byte
-pub(crate) const STORAGE_DISK_USAGE_CREDIT_PER_BYTE: u64 = 27000;
-/// Storage processing credit per byte
-pub(crate) const STORAGE_PROCESSING_CREDIT_PER_BYTE: u64 = 400;
-/// Storage load credit per byte
-pub(crate) const STORAGE_LOAD_CREDIT_PER_BYTE: u64 = 400;
-/// Non storage load credit per byte
-pub(crate) const NON_STORAGE_LOAD_CREDIT_PER_BYTE: u64 = 30;
-/// Query credit per byte
-pub(crate) const QUERY_CREDIT_PER_BYTE: u64 = 10;
-/// Storage seek cost
-pub(crate) const STORAGE_SEEK_COST: u64 = 4000;
+pub use dpp::state_transition::fee::constants::{
+    STORAGE_DISK_USAGE_CREDIT_PER_BYTE, STORAGE_LOAD_CREDIT_PER_BYTE,
+    STORAGE_PROCESSING_CREDIT_PER_BYTE, STORAGE_SEEK_COST,
+};
+pub use dpp::state_transition::fee::fee_schedule::{
+    FeeSchedule, FeeVersion, FEE_SCHEDULE_V1, INITIAL_FEE_VERSION, LATEST_FEE_VERSION,
+};
@@ -32,13 +32,12 @@
 //! Fee distribution is based on epochs. One epoch is about 18 days
 //!
 
-use crate::fee::credits::{Credits, SignedCredits};
+use crate::fee::credits::SignedCredits;
 use nohash_hasher::IntMap;
 
 pub mod distribution;
 
-/// Epoch index type
-pub type EpochIndex = u16;
+pub use dpp::state_transition::fee::{CreditsPerEpoch, EpochIndex};
 
 /// Genesis epoch index
 pub const GENESIS_EPOCH_INDEX: EpochIndex = 0;
@@ -52,7 +51,5 @@ pub const PERPETUAL_STORAGE_YEARS: u16 = 50;
 /// Perpetual storage epochs
 pub const PERPETUAL_STORAGE_EPOCHS: u16 = PERPETUAL_STORAGE_YEARS * EPOCHS_PER_YEAR;
 
-/// Credits per epoch map
-pub type CreditsPerEpoch = IntMap<EpochIndex, Credits>;
 /// Signed credits per epoch map
 pub type SignedCreditsPerEpoch = IntMap<EpochIndex, SignedCredits>;
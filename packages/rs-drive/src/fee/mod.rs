@@ -33,6 +33,7 @@ use enum_map::EnumMap;
 
 use crate::error::fee::FeeError;
 use crate::error::Error;
+use crate::fee::credits::Credits;
 use crate::fee::op::{BaseOp, DriveOperation};
 use crate::fee::result::FeeResult;
 use crate::fee_pools::epochs::Epoch;
@@ -43,10 +44,14 @@ pub mod epoch;
 pub mod op;
 pub mod result;
 
-/// Default original fee multiplier
+pub use dpp::state_transition::fee::{apply_fee_multiplier, NEUTRAL_FEE_MULTIPLIER};
+
+/// Fee multiplier stored by epochs started before fee multipliers were applied to
+/// processing fees, such epochs are charged with the neutral fee multiplier
 pub const DEFAULT_ORIGINAL_FEE_MULTIPLIER: f64 = 2.0;
 
-/// Calculates fees for the given operations. Returns the storage and processing costs.
+/// Calculates fees for the given operations using the fee schedule and the fee multiplier
+/// of the epoch. Returns the storage and processing costs.
 pub fn calculate_fee(
     base_operations: Option<EnumMap<BaseOp, u64>>,
     drive_operations: Option<Vec<DriveOperation>>,
@@ -54,15 +59,18 @@ pub fn calculate_fee(
 ) -> Result<FeeResult, Error> {
     let mut aggregate_fee_result = FeeResult::default();
     if let Some(base_operations) = base_operations {
+        let mut base_operations_fee: Credits = 0;
         for (base_op, count) in base_operations.iter() {
             match base_op.cost().checked_mul(*count) {
                 None => return Err(Error::Fee(FeeError::Overflow("overflow error"))),
-                Some(cost) => match aggregate_fee_result.processing_fee.checked_add(cost) {
+                Some(cost) => match base_operations_fee.checked_add(cost) {
                     None => return Err(Error::Fee(FeeError::Overflow("overflow error"))),
-                    Some(value) => aggregate_fee_result.processing_fee = value,
+                    Some(value) => base_operations_fee = value,
                 },
             }
         }
+        aggregate_fee_result.processing_fee =
+            apply_fee_multiplier(base_operations_fee, epoch.fee_multiplier)?;
     }
 
     if let Some(drive_operations) = drive_operations {
@@ -75,6 +83,76 @@ pub fn calculate_fee(
     Ok(aggregate_fee_result)
 }
 
+/// Calculates fees for the given operations like `calculate_fee`, leaving out the fee
+/// multiplier of the epoch. Such fees are pushed as `PreCalculatedFeeResult` operations,
+/// which are charged with the fee multiplier of the epoch they are consumed in.
+pub fn calculate_fee_without_multiplier(
+    drive_operations: Vec<DriveOperation>,
+    epoch: &Epoch,
+) -> Result<FeeResult, Error> {
+    let neutral_epoch = Epoch {
+        fee_multiplier: NEUTRAL_FEE_MULTIPLIER,
+        ..epoch.clone()
+    };
+    calculate_fee(None, Some(drive_operations), &neutral_epoch)
+}
+
 pub(crate) fn get_overflow_error(str: &'static str) -> Error {
     Error::Fee(FeeError::Overflow(str))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use costs::storage_cost::StorageCost;
+    use costs::OperationCost;
+
+    #[test]
+    fn test_calculate_fee_applies_epoch_fee_multiplier_to_processing_fees() {
+        let operation_cost = OperationCost {
+            seek_count: 1,
+            storage_cost: StorageCost {
+                added_bytes: 10,
+                ..Default::default()
+            },
+            storage_loaded_bytes: 5,
+            hash_node_calls: 2,
+        };
+
+        let mut base_operations = EnumMap::default();
+        base_operations[BaseOp::Add] = 3;
+
+        let neutral_epoch = Epoch::new(0);
+        let neutral_fees = calculate_fee(
+            Some(base_operations),
+            Some(vec![DriveOperation::CalculatedCostOperation(
+                operation_cost.clone(),
+            )]),
+            &neutral_epoch,
+        )
+        .expect("should calculate fees");
+
+        let epoch = Epoch::new_with_fee_parameters(0, neutral_epoch.fee_version, None, 2.5);
+        let fees = calculate_fee(
+            Some(base_operations),
+            Some(vec![DriveOperation::CalculatedCostOperation(
+                operation_cost,
+            )]),
+            &epoch,
+        )
+        .expect("should calculate fees");
+
+        assert_eq!(fees.storage_fee, neutral_fees.storage_fee);
+        assert_eq!(fees.processing_fee, neutral_fees.processing_fee * 5 / 2);
+    }
+
+    #[test]
+    fn test_apply_fee_multiplier() {
+        assert_eq!(apply_fee_multiplier(1000, 1.0).unwrap(), 1000);
+        assert_eq!(apply_fee_multiplier(1000, 2.0).unwrap(), 2000);
+        assert_eq!(apply_fee_multiplier(1001, 0.5).unwrap(), 500);
+        assert!(apply_fee_multiplier(1000, -1.0).is_err());
+        assert!(apply_fee_multiplier(1000, f64::NAN).is_err());
+        assert!(apply_fee_multiplier(u64::MAX, 2.0).is_err());
+    }
+}
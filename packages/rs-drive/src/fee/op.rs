@@ -45,15 +45,12 @@ use grovedb::{batch::GroveDbOp, Element};
 use crate::drive::flags::StorageFlags;
 use crate::error::drive::DriveError;
 use crate::error::Error;
-use crate::fee::default_costs::{
-    STORAGE_DISK_USAGE_CREDIT_PER_BYTE, STORAGE_LOAD_CREDIT_PER_BYTE,
-    STORAGE_PROCESSING_CREDIT_PER_BYTE, STORAGE_SEEK_COST,
-};
+use crate::fee::default_costs::FeeSchedule;
 use crate::fee::op::DriveOperation::{
     CalculatedCostOperation, GroveOperation, PreCalculatedFeeResult,
 };
 use crate::fee::result::refunds::FeeRefunds;
-use crate::fee::{get_overflow_error, FeeResult};
+use crate::fee::{apply_fee_multiplier, get_overflow_error, FeeResult};
 use crate::fee_pools::epochs::Epoch;
 
 /// Base ops
@@ -147,12 +144,12 @@ pub enum FunctionOp {
 }
 
 impl FunctionOp {
-    /// Cost
-    pub fn cost(&self, _epoch: &Epoch) -> u64 {
+    /// Cost in the given fee schedule
+    pub fn cost(&self, fee_schedule: &FeeSchedule) -> u64 {
         match self {
-            FunctionOp::Sha256 => 4000,
-            FunctionOp::Sha256_2 => 8000,
-            FunctionOp::Blake3 => 1000,
+            FunctionOp::Sha256 => fee_schedule.sha256_cost,
+            FunctionOp::Sha256_2 => fee_schedule.sha256_2_cost,
+            FunctionOp::Blake3 => fee_schedule.blake3_cost,
         }
     }
 }
@@ -169,7 +166,8 @@ pub enum DriveOperation {
 }
 
 impl DriveOperation {
-    /// Returns a list of the costs of the Drive operations.
+    /// Returns a list of the costs of the Drive operations, with processing fees scaled
+    /// by the fee multiplier of the epoch.
     pub fn consume_to_fees(
         drive_operation: Vec<DriveOperation>,
         epoch: &Epoch,
@@ -177,11 +175,16 @@ impl DriveOperation {
         drive_operation
             .into_iter()
             .map(|operation| match operation {
-                PreCalculatedFeeResult(f) => Ok(f),
+                PreCalculatedFeeResult(mut f) => {
+                    f.processing_fee =
+                        apply_fee_multiplier(f.processing_fee, epoch.fee_multiplier)?;
+                    Ok(f)
+                }
                 _ => {
                     let cost = operation.operation_cost()?;
                     let storage_fee = cost.storage_cost(epoch)?;
-                    let processing_fee = cost.ephemeral_cost(epoch)?;
+                    let processing_fee =
+                        apply_fee_multiplier(cost.ephemeral_cost(epoch)?, epoch.fee_multiplier)?;
                     let (removed_bytes_from_epochs_by_identities, removed_bytes_from_system) =
                         match cost.storage_cost.removed_bytes {
                             NoStorageRemoval => (FeeRefunds::default(), 0),
@@ -265,6 +268,22 @@ impl DriveOperation {
         DriveOperation::for_known_path_key_element(path, key, tree)
     }
 
+    /// Sets `GroveOperation` for inserting an empty sum tree at the given path and key
+    pub fn for_known_path_key_empty_sum_tree(
+        path: Vec<Vec<u8>>,
+        key: Vec<u8>,
+        storage_flags: Option<&StorageFlags>,
+    ) -> Self {
+        let tree = match storage_flags {
+            Some(storage_flags) => {
+                Element::empty_sum_tree_with_flags(storage_flags.to_some_element_flags())
+            }
+            None => Element::empty_sum_tree(),
+        };
+
+        DriveOperation::for_known_path_key_element(path, key, tree)
+    }
+
     /// Sets `GroveOperation` for inserting an empty tree at the given path and key
     pub fn for_estimated_path_key_empty_tree(
         path: KeyInfoPath,
@@ -307,7 +326,7 @@ pub trait DriveCost {
 impl DriveCost for OperationCost {
     /// Return the ephemeral cost from the operation
     fn ephemeral_cost(&self, epoch: &Epoch) -> Result<u64, Error> {
-        //todo: deal with epochs
+        let fee_schedule = epoch.fee_schedule()?;
         let OperationCost {
             seek_count,
             storage_cost,
@@ -315,23 +334,23 @@ impl DriveCost for OperationCost {
             hash_node_calls,
         } = self;
         let seek_cost = (*seek_count as u64)
-            .checked_mul(STORAGE_SEEK_COST)
+            .checked_mul(fee_schedule.storage_seek_cost)
             .ok_or_else(|| get_overflow_error("seek cost overflow"))?;
         let storage_added_bytes_ephemeral_cost = (storage_cost.added_bytes as u64)
-            .checked_mul(STORAGE_PROCESSING_CREDIT_PER_BYTE)
+            .checked_mul(fee_schedule.storage_processing_credit_per_byte)
             .ok_or_else(|| get_overflow_error("storage written bytes cost overflow"))?;
         let storage_replaced_bytes_ephemeral_cost = (storage_cost.replaced_bytes as u64)
-            .checked_mul(STORAGE_PROCESSING_CREDIT_PER_BYTE)
+            .checked_mul(fee_schedule.storage_processing_credit_per_byte)
             .ok_or_else(|| get_overflow_error("storage written bytes cost overflow"))?;
         let storage_removed_bytes_ephemeral_cost =
             (storage_cost.removed_bytes.total_removed_bytes() as u64)
-                .checked_mul(STORAGE_PROCESSING_CREDIT_PER_BYTE)
+                .checked_mul(fee_schedule.storage_processing_credit_per_byte)
                 .ok_or_else(|| get_overflow_error("storage written bytes cost overflow"))?;
         let storage_loaded_bytes_cost = (*storage_loaded_bytes as u64)
-            .checked_mul(STORAGE_LOAD_CREDIT_PER_BYTE)
+            .checked_mul(fee_schedule.storage_load_credit_per_byte)
             .ok_or_else(|| get_overflow_error("storage loaded cost overflow"))?;
         let hash_node_cost = (*hash_node_calls as u64)
-            .checked_mul(FunctionOp::Blake3.cost(epoch))
+            .checked_mul(FunctionOp::Blake3.cost(fee_schedule))
             .ok_or_else(|| get_overflow_error("hash node cost overflow"))?;
         seek_cost
             .checked_add(storage_added_bytes_ephemeral_cost)
@@ -343,11 +362,11 @@ impl DriveCost for OperationCost {
     }
 
     /// Return the storage cost from the operation
-    fn storage_cost(&self, _epoch: &Epoch) -> Result<u64, Error> {
-        //todo: deal with epochs
+    fn storage_cost(&self, epoch: &Epoch) -> Result<u64, Error> {
+        let fee_schedule = epoch.fee_schedule()?;
         let OperationCost { storage_cost, .. } = self;
         (storage_cost.added_bytes as u64)
-            .checked_mul(STORAGE_DISK_USAGE_CREDIT_PER_BYTE)
+            .checked_mul(fee_schedule.storage_disk_usage_credit_per_byte)
             .ok_or_else(|| get_overflow_error("storage written bytes cost overflow"))
     }
 }
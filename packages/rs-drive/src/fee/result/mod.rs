@@ -34,56 +34,10 @@
 //! computation and storage. It also contains fees to refund
 //! for removed data from the state.
 //!
+//! Fee results are defined in DPP, so the fees it calculates for dry runs
+//! can be compared with the fees Drive charges.
+//!
 
-use crate::error::fee::FeeError;
-use crate::error::Error;
-use crate::fee::credits::Credits;
-use crate::fee::result::refunds::FeeRefunds;
+pub use dpp::state_transition::fee::fee_result::FeeResult;
 
 pub mod refunds;
-
-/// Fee Result
-#[derive(Debug, Clone, Eq, PartialEq, Default)]
-pub struct FeeResult {
-    /// Storage fee
-    pub storage_fee: Credits,
-    /// Processing fee
-    pub processing_fee: Credits,
-    /// Credits to refund to identities
-    pub fee_refunds: FeeRefunds,
-    /// Removed bytes not needing to be refunded to identities
-    pub removed_bytes_from_system: u32,
-}
-
-impl FeeResult {
-    /// Creates a FeeResult instance with specified storage and processing fees
-    pub fn from_fees(storage_fee: Credits, processing_fee: Credits) -> Self {
-        FeeResult {
-            storage_fee,
-            processing_fee,
-            ..Default::default()
-        }
-    }
-
-    /// Adds and self assigns result between two Fee Results
-    pub fn checked_add_assign(&mut self, rhs: Self) -> Result<(), Error> {
-        self.storage_fee = self
-            .storage_fee
-            .checked_add(rhs.storage_fee)
-            .ok_or(Error::Fee(FeeError::Overflow("storage fee overflow error")))?;
-        self.processing_fee =
-            self.processing_fee
-                .checked_add(rhs.processing_fee)
-                .ok_or(Error::Fee(FeeError::Overflow(
-                    "processing fee overflow error",
-                )))?;
-        self.fee_refunds.checked_add_assign(rhs.fee_refunds)?;
-        self.removed_bytes_from_system = self
-            .removed_bytes_from_system
-            .checked_add(rhs.removed_bytes_from_system)
-            .ok_or(Error::Fee(FeeError::Overflow(
-                "removed_bytes_from_system overflow error",
-            )))?;
-        Ok(())
-    }
-}
@@ -10,5 +10,9 @@ pub const KEY_START_BLOCK_HEIGHT: &[u8; 1] = b"c";
 pub const KEY_PROPOSERS: &[u8; 1] = b"m";
 /// Fee multiplier key
 pub const KEY_FEE_MULTIPLIER: &[u8; 1] = b"x";
+/// Fee version key
+pub const KEY_FEE_VERSION: &[u8; 1] = b"v";
+/// Fee schedule key
+pub const KEY_FEE_SCHEDULE: &[u8; 1] = b"f";
 /// Epoch storage offset
 pub(crate) const EPOCH_STORAGE_OFFSET: u16 = 256;
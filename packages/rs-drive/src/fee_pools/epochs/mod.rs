@@ -35,27 +35,85 @@ pub mod epoch_key_constants;
 pub mod operations_factory;
 pub mod paths;
 
+use crate::error::Error;
+use crate::fee::default_costs::{FeeSchedule, FeeVersion, LATEST_FEE_VERSION};
 use crate::fee::epoch::EpochIndex;
+use crate::fee::NEUTRAL_FEE_MULTIPLIER;
 use serde::{Deserialize, Serialize};
 
 // TODO: I would call it EpochTree because it represent pool,
 //  not just Epoch which is more abstract thing that we will probably need in future too
 
 /// Epoch struct
-#[derive(Serialize, Deserialize, Default, Clone, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct Epoch {
     /// Epoch index
     pub index: EpochIndex,
     /// Epoch key
     pub(crate) key: [u8; 2],
+    /// Version of the fee schedule used to calculate fees during the epoch
+    #[serde(default = "latest_fee_version")]
+    pub fee_version: FeeVersion,
+    /// Fee schedule activated by governance for the epoch, used instead of
+    /// the fee schedule of the fee version
+    #[serde(default)]
+    pub fee_schedule: Option<FeeSchedule>,
+    /// Multiplier applied to processing fees during the epoch
+    #[serde(default = "neutral_fee_multiplier")]
+    pub fee_multiplier: f64,
+}
+
+fn latest_fee_version() -> FeeVersion {
+    LATEST_FEE_VERSION
+}
+
+fn neutral_fee_multiplier() -> f64 {
+    NEUTRAL_FEE_MULTIPLIER
+}
+
+impl Default for Epoch {
+    fn default() -> Self {
+        Self {
+            index: Default::default(),
+            key: Default::default(),
+            fee_version: LATEST_FEE_VERSION,
+            fee_schedule: None,
+            fee_multiplier: NEUTRAL_FEE_MULTIPLIER,
+        }
+    }
 }
 
 impl Epoch {
-    /// Create new epoch
+    /// Create new epoch using the latest fee schedule and no fee multiplier
     pub fn new(index: EpochIndex) -> Self {
+        Self::new_with_fee_parameters(index, LATEST_FEE_VERSION, None, NEUTRAL_FEE_MULTIPLIER)
+    }
+
+    /// Create new epoch using the given fee schedule, or the fee schedule of
+    /// the given fee version if none, and the given processing fee multiplier
+    pub fn new_with_fee_parameters(
+        index: EpochIndex,
+        fee_version: FeeVersion,
+        fee_schedule: Option<FeeSchedule>,
+        fee_multiplier: f64,
+    ) -> Self {
         let key = paths::encode_epoch_index_key(index).expect("epoch index is too high");
 
-        Self { index, key }
+        Self {
+            index,
+            key,
+            fee_version,
+            fee_schedule,
+            fee_multiplier,
+        }
+    }
+
+    /// Returns the fee schedule used to calculate fees during the epoch
+    pub fn fee_schedule(&self) -> Result<&FeeSchedule, Error> {
+        match &self.fee_schedule {
+            Some(fee_schedule) => Ok(fee_schedule),
+            None => Ok(FeeSchedule::for_version(self.fee_version)?),
+        }
     }
 }
@@ -37,9 +37,10 @@ use crate::drive::fee_pools::pools_vec_path;
 use crate::drive::Drive;
 use crate::error::Error;
 use crate::fee::credits::{Creditable, Credits};
+use crate::fee::default_costs::FeeSchedule;
 use crate::fee_pools::epochs::epoch_key_constants::{
-    KEY_FEE_MULTIPLIER, KEY_POOL_PROCESSING_FEES, KEY_POOL_STORAGE_FEES, KEY_PROPOSERS,
-    KEY_START_BLOCK_HEIGHT, KEY_START_TIME,
+    KEY_FEE_MULTIPLIER, KEY_FEE_SCHEDULE, KEY_FEE_VERSION, KEY_POOL_PROCESSING_FEES,
+    KEY_POOL_STORAGE_FEES, KEY_PROPOSERS, KEY_START_BLOCK_HEIGHT, KEY_START_TIME,
 };
 use crate::fee_pools::epochs::Epoch;
 use grovedb::batch::GroveDbOp;
@@ -102,6 +103,12 @@ impl Epoch {
 
         batch.push(self.update_fee_multiplier_operation(multiplier));
 
+        batch.push(self.update_fee_version_operation());
+
+        if let Some(fee_schedule) = &self.fee_schedule {
+            batch.push(self.update_fee_schedule_operation(fee_schedule));
+        }
+
         batch.push(self.update_start_time_operation(start_time_ms));
     }
 
@@ -141,6 +148,24 @@ impl Epoch {
         )
     }
 
+    /// Returns a groveDB op which stores the version of the fee schedule used by the epoch.
+    pub fn update_fee_version_operation(&self) -> GroveDbOp {
+        GroveDbOp::insert_op(
+            self.get_vec_path(),
+            KEY_FEE_VERSION.to_vec(),
+            Element::Item(self.fee_version.to_be_bytes().to_vec(), None),
+        )
+    }
+
+    /// Returns a groveDB op which stores the fee schedule activated by governance for the epoch.
+    pub fn update_fee_schedule_operation(&self, fee_schedule: &FeeSchedule) -> GroveDbOp {
+        GroveDbOp::insert_op(
+            self.get_vec_path(),
+            KEY_FEE_SCHEDULE.to_vec(),
+            Element::Item(fee_schedule.to_be_bytes(), None),
+        )
+    }
+
     /// Returns a groveDB op which updates the epoch processing credits for distribution.
     pub fn update_processing_fee_pool_operation(
         &self,
@@ -396,6 +421,18 @@ mod tests {
 
             assert_eq!(stored_multiplier, multiplier);
 
+            let stored_fee_version = drive
+                .get_epoch_fee_version(&epoch, Some(&transaction))
+                .expect("should get fee version");
+
+            assert_eq!(stored_fee_version, epoch.fee_version);
+
+            let stored_fee_schedule = drive
+                .get_epoch_fee_schedule(&epoch, Some(&transaction))
+                .expect("should get fee schedule");
+
+            assert_eq!(stored_fee_schedule, None);
+
             let stored_start_time = drive
                 .get_epoch_start_time(&epoch, Some(&transaction))
                 .expect("should get start time");
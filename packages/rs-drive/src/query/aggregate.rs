@@ -0,0 +1,444 @@
+// MIT LICENSE
+//
+// Copyright (c) 2021 Dash Core Group
+//
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+//
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+//
+
+//! Query Aggregates
+//!
+//! This module computes the count, sum, minimum or maximum of the documents matched by
+//! a query by traversing the index of the query, without returning the documents.
+//! Aggregates cover all the matching documents, the limit and offset of the query are
+//! ignored, and can be proved like the documents of a query.
+//!
+//! Aggregates are limited:
+//! - the matched documents are loaded and aggregated one by one, there are no count or
+//!   sum trees, so queries matching more than `MAX_AGGREGATED_DOCUMENTS` (10,000)
+//!   documents fail with `TooManyDocumentsToAggregate`;
+//! - queries with or clauses and queries at a block time on several documents can't be
+//!   made as a single path query and fail with `Unsupported`.
+//!
+
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+
+use ciborium::value::{Integer, Value};
+use dpp::data_contract::extra::{DocumentFieldType, DocumentType};
+use grovedb::query_result_type::QueryResultType::QueryKeyElementPairResultType;
+use grovedb::{Error as GroveError, PathQuery, TransactionArg};
+
+use crate::contract::document::Document;
+use crate::drive::block_info::BlockInfo;
+use crate::drive::Drive;
+use crate::error::query::QueryError;
+use crate::error::Error;
+use crate::fee::calculate_fee;
+use crate::fee::op::DriveOperation;
+use crate::query::defaults::MAX_AGGREGATED_DOCUMENTS;
+use crate::query::DriveQuery;
+
+/// An aggregate over the documents matched by a query
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Aggregate {
+    /// The number of documents
+    Count,
+    /// The sum of a numeric field, documents without a value for it are skipped
+    Sum(String),
+    /// The minimum of a numeric field, documents without a value for it are skipped
+    Min(String),
+    /// The maximum of a numeric field, documents without a value for it are skipped
+    Max(String),
+}
+
+/// The result of an aggregate
+#[derive(Clone, Debug, PartialEq)]
+pub enum AggregateResult {
+    /// The number of documents
+    Count(u64),
+    /// The sum, an integer for integer fields and a float for other numeric fields
+    Sum(Value),
+    /// The minimum value, if any document has one
+    Min(Option<Value>),
+    /// The maximum value, if any document has one
+    Max(Option<Value>),
+}
+
+/// Returns the value of a number as a float to sum it with other numbers.
+fn numeric_value(value: &Value) -> Option<f64> {
+    match value {
+        Value::Integer(integer) => Some(i128::from(*integer) as f64),
+        Value::Float(float) => Some(*float),
+        _ => None,
+    }
+}
+
+/// Compares two numbers. Integers are compared exactly, as floats can't represent
+/// every 64 bit integer.
+fn compare_numeric_values(value: &Value, other_value: &Value) -> Option<Ordering> {
+    match (value, other_value) {
+        (Value::Integer(integer), Value::Integer(other_integer)) => {
+            Some(i128::from(*integer).cmp(&i128::from(*other_integer)))
+        }
+        _ => numeric_value(value)?.partial_cmp(&numeric_value(other_value)?),
+    }
+}
+
+impl Aggregate {
+    /// Returns the type of the aggregated field, which must be numeric.
+    fn aggregated_field_type(
+        &self,
+        document_type: &DocumentType,
+    ) -> Result<Option<DocumentFieldType>, Error> {
+        let field = match self {
+            Aggregate::Count => return Ok(None),
+            Aggregate::Sum(field) | Aggregate::Min(field) | Aggregate::Max(field) => field,
+        };
+        let field_type = match field.as_str() {
+            "$revision" => DocumentFieldType::Integer,
+            "$createdAt" | "$updatedAt" => DocumentFieldType::Date,
+            _ => document_type
+                .properties
+                .get(field)
+                .map(|document_field| document_field.document_type.clone())
+                .ok_or(Error::Query(QueryError::InvalidAggregate(
+                    "aggregated field is not a property of the document type",
+                )))?,
+        };
+        match field_type {
+            DocumentFieldType::Integer | DocumentFieldType::Number | DocumentFieldType::Date => {
+                Ok(Some(field_type))
+            }
+            _ => Err(Error::Query(QueryError::InvalidAggregate(
+                "only numeric fields can be aggregated",
+            ))),
+        }
+    }
+
+    /// Returns the values of the aggregated field of the documents that have one.
+    fn field_values<'b>(
+        field: &'b str,
+        documents: &'b [Document],
+    ) -> impl Iterator<Item = &'b Value> + 'b {
+        documents
+            .iter()
+            .filter_map(move |document| document.properties.get(field))
+            .filter(|value| !value.is_null())
+    }
+
+    /// Computes the aggregate over the given documents.
+    fn aggregate_documents(
+        &self,
+        document_type: &DocumentType,
+        documents: &[Document],
+    ) -> Result<AggregateResult, Error> {
+        let field_type = self.aggregated_field_type(document_type)?;
+        let not_a_number_error = || {
+            Error::Query(QueryError::InvalidAggregate(
+                "aggregated field has a value that is not a number",
+            ))
+        };
+        match self {
+            Aggregate::Count => Ok(AggregateResult::Count(documents.len() as u64)),
+            Aggregate::Sum(field) => {
+                if field_type == Some(DocumentFieldType::Integer) {
+                    let mut sum: i128 = 0;
+                    for value in Self::field_values(field, documents) {
+                        let integer = value.as_integer().ok_or_else(not_a_number_error)?;
+                        sum = sum
+                            .checked_add(i128::from(integer))
+                            .ok_or(Error::Query(QueryError::InvalidAggregate("sum overflows")))?;
+                    }
+                    let sum = Integer::try_from(sum)
+                        .map_err(|_| Error::Query(QueryError::InvalidAggregate("sum overflows")))?;
+                    Ok(AggregateResult::Sum(Value::Integer(sum)))
+                } else {
+                    let mut sum: f64 = 0.0;
+                    for value in Self::field_values(field, documents) {
+                        sum += numeric_value(value).ok_or_else(not_a_number_error)?;
+                    }
+                    Ok(AggregateResult::Sum(Value::Float(sum)))
+                }
+            }
+            Aggregate::Min(field) | Aggregate::Max(field) => {
+                let is_min = matches!(self, Aggregate::Min(_));
+                let mut best: Option<&Value> = None;
+                for value in Self::field_values(field, documents) {
+                    numeric_value(value).ok_or_else(not_a_number_error)?;
+                    let is_better = match best {
+                        None => true,
+                        Some(best_value) => {
+                            let ordering = compare_numeric_values(value, best_value);
+                            if is_min {
+                                ordering == Some(Ordering::Less)
+                            } else {
+                                ordering == Some(Ordering::Greater)
+                            }
+                        }
+                    };
+                    if is_better {
+                        best = Some(value);
+                    }
+                }
+                let best = best.cloned();
+                if is_min {
+                    Ok(AggregateResult::Min(best))
+                } else {
+                    Ok(AggregateResult::Max(best))
+                }
+            }
+        }
+    }
+}
+
+impl<'a> DriveQuery<'a> {
+    /// Returns the path query traversing the documents to aggregate. It is limited to one
+    /// document more than can be aggregated to detect queries matching too many documents.
+    fn aggregate_path_query(
+        &self,
+        starts_at_document: Option<(Document, bool)>,
+    ) -> Result<PathQuery, Error> {
+        let mut path_query = self.construct_path_query(starts_at_document)?;
+        path_query.query.limit = Some(MAX_AGGREGATED_DOCUMENTS + 1);
+        path_query.query.offset = None;
+        Ok(path_query)
+    }
+
+    /// Makes sure the number of traversed documents can be aggregated.
+    fn verify_aggregated_document_count(count: usize) -> Result<(), Error> {
+        if count > MAX_AGGREGATED_DOCUMENTS as usize {
+            Err(Error::Query(QueryError::TooManyDocumentsToAggregate(
+                "the query matches too many documents to be aggregated",
+            )))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Computes the aggregate over serialized documents returned by the aggregate path query.
+    fn aggregate_serialized_documents(
+        &self,
+        aggregate: &Aggregate,
+        serialized_documents: Vec<Vec<u8>>,
+    ) -> Result<AggregateResult, Error> {
+        Self::verify_aggregated_document_count(serialized_documents.len())?;
+        let documents = self
+            .filter_serialized_documents_by_range_filter_clauses(serialized_documents)?
+            .iter()
+            .map(|serialized_document| {
+                Document::from_stored_bytes(serialized_document.as_slice(), self.document_type)
+            })
+            .collect::<Result<Vec<Document>, Error>>()?;
+        aggregate.aggregate_documents(self.document_type, &documents)
+    }
+
+    /// Executes an aggregate over the documents of the query with no proof and returns
+    /// the result and fee. Fails if the query matches more than `MAX_AGGREGATED_DOCUMENTS`
+    /// documents, has or clauses or is at a block time on several documents.
+    pub fn execute_aggregate_no_proof(
+        &self,
+        aggregate: &Aggregate,
+        drive: &Drive,
+        block_info: Option<BlockInfo>,
+        transaction: TransactionArg,
+    ) -> Result<(AggregateResult, u64), Error> {
+        let mut drive_operations = vec![];
+        let result = self.execute_aggregate_no_proof_internal(
+            aggregate,
+            drive,
+            transaction,
+            &mut drive_operations,
+        )?;
+        let cost = if let Some(block_info) = block_info {
+            let fee_result = calculate_fee(None, Some(drive_operations), &block_info.epoch)?;
+            fee_result.processing_fee
+        } else {
+            0
+        };
+        Ok((result, cost))
+    }
+
+    /// Executes an internal aggregate over the documents of the query with no proof.
+    /// Counts without range filter clauses only traverse the index, the documents are
+    /// not fetched.
+    pub(crate) fn execute_aggregate_no_proof_internal(
+        &self,
+        aggregate: &Aggregate,
+        drive: &Drive,
+        transaction: TransactionArg,
+        drive_operations: &mut Vec<DriveOperation>,
+    ) -> Result<AggregateResult, Error> {
+        aggregate.aggregated_field_type(self.document_type)?;
+
+        let starts_at_document =
+            self.fetch_starts_at_document_operations(drive, transaction, drive_operations)?;
+        let path_query = self.aggregate_path_query(starts_at_document)?;
+
+        if *aggregate == Aggregate::Count && self.internal_clauses.range_filter_clauses.is_empty() {
+            let query_result = drive.grove_get_raw_path_query(
+                &path_query,
+                transaction,
+                QueryKeyElementPairResultType,
+                drive_operations,
+            );
+            let count = match query_result {
+                Err(Error::GroveDB(GroveError::PathKeyNotFound(_)))
+                | Err(Error::GroveDB(GroveError::PathNotFound(_)))
+                | Err(Error::GroveDB(GroveError::PathParentLayerNotFound(_))) => 0,
+                _ => query_result?.0.to_key_elements().len(),
+            };
+            Self::verify_aggregated_document_count(count)?;
+            return Ok(AggregateResult::Count(count as u64));
+        }
+
+        let query_result = drive.grove_get_path_query(&path_query, transaction, drive_operations);
+        let serialized_documents = match query_result {
+            Err(Error::GroveDB(GroveError::PathKeyNotFound(_)))
+            | Err(Error::GroveDB(GroveError::PathNotFound(_)))
+            | Err(Error::GroveDB(GroveError::PathParentLayerNotFound(_))) => vec![],
+            _ => query_result?.0,
+        };
+        self.aggregate_serialized_documents(aggregate, serialized_documents)
+    }
+
+    /// Executes an aggregate over the documents of the query with proof and returns the
+    /// proof and fee. The proof covers the aggregated documents, so it has the same
+    /// limits as `execute_aggregate_no_proof`.
+    pub fn execute_aggregate_with_proof(
+        &self,
+        aggregate: &Aggregate,
+        drive: &Drive,
+        block_info: Option<BlockInfo>,
+        transaction: TransactionArg,
+    ) -> Result<(Vec<u8>, u64), Error> {
+        let mut drive_operations = vec![];
+        let proof = self.execute_aggregate_with_proof_internal(
+            aggregate,
+            drive,
+            transaction,
+            &mut drive_operations,
+        )?;
+        let cost = if let Some(block_info) = block_info {
+            let fee_result = calculate_fee(None, Some(drive_operations), &block_info.epoch)?;
+            fee_result.processing_fee
+        } else {
+            0
+        };
+        Ok((proof, cost))
+    }
+
+    /// Executes an internal aggregate over the documents of the query with proof.
+    pub(crate) fn execute_aggregate_with_proof_internal(
+        &self,
+        aggregate: &Aggregate,
+        drive: &Drive,
+        transaction: TransactionArg,
+        drive_operations: &mut Vec<DriveOperation>,
+    ) -> Result<Vec<u8>, Error> {
+        aggregate.aggregated_field_type(self.document_type)?;
+
+        let starts_at_document =
+            self.fetch_starts_at_document_operations(drive, transaction, drive_operations)?;
+        let path_query = self.aggregate_path_query(starts_at_document)?;
+        drive.grove_get_proved_path_query(&path_query, transaction, drive_operations)
+    }
+
+    /// Verifies a proof of an aggregate against the expected root hash and computes the
+    /// aggregate from the proved documents. Doesn't need access to Drive, so it can be
+    /// used by clients to check aggregates from untrusted nodes.
+    /// The start document must be given if the query has a start.
+    pub fn verify_aggregate_proof(
+        &self,
+        aggregate: &Aggregate,
+        proof: &[u8],
+        expected_root_hash: [u8; 32],
+        starts_at_document: Option<Document>,
+    ) -> Result<AggregateResult, Error> {
+        let starts_at_document = self.starts_at_document_for_verification(starts_at_document)?;
+
+        let path_query = self.aggregate_path_query(starts_at_document)?;
+
+        let (root_hash, values) = Self::verify_path_query_proof(proof, &path_query)?;
+
+        if root_hash != expected_root_hash {
+            return Err(Error::Query(QueryError::InvalidProof(
+                "proof root hash does not match the expected root hash",
+            )));
+        }
+
+        self.aggregate_serialized_documents(aggregate, values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use ciborium::value::Value;
+    use dpp::data_contract::extra::{DocumentField, DocumentFieldType, DocumentType};
+
+    use crate::contract::document::Document;
+    use crate::query::aggregate::{Aggregate, AggregateResult};
+
+    #[test]
+    fn test_min_and_max_of_integers_too_large_for_floats() {
+        let mut document_type = DocumentType::default();
+        document_type.properties.insert(
+            "amount".to_string(),
+            DocumentField {
+                document_type: DocumentFieldType::Integer,
+                required: true,
+            },
+        );
+
+        // both values are rounded to the same float
+        let smaller = i64::MAX - 1;
+        let larger = i64::MAX;
+        assert_eq!(smaller as f64, larger as f64);
+
+        let documents: Vec<Document> = [larger, smaller, larger]
+            .iter()
+            .enumerate()
+            .map(|(i, amount)| Document {
+                id: [i as u8; 32],
+                properties: BTreeMap::from([("amount".to_string(), Value::from(*amount))]),
+                owner_id: [0; 32],
+            })
+            .collect();
+
+        assert_eq!(
+            Aggregate::Min("amount".to_string())
+                .aggregate_documents(&document_type, &documents)
+                .expect("expected to compute the minimum"),
+            AggregateResult::Min(Some(Value::from(smaller)))
+        );
+        assert_eq!(
+            Aggregate::Max("amount".to_string())
+                .aggregate_documents(&document_type, &documents)
+                .expect("expected to compute the maximum"),
+            AggregateResult::Max(Some(Value::from(larger)))
+        );
+    }
+}
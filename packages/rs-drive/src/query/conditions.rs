@@ -44,6 +44,8 @@ use WhereOperator::{
 use crate::contract::document::Document;
 use crate::error::query::QueryError;
 use crate::error::Error;
+use crate::query::defaults::MAX_OR_CLAUSE_BRANCHES;
+use crate::query::sql::SqlSource;
 use dpp::data_contract::extra::DocumentType;
 
 /// Converts SQL values to CBOR.
@@ -70,6 +72,37 @@ fn sql_value_to_cbor(sql_value: ast::Value) -> Option<Value> {
     }
 }
 
+/// Converts SQL values, including signed numbers, to CBOR.
+pub(crate) fn sql_expr_to_cbor(sql_expr: &ast::Expr) -> Option<Value> {
+    match sql_expr {
+        ast::Expr::Value(sql_value) => sql_value_to_cbor(sql_value.clone()),
+        ast::Expr::UnaryOp {
+            op: ast::UnaryOperator::Plus,
+            expr,
+        } if matches!(**expr, ast::Expr::Value(ast::Value::Number(..))) => sql_expr_to_cbor(expr),
+        ast::Expr::UnaryOp {
+            op: ast::UnaryOperator::Minus,
+            expr,
+        } => match sql_expr_to_cbor(expr)? {
+            Value::Integer(integer) => i64::try_from(-i128::from(integer))
+                .ok()
+                .map(|num| Value::Integer(Integer::from(num))),
+            Value::Float(float) => Some(Value::Float(-float)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// The name of the field a SQL identifier refers to, system fields like `$id` are written
+/// as quoted identifiers.
+fn sql_field_name(sql_expr: &ast::Expr) -> Option<String> {
+    match sql_expr {
+        ast::Expr::Identifier(ident) => Some(ident.value.clone()),
+        _ => None,
+    }
+}
+
 /// Where operator arguments
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum WhereOperator {
@@ -143,6 +176,30 @@ impl WhereOperator {
             ))),
         }
     }
+
+    /// Negates the where operator, only comparisons can be negated
+    pub fn negate(&self) -> Result<WhereOperator, Error> {
+        match self {
+            GreaterThan => Ok(LessThanOrEquals),
+            GreaterThanOrEquals => Ok(LessThan),
+            LessThan => Ok(GreaterThanOrEquals),
+            LessThanOrEquals => Ok(GreaterThan),
+            Equal => Err(Error::Query(QueryError::Unsupported(
+                "Invalid query: negated equality clause not supported",
+            ))),
+            Between | BetweenExcludeBounds | BetweenExcludeLeft | BetweenExcludeRight => {
+                Err(Error::Query(QueryError::InvalidBetweenClause(
+                    "Invalid query: negated between clause must be split into two ranges",
+                )))
+            }
+            In => Err(Error::Query(QueryError::Unsupported(
+                "Invalid query: negated in clause not supported",
+            ))),
+            StartsWith => Err(Error::Query(QueryError::Unsupported(
+                "Invalid query: negated startsWith clause not supported",
+            ))),
+        }
+    }
 }
 
 impl WhereOperator {
@@ -198,6 +255,14 @@ impl WhereOperator {
     }
 }
 
+/// Equal clauses by field, the range clause, the in clause and the range filter clauses
+type GroupedClauses = (
+    BTreeMap<String, WhereClause>,
+    Option<WhereClause>,
+    Option<WhereClause>,
+    Vec<WhereClause>,
+);
+
 /// Where clause struct
 #[derive(Clone, Debug, PartialEq)]
 pub struct WhereClause {
@@ -361,12 +426,11 @@ impl<'a> WhereClause {
         }
     }
 
-    /// Given a list of where clauses, returns them in groups of equal, range, and in clauses
-    pub(crate) fn group_clauses(
-        where_clauses: &'a [WhereClause],
-    ) -> Result<(BTreeMap<String, Self>, Option<Self>, Option<Self>), Error> {
+    /// Given a list of where clauses, returns them in groups of equal, range, and in clauses,
+    /// along with the range clauses on other fields than the range clause
+    pub(crate) fn group_clauses(where_clauses: &'a [WhereClause]) -> Result<GroupedClauses, Error> {
         if where_clauses.is_empty() {
-            return Ok((BTreeMap::new(), None, None));
+            return Ok((BTreeMap::new(), None, None, vec![]));
         }
         let equal_clauses_array =
             where_clauses
@@ -426,9 +490,46 @@ impl<'a> WhereClause {
             ))),
         }?;
 
+        // Range clauses are grouped by field. The range on the first field is queried with
+        // an index, while the ranges on the other fields filter the documents it returns.
+        let mut range_fields: Vec<&str> = vec![];
+        for where_clause in where_clauses.iter() {
+            if where_clause.operator.is_range()
+                && where_clause.operator != In
+                && !range_fields.contains(&where_clause.field.as_str())
+            {
+                range_fields.push(where_clause.field.as_str());
+            }
+        }
+
+        let mut range_clauses = range_fields
+            .into_iter()
+            .map(|field| Self::group_range_clauses_for_field(where_clauses, field, &known_fields))
+            .collect::<Result<Vec<Option<WhereClause>>, Error>>()?
+            .into_iter()
+            .flatten();
+
+        let range_clause = range_clauses.next();
+        let range_filter_clauses = range_clauses.collect();
+
+        Ok((equal_clauses, range_clause, in_clause, range_filter_clauses))
+    }
+
+    /// Given a list of where clauses, groups the range clauses on the given field into one
+    fn group_range_clauses_for_field(
+        where_clauses: &'a [WhereClause],
+        field: &str,
+        known_fields: &BTreeSet<String>,
+    ) -> Result<Option<Self>, Error> {
+        let where_clauses = where_clauses
+            .iter()
+            .filter(|where_clause| where_clause.field == field)
+            .collect::<Vec<&WhereClause>>();
+
         // In order to group range clauses
         let groupable_range_clauses: Vec<&WhereClause> = where_clauses
             .iter()
+            .copied()
             .filter(|where_clause| match where_clause.operator {
                 Equal => false,
                 In => false,
@@ -446,6 +547,7 @@ impl<'a> WhereClause {
 
         let non_groupable_range_clauses: Vec<&WhereClause> = where_clauses
             .iter()
+            .copied()
             .filter(|where_clause| match where_clause.operator {
                 Equal => false,
                 In => false,
@@ -461,109 +563,101 @@ impl<'a> WhereClause {
             })
             .collect();
 
-        let range_clause =
-            if non_groupable_range_clauses.is_empty() {
-                if groupable_range_clauses.is_empty() {
-                    Ok(None)
-                } else if groupable_range_clauses.len() == 1 {
-                    let clause = *groupable_range_clauses.first().unwrap();
-                    if known_fields.contains(clause.field.as_str()) {
-                        Err(Error::Query(QueryError::InvalidWhereClauseComponents(
-                            "in clause has same field as an equality clause",
-                        )))
-                    } else {
-                        Ok(Some(clause.clone()))
-                    }
-                } else if groupable_range_clauses.len() > 2 {
-                    Err(Error::Query(QueryError::MultipleRangeClauses(
-                        "there can only be at most 2 range clauses that must be on the same field",
+        if non_groupable_range_clauses.is_empty() {
+            if groupable_range_clauses.is_empty() {
+                Ok(None)
+            } else if groupable_range_clauses.len() == 1 {
+                let clause = *groupable_range_clauses.first().unwrap();
+                if known_fields.contains(clause.field.as_str()) {
+                    Err(Error::Query(QueryError::InvalidWhereClauseComponents(
+                        "in clause has same field as an equality clause",
                     )))
                 } else {
-                    let first_field = groupable_range_clauses.first().unwrap().field.as_str();
-                    if known_fields.contains(first_field) {
-                        Err(Error::Query(QueryError::InvalidWhereClauseComponents(
-                            "a range clause has same field as an equality or in clause",
-                        )))
-                    } else if groupable_range_clauses
-                        .iter()
-                        .any(|&z| z.field.as_str() != first_field)
-                    {
-                        Err(Error::Query(QueryError::MultipleRangeClauses(
-                            "all ranges must be on same field",
-                        )))
-                    } else {
-                        let lower_upper_error = || {
-                            Error::Query(QueryError::RangeClausesNotGroupable(
-                                "lower and upper bounds must be passed if providing 2 ranges",
-                            ))
-                        };
-
-                        // we need to find the bounds of the clauses
-                        let lower_bounds_clause =
-                            WhereClause::lower_bound_clause(groupable_range_clauses.as_slice())?
-                                .ok_or_else(lower_upper_error)?;
-                        let upper_bounds_clause =
-                            WhereClause::upper_bound_clause(groupable_range_clauses.as_slice())?
-                                .ok_or_else(lower_upper_error)?;
-
-                        let operator =
-                            match (lower_bounds_clause.operator, upper_bounds_clause.operator) {
-                                (GreaterThanOrEquals, LessThanOrEquals) => Some(Between),
-                                (GreaterThanOrEquals, LessThan) => Some(BetweenExcludeRight),
-                                (GreaterThan, LessThanOrEquals) => Some(BetweenExcludeLeft),
-                                (GreaterThan, LessThan) => Some(BetweenExcludeBounds),
-                                _ => None,
-                            }
+                    Ok(Some(clause.clone()))
+                }
+            } else if groupable_range_clauses.len() > 2 {
+                Err(Error::Query(QueryError::MultipleRangeClauses(
+                    "there can only be at most 2 range clauses that must be on the same field",
+                )))
+            } else {
+                let first_field = groupable_range_clauses.first().unwrap().field.as_str();
+                if known_fields.contains(first_field) {
+                    Err(Error::Query(QueryError::InvalidWhereClauseComponents(
+                        "a range clause has same field as an equality or in clause",
+                    )))
+                } else {
+                    let lower_upper_error = || {
+                        Error::Query(QueryError::RangeClausesNotGroupable(
+                            "lower and upper bounds must be passed if providing 2 ranges",
+                        ))
+                    };
+
+                    // we need to find the bounds of the clauses
+                    let lower_bounds_clause =
+                        WhereClause::lower_bound_clause(groupable_range_clauses.as_slice())?
+                            .ok_or_else(lower_upper_error)?;
+                    let upper_bounds_clause =
+                        WhereClause::upper_bound_clause(groupable_range_clauses.as_slice())?
                             .ok_or_else(lower_upper_error)?;
 
-                        if upper_bounds_clause
-                            .less_than(lower_bounds_clause, operator == BetweenExcludeBounds)?
-                        {
-                            return Err(Error::Query(QueryError::MultipleRangeClauses(
-                                "lower bounds must be under upper bounds",
-                            )));
+                    let operator =
+                        match (lower_bounds_clause.operator, upper_bounds_clause.operator) {
+                            (GreaterThanOrEquals, LessThanOrEquals) => Some(Between),
+                            (GreaterThanOrEquals, LessThan) => Some(BetweenExcludeRight),
+                            (GreaterThan, LessThanOrEquals) => Some(BetweenExcludeLeft),
+                            (GreaterThan, LessThan) => Some(BetweenExcludeBounds),
+                            _ => None,
                         }
+                        .ok_or_else(lower_upper_error)?;
 
-                        Ok(Some(WhereClause {
-                            field: groupable_range_clauses.first().unwrap().field.clone(),
-                            operator,
-                            value: Value::Array(vec![
-                                lower_bounds_clause.value.clone(),
-                                upper_bounds_clause.value.clone(),
-                            ]),
-                        }))
+                    if upper_bounds_clause
+                        .less_than(lower_bounds_clause, operator == BetweenExcludeBounds)?
+                    {
+                        return Err(Error::Query(QueryError::MultipleRangeClauses(
+                            "lower bounds must be under upper bounds",
+                        )));
                     }
+
+                    Ok(Some(WhereClause {
+                        field: groupable_range_clauses.first().unwrap().field.clone(),
+                        operator,
+                        value: Value::Array(vec![
+                            lower_bounds_clause.value.clone(),
+                            upper_bounds_clause.value.clone(),
+                        ]),
+                    }))
                 }
-            } else if non_groupable_range_clauses.len() == 1 && groupable_range_clauses.is_empty() {
-                let where_clause = *non_groupable_range_clauses.get(0).unwrap();
-                if where_clause.operator == StartsWith {
-                    // Starts with must null be against an empty string
-                    if let Value::Text(text) = &where_clause.value {
-                        if text.is_empty() {
-                            return Err(Error::Query(QueryError::StartsWithIllegalString(
-                                "starts with can not start with an empty string",
-                            )));
-                        }
+            }
+        } else if non_groupable_range_clauses.len() == 1 && groupable_range_clauses.is_empty() {
+            let where_clause = *non_groupable_range_clauses.get(0).unwrap();
+            if where_clause.operator == StartsWith {
+                // Starts with must null be against an empty string
+                if let Value::Text(text) = &where_clause.value {
+                    if text.is_empty() {
+                        return Err(Error::Query(QueryError::StartsWithIllegalString(
+                            "starts with can not start with an empty string",
+                        )));
                     }
                 }
-                if known_fields.contains(where_clause.field.as_str()) {
-                    Err(Error::Query(QueryError::DuplicateNonGroupableClauseSameField(
-                    "a non groupable range clause has same field as an equality or in clause",
-                )))
-                } else {
-                    Ok(Some(where_clause.clone()))
-                }
-            } else if groupable_range_clauses.is_empty() {
-                Err(Error::Query(QueryError::MultipleRangeClauses(
-                    "there can not be more than 1 non groupable range clause",
-                )))
+            }
+            if known_fields.contains(where_clause.field.as_str()) {
+                Err(Error::Query(
+                    QueryError::DuplicateNonGroupableClauseSameField(
+                        "a non groupable range clause has same field as an equality or in clause",
+                    ),
+                ))
             } else {
-                Err(Error::Query(QueryError::RangeClausesNotGroupable(
-                    "clauses are not groupable",
-                )))
-            }?;
-
-        Ok((equal_clauses, range_clause, in_clause))
+                Ok(Some(where_clause.clone()))
+            }
+        } else if groupable_range_clauses.is_empty() {
+            Err(Error::Query(QueryError::MultipleRangeClauses(
+                "there can not be more than 1 non groupable range clause",
+            )))
+        } else {
+            Err(Error::Query(QueryError::RangeClausesNotGroupable(
+                "clauses are not groupable",
+            )))
+        }?
     }
 
     fn split_value_for_between(
@@ -591,6 +685,56 @@ impl<'a> WhereClause {
         Ok((left_key, right_key))
     }
 
+    /// Returns true if the clause matches the serialized value of its field in a document.
+    /// Documents without a value for the field are matched as an empty key, like in indexes.
+    pub(crate) fn matches_serialized_value(
+        &self,
+        document_type: &DocumentType,
+        serialized_value: Option<&[u8]>,
+    ) -> Result<bool, Error> {
+        let value = serialized_value.unwrap_or_default();
+        let key = || document_type.serialize_value_for_key(self.field.as_str(), &self.value);
+        let matches = match self.operator {
+            Equal => value == key()?.as_slice(),
+            In => {
+                let mut matches = false;
+                for in_value in self.in_values()?.iter() {
+                    if value
+                        == document_type
+                            .serialize_value_for_key(self.field.as_str(), in_value)?
+                            .as_slice()
+                    {
+                        matches = true;
+                        break;
+                    }
+                }
+                matches
+            }
+            GreaterThan => value > key()?.as_slice(),
+            GreaterThanOrEquals => value >= key()?.as_slice(),
+            LessThan => value < key()?.as_slice(),
+            LessThanOrEquals => value <= key()?.as_slice(),
+            Between => {
+                let (left_key, right_key) = self.split_value_for_between(document_type)?;
+                value >= left_key.as_slice() && value <= right_key.as_slice()
+            }
+            BetweenExcludeBounds => {
+                let (left_key, right_key) = self.split_value_for_between(document_type)?;
+                value > left_key.as_slice() && value < right_key.as_slice()
+            }
+            BetweenExcludeLeft => {
+                let (left_key, right_key) = self.split_value_for_between(document_type)?;
+                value > left_key.as_slice() && value <= right_key.as_slice()
+            }
+            BetweenExcludeRight => {
+                let (left_key, right_key) = self.split_value_for_between(document_type)?;
+                value >= left_key.as_slice() && value < right_key.as_slice()
+            }
+            StartsWith => value.starts_with(key()?.as_slice()),
+        };
+        Ok(matches)
+    }
+
     /// Returns a path query given the parameters
     // The start at document fields are:
     // document: The Document that we should start at
@@ -932,132 +1076,306 @@ impl<'a> WhereClause {
         Ok(query)
     }
 
-    /// Build where clauses from operations
-    pub(crate) fn build_where_clauses_from_operations(
-        binary_operation: &ast::Expr,
-        where_clauses: &mut Vec<WhereClause>,
-    ) -> Result<(), Error> {
-        match &binary_operation {
+    /// Build a where clause from a SQL condition, negated if it is under a `NOT`
+    fn where_clause_from_operation(
+        operation: &ast::Expr,
+        negated: bool,
+    ) -> Result<WhereClause, Error> {
+        match operation {
             ast::Expr::InList {
                 expr,
                 list,
-                negated,
+                negated: in_negated,
             } => {
-                if *negated {
+                if negated != *in_negated {
                     return Err(Error::Query(QueryError::Unsupported(
                         "Invalid query: negated in clause not supported",
                     )));
                 }
 
-                let field_name = if let ast::Expr::Identifier(ident) = &**expr {
-                    ident.value.clone()
-                } else {
-                    return Err(Error::Query(QueryError::InvalidInClause(
+                let field_name = sql_field_name(expr).ok_or(Error::Query(
+                    QueryError::InvalidInClause(
                         "Invalid query: in clause should start with an identifier",
-                    )));
-                };
+                    ),
+                ))?;
 
-                let mut in_values: Vec<Value> = Vec::new();
-                for value in list {
-                    if let ast::Expr::Value(sql_value) = value {
-                        let cbor_val = sql_value_to_cbor(sql_value.clone()).ok_or({
-                            Error::Query(QueryError::InvalidSQL(
-                                "Invalid query: unexpected value type",
-                            ))
-                        })?;
-                        in_values.push(cbor_val);
-                    } else {
-                        return Err(Error::Query(QueryError::InvalidSQL(
+                let in_values = list
+                    .iter()
+                    .map(|value| {
+                        sql_expr_to_cbor(value).ok_or(Error::Query(QueryError::InvalidSQL(
                             "Invalid query: expected a list of sql values",
-                        )));
-                    }
-                }
+                        )))
+                    })
+                    .collect::<Result<Vec<Value>, Error>>()?;
 
-                where_clauses.push(WhereClause {
+                Ok(WhereClause {
                     field: field_name,
-                    operator: WhereOperator::In,
+                    operator: In,
                     value: Value::Array(in_values),
-                });
+                })
+            }
+            ast::Expr::InSubquery { .. } => Err(Error::Query(QueryError::Unsupported(
+                "Invalid query: in clauses can only take a list of values, subqueries are not supported",
+            ))),
+            ast::Expr::Between {
+                expr,
+                negated: between_negated,
+                low,
+                high,
+            } => {
+                if negated != *between_negated {
+                    // not between clauses are split into two ranges by the caller
+                    return Err(Error::Query(QueryError::InvalidBetweenClause(
+                        "Invalid query: not between clause must be split into two ranges",
+                    )));
+                }
 
-                Ok(())
+                let field_name = sql_field_name(expr).ok_or(Error::Query(
+                    QueryError::InvalidBetweenClause(
+                        "Invalid query: between clause should start with an identifier",
+                    ),
+                ))?;
+                let bound_error = || {
+                    Error::Query(QueryError::InvalidBetweenClause(
+                        "Invalid query: between bounds must be sql values",
+                    ))
+                };
+                let low_value = sql_expr_to_cbor(low).ok_or_else(bound_error)?;
+                let high_value = sql_expr_to_cbor(high).ok_or_else(bound_error)?;
+
+                Ok(WhereClause {
+                    field: field_name,
+                    operator: Between,
+                    value: Value::Array(vec![low_value, high_value]),
+                })
             }
             ast::Expr::BinaryOp { left, op, right } => {
-                if *op == ast::BinaryOperator::And {
-                    Self::build_where_clauses_from_operations(left, where_clauses)?;
-                    Self::build_where_clauses_from_operations(right, where_clauses)?;
+                let (op, negated) = match op {
+                    ast::BinaryOperator::NotLike => (&ast::BinaryOperator::Like, !negated),
+                    op => (op, negated),
+                };
+                let mut where_operator = WhereOperator::from_sql_operator(op.clone())
+                    .ok_or(Error::Query(QueryError::Unsupported("Unknown operator")))?;
+
+                let (field_name, value_expr) = if let Some(field_name) = sql_field_name(left) {
+                    (field_name, &**right)
+                } else if let Some(field_name) = sql_field_name(right) {
+                    where_operator = where_operator.flip()?;
+                    (field_name, &**left)
                 } else {
-                    let mut where_operator = WhereOperator::from_sql_operator(op.clone())
-                        .ok_or(Error::Query(QueryError::Unsupported("Unknown operator")))?;
+                    return Err(Error::Query(QueryError::InvalidSQL(
+                        "Invalid query: where clause should have field name and value",
+                    )));
+                };
 
-                    let identifier;
-                    let value_expr;
+                if negated {
+                    where_operator = where_operator.negate()?;
+                }
 
-                    if matches!(&**left, ast::Expr::Identifier(_))
-                        && matches!(&**right, ast::Expr::Value(_))
-                    {
-                        identifier = &**left;
-                        value_expr = &**right;
-                    } else if matches!(&**right, ast::Expr::Identifier(_))
-                        && matches!(&**left, ast::Expr::Value(_))
-                    {
-                        identifier = &**right;
-                        value_expr = &**left;
-                        where_operator = where_operator.flip()?;
-                    } else {
-                        return Err(Error::Query(QueryError::InvalidSQL(
-                            "Invalid query: where clause should have field name and value",
-                        )));
-                    }
+                if field_name == "$id" && where_operator.is_range() && where_operator != In {
+                    return Err(Error::Query(QueryError::InvalidSQL(
+                        "Invalid query: ranges on $id can only be used as a top level start condition",
+                    )));
+                }
 
-                    let field_name = if let ast::Expr::Identifier(ident) = identifier {
-                        ident.value.clone()
-                    } else {
-                        panic!("unreachable: confirmed it's identifier variant");
-                    };
+                let cbor_val = sql_expr_to_cbor(value_expr).ok_or({
+                    Error::Query(QueryError::InvalidSQL(
+                        "Invalid query: where clause should have field name and value",
+                    ))
+                })?;
 
-                    let value = if let ast::Expr::Value(value) = value_expr {
-                        let cbor_val = sql_value_to_cbor(value.clone()).ok_or({
-                            Error::Query(QueryError::InvalidSQL(
-                                "Invalid query: unexpected value type",
-                            ))
-                        })?;
-                        if where_operator == StartsWith {
-                            // make sure the value is of the right format i.e prefix%
-                            let inner_text = cbor_val.as_text().ok_or({
-                                Error::Query(QueryError::InvalidStartsWithClause(
-                                    "Invalid query: startsWith takes text",
-                                ))
-                            })?;
-                            let match_locations: Vec<_> = inner_text.match_indices('%').collect();
-                            if match_locations.len() == 1
-                                && match_locations[0].0 == inner_text.len() - 1
-                            {
-                                Value::Text(String::from(&inner_text[..(inner_text.len() - 1)]))
-                            } else {
-                                return Err(Error::Query(QueryError::Unsupported(
-                                    "Invalid query: like can only be used to represent startswith",
-                                )));
-                            }
-                        } else {
-                            cbor_val
-                        }
-                    } else {
-                        panic!("unreachable: confirmed it's value variant");
-                    };
+                let value = if where_operator == StartsWith {
+                    // make sure the value is of the right format i.e prefix%
+                    let inner_text = cbor_val.as_text().ok_or({
+                        Error::Query(QueryError::InvalidStartsWithClause(
+                            "Invalid query: startsWith takes text",
+                        ))
+                    })?;
+                    let prefix = inner_text.strip_suffix('%').ok_or({
+                        Error::Query(QueryError::InvalidStartsWithClause(
+                            "Invalid query: like patterns must end with %",
+                        ))
+                    })?;
+                    if prefix.contains(|c| c == '%' || c == '_') {
+                        return Err(Error::Query(QueryError::InvalidStartsWithClause(
+                            "Invalid query: like can only be used to represent startswith, wildcards are only allowed at the end",
+                        )));
+                    }
+                    if prefix.is_empty() {
+                        return Err(Error::Query(QueryError::StartsWithIllegalString(
+                            "starts with can not start with an empty string",
+                        )));
+                    }
+                    Value::Text(String::from(prefix))
+                } else {
+                    cbor_val
+                };
 
-                    where_clauses.push(WhereClause {
-                        field: field_name,
-                        operator: where_operator,
-                        value,
-                    });
-                }
-                Ok(())
+                Ok(WhereClause {
+                    field: field_name,
+                    operator: where_operator,
+                    value,
+                })
             }
             _ => Err(Error::Query(QueryError::InvalidSQL(
                 "Issue parsing sql: invalid selection format",
             ))),
         }
     }
+
+    /// Build where clauses in disjunctive normal form from operations, i.e. alternatives
+    /// of where clauses, a document matches the selection if it matches all clauses of
+    /// any alternative. Negations are pushed down to the conditions, where comparisons
+    /// are inverted and `NOT BETWEEN` becomes two alternative ranges.
+    pub(crate) fn build_where_clause_alternatives_from_operations(
+        operation: &ast::Expr,
+        negated: bool,
+        sql_source: &SqlSource,
+    ) -> Result<Vec<Vec<WhereClause>>, Error> {
+        match operation {
+            ast::Expr::Nested(expr) => {
+                Self::build_where_clause_alternatives_from_operations(expr, negated, sql_source)
+            }
+            ast::Expr::UnaryOp {
+                op: ast::UnaryOperator::Not,
+                expr,
+            } => Self::build_where_clause_alternatives_from_operations(expr, !negated, sql_source),
+            ast::Expr::BinaryOp { left, op, right }
+                if matches!(op, ast::BinaryOperator::And | ast::BinaryOperator::Or) =>
+            {
+                let left_alternatives = Self::build_where_clause_alternatives_from_operations(
+                    left, negated, sql_source,
+                )?;
+                let right_alternatives = Self::build_where_clause_alternatives_from_operations(
+                    right, negated, sql_source,
+                )?;
+                // De Morgan's laws
+                if (*op == ast::BinaryOperator::And) != negated {
+                    Self::and_alternatives(left_alternatives, right_alternatives)
+                } else {
+                    Self::or_alternatives(left_alternatives, right_alternatives)
+                }
+            }
+            ast::Expr::Between {
+                expr,
+                negated: between_negated,
+                low,
+                high,
+            } if negated != *between_negated => {
+                let below = ast::Expr::BinaryOp {
+                    left: expr.clone(),
+                    op: ast::BinaryOperator::Lt,
+                    right: low.clone(),
+                };
+                let above = ast::Expr::BinaryOp {
+                    left: expr.clone(),
+                    op: ast::BinaryOperator::Gt,
+                    right: high.clone(),
+                };
+                let where_clause = |expr: &ast::Expr| {
+                    Self::where_clause_from_operation(expr, false)
+                        .map_err(|e| sql_source.error_at(&operation.to_string(), e))
+                };
+                Self::or_alternatives(
+                    vec![vec![where_clause(&below)?]],
+                    vec![vec![where_clause(&above)?]],
+                )
+            }
+            _ => {
+                let where_clause = Self::where_clause_from_operation(operation, negated)
+                    .map_err(|e| sql_source.error_at(&operation.to_string(), e))?;
+                Ok(vec![vec![where_clause]])
+            }
+        }
+    }
+
+    /// Build where clauses in disjunctive normal form from a list of clause components.
+    /// Components are either a where clause `[<field>, <operator>, <value>]` or an or clause
+    /// `["or", [<alternative>, ...]]` where each alternative is itself a list of components.
+    /// All components of the list must match.
+    pub(crate) fn build_where_clause_alternatives_from_components(
+        components: &[Value],
+    ) -> Result<Vec<Vec<WhereClause>>, Error> {
+        components
+            .iter()
+            .try_fold(vec![vec![]], |alternatives, component| {
+                let Value::Array(clause_components) = component else {
+                    return Err(Error::Query(QueryError::InvalidFormatWhereClause(
+                        "where clause must be an array",
+                    )));
+                };
+
+                let component_alternatives = match clause_components.as_slice() {
+                    [Value::Text(operator), Value::Array(or_alternatives)]
+                        if operator.as_str() == "or" =>
+                    {
+                        if or_alternatives.is_empty() {
+                            return Err(Error::Query(QueryError::InvalidOrClause(
+                                "or clause must have at least one alternative",
+                            )));
+                        }
+                        or_alternatives.iter().try_fold(
+                            vec![],
+                            |or_clause_alternatives, alternative| {
+                                let Value::Array(alternative_components) = alternative else {
+                                    return Err(Error::Query(QueryError::InvalidOrClause(
+                                        "or clause alternatives must be arrays of where clauses",
+                                    )));
+                                };
+                                Self::or_alternatives(
+                                    or_clause_alternatives,
+                                    Self::build_where_clause_alternatives_from_components(
+                                        alternative_components,
+                                    )?,
+                                )
+                            },
+                        )?
+                    }
+                    _ => vec![vec![WhereClause::from_components(clause_components)?]],
+                };
+
+                Self::and_alternatives(alternatives, component_alternatives)
+            })
+    }
+
+    /// Combines two lists of where clause alternatives with an `AND`, distributing it
+    /// over the alternatives.
+    fn and_alternatives(
+        left: Vec<Vec<WhereClause>>,
+        right: Vec<Vec<WhereClause>>,
+    ) -> Result<Vec<Vec<WhereClause>>, Error> {
+        let mut alternatives = Vec::with_capacity(left.len() * right.len());
+        for left_clauses in left.iter() {
+            for right_clauses in right.iter() {
+                let mut clauses = left_clauses.clone();
+                clauses.extend(right_clauses.iter().cloned());
+                alternatives.push(clauses);
+            }
+        }
+        Self::verify_alternatives_count(alternatives)
+    }
+
+    /// Combines two lists of where clause alternatives with an `OR`.
+    fn or_alternatives(
+        mut left: Vec<Vec<WhereClause>>,
+        right: Vec<Vec<WhereClause>>,
+    ) -> Result<Vec<Vec<WhereClause>>, Error> {
+        left.extend(right);
+        Self::verify_alternatives_count(left)
+    }
+
+    /// Returns an error if there are too many alternatives to be queried.
+    fn verify_alternatives_count(
+        alternatives: Vec<Vec<WhereClause>>,
+    ) -> Result<Vec<Vec<WhereClause>>, Error> {
+        if alternatives.len() > MAX_OR_CLAUSE_BRANCHES {
+            Err(Error::Query(QueryError::InvalidOrClause(
+                "or clauses can not be expanded to more than 10 alternatives",
+            )))
+        } else {
+            Ok(alternatives)
+        }
+    }
 }
 
 #[cfg(test)]
@@ -1066,7 +1384,8 @@ mod tests {
 
     use crate::query::conditions::WhereClause;
     use crate::query::conditions::WhereOperator::{
-        Equal, GreaterThan, GreaterThanOrEquals, In, LessThan, LessThanOrEquals,
+        BetweenExcludeBounds, Equal, GreaterThan, GreaterThanOrEquals, In, LessThan,
+        LessThanOrEquals,
     };
 
     #[test]
@@ -1089,7 +1408,7 @@ mod tests {
                     value: Value::Float(1.0),
                 },
             ];
-            let (_, range_clause, _) = WhereClause::group_clauses(&where_clauses)
+            let (_, range_clause, _, _) = WhereClause::group_clauses(&where_clauses)
                 .expect("expected to have groupable pair");
             range_clause.expect("expected to have range clause returned");
         }
@@ -1115,7 +1434,7 @@ mod tests {
                     value: Value::Float(0.0),
                 },
             ];
-            let (_, range_clause, _) = WhereClause::group_clauses(&where_clauses)
+            let (_, range_clause, _, _) = WhereClause::group_clauses(&where_clauses)
                 .expect("expected to have groupable pair");
             range_clause.expect("expected to have range clause returned");
         }
@@ -1143,7 +1462,7 @@ mod tests {
     }
 
     #[test]
-    fn test_different_fields_grouping_causes_error() {
+    fn test_different_fields_grouping_returns_range_filter_clauses() {
         let where_clauses = vec![
             WhereClause {
                 field: "a".to_string(),
@@ -1155,9 +1474,22 @@ mod tests {
                 operator: GreaterThan,
                 value: Value::Float(1.0),
             },
+            WhereClause {
+                field: "b".to_string(),
+                operator: LessThan,
+                value: Value::Float(2.0),
+            },
         ];
-        WhereClause::group_clauses(&where_clauses)
-            .expect_err("different fields should not be groupable");
+        let (_, range_clause, _, range_filter_clauses) = WhereClause::group_clauses(&where_clauses)
+            .expect("ranges on different fields should be grouped by field");
+
+        assert_eq!(
+            range_clause.expect("expected to have range clause returned"),
+            where_clauses[0]
+        );
+        assert_eq!(range_filter_clauses.len(), 1);
+        assert_eq!(range_filter_clauses[0].field, "b");
+        assert_eq!(range_filter_clauses[0].operator, BetweenExcludeBounds);
     }
 
     #[test]
@@ -0,0 +1,179 @@
+// MIT LICENSE
+//
+// Copyright (c) 2021 Dash Core Group
+//
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+//
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+//
+
+//! Query Continuation Tokens
+//!
+//! This module defines the opaque tokens continuing a query after the last document of a
+//! page. A token holds the values the indices of the document type have for that document,
+//! so the next page starts at the same index position even if the document was changed or
+//! deleted in between, without having to fetch it.
+//!
+
+use std::collections::BTreeSet;
+use std::io::{BufReader, Read};
+
+use dpp::data_contract::extra::DocumentType;
+use integer_encoding::{VarInt, VarIntReader};
+
+use crate::contract::document::Document;
+use crate::error::query::QueryError;
+use crate::error::Error;
+
+/// The version of the serialization of continuation tokens
+const CONTINUATION_TOKEN_VERSION: u64 = 0;
+
+/// A token to continue a query after the last document of a page
+#[derive(Clone, Debug, PartialEq)]
+pub struct ContinuationToken {
+    /// The contract of the query
+    pub contract_id: [u8; 32],
+    /// The document type of the query
+    pub document_type_name: String,
+    /// The last document of the page with only its indexed properties
+    pub last_document: Document,
+}
+
+/// Maps a failed read of a continuation token to an error.
+fn read_error<E>(_: E) -> Error {
+    Error::Query(QueryError::InvalidContinuationToken(
+        "error reading from continuation token",
+    ))
+}
+
+impl ContinuationToken {
+    /// Creates the token continuing a query on the document type after the given document.
+    pub fn after_document(
+        contract_id: [u8; 32],
+        document_type: &DocumentType,
+        document: &Document,
+    ) -> Self {
+        let indexed_properties: BTreeSet<&str> = document_type
+            .indices
+            .iter()
+            .flat_map(|index| index.properties.iter())
+            .filter_map(|index_property| index_property.name.split('.').next())
+            .collect();
+        let properties = document
+            .properties
+            .iter()
+            .filter(|(name, _)| indexed_properties.contains(name.as_str()))
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect();
+        ContinuationToken {
+            contract_id,
+            document_type_name: document_type.name.clone(),
+            last_document: Document {
+                id: document.id,
+                properties,
+                owner_id: document.owner_id,
+            },
+        }
+    }
+
+    /// Serializes the token to opaque bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buffer = CONTINUATION_TOKEN_VERSION.encode_var_vec();
+        buffer.extend_from_slice(&self.contract_id);
+        buffer.extend((self.document_type_name.len() as u64).encode_var_vec());
+        buffer.extend_from_slice(self.document_type_name.as_bytes());
+        buffer.extend(self.last_document.to_cbor());
+        buffer
+    }
+
+    /// Deserializes a token from the bytes it was serialized to.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let mut buf = BufReader::new(bytes);
+        let version: u64 = buf.read_varint().map_err(read_error)?;
+        if version != CONTINUATION_TOKEN_VERSION {
+            return Err(Error::Query(QueryError::InvalidContinuationToken(
+                "unknown continuation token version",
+            )));
+        }
+        let mut contract_id = [0; 32];
+        buf.read_exact(&mut contract_id).map_err(read_error)?;
+        let document_type_name_length: u64 = buf.read_varint().map_err(read_error)?;
+        if document_type_name_length > bytes.len() as u64 {
+            return Err(Error::Query(QueryError::InvalidContinuationToken(
+                "document type name is longer than the continuation token",
+            )));
+        }
+        let mut document_type_name = vec![0; document_type_name_length as usize];
+        buf.read_exact(&mut document_type_name)
+            .map_err(read_error)?;
+        let document_type_name = String::from_utf8(document_type_name).map_err(read_error)?;
+        let mut document_cbor = vec![];
+        buf.read_to_end(&mut document_cbor).map_err(read_error)?;
+        let last_document = Document::from_cbor(document_cbor.as_slice(), None, None)?;
+        Ok(ContinuationToken {
+            contract_id,
+            document_type_name,
+            last_document,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::json_document_to_cbor;
+    use crate::contract::{Contract, CreateRandomDocument};
+    use dpp::data_contract::extra::DriveContractExt;
+
+    #[test]
+    fn test_continuation_token_keeps_only_indexed_properties() {
+        let family_cbor = json_document_to_cbor(
+            "tests/supporting_files/contract/family/family-contract.json",
+            Some(1),
+        );
+        let contract = <Contract as DriveContractExt>::from_cbor(&family_cbor, None)
+            .expect("expected to deserialize the contract");
+        let document_type = contract
+            .document_type_for_name("person")
+            .expect("expected to get person document type");
+        let mut document = document_type.random_document(Some(5));
+        document
+            .properties
+            .insert("notIndexed".to_string(), "value".into());
+
+        let token = ContinuationToken::after_document(
+            *<Contract as DriveContractExt>::id(&contract),
+            document_type,
+            &document,
+        );
+        assert!(!token.last_document.properties.contains_key("notIndexed"));
+        assert_eq!(
+            token.last_document.properties.get("age"),
+            document.properties.get("age")
+        );
+
+        let deserialized_token = ContinuationToken::from_bytes(token.to_bytes().as_slice())
+            .expect("expected to deserialize the token");
+        assert_eq!(deserialized_token, token);
+    }
+}
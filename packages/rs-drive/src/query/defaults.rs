@@ -4,3 +4,11 @@ pub(crate) const DEFAULT_QUERY_LIMIT: u16 = 100;
 pub(crate) const MAX_QUERY_LIMIT: u16 = 100;
 /// Max index difference constant
 pub(crate) const MAX_INDEX_DIFFERENCE: u16 = 2;
+/// Max number of alternatives a query with or clauses can be expanded to
+pub(crate) const MAX_OR_CLAUSE_BRANCHES: usize = 10;
+/// Max number of pages of documents scanned to apply range filter clauses
+pub(crate) const MAX_RANGE_FILTER_PAGES: u16 = 10;
+/// Max number of documents an aggregate query can traverse
+pub(crate) const MAX_AGGREGATED_DOCUMENTS: u16 = 10000;
+/// Max number of documents a query at a block time on several documents can traverse
+pub(crate) const MAX_DOCUMENTS_AT_BLOCK_TIME: u16 = 1000;
@@ -27,25 +27,26 @@
 // DEALINGS IN THE SOFTWARE.
 //
 
+use std::cmp::Ordering;
 use std::collections::BTreeMap;
 use std::ops::BitXor;
 
 use ciborium::value::Value;
 use dpp::data_contract::extra::ContractError;
 use dpp::data_contract::extra::DriveContractExt;
-use dpp::data_contract::extra::{encode_float, Index, IndexProperty};
+use dpp::data_contract::extra::{value_from_drive_protobuf, DriveEncoding};
+use dpp::data_contract::extra::{Index, IndexProperty};
 /// Import grovedb
 pub use grovedb::{
     Element, Error as GroveError, GroveDb, PathQuery, Query, QueryItem, SizedQuery, TransactionArg,
 };
 
+use grovedb::query_result_type::QueryResultType::QueryKeyElementPairResultType;
 use indexmap::IndexMap;
 use sqlparser::ast;
 use sqlparser::ast::TableFactor::Table;
 use sqlparser::ast::Value::Number;
-use sqlparser::ast::{OrderByExpr, Select, Statement};
-use sqlparser::dialect::GenericDialect;
-use sqlparser::parser::Parser;
+use sqlparser::ast::{OrderByExpr, Select};
 
 use conditions::WhereOperator::{Equal, In};
 /// Import conditions
@@ -54,8 +55,11 @@ pub use conditions::{WhereClause, WhereOperator};
 pub use ordering::OrderClause;
 
 use crate::common::bytes_for_system_value;
+use crate::common::encode::encode_unsigned_integer;
 use crate::contract::{document::Document, Contract};
 use crate::drive::block_info::BlockInfo;
+use crate::query::continuation::ContinuationToken;
+use crate::query::sql::SqlSource;
 
 use crate::drive::grove_operations::QueryType::StatefulQuery;
 use crate::drive::Drive;
@@ -67,9 +71,14 @@ use crate::fee::calculate_fee;
 use crate::fee::op::DriveOperation;
 use dpp::data_contract::extra::DocumentType;
 
+/// Aggregate module
+pub mod aggregate;
 pub mod conditions;
+/// Continuation module
+pub mod continuation;
 mod defaults;
 pub mod ordering;
+mod sql;
 mod test_index;
 
 /// Internal clauses struct
@@ -85,6 +94,9 @@ pub struct InternalClauses {
     pub range_clause: Option<WhereClause>,
     /// Equal clause
     pub equal_clauses: BTreeMap<String, WhereClause>,
+    /// Range clauses on other fields than the range clause, they filter the documents
+    /// returned by the index instead of being part of the index query
+    pub range_filter_clauses: Vec<WhereClause>,
 }
 
 impl InternalClauses {
@@ -99,7 +111,8 @@ impl InternalClauses {
             // One is set, all rest must be empty
             !(self.in_clause.is_some()
                 || self.range_clause.is_some()
-                || !self.equal_clauses.is_empty())
+                || !self.equal_clauses.is_empty()
+                || !self.range_filter_clauses.is_empty())
         } else {
             !(self.primary_key_in_clause.is_some() && self.primary_key_equal_clause.is_some())
         }
@@ -115,10 +128,88 @@ impl InternalClauses {
         self.in_clause.is_none()
             && self.range_clause.is_none()
             && self.equal_clauses.is_empty()
+            && self.range_filter_clauses.is_empty()
             && self.primary_key_in_clause.is_none()
             && self.primary_key_equal_clause.is_none()
     }
 
+    /// Returns true if the document matches the clauses on its fields. Primary key clauses
+    /// are left out, as they are matched on the id of documents by the query itself.
+    pub(crate) fn matches_document(
+        &self,
+        document_type: &DocumentType,
+        document: &Document,
+    ) -> Result<bool, Error> {
+        let where_clauses = self
+            .equal_clauses
+            .values()
+            .chain(self.in_clause.iter())
+            .chain(self.range_clause.iter())
+            .chain(self.range_filter_clauses.iter());
+
+        for where_clause in where_clauses {
+            let serialized_value = document.get_raw_for_document_type(
+                where_clause.field.as_str(),
+                document_type,
+                None,
+            )?;
+            if !where_clause.matches_serialized_value(document_type, serialized_value.as_deref())? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Makes the range clause the one on the field coming first in the order by, as only
+    /// one range can be queried with an index, the others become range filter clauses.
+    fn select_range_clause_for_order_by(&mut self, order_by: &IndexMap<String, OrderClause>) {
+        let order_by_position = |where_clause: &WhereClause| {
+            order_by
+                .get_index_of(where_clause.field.as_str())
+                .unwrap_or(usize::MAX)
+        };
+
+        if let Some(range_clause) = &mut self.range_clause {
+            let first_range_filter_clause = self
+                .range_filter_clauses
+                .iter()
+                .enumerate()
+                .map(|(index, where_clause)| (index, order_by_position(where_clause)))
+                .min_by_key(|(_, position)| *position);
+
+            if let Some((index, position)) = first_range_filter_clause {
+                if position < order_by_position(range_clause) {
+                    std::mem::swap(range_clause, &mut self.range_filter_clauses[index]);
+                }
+            }
+        }
+    }
+
+    /// Extracts the alternatives of `WhereClause`s and returns them as type `InternalClauses`
+    /// along with the `InternalClauses` of each alternative if there are more than one.
+    fn extract_from_clause_alternatives(
+        where_clause_alternatives: Vec<Vec<WhereClause>>,
+        order_by: &IndexMap<String, OrderClause>,
+    ) -> Result<(Self, Vec<Self>), Error> {
+        let mut internal_clauses_alternatives = where_clause_alternatives
+            .into_iter()
+            .map(|where_clauses| {
+                let mut internal_clauses = Self::extract_from_clauses(where_clauses)?;
+                internal_clauses.select_range_clause_for_order_by(order_by);
+                Ok(internal_clauses)
+            })
+            .collect::<Result<Vec<Self>, Error>>()?;
+
+        if internal_clauses_alternatives.len() > 1 {
+            Ok((InternalClauses::default(), internal_clauses_alternatives))
+        } else {
+            Ok((
+                internal_clauses_alternatives.pop().unwrap_or_default(),
+                vec![],
+            ))
+        }
+    }
+
     /// Extracts the `WhereClause`s and returns them as type `InternalClauses`.
     fn extract_from_clauses(all_where_clauses: Vec<WhereClause>) -> Result<Self, Error> {
         let primary_key_equal_clauses_array = all_where_clauses
@@ -143,7 +234,7 @@ impl InternalClauses {
             })
             .collect::<Vec<WhereClause>>();
 
-        let (equal_clauses, range_clause, in_clause) =
+        let (equal_clauses, range_clause, in_clause, range_filter_clauses) =
             WhereClause::group_clauses(&all_where_clauses)?;
 
         let primary_key_equal_clause = match primary_key_equal_clauses_array.len() {
@@ -182,6 +273,7 @@ impl InternalClauses {
             in_clause,
             range_clause,
             equal_clauses,
+            range_filter_clauses,
         };
 
         match internal_clauses.verify() {
@@ -202,6 +294,9 @@ pub struct DriveQuery<'a> {
     pub document_type: &'a DocumentType,
     /// Internal clauses
     pub internal_clauses: InternalClauses,
+    /// Alternatives of internal clauses, documents matching any of them are returned.
+    /// If there are alternatives the internal clauses are empty.
+    pub or_clauses: Vec<InternalClauses>,
     /// Offset
     pub offset: u16,
     /// Limit
@@ -212,6 +307,8 @@ pub struct DriveQuery<'a> {
     pub start_at: Option<Vec<u8>>,
     /// Start at included
     pub start_at_included: bool,
+    /// Continuation token of a previous page, the query continues after its last document
+    pub continuation_token: Option<ContinuationToken>,
     /// Block time
     pub block_time: Option<f64>,
 }
@@ -221,6 +318,7 @@ impl<'a> DriveQuery<'a> {
     pub fn is_for_primary_key(&self) -> bool {
         self.internal_clauses.is_for_primary_key()
             || (self.internal_clauses.is_empty()
+                && self.or_clauses.is_empty()
                 && (self.order_by.is_empty()
                     || (self.order_by.len() == 1
                         && self
@@ -233,15 +331,81 @@ impl<'a> DriveQuery<'a> {
                             == "$id")))
     }
 
+    /// Returns true if the query returns the documents as they were at its block time.
+    /// The block time is ignored for document types not keeping history.
+    pub fn is_at_block_time(&self) -> bool {
+        self.block_time.is_some() && self.document_type.documents_keep_history
+    }
+
+    /// Returns true if the query is at a block time and can match several documents.
+    /// The revision of each document at the block time is then resolved on its own, as
+    /// a single path query can not limit the revisions returned for every document, and
+    /// the query is evaluated on these revisions.
+    pub fn is_at_block_time_on_several_documents(&self) -> bool {
+        self.is_at_block_time() && self.internal_clauses.primary_key_equal_clause.is_none()
+    }
+
+    /// Returns the query of the revisions of a document keeping history stored at or before
+    /// the block time, latest first. Revisions are stored under the encoded time in
+    /// milliseconds at which they were stored, next to the reference to the latest revision
+    /// at key 0 which is left out.
+    pub(crate) fn history_query_at_block_time(block_time: f64) -> Result<Query, Error> {
+        let mut query = Query::new_with_direction(false);
+        query.insert_range_inclusive(
+            encode_unsigned_integer(0)?..=encode_unsigned_integer(block_time as u64)?,
+        );
+        Ok(query)
+    }
+
     /// Converts a query CBOR to a `DriveQuery`.
     pub fn from_cbor(
         query_cbor: &[u8],
         contract: &'a Contract,
         document_type: &'a DocumentType,
     ) -> Result<Self, Error> {
-        let mut query_document: BTreeMap<String, Value> = ciborium::de::from_reader(query_cbor)
+        let query_document: BTreeMap<String, Value> = ciborium::de::from_reader(query_cbor)
             .map_err(|_| Error::Structure(StructureError::InvalidCBOR("unable to decode query")))?;
+        Self::from_btree_map_value(query_document, contract, document_type)
+    }
+
+    /// Converts a query serialized in the given encoding to a `DriveQuery`.
+    pub fn from_encoded(
+        serialized_query: &[u8],
+        contract: &'a Contract,
+        document_type: &'a DocumentType,
+        encoding: DriveEncoding,
+    ) -> Result<Self, Error> {
+        match encoding {
+            DriveEncoding::DriveCbor => Self::from_cbor(serialized_query, contract, document_type),
+            DriveEncoding::DriveProtobuf => {
+                let query_map = match value_from_drive_protobuf(serialized_query)? {
+                    Value::Map(query_map) => query_map,
+                    _ => {
+                        return Err(Error::Query(QueryError::InvalidFormatWhereClause(
+                            "query must be a map",
+                        )))
+                    }
+                };
+                let query_document = query_map
+                    .into_iter()
+                    .map(|(key, value)| match key {
+                        Value::Text(key) => Ok((key, value)),
+                        _ => Err(Error::Query(QueryError::InvalidFormatWhereClause(
+                            "query keys must be strings",
+                        ))),
+                    })
+                    .collect::<Result<BTreeMap<String, Value>, Error>>()?;
+                Self::from_btree_map_value(query_document, contract, document_type)
+            }
+        }
+    }
 
+    /// Converts a query map to a `DriveQuery`.
+    pub fn from_btree_map_value(
+        mut query_document: BTreeMap<String, Value>,
+        contract: &'a Contract,
+        document_type: &'a DocumentType,
+    ) -> Result<Self, Error> {
         let limit: u16 = query_document
             .remove("limit")
             .map_or(Some(defaults::DEFAULT_QUERY_LIMIT), |id_cbor| {
@@ -270,31 +434,17 @@ impl<'a> DriveQuery<'a> {
             }
         });
 
-        let all_where_clauses: Vec<WhereClause> =
-            query_document
-                .remove("where")
-                .map_or(Ok(vec![]), |id_cbor| {
-                    if let Value::Array(clauses) = id_cbor {
-                        clauses
-                            .iter()
-                            .map(|where_clause| {
-                                if let Value::Array(clauses_components) = where_clause {
-                                    WhereClause::from_components(clauses_components)
-                                } else {
-                                    Err(Error::Query(QueryError::InvalidFormatWhereClause(
-                                        "where clause must be an array",
-                                    )))
-                                }
-                            })
-                            .collect::<Result<Vec<WhereClause>, Error>>()
-                    } else {
-                        Err(Error::Query(QueryError::InvalidFormatWhereClause(
-                            "where clause must be an array",
-                        )))
-                    }
-                })?;
-
-        let internal_clauses = InternalClauses::extract_from_clauses(all_where_clauses)?;
+        let where_clause_alternatives: Vec<Vec<WhereClause>> = query_document
+            .remove("where")
+            .map_or(Ok(vec![vec![]]), |id_cbor| {
+                if let Value::Array(clauses) = id_cbor {
+                    WhereClause::build_where_clause_alternatives_from_components(&clauses)
+                } else {
+                    Err(Error::Query(QueryError::InvalidFormatWhereClause(
+                        "where clause must be an array",
+                    )))
+                }
+            })?;
 
         let start_at_option = query_document.remove("startAt");
         let start_after_option = query_document.remove("startAfter");
@@ -322,6 +472,29 @@ impl<'a> DriveQuery<'a> {
             None
         };
 
+        let continuation_token = query_document
+            .remove("continuationToken")
+            .map(|token_value| {
+                if start_at.is_some() {
+                    return Err(Error::Query(QueryError::DuplicateStartConditions(
+                        "a continuation token can not be combined with startAt or startAfter",
+                    )));
+                }
+                let token_bytes = bytes_for_system_value(&token_value)?.ok_or(Error::Query(
+                    QueryError::InvalidContinuationToken("continuation token must be bytes"),
+                ))?;
+                let token = ContinuationToken::from_bytes(token_bytes.as_slice())?;
+                if token.contract_id != contract.id.buffer
+                    || token.document_type_name != document_type.name
+                {
+                    return Err(Error::Query(QueryError::InvalidContinuationToken(
+                        "continuation token is for another contract or document type",
+                    )));
+                }
+                Ok(token)
+            })
+            .transpose()?;
+
         let order_by: IndexMap<String, OrderClause> = query_document
             .remove("orderBy")
             .map_or(vec![], |id_cbor| {
@@ -344,6 +517,11 @@ impl<'a> DriveQuery<'a> {
             .map(|order_clause| Ok((order_clause.field.clone(), order_clause.to_owned())))
             .collect::<Result<IndexMap<String, OrderClause>, Error>>()?;
 
+        let (internal_clauses, or_clauses) = InternalClauses::extract_from_clause_alternatives(
+            where_clause_alternatives,
+            &order_by,
+        )?;
+
         if !query_document.is_empty() {
             return Err(Error::Query(QueryError::Unsupported(
                 "unsupported syntax in where clause",
@@ -354,133 +532,253 @@ impl<'a> DriveQuery<'a> {
             contract,
             document_type,
             internal_clauses,
+            or_clauses,
             offset: 0,
             limit,
             order_by,
             start_at,
             start_at_included,
+            continuation_token,
             block_time,
         })
     }
 
     /// Converts a SQL expression to a `DriveQuery`.
+    ///
+    /// Besides where clauses, `ORDER BY`, `LIMIT` and `OFFSET`, a top level condition on
+    /// `"$id"` in the direction of the order starts the query at (`>=` ascending, `<=`
+    /// descending) or after (`>` ascending, `<` descending) a document, and
+    /// `FROM <document type> AS OF <block time>` queries documents at a block time.
+    /// Errors carry the position of the part of the query causing them when it is known.
     pub fn from_sql_expr(sql_string: &str, contract: &'a Contract) -> Result<Self, Error> {
-        let dialect: GenericDialect = sqlparser::dialect::GenericDialect {};
-        let statements: Vec<Statement> = Parser::parse_sql(&dialect, sql_string)
-            .map_err(|_| Error::Query(QueryError::InvalidSQL("Issue parsing sql")))?;
-
-        // Should ideally iterate over each statement
-        let first_statement = statements
-            .get(0)
-            .ok_or(Error::Query(QueryError::InvalidSQL("Issue parsing sql")))?;
+        let sql_source = SqlSource::tokenize(sql_string)?;
+        let statement = sql_source.parse_statement()?;
 
-        let query: &ast::Query = match first_statement {
+        let query: &ast::Query = match &statement {
             ast::Statement::Query(query_struct) => Some(query_struct),
             _ => None,
         }
-        .ok_or(Error::Query(QueryError::InvalidSQL("Issue parsing sql")))?;
+        .ok_or(Error::Query(QueryError::InvalidSQL(
+            "Invalid query: only select statements are supported",
+        )))?;
+
+        if query.with.is_some() {
+            return Err(sql_source.error_at(
+                "WITH",
+                Error::Query(QueryError::Unsupported(
+                    "Invalid query: common table expressions are not supported",
+                )),
+            ));
+        }
+        if query.fetch.is_some() {
+            return Err(sql_source.error_at(
+                "FETCH",
+                Error::Query(QueryError::Unsupported(
+                    "Invalid query: fetch is not supported, use limit instead",
+                )),
+            ));
+        }
 
         let limit: u16 = if let Some(limit_expr) = &query.limit {
             match limit_expr {
-                ast::Expr::Value(Number(num_string, _)) => {
-                    let cast_num_string: &String = num_string;
-                    cast_num_string.parse::<u16>().ok()
-                }
+                ast::Expr::Value(Number(num_string, _)) => num_string
+                    .parse::<u16>()
+                    .ok()
+                    .filter(|limit| *limit > 0 && *limit <= defaults::DEFAULT_QUERY_LIMIT),
                 _ => None,
             }
-            .ok_or(Error::Query(QueryError::InvalidLimit(
-                "Issue parsing sql: invalid limit value",
-            )))?
+            .ok_or_else(|| {
+                sql_source.error_at(
+                    &format!("LIMIT {}", limit_expr),
+                    Error::Query(QueryError::InvalidLimit(
+                        "limit should be a integer from 1 to 100",
+                    )),
+                )
+            })?
         } else {
             defaults::DEFAULT_QUERY_LIMIT
         };
 
+        let offset: u16 = if let Some(offset) = &query.offset {
+            match &offset.value {
+                ast::Expr::Value(Number(num_string, _)) => num_string.parse::<u16>().ok(),
+                _ => None,
+            }
+            .ok_or_else(|| {
+                sql_source.error_at(
+                    &format!("OFFSET {}", offset.value),
+                    Error::Query(QueryError::InvalidSQL(
+                        "Invalid query: offset should be a integer from 0 to 65535",
+                    )),
+                )
+            })?
+        } else {
+            0
+        };
+
         let order_by: IndexMap<String, OrderClause> = query
             .order_by
             .iter()
             .map(|order_exp: &OrderByExpr| {
-                let ascending = order_exp.asc.is_none() || order_exp.asc.unwrap();
-                let field = order_exp.expr.to_string();
-                (field.clone(), OrderClause { field, ascending })
+                let order_error = |error: QueryError| {
+                    sql_source.error_at(&order_exp.to_string(), Error::Query(error))
+                };
+                if order_exp.nulls_first.is_some() {
+                    return Err(order_error(QueryError::Unsupported(
+                        "Invalid query: nulls first and nulls last are not supported",
+                    )));
+                }
+                let field = match &order_exp.expr {
+                    ast::Expr::Identifier(ident) => Ok(ident.value.clone()),
+                    _ => Err(order_error(QueryError::InvalidOrderByProperties(
+                        "Invalid query: order by should only contain field names",
+                    ))),
+                }?;
+                let ascending = order_exp.asc.unwrap_or(true);
+                Ok((field.clone(), OrderClause { field, ascending }))
             })
-            .collect::<IndexMap<String, OrderClause>>();
+            .collect::<Result<IndexMap<String, OrderClause>, Error>>()?;
 
         // Grab the select section of the query
         let select: &Select = match &query.body {
             ast::SetExpr::Select(select) => Some(select),
             _ => None,
         }
-        .ok_or(Error::Query(QueryError::InvalidSQL("Issue parsing sql")))?;
+        .ok_or(Error::Query(QueryError::Unsupported(
+            "Invalid query: set operations and values are not supported",
+        )))?;
+
+        if select.distinct {
+            return Err(sql_source.error_at(
+                "DISTINCT",
+                Error::Query(QueryError::Unsupported(
+                    "Invalid query: distinct is not supported",
+                )),
+            ));
+        }
+        if !select.group_by.is_empty() || select.having.is_some() {
+            return Err(sql_source.error_at(
+                "GROUP BY",
+                Error::Query(QueryError::Unsupported(
+                    "Invalid query: group by and having are not supported",
+                )),
+            ));
+        }
 
         // Get the document type from the 'from' section
-        let document_type_name = match &select
-            .from
-            .get(0)
-            .ok_or(Error::Query(QueryError::InvalidSQL(
+        let table = match select.from.as_slice() {
+            [table] if table.joins.is_empty() => Ok(table),
+            [] => Err(Error::Query(QueryError::InvalidSQL(
                 "Invalid query: missing from section",
-            )))?
-            .relation
-        {
-            Table {
-                name,
-                alias: _,
-                args: _,
-                with_hints: _,
-            } => name.0.get(0).as_ref().map(|identifier| &identifier.value),
+            ))),
+            _ => Err(sql_source.error_at(
+                "JOIN",
+                Error::Query(QueryError::Unsupported(
+                    "Invalid query: only one document type can be queried",
+                )),
+            )),
+        }?;
+        let document_type_name = match &table.relation {
+            Table { name, .. } => name.0.get(0).map(|identifier| &identifier.value),
             _ => None,
         }
-        .ok_or(Error::Query(QueryError::InvalidSQL(
-            "Issue parsing sql: invalid from value",
-        )))?;
+        .ok_or_else(|| {
+            sql_source.error_at(
+                &table.relation.to_string(),
+                Error::Query(QueryError::InvalidSQL(
+                    "Issue parsing sql: invalid from value",
+                )),
+            )
+        })?;
 
-        let document_type =
-            contract
-                .document_types()
-                .get(document_type_name)
-                .ok_or(Error::Query(QueryError::DocumentTypeNotFound(
-                    "document type not found in contract",
-                )))?;
+        let document_type = contract
+            .document_types()
+            .get(document_type_name)
+            .ok_or_else(|| {
+                sql_source.error_at(
+                    &table.relation.to_string(),
+                    Error::Query(QueryError::DocumentTypeNotFound(
+                        "document type not found in contract",
+                    )),
+                )
+            })?;
 
         // Restrictions
         // only binary where clauses are supported
         // i.e. [<fieldname>, <operator>, <value>]
-        // [and] is used to separate where clauses
+        // [and] and [or] are used to separate where clauses
         // currently where clauses are either binary operations or list descriptions (in clauses)
-        // hence once [and] is encountered [left] and [right] must be only one of the above
-        // i.e other where clauses
-        // e.g. firstname = wisdom and lastname = ogwu
-        // if op is not [and] then [left] or [right] must not be a binary operation or list description
-        let mut all_where_clauses: Vec<WhereClause> = Vec::new();
-        let selection_tree = select.selection.as_ref();
-
-        // Where clauses are optional
-        if let Some(selection_tree) = selection_tree {
-            WhereClause::build_where_clauses_from_operations(
-                selection_tree,
-                &mut all_where_clauses,
-            )?;
+        // hence once [and] or [or] is encountered [left] and [right] must be only one of the above
+        // i.e other where clauses, possibly nested in parentheses
+        // e.g. firstname = wisdom and (lastname = ogwu or age > 20)
+        // if op is not [and] or [or] then [left] or [right] must not be a binary operation or list description
+        // the selection is expanded into alternatives that are each queried on their own index
+        // top level conditions on $id are the start of the query and not where clauses
+        let ascending = order_by
+            .values()
+            .next()
+            .map_or(true, |order_clause| order_clause.ascending);
+        let mut start: Option<(Value, bool)> = None;
+        let mut conditions: Vec<ast::Expr> = vec![];
+        if let Some(selection) = &select.selection {
+            for condition in sql::top_level_conditions(selection) {
+                match sql::start_condition(condition, ascending)
+                    .map_err(|e| sql_source.error_at(&condition.to_string(), e))?
+                {
+                    Some(_) if start.is_some() => {
+                        return Err(sql_source.error_at(
+                            &condition.to_string(),
+                            Error::Query(QueryError::DuplicateStartConditions(
+                                "only one condition on $id can start the query",
+                            )),
+                        ))
+                    }
+                    Some(start_condition) => start = Some(start_condition),
+                    None => conditions.push(condition.clone()),
+                }
+            }
         }
+        let selection = conditions
+            .into_iter()
+            .reduce(|left, right| ast::Expr::BinaryOp {
+                left: Box::new(left),
+                op: ast::BinaryOperator::And,
+                right: Box::new(right),
+            });
+
+        let where_clause_alternatives = match &selection {
+            // Where clauses are optional
+            None => vec![vec![]],
+            Some(selection_tree) => WhereClause::build_where_clause_alternatives_from_operations(
+                selection_tree,
+                false,
+                &sql_source,
+            )?,
+        };
 
-        let internal_clauses = InternalClauses::extract_from_clauses(all_where_clauses)?;
+        let (internal_clauses, or_clauses) =
+            InternalClauses::extract_from_clause_alternatives(where_clause_alternatives, &order_by)
+                .map_err(|e| sql_source.error_at("WHERE", e))?;
 
-        let start_at_option = None;
-        let start_at_included = true;
-        let start_at: Option<Vec<u8>> = if start_at_option.is_some() {
-            bytes_for_system_value(start_at_option.unwrap())?
-        } else {
-            None
+        let (start_at, start_at_included) = match start {
+            Some((start_value, start_at_included)) => {
+                (bytes_for_system_value(&start_value)?, start_at_included)
+            }
+            None => (None, true),
         };
 
         Ok(DriveQuery {
             contract,
             document_type,
             internal_clauses,
-            offset: 0,
+            or_clauses,
+            offset,
             limit,
             order_by,
             start_at,
             start_at_included,
-            block_time: None,
+            continuation_token: None,
+            block_time: sql_source.block_time,
         })
     }
 
@@ -491,15 +789,22 @@ impl<'a> DriveQuery<'a> {
         transaction: TransactionArg,
         drive_operations: &mut Vec<DriveOperation>,
     ) -> Result<PathQuery, Error> {
-        // First we should get the overall document_type_path
-        let document_type_path = self
-            .contract
-            .document_type_path(self.document_type.name.as_str())
-            .into_iter()
-            .map(|a| a.to_vec())
-            .collect::<Vec<Vec<u8>>>();
+        let starts_at_document =
+            self.fetch_starts_at_document_operations(drive, transaction, drive_operations)?;
+        self.construct_path_query(starts_at_document)
+    }
 
-        let starts_at_document: Option<(Document, bool)> = match &self.start_at {
+    /// Fetches the document the query starts at or after, if the query has a start.
+    fn fetch_starts_at_document_operations(
+        &self,
+        drive: &Drive,
+        transaction: TransactionArg,
+        drive_operations: &mut Vec<DriveOperation>,
+    ) -> Result<Option<(Document, bool)>, Error> {
+        if let Some(continuation_token) = &self.continuation_token {
+            return Ok(Some((continuation_token.last_document.clone(), false)));
+        }
+        match &self.start_at {
             None => Ok(None),
             Some(starts_at) => {
                 // First if we have a startAt or or startsAfter we must get the element
@@ -550,7 +855,8 @@ impl<'a> DriveQuery<'a> {
                     )))?;
 
                 if let Element::Item(item, _) = start_at_document {
-                    let document = Document::from_cbor(item.as_slice(), None, None)?;
+                    let document =
+                        Document::from_stored_bytes(item.as_slice(), self.document_type)?;
                     Ok(Some((document, self.start_at_included)))
                 } else {
                     Err(Error::Drive(DriveError::CorruptedDocumentPath(
@@ -558,7 +864,34 @@ impl<'a> DriveQuery<'a> {
                     )))
                 }
             }
-        }?;
+        }
+    }
+
+    /// Constructs a path query given the starting document, if the query has a start.
+    pub fn construct_path_query(
+        &self,
+        starts_at_document: Option<(Document, bool)>,
+    ) -> Result<PathQuery, Error> {
+        if self.is_at_block_time_on_several_documents() {
+            return Err(Error::Query(QueryError::Unsupported(
+                "queries at a block time on several documents can not be made as a single path query",
+            )));
+        }
+
+        if !self.or_clauses.is_empty() {
+            return Err(Error::Query(QueryError::Unsupported(
+                "queries with or clauses can not be made as a single path query, each alternative is proved on its own with execute_with_proofs",
+            )));
+        }
+
+        // First we should get the overall document_type_path
+        let document_type_path = self
+            .contract
+            .document_type_path(self.document_type.name.as_str())
+            .into_iter()
+            .map(|a| a.to_vec())
+            .collect::<Vec<Vec<u8>>>();
+
         if self.is_for_primary_key() {
             self.get_primary_key_path_query(document_type_path, starts_at_document)
         } else {
@@ -587,10 +920,8 @@ impl<'a> DriveQuery<'a> {
             if self.document_type.documents_keep_history {
                 // if the documents keep history then we should insert a subquery
                 if let Some(block_time) = self.block_time {
-                    let encoded_block_time = encode_float(block_time)?;
-                    let mut sub_query = Query::new_with_direction(false);
-                    sub_query.insert_range_to_inclusive(..=encoded_block_time);
-                    query.set_subquery(sub_query);
+                    // the limit of 1 only keeps the latest revision before the block time
+                    query.set_subquery(Self::history_query_at_block_time(block_time)?);
                 } else {
                     query.set_subquery_key(vec![0]);
                 }
@@ -652,16 +983,12 @@ impl<'a> DriveQuery<'a> {
 
                 if self.document_type.documents_keep_history {
                     // if the documents keep history then we should insert a subquery
-                    if let Some(_block_time) = self.block_time {
-                        //todo
-                        return Err(Error::Query(QueryError::Unsupported("Not yet implemented")));
+                    if self.block_time.is_some() {
                         // in order to be able to do this we would need limited subqueries
                         // as we only want the first element before the block_time
-
-                        // let encoded_block_time = encode_float(block_time)?;
-                        // let mut sub_query = Query::new_with_direction(false);
-                        // sub_query.insert_range_to_inclusive(..=encoded_block_time);
-                        // query.set_subquery(sub_query);
+                        return Err(Error::Query(QueryError::Unsupported(
+                            "queries at a block time on several documents can not be made as a single path query",
+                        )));
                     } else {
                         query.set_subquery_key(vec![0]);
                     }
@@ -691,15 +1018,12 @@ impl<'a> DriveQuery<'a> {
 
                 if self.document_type.documents_keep_history {
                     // if the documents keep history then we should insert a subquery
-                    if let Some(_block_time) = self.block_time {
-                        return Err(Error::Query(QueryError::Unsupported("Not yet implemented")));
+                    if self.block_time.is_some() {
                         // in order to be able to do this we would need limited subqueries
                         // as we only want the first element before the block_time
-
-                        // let encoded_block_time = encode_float(block_time)?;
-                        // let mut sub_query = Query::new_with_direction(false);
-                        // sub_query.insert_range_to_inclusive(..=encoded_block_time);
-                        // query.set_subquery(sub_query);
+                        return Err(Error::Query(QueryError::Unsupported(
+                            "queries at a block time on several documents can not be made as a single path query",
+                        )));
                     } else {
                         query.set_subquery_key(vec![0]);
                     }
@@ -993,6 +1317,13 @@ impl<'a> DriveQuery<'a> {
         document_type_path: Vec<Vec<u8>>,
         starts_at_document: Option<(Document, bool)>,
     ) -> Result<PathQuery, Error> {
+        if self.is_at_block_time() {
+            // index references point to the latest revision of documents
+            return Err(Error::Query(QueryError::Unsupported(
+                "queries at a block time on several documents can not be made as a single path query",
+            )));
+        }
+
         let index = self.find_best_index()?;
         let ordered_clauses: Vec<&WhereClause> = index
             .properties
@@ -1204,17 +1535,58 @@ impl<'a> DriveQuery<'a> {
     }
 
     /// Executes an internal query with proof and returns the items.
+    ///
+    /// A query with range filter clauses may need several pages of the index query to be
+    /// scanned, which a single proof doesn't cover, so it must be proved one page at a time
+    /// with `execute_page_with_proof`.
     pub(crate) fn execute_with_proof_internal(
         self,
         drive: &Drive,
         transaction: TransactionArg,
         drive_operations: &mut Vec<DriveOperation>,
     ) -> Result<Vec<u8>, Error> {
+        if !self.internal_clauses.range_filter_clauses.is_empty() {
+            return Err(Error::Query(QueryError::Unsupported(
+                "queries with range filter clauses can only be proved one page at a time",
+            )));
+        }
+
         let path_query =
             self.construct_path_query_operations(drive, transaction, drive_operations)?;
         drive.grove_get_proved_path_query(&path_query, transaction, drive_operations)
     }
 
+    /// Executes a query with proofs and returns the proofs and fee.
+    /// Queries with or clauses have a proof for each alternative, in the order of the
+    /// alternatives, which are merged when verified with `verify_proofs`.
+    /// Other queries have a single proof.
+    pub fn execute_with_proofs(
+        self,
+        drive: &Drive,
+        block_info: Option<BlockInfo>,
+        transaction: TransactionArg,
+    ) -> Result<(Vec<Vec<u8>>, u64), Error> {
+        let mut drive_operations = vec![];
+        let proofs = if self.or_clauses.is_empty() {
+            vec![self.execute_with_proof_internal(drive, transaction, &mut drive_operations)?]
+        } else {
+            self.or_clauses
+                .iter()
+                .map(|internal_clauses| {
+                    self.or_clause_alternative_query(internal_clauses)?
+                        .execute_with_proof_internal(drive, transaction, &mut drive_operations)
+                })
+                .collect::<Result<Vec<Vec<u8>>, Error>>()?
+        };
+        let cost = if let Some(block_info) = block_info {
+            let fee_result = calculate_fee(None, Some(drive_operations), &block_info.epoch)?;
+            fee_result.processing_fee
+        } else {
+            0
+        };
+        Ok((proofs, cost))
+    }
+
     /// Executes a query with proof and returns the root hash, items, and fee.
     pub fn execute_with_proof_only_get_elements(
         self,
@@ -1238,23 +1610,99 @@ impl<'a> DriveQuery<'a> {
     }
 
     /// Executes an internal query with proof and returns the root hash and values.
+    /// The pages of a query with range filter clauses are scanned as without proofs,
+    /// each page being proved.
     pub(crate) fn execute_with_proof_only_get_elements_internal(
         self,
         drive: &Drive,
         transaction: TransactionArg,
         drive_operations: &mut Vec<DriveOperation>,
     ) -> Result<([u8; 32], Vec<Vec<u8>>), Error> {
+        if !self.or_clauses.is_empty() {
+            let mut root_hash = None;
+            let alternatives_items = self
+                .or_clauses
+                .iter()
+                .map(|internal_clauses| {
+                    let (alternative_root_hash, items) = self
+                        .or_clause_alternative_query(internal_clauses)?
+                        .execute_with_proof_only_get_elements_internal(
+                            drive,
+                            transaction,
+                            drive_operations,
+                        )?;
+                    if *root_hash.get_or_insert(alternative_root_hash) != alternative_root_hash {
+                        return Err(Error::Drive(DriveError::CorruptedCodeExecution(
+                            "alternatives of or clauses must be proved against the same state",
+                        )));
+                    }
+                    Ok(items)
+                })
+                .collect::<Result<Vec<Vec<Vec<u8>>>, Error>>()?;
+
+            let root_hash = root_hash.ok_or(Error::Drive(DriveError::CorruptedCodeExecution(
+                "queries with or clauses must have at least one alternative",
+            )))?;
+
+            return Ok((
+                root_hash,
+                self.merge_or_clause_alternatives_items(alternatives_items)?,
+            ));
+        }
+
+        if !self.internal_clauses.range_filter_clauses.is_empty() {
+            let starts_at_document =
+                self.fetch_starts_at_document_operations(drive, transaction, drive_operations)?;
+            let mut root_hash = None;
+            let (values, _, incomplete_after) =
+                self.scan_with_range_filter_clauses(starts_at_document, |path_query| {
+                    let proof = drive.grove_get_proved_path_query(
+                        path_query,
+                        transaction,
+                        drive_operations,
+                    )?;
+                    let (page_root_hash, page) =
+                        Self::verify_path_query_proof(proof.as_slice(), path_query)?;
+                    root_hash = Some(page_root_hash);
+                    Ok((page, 0))
+                })?;
+
+            if incomplete_after.is_some() {
+                return Err(Self::incomplete_range_filter_scan_error());
+            }
+
+            let root_hash = root_hash.ok_or(Error::Drive(DriveError::CorruptedCodeExecution(
+                "range filter scan must query at least one page",
+            )))?;
+
+            return Ok((root_hash, values));
+        }
+
         let path_query =
             self.construct_path_query_operations(drive, transaction, drive_operations)?;
 
         let proof =
             drive.grove_get_proved_path_query(&path_query, transaction, drive_operations)?;
-        let (root_hash, mut key_value_elements) =
-            GroveDb::verify_query(proof.as_slice(), &path_query).map_err(Error::GroveDB)?;
+
+        Self::verify_path_query_proof(proof.as_slice(), &path_query)
+    }
+
+    /// Verifies a proof of a path query and returns the root hash and the proved values.
+    fn verify_path_query_proof(
+        proof: &[u8],
+        path_query: &PathQuery,
+    ) -> Result<([u8; 32], Vec<Vec<u8>>), Error> {
+        let (root_hash, key_value_elements) =
+            GroveDb::verify_query(proof, path_query).map_err(Error::GroveDB)?;
 
         let mut values = vec![];
         for proved_key_value in key_value_elements.into_iter() {
-            let element = Element::deserialize(proved_key_value.value.as_slice()).unwrap();
+            let element =
+                Element::deserialize(proved_key_value.value.as_slice()).map_err(|_| {
+                    Error::Query(QueryError::InvalidProof(
+                        "proof contains an invalid element",
+                    ))
+                })?;
             match element {
                 Element::Item(val, _) => values.push(val),
                 Element::SumItem(val, _) => values.push(val.to_be_bytes().to_vec()),
@@ -1269,6 +1717,145 @@ impl<'a> DriveQuery<'a> {
         Ok((root_hash, values))
     }
 
+    /// Checks the document given to verify a proof is the start of the query.
+    fn starts_at_document_for_verification(
+        &self,
+        starts_at_document: Option<Document>,
+    ) -> Result<Option<(Document, bool)>, Error> {
+        if let Some(continuation_token) = &self.continuation_token {
+            return Ok(Some((continuation_token.last_document.clone(), false)));
+        }
+        match (&self.start_at, starts_at_document) {
+            (None, _) => Ok(None),
+            (Some(starts_at), Some(document)) => {
+                if document.id.as_slice() != starts_at.as_slice() {
+                    return Err(Error::Query(QueryError::StartDocumentNotFound(
+                        "given start document does not match the start of the query",
+                    )));
+                }
+                Ok(Some((document, self.start_at_included)))
+            }
+            (Some(_), None) => Err(Error::Query(QueryError::StartDocumentNotFound(
+                "start document is required to verify the proof of a query with a start",
+            ))),
+        }
+    }
+
+    /// Verifies a proof of the query against the expected root hash and returns the
+    /// serialized documents. Doesn't need access to Drive, so it can be used by clients
+    /// to check query results from untrusted nodes.
+    ///
+    /// A query with a start can only be verified given the document it starts at or after.
+    /// Range filter clauses are applied on the proved documents, as the proof only covers
+    /// the index query.
+    pub fn verify_proof_keep_serialized(
+        &self,
+        proof: &[u8],
+        expected_root_hash: [u8; 32],
+        starts_at_document: Option<Document>,
+    ) -> Result<Vec<Vec<u8>>, Error> {
+        let starts_at_document = self.starts_at_document_for_verification(starts_at_document)?;
+
+        let path_query = self.construct_path_query(starts_at_document)?;
+
+        let (root_hash, values) = Self::verify_path_query_proof(proof, &path_query)?;
+
+        if root_hash != expected_root_hash {
+            return Err(Error::Query(QueryError::InvalidProof(
+                "proof root hash does not match the expected root hash",
+            )));
+        }
+
+        self.filter_serialized_documents_by_range_filter_clauses(values)
+    }
+
+    /// Verifies the proofs returned by `execute_with_proofs` against the expected root hash
+    /// and returns the serialized documents. The documents proved for each alternative of
+    /// the or clauses are merged in the order of the query, as they are without proofs.
+    pub fn verify_proofs_keep_serialized(
+        &self,
+        proofs: &[Vec<u8>],
+        expected_root_hash: [u8; 32],
+        starts_at_document: Option<Document>,
+    ) -> Result<Vec<Vec<u8>>, Error> {
+        if self.or_clauses.is_empty() {
+            let [proof] = proofs else {
+                return Err(Error::Query(QueryError::InvalidProof(
+                    "a query without or clauses must have a single proof",
+                )));
+            };
+            return self.verify_proof_keep_serialized(
+                proof,
+                expected_root_hash,
+                starts_at_document,
+            );
+        }
+
+        if proofs.len() != self.or_clauses.len() {
+            return Err(Error::Query(QueryError::InvalidProof(
+                "a query with or clauses must have a proof for each alternative",
+            )));
+        }
+
+        let alternatives_items = self
+            .or_clauses
+            .iter()
+            .zip(proofs.iter())
+            .map(|(internal_clauses, proof)| {
+                self.or_clause_alternative_query(internal_clauses)?
+                    .verify_proof_keep_serialized(
+                        proof,
+                        expected_root_hash,
+                        starts_at_document.clone(),
+                    )
+            })
+            .collect::<Result<Vec<Vec<Vec<u8>>>, Error>>()?;
+
+        self.merge_or_clause_alternatives_items(alternatives_items)
+    }
+
+    /// Verifies the proofs returned by `execute_with_proofs` against the expected root hash
+    /// and returns the documents.
+    pub fn verify_proofs(
+        &self,
+        proofs: &[Vec<u8>],
+        expected_root_hash: [u8; 32],
+    ) -> Result<Vec<Document>, Error> {
+        self.verify_proofs_keep_serialized(proofs, expected_root_hash, None)?
+            .iter()
+            .map(|serialized_document| {
+                Document::from_stored_bytes(serialized_document.as_slice(), self.document_type)
+            })
+            .collect()
+    }
+
+    /// Verifies a proof of the query against the expected root hash and returns the documents.
+    /// Doesn't need access to Drive, so it can be used by clients to check query results
+    /// from untrusted nodes.
+    pub fn verify_proof(
+        &self,
+        proof: &[u8],
+        expected_root_hash: [u8; 32],
+    ) -> Result<Vec<Document>, Error> {
+        self.verify_proof_with_start_document(proof, expected_root_hash, None)
+    }
+
+    /// Verifies a proof of the query against the expected root hash and returns the documents.
+    /// The start document must be given if the query has a start.
+    pub fn verify_proof_with_start_document(
+        &self,
+        proof: &[u8],
+        expected_root_hash: [u8; 32],
+        starts_at_document: Option<Document>,
+    ) -> Result<Vec<Document>, Error> {
+        self.verify_proof_keep_serialized(proof, expected_root_hash, starts_at_document)?
+            .iter()
+            .map(|serialized_document| {
+                Document::from_stored_bytes(serialized_document.as_slice(), self.document_type)
+            })
+            .collect()
+    }
+
     /// Executes a query with no proof and returns the items, skipped items, and fee.
     pub fn execute_no_proof(
         &self,
@@ -1288,15 +1875,135 @@ impl<'a> DriveQuery<'a> {
         Ok((items, skipped, cost))
     }
 
-    /// Executes an internal query with no proof and returns the values and skipped items.
-    pub(crate) fn execute_no_proof_internal(
+    /// Returns the token continuing the query after a page of serialized documents, if the
+    /// page is full. A page that isn't full is the last one.
+    pub fn continuation_token_for_page(
+        &self,
+        serialized_documents: &[Vec<u8>],
+    ) -> Result<Option<ContinuationToken>, Error> {
+        if serialized_documents.len() < self.limit as usize {
+            return Ok(None);
+        }
+        serialized_documents
+            .last()
+            .map(|serialized_document| {
+                let document = Document::from_stored_bytes(
+                    serialized_document.as_slice(),
+                    self.document_type,
+                )?;
+                Ok(ContinuationToken::after_document(
+                    self.contract.id.buffer,
+                    self.document_type,
+                    &document,
+                ))
+            })
+            .transpose()
+    }
+
+    /// Executes a query with no proof and returns the items, the token continuing the
+    /// query on the next page if there may be more items, and the fee.
+    ///
+    /// If the scan of a query with range filter clauses stops before the limit is reached,
+    /// the page is incomplete: it has fewer items than the limit and the token continues
+    /// the query after the last scanned document.
+    pub fn execute_page_no_proof(
         &self,
         drive: &Drive,
+        block_info: Option<BlockInfo>,
         transaction: TransactionArg,
-        drive_operations: &mut Vec<DriveOperation>,
-    ) -> Result<(Vec<Vec<u8>>, u16), Error> {
-        let path_query =
-            self.construct_path_query_operations(drive, transaction, drive_operations)?;
+    ) -> Result<(Vec<Vec<u8>>, Option<ContinuationToken>, u64), Error> {
+        if !self.is_range_filter_scan() {
+            let (items, _, cost) = self.execute_no_proof(drive, block_info, transaction)?;
+            let continuation_token = self.continuation_token_for_page(&items)?;
+            return Ok((items, continuation_token, cost));
+        }
+
+        let mut drive_operations = vec![];
+        let (items, _, incomplete_after) = self
+            .execute_with_range_filter_clauses_no_proof_internal(
+                drive,
+                transaction,
+                &mut drive_operations,
+            )?;
+        let continuation_token = match incomplete_after {
+            Some(last_scanned_document) => Some(ContinuationToken::after_document(
+                self.contract.id.buffer,
+                self.document_type,
+                &last_scanned_document,
+            )),
+            None => self.continuation_token_for_page(&items)?,
+        };
+        let cost = if let Some(block_info) = block_info {
+            let fee_result = calculate_fee(None, Some(drive_operations), &block_info.epoch)?;
+            fee_result.processing_fee
+        } else {
+            0
+        };
+        Ok((items, continuation_token, cost))
+    }
+
+    /// Executes a query with proof and returns the proof, the token continuing the query
+    /// on the next page if there may be more items, and the fee. Clients verifying the
+    /// proof can create the same token with `ContinuationToken::after_document` from the
+    /// last proved document of a full page.
+    pub fn execute_page_with_proof(
+        &self,
+        drive: &Drive,
+        block_info: Option<BlockInfo>,
+        transaction: TransactionArg,
+    ) -> Result<(Vec<u8>, Option<ContinuationToken>, u64), Error> {
+        let mut drive_operations = vec![];
+        let path_query =
+            self.construct_path_query_operations(drive, transaction, &mut drive_operations)?;
+        let proof =
+            drive.grove_get_proved_path_query(&path_query, transaction, &mut drive_operations)?;
+        // the position of the page is the one of the last proved document, before range
+        // filter clauses are applied
+        let (_, values) = Self::verify_path_query_proof(proof.as_slice(), &path_query)?;
+        let continuation_token = self.continuation_token_for_page(&values)?;
+        let cost = if let Some(block_info) = block_info {
+            let fee_result = calculate_fee(None, Some(drive_operations), &block_info.epoch)?;
+            fee_result.processing_fee
+        } else {
+            0
+        };
+        Ok((proof, continuation_token, cost))
+    }
+
+    /// Executes an internal query with no proof and returns the values and skipped items.
+    pub(crate) fn execute_no_proof_internal(
+        &self,
+        drive: &Drive,
+        transaction: TransactionArg,
+        drive_operations: &mut Vec<DriveOperation>,
+    ) -> Result<(Vec<Vec<u8>>, u16), Error> {
+        if self.is_at_block_time_on_several_documents() {
+            return self.execute_at_block_time_no_proof_internal(
+                drive,
+                transaction,
+                drive_operations,
+            );
+        }
+
+        if !self.or_clauses.is_empty() {
+            return self.execute_or_clauses_no_proof_internal(drive, transaction, drive_operations);
+        }
+
+        if !self.internal_clauses.range_filter_clauses.is_empty() {
+            let (items, skipped, incomplete_after) = self
+                .execute_with_range_filter_clauses_no_proof_internal(
+                    drive,
+                    transaction,
+                    drive_operations,
+                )?;
+            if incomplete_after.is_some() {
+                return Err(Self::incomplete_range_filter_scan_error());
+            }
+            return Ok((items, skipped));
+        }
+
+        let path_query =
+            self.construct_path_query_operations(drive, transaction, drive_operations)?;
         let query_result = drive.grove_get_path_query(&path_query, transaction, drive_operations);
         match query_result {
             Err(Error::GroveDB(GroveError::PathKeyNotFound(_)))
@@ -1310,6 +2017,629 @@ impl<'a> DriveQuery<'a> {
             }
         }
     }
+
+    /// Returns true if the document matches all range filter clauses of the query.
+    fn matches_range_filter_clauses(&self, document: &Document) -> Result<bool, Error> {
+        for where_clause in self.internal_clauses.range_filter_clauses.iter() {
+            let serialized_value = document.get_raw_for_document_type(
+                where_clause.field.as_str(),
+                self.document_type,
+                None,
+            )?;
+            if !where_clause
+                .matches_serialized_value(self.document_type, serialized_value.as_deref())?
+            {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Removes the serialized documents not matching the range filter clauses of the query.
+    fn filter_serialized_documents_by_range_filter_clauses(
+        &self,
+        serialized_documents: Vec<Vec<u8>>,
+    ) -> Result<Vec<Vec<u8>>, Error> {
+        if self.internal_clauses.range_filter_clauses.is_empty() {
+            return Ok(serialized_documents);
+        }
+
+        let mut filtered_documents = vec![];
+        for serialized_document in serialized_documents {
+            let document =
+                Document::from_stored_bytes(serialized_document.as_slice(), self.document_type)?;
+            if self.matches_range_filter_clauses(&document)? {
+                filtered_documents.push(serialized_document);
+            }
+        }
+        Ok(filtered_documents)
+    }
+
+    /// Returns true if the query is executed by scanning the pages of its index query
+    /// and applying its range filter clauses on the scanned documents.
+    fn is_range_filter_scan(&self) -> bool {
+        !self.is_at_block_time_on_several_documents()
+            && self.or_clauses.is_empty()
+            && !self.internal_clauses.range_filter_clauses.is_empty()
+    }
+
+    /// Returns the error of a query with range filter clauses for which more than
+    /// `MAX_RANGE_FILTER_PAGES` pages would have to be scanned.
+    fn incomplete_range_filter_scan_error() -> Error {
+        Error::Query(QueryError::IncompleteQuery(
+            "range filter clauses filter out too many documents of the index query, \
+            the query must be continued page by page",
+        ))
+    }
+
+    /// Scans the pages of the index query of a query with range filter clauses. The documents
+    /// of each page are filtered, and the next pages are queried after the last scanned
+    /// document until the limit is reached or there are no more documents.
+    ///
+    /// At most `MAX_RANGE_FILTER_PAGES` pages are scanned. Returns the matching documents,
+    /// the skipped documents, and the last scanned document if the scan stopped before it
+    /// was complete, so that the query can be continued after it.
+    fn scan_with_range_filter_clauses<F>(
+        &self,
+        mut starts_at_document: Option<(Document, bool)>,
+        mut query_page: F,
+    ) -> Result<(Vec<Vec<u8>>, u16, Option<Document>), Error>
+    where
+        F: FnMut(&PathQuery) -> Result<(Vec<Vec<u8>>, u16), Error>,
+    {
+        let mut items = vec![];
+        let mut skipped: u16 = 0;
+        // the offset can only be applied on the filtered documents
+        let mut offset = self.offset;
+        let mut last_document = None;
+
+        for _ in 0..defaults::MAX_RANGE_FILTER_PAGES {
+            let mut path_query = self.construct_path_query(starts_at_document.take())?;
+            path_query.query.offset = None;
+
+            let (page, page_skipped) = query_page(&path_query)?;
+            skipped = skipped.saturating_add(page_skipped);
+
+            let page_is_full = page.len() >= self.limit as usize;
+
+            for item in page {
+                let document = Document::from_stored_bytes(item.as_slice(), self.document_type)?;
+                if self.matches_range_filter_clauses(&document)? {
+                    if offset > 0 {
+                        offset -= 1;
+                        skipped = skipped.saturating_add(1);
+                    } else {
+                        items.push(item);
+                        if items.len() >= self.limit as usize {
+                            return Ok((items, skipped, None));
+                        }
+                    }
+                }
+                last_document = Some(document);
+            }
+
+            if !page_is_full {
+                return Ok((items, skipped, None));
+            }
+
+            starts_at_document = last_document.clone().map(|document| (document, false));
+        }
+
+        Ok((items, skipped, last_document))
+    }
+
+    /// Executes a query with range filter clauses by scanning the pages of its index query.
+    /// The costs of querying every page are added to the drive operations, so they are part
+    /// of the processing fee. Returns the last scanned document if the scan is incomplete.
+    fn execute_with_range_filter_clauses_no_proof_internal(
+        &self,
+        drive: &Drive,
+        transaction: TransactionArg,
+        drive_operations: &mut Vec<DriveOperation>,
+    ) -> Result<(Vec<Vec<u8>>, u16, Option<Document>), Error> {
+        let starts_at_document =
+            self.fetch_starts_at_document_operations(drive, transaction, drive_operations)?;
+
+        self.scan_with_range_filter_clauses(starts_at_document, |path_query| {
+            match drive.grove_get_path_query(path_query, transaction, drive_operations) {
+                Err(Error::GroveDB(GroveError::PathKeyNotFound(_)))
+                | Err(Error::GroveDB(GroveError::PathNotFound(_)))
+                | Err(Error::GroveDB(GroveError::PathParentLayerNotFound(_))) => Ok((vec![], 0)),
+                query_result => query_result,
+            }
+        })
+    }
+
+    /// Returns the query of one of the alternatives of the or clauses.
+    /// The offset can only be applied once the results of all alternatives are merged,
+    /// so each alternative returns enough documents to also cover the offset.
+    fn or_clause_alternative_query(
+        &self,
+        internal_clauses: &InternalClauses,
+    ) -> Result<DriveQuery<'a>, Error> {
+        let continuation_token = match &self.continuation_token {
+            None => None,
+            Some(continuation_token) => {
+                self.or_clause_alternative_continuation_token(internal_clauses, continuation_token)?
+            }
+        };
+        Ok(DriveQuery {
+            contract: self.contract,
+            document_type: self.document_type,
+            internal_clauses: internal_clauses.clone(),
+            or_clauses: vec![],
+            offset: 0,
+            limit: self.limit.saturating_add(self.offset),
+            order_by: self.order_by.clone(),
+            start_at: self.start_at.clone(),
+            start_at_included: self.start_at_included,
+            continuation_token,
+            block_time: self.block_time,
+        })
+    }
+
+    /// Returns the token continuing one of the alternatives of the or clauses after the
+    /// last document of a page, which may not match the alternative.
+    ///
+    /// The documents of an alternative all have the values of its equal clauses, so the
+    /// token continues it after a document with these values and the order by values of
+    /// the last document. If an equal clause on an order by field alone places all the
+    /// documents of the alternative before or after the last document, the alternative
+    /// is queried from its start instead, the documents before the last document are
+    /// then removed when the alternatives are merged.
+    fn or_clause_alternative_continuation_token(
+        &self,
+        internal_clauses: &InternalClauses,
+        continuation_token: &ContinuationToken,
+    ) -> Result<Option<ContinuationToken>, Error> {
+        let mut last_document = continuation_token.last_document.clone();
+        for (field, equal_clause) in internal_clauses.equal_clauses.iter() {
+            if field == "$ownerId" {
+                last_document.owner_id = bytes_for_system_value(&equal_clause.value)?
+                    .and_then(|owner_id| owner_id.try_into().ok())
+                    .ok_or(Error::Query(QueryError::InvalidWhereClauseComponents(
+                        "owner id must be 32 bytes",
+                    )))?;
+            } else {
+                last_document
+                    .properties
+                    .insert(field.clone(), equal_clause.value.clone());
+            }
+        }
+
+        for field in self.order_by.keys() {
+            if !internal_clauses.equal_clauses.contains_key(field) {
+                break;
+            }
+            let alternative_value =
+                last_document.get_raw_for_document_type(field, self.document_type, None)?;
+            let last_value = continuation_token.last_document.get_raw_for_document_type(
+                field,
+                self.document_type,
+                None,
+            )?;
+            if alternative_value != last_value {
+                return Ok(None);
+            }
+        }
+
+        Ok(Some(ContinuationToken {
+            last_document,
+            ..continuation_token.clone()
+        }))
+    }
+
+    /// Executes each alternative of the or clauses on its own index and merges the results
+    /// in the order of the query. Documents matching more than one alternative are only
+    /// returned once. Skipped documents aren't counted, as the alternatives scan documents
+    /// which may overlap.
+    fn execute_or_clauses_no_proof_internal(
+        &self,
+        drive: &Drive,
+        transaction: TransactionArg,
+        drive_operations: &mut Vec<DriveOperation>,
+    ) -> Result<(Vec<Vec<u8>>, u16), Error> {
+        let alternatives_items = self
+            .or_clauses
+            .iter()
+            .map(|internal_clauses| {
+                self.or_clause_alternative_query(internal_clauses)?
+                    .execute_no_proof_internal(drive, transaction, drive_operations)
+                    .map(|(items, _)| items)
+            })
+            .collect::<Result<Vec<Vec<Vec<u8>>>, Error>>()?;
+
+        Ok((
+            self.merge_or_clause_alternatives_items(alternatives_items)?,
+            0,
+        ))
+    }
+
+    /// Returns the values of a document for the order by fields of the query.
+    fn order_by_values(&self, document: &Document) -> Result<Vec<Option<Vec<u8>>>, Error> {
+        self.order_by
+            .keys()
+            .map(|field| document.get_raw_for_document_type(field, self.document_type, None))
+            .collect()
+    }
+
+    /// Compares documents by their values for the order by fields of the query. Documents
+    /// with the same values are ordered by id in the direction of the last field.
+    fn compare_order_by_values(
+        &self,
+        (id_a, values_a): (&[u8; 32], &[Option<Vec<u8>>]),
+        (id_b, values_b): (&[u8; 32], &[Option<Vec<u8>>]),
+    ) -> Ordering {
+        let ascending_ids = self
+            .order_by
+            .values()
+            .last()
+            .map(|order_clause| order_clause.ascending)
+            .unwrap_or(true);
+        values_a
+            .iter()
+            .zip(values_b.iter())
+            .zip(self.order_by.values())
+            .map(|((value_a, value_b), order_clause)| {
+                if order_clause.ascending {
+                    value_a.cmp(value_b)
+                } else {
+                    value_b.cmp(value_a)
+                }
+            })
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or_else(|| {
+                if ascending_ids {
+                    id_a.cmp(id_b)
+                } else {
+                    id_b.cmp(id_a)
+                }
+            })
+    }
+
+    /// Merges the serialized documents returned by each alternative of the or clauses in the
+    /// order of the query, then applies its offset and limit. Documents matching more than
+    /// one alternative are only returned once.
+    fn merge_or_clause_alternatives_items(
+        &self,
+        alternatives_items: Vec<Vec<Vec<u8>>>,
+    ) -> Result<Vec<Vec<u8>>, Error> {
+        // documents by id with their values for the order by fields
+        let mut documents: BTreeMap<[u8; 32], (Vec<Option<Vec<u8>>>, Vec<u8>)> = BTreeMap::new();
+
+        for item in alternatives_items.into_iter().flatten() {
+            let document = Document::from_stored_bytes(item.as_slice(), self.document_type)?;
+            if documents.contains_key(&document.id) {
+                continue;
+            }
+            let order_by_values = self.order_by_values(&document)?;
+            documents.insert(document.id, (order_by_values, item));
+        }
+
+        let mut documents = documents.into_iter().collect::<Vec<_>>();
+        documents.sort_by(|(id_a, (values_a, _)), (id_b, (values_b, _))| {
+            self.compare_order_by_values((id_a, values_a), (id_b, values_b))
+        });
+
+        // alternatives queried from their start also return documents before the last
+        // document of the previous page
+        if let Some(continuation_token) = &self.continuation_token {
+            let last_document = &continuation_token.last_document;
+            let last_values = self.order_by_values(last_document)?;
+            documents.retain(|(id, (values, _))| {
+                self.compare_order_by_values((id, values), (&last_document.id, &last_values))
+                    .is_gt()
+            });
+        }
+
+        Ok(documents
+            .into_iter()
+            .skip(self.offset as usize)
+            .take(self.limit as usize)
+            .map(|(_, (_, item))| item)
+            .collect())
+    }
+
+    /// Returns the path query of the ids of the documents of a document type keeping history,
+    /// as its primary key tree holds the tree of revisions of each document. One more id than
+    /// `MAX_DOCUMENTS_AT_BLOCK_TIME` is queried to find out if there are too many documents.
+    fn document_ids_at_block_time_path_query(&self) -> PathQuery {
+        let path = self
+            .contract
+            .documents_primary_key_path(self.document_type.name.as_str())
+            .into_iter()
+            .map(|a| a.to_vec())
+            .collect::<Vec<Vec<u8>>>();
+
+        let mut query = Query::new();
+        query.insert_all();
+
+        PathQuery::new(
+            path,
+            SizedQuery::new(query, Some(defaults::MAX_DOCUMENTS_AT_BLOCK_TIME + 1), None),
+        )
+    }
+
+    /// Converts the keys of the primary key tree of a document type to the ids of its documents.
+    fn document_ids_from_keys(keys: Vec<Vec<u8>>) -> Result<Vec<[u8; 32]>, Error> {
+        if keys.len() > defaults::MAX_DOCUMENTS_AT_BLOCK_TIME as usize {
+            return Err(Error::Query(QueryError::TooManyDocumentsAtBlockTime(
+                "queries at a block time on several documents can only traverse 1000 documents",
+            )));
+        }
+        keys.into_iter()
+            .map(|key| {
+                key.try_into().map_err(|_| {
+                    Error::Drive(DriveError::CorruptedDocumentPath(
+                        "document id must be 32 bytes",
+                    ))
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the path query of the revision of a document stored at or before the block time.
+    fn document_at_block_time_path_query(
+        &self,
+        document_id: [u8; 32],
+        block_time: f64,
+    ) -> Result<PathQuery, Error> {
+        let path = self
+            .contract
+            .documents_primary_key_path(self.document_type.name.as_str())
+            .into_iter()
+            .map(|a| a.to_vec())
+            .collect::<Vec<Vec<u8>>>();
+
+        let mut query = Query::new();
+        query.insert_key(document_id.to_vec());
+        query.set_subquery(Self::history_query_at_block_time(block_time)?);
+
+        Ok(PathQuery::new(path, SizedQuery::new(query, Some(1), None)))
+    }
+
+    /// Returns true if the document matches the where clauses of the query, or one of the
+    /// alternatives of its or clauses.
+    fn matches_where_clauses(&self, document: &Document) -> Result<bool, Error> {
+        if self.or_clauses.is_empty() {
+            return self
+                .internal_clauses
+                .matches_document(self.document_type, document);
+        }
+
+        for internal_clauses in self.or_clauses.iter() {
+            if internal_clauses.matches_document(self.document_type, document)? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Selects the serialized revisions at the block time matching the where clauses, orders
+    /// them by their values for the order by fields, then applies the start, offset and limit
+    /// of the query. Returns the selected revisions and the number of skipped revisions.
+    ///
+    /// The start document is positioned by its revision at the block time, whether it
+    /// matches the where clauses or not.
+    fn select_revisions_at_block_time(
+        &self,
+        revisions: Vec<Vec<u8>>,
+    ) -> Result<(Vec<Vec<u8>>, u16), Error> {
+        let mut documents = vec![];
+        for revision in revisions {
+            let document = Document::from_stored_bytes(revision.as_slice(), self.document_type)?;
+            let matches = self.matches_where_clauses(&document)?;
+            documents.push((
+                document.id,
+                self.order_by_values(&document)?,
+                matches,
+                revision,
+            ));
+        }
+
+        let start = if let Some(continuation_token) = &self.continuation_token {
+            let last_document = &continuation_token.last_document;
+            Some((
+                last_document.id,
+                self.order_by_values(last_document)?,
+                false,
+            ))
+        } else if let Some(start_at) = &self.start_at {
+            let (id, values, _, _) = documents
+                .iter()
+                .find(|(id, _, _, _)| id.as_slice() == start_at.as_slice())
+                .ok_or(Error::Query(QueryError::StartDocumentNotFound(
+                    "start document has no revision at the block time",
+                )))?;
+            Some((*id, values.clone(), self.start_at_included))
+        } else {
+            None
+        };
+
+        documents.retain(|(id, values, matches, _)| {
+            *matches
+                && start
+                    .as_ref()
+                    .map_or(true, |(start_id, start_values, start_included)| {
+                        let ordering =
+                            self.compare_order_by_values((id, values), (start_id, start_values));
+                        ordering.is_gt() || (*start_included && ordering.is_eq())
+                    })
+        });
+        documents.sort_by(|(id_a, values_a, _, _), (id_b, values_b, _, _)| {
+            self.compare_order_by_values((id_a, values_a), (id_b, values_b))
+        });
+
+        let skipped = documents.len().min(self.offset as usize) as u16;
+        let revisions = documents
+            .into_iter()
+            .skip(self.offset as usize)
+            .take(self.limit as usize)
+            .map(|(_, _, _, revision)| revision)
+            .collect();
+        Ok((revisions, skipped))
+    }
+
+    /// Executes a query at a block time on several documents. As indexes only hold the latest
+    /// revision of documents, the revision at the block time of every document of the
+    /// document type is fetched, then the where clauses and order by are evaluated on these
+    /// revisions. Documents created after the block time are left out.
+    ///
+    /// Queries on document types with more than `MAX_DOCUMENTS_AT_BLOCK_TIME` documents fail
+    /// with `TooManyDocumentsAtBlockTime`.
+    fn execute_at_block_time_no_proof_internal(
+        &self,
+        drive: &Drive,
+        transaction: TransactionArg,
+        drive_operations: &mut Vec<DriveOperation>,
+    ) -> Result<(Vec<Vec<u8>>, u16), Error> {
+        let block_time =
+            self.block_time
+                .ok_or(Error::Drive(DriveError::CorruptedCodeExecution(
+                    "query at a block time must have a block time",
+                )))?;
+
+        let (query_result, _) = drive.grove_get_raw_path_query(
+            &self.document_ids_at_block_time_path_query(),
+            transaction,
+            QueryKeyElementPairResultType,
+            drive_operations,
+        )?;
+        let document_ids = Self::document_ids_from_keys(
+            query_result
+                .to_key_elements()
+                .into_iter()
+                .map(|(key, _)| key)
+                .collect(),
+        )?;
+
+        let mut revisions = vec![];
+        for document_id in document_ids {
+            let path_query = self.document_at_block_time_path_query(document_id, block_time)?;
+            let (values, _) =
+                drive.grove_get_path_query(&path_query, transaction, drive_operations)?;
+            revisions.extend(values.into_iter().next());
+        }
+
+        self.select_revisions_at_block_time(revisions)
+    }
+
+    /// Executes a query at a block time with proofs. Returns the proof of the ids of the
+    /// documents of the document type, the proof of the revision at the block time of each
+    /// of these documents, and the fee.
+    pub fn execute_at_block_time_with_proof(
+        &self,
+        drive: &Drive,
+        block_info: Option<BlockInfo>,
+        transaction: TransactionArg,
+    ) -> Result<(Vec<u8>, BTreeMap<[u8; 32], Vec<u8>>, u64), Error> {
+        let mut drive_operations = vec![];
+        let (documents_proof, revision_proofs) = self.execute_at_block_time_with_proof_internal(
+            drive,
+            transaction,
+            &mut drive_operations,
+        )?;
+        let cost = if let Some(block_info) = block_info {
+            let fee_result = calculate_fee(None, Some(drive_operations), &block_info.epoch)?;
+            fee_result.processing_fee
+        } else {
+            0
+        };
+        Ok((documents_proof, revision_proofs, cost))
+    }
+
+    /// Executes an internal query at a block time with proofs and returns the proof of the
+    /// ids of the documents of the document type and the proofs of their revision at the
+    /// block time.
+    pub(crate) fn execute_at_block_time_with_proof_internal(
+        &self,
+        drive: &Drive,
+        transaction: TransactionArg,
+        drive_operations: &mut Vec<DriveOperation>,
+    ) -> Result<(Vec<u8>, BTreeMap<[u8; 32], Vec<u8>>), Error> {
+        let block_time = self.block_time.ok_or(Error::Query(QueryError::Unsupported(
+            "query must have a block time to be proved at a block time",
+        )))?;
+
+        let path_query = self.document_ids_at_block_time_path_query();
+        let documents_proof =
+            drive.grove_get_proved_path_query(&path_query, transaction, drive_operations)?;
+        let (_, proved_key_values) = GroveDb::verify_query(documents_proof.as_slice(), &path_query)
+            .map_err(Error::GroveDB)?;
+        let document_ids = Self::document_ids_from_keys(
+            proved_key_values
+                .into_iter()
+                .map(|proved_key_value| proved_key_value.key)
+                .collect(),
+        )?;
+
+        let mut revision_proofs = BTreeMap::new();
+        for document_id in document_ids {
+            let path_query = self.document_at_block_time_path_query(document_id, block_time)?;
+            let revision_proof =
+                drive.grove_get_proved_path_query(&path_query, transaction, drive_operations)?;
+            revision_proofs.insert(document_id, revision_proof);
+        }
+
+        Ok((documents_proof, revision_proofs))
+    }
+
+    /// Verifies the proofs of a query at a block time against the expected root hash and
+    /// returns the documents as they were at the block time. Doesn't need access to Drive.
+    ///
+    /// The where clauses and order by are evaluated on the proved revisions at the block
+    /// time, the same way as for queries without proofs.
+    pub fn verify_at_block_time_proof(
+        &self,
+        documents_proof: &[u8],
+        revision_proofs: &BTreeMap<[u8; 32], Vec<u8>>,
+        expected_root_hash: [u8; 32],
+    ) -> Result<Vec<Document>, Error> {
+        let block_time = self.block_time.ok_or(Error::Query(QueryError::Unsupported(
+            "query must have a block time to be verified at a block time",
+        )))?;
+
+        let proved_elements = Drive::grove_verify_proved_path_query(
+            documents_proof,
+            &self.document_ids_at_block_time_path_query(),
+            expected_root_hash,
+        )?;
+        let document_ids = Self::document_ids_from_keys(
+            proved_elements.into_iter().map(|(key, _)| key).collect(),
+        )?;
+
+        let mut revisions = vec![];
+        for document_id in document_ids {
+            let revision_proof =
+                revision_proofs
+                    .get(&document_id)
+                    .ok_or(Error::Query(QueryError::InvalidProof(
+                        "missing proof of a document revision at the block time",
+                    )))?;
+            let path_query = self.document_at_block_time_path_query(document_id, block_time)?;
+            let proved_elements = Drive::grove_verify_proved_path_query(
+                revision_proof,
+                &path_query,
+                expected_root_hash,
+            )?;
+
+            match proved_elements.into_iter().next() {
+                None => {}
+                Some((_, Element::Item(value, _))) => revisions.push(value),
+                Some(_) => {
+                    return Err(Error::Query(QueryError::InvalidProof(
+                        "proved document revision must be an item",
+                    )));
+                }
+            }
+        }
+
+        let (revisions, _) = self.select_revisions_at_block_time(revisions)?;
+        revisions
+            .iter()
+            .map(|revision| Document::from_stored_bytes(revision.as_slice(), self.document_type))
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -1324,7 +2654,7 @@ mod tests {
     use crate::drive::flags::StorageFlags;
     use crate::drive::Drive;
     use crate::query::DriveQuery;
-    use dpp::data_contract::extra::DocumentType;
+    use dpp::data_contract::extra::{cbor_to_drive_protobuf, DocumentType, DriveEncoding};
     //noinspection RsUnusedImport
     use serde_json::Value::Null;
 
@@ -1392,11 +2722,43 @@ mod tests {
     }
 
     #[test]
-    fn test_invalid_query_ranges_different_fields() {
+    fn test_query_protobuf_encoding_matches_cbor() {
+        let query_value = json!({
+            "where": [
+                ["firstName", "==", "Alice"],
+                ["lastName", ">", "Adams"],
+            ],
+            "limit": 10,
+            "orderBy": [
+                ["lastName", "desc"],
+            ]
+        });
+        let contract = Contract::default();
+        let document_type = DocumentType::default();
+
+        let where_cbor = common::value_to_cbor(query_value, None);
+        let where_protobuf = cbor_to_drive_protobuf(where_cbor.as_slice(), 0)
+            .expect("expected to convert the query to protobuf");
+
+        let cbor_query = DriveQuery::from_cbor(where_cbor.as_slice(), &contract, &document_type)
+            .expect("expected to decode the cbor query");
+        let protobuf_query = DriveQuery::from_encoded(
+            where_protobuf.as_slice(),
+            &contract,
+            &document_type,
+            DriveEncoding::DriveProtobuf,
+        )
+        .expect("expected to decode the protobuf query");
+
+        assert_eq!(cbor_query, protobuf_query);
+    }
+
+    #[test]
+    fn test_valid_query_ranges_different_fields() {
         let query_value = json!({
             "where": [
-                ["firstName", "<", "Gilligan"],
                 ["lastName", "<", "Michelle"],
+                ["firstName", "<", "Gilligan"],
             ],
             "limit": 100,
             "orderBy": [
@@ -1408,8 +2770,23 @@ mod tests {
         let document_type = DocumentType::default();
 
         let where_cbor = common::value_to_cbor(query_value, None);
-        DriveQuery::from_cbor(where_cbor.as_slice(), &contract, &document_type)
-            .expect_err("all ranges must be on same field");
+        let query = DriveQuery::from_cbor(where_cbor.as_slice(), &contract, &document_type)
+            .expect("ranges on different fields should be allowed");
+
+        // the range on the first order by field is queried with the index
+        assert_eq!(
+            query
+                .internal_clauses
+                .range_clause
+                .expect("expected a range clause")
+                .field,
+            "firstName"
+        );
+        assert_eq!(query.internal_clauses.range_filter_clauses.len(), 1);
+        assert_eq!(
+            query.internal_clauses.range_filter_clauses[0].field,
+            "lastName"
+        );
     }
 
     #[test]
@@ -1776,4 +3153,56 @@ mod tests {
         DriveQuery::from_cbor(where_cbor.as_slice(), &contract, &document_type)
             .expect_err("starts with can not start with an empty string");
     }
+
+    #[test]
+    fn test_valid_query_or_clauses_are_distributed() {
+        let query_value = json!({
+            "where": [
+                ["age", ">", 20],
+                ["or", [
+                    [["firstName", "==", "Adey"]],
+                    [["firstName", "==", "Briney"], ["lastName", "==", "Smith"]],
+                ]],
+            ],
+            "limit": 100,
+            "orderBy": [
+                ["age", "asc"],
+            ],
+        });
+
+        let contract = Contract::default();
+        let document_type = DocumentType::default();
+
+        let where_cbor = common::value_to_cbor(query_value, None);
+        let query = DriveQuery::from_cbor(where_cbor.as_slice(), &contract, &document_type)
+            .expect("the query should be created");
+
+        assert!(query.internal_clauses.is_empty());
+        assert_eq!(query.or_clauses.len(), 2);
+        assert!(query
+            .or_clauses
+            .iter()
+            .all(|internal_clauses| internal_clauses.range_clause.is_some()));
+        assert_eq!(query.or_clauses[0].equal_clauses.len(), 1);
+        assert_eq!(query.or_clauses[1].equal_clauses.len(), 2);
+    }
+
+    #[test]
+    fn test_invalid_query_too_many_or_clause_alternatives() {
+        let alternatives = (0..11)
+            .map(|i| json!([["firstName", "==", format!("name{}", i)]]))
+            .collect::<Vec<_>>();
+        let query_value = json!({
+            "where": [
+                ["or", alternatives],
+            ],
+        });
+
+        let contract = Contract::default();
+        let document_type = DocumentType::default();
+
+        let where_cbor = common::value_to_cbor(query_value, None);
+        DriveQuery::from_cbor(where_cbor.as_slice(), &contract, &document_type)
+            .expect_err("or clauses should not expand to too many alternatives");
+    }
 }
@@ -0,0 +1,333 @@
+// MIT LICENSE
+//
+// Copyright (c) 2021 Dash Core Group
+//
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+//
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+//
+
+//! Query SQL Source
+//!
+//! This module tokenizes the SQL front end of drive queries. It keeps the position of each
+//! token so errors can point at the part of the query causing them, and it extracts the
+//! `AS OF <block time>` clause following the document type, which sqlparser does not know.
+//!
+
+use ciborium::value::Value;
+use sqlparser::ast;
+use sqlparser::ast::Statement;
+use sqlparser::dialect::GenericDialect;
+use sqlparser::parser::Parser;
+use sqlparser::tokenizer::{Token, Tokenizer};
+
+use crate::error::query::{QueryError, SqlPosition};
+use crate::error::Error;
+use crate::query::conditions::sql_expr_to_cbor;
+
+/// A tokenized SQL query
+pub(crate) struct SqlSource {
+    /// Tokens of the query with their positions, the `AS OF` clause excluded
+    tokens: Vec<(Token, SqlPosition)>,
+    /// The block time of the `AS OF` clause
+    pub(crate) block_time: Option<f64>,
+}
+
+impl SqlSource {
+    /// Tokenizes a SQL query.
+    pub(crate) fn tokenize(sql_string: &str) -> Result<Self, Error> {
+        let dialect = GenericDialect {};
+        let tokens = Tokenizer::new(&dialect, sql_string)
+            .tokenize()
+            .map_err(|e| {
+                Error::Query(QueryError::InvalidSQLAt {
+                    error: Box::new(QueryError::InvalidSQL("Issue parsing sql: invalid token")),
+                    position: SqlPosition {
+                        line: e.line,
+                        column: e.col,
+                    },
+                })
+            })?;
+
+        let mut position = SqlPosition { line: 1, column: 1 };
+        let mut positioned_tokens = Vec::with_capacity(tokens.len());
+        for token in tokens {
+            let token_position = position;
+            for character in token.to_string().chars() {
+                if character == '\n' {
+                    position.line += 1;
+                    position.column = 1;
+                } else {
+                    position.column += 1;
+                }
+            }
+            positioned_tokens.push((token, token_position));
+        }
+
+        let mut sql_source = SqlSource {
+            tokens: positioned_tokens,
+            block_time: None,
+        };
+        sql_source.extract_block_time()?;
+        Ok(sql_source)
+    }
+
+    /// Indexes of the tokens that are not whitespace.
+    fn significant_token_indexes(&self) -> Vec<usize> {
+        self.tokens
+            .iter()
+            .enumerate()
+            .filter(|(_, (token, _))| !matches!(token, Token::Whitespace(_)))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Removes the `AS OF <block time>` clause following `FROM <document type>`.
+    fn extract_block_time(&mut self) -> Result<(), Error> {
+        let significant = self.significant_token_indexes();
+        let is_word = |index: usize, word: &str| matches!(&self.tokens[index].0, Token::Word(w) if w.quote_style.is_none() && w.value.eq_ignore_ascii_case(word));
+
+        let mut block_time_clause = None;
+        for (i, window) in significant.windows(4).enumerate() {
+            if !(is_word(window[0], "from")
+                && matches!(self.tokens[window[1]].0, Token::Word(_))
+                && is_word(window[2], "as")
+                && is_word(window[3], "of"))
+            {
+                continue;
+            }
+            let position = self.tokens[window[2]].1;
+            if block_time_clause.is_some() {
+                return Err(Error::Query(QueryError::InvalidSQLAt {
+                    error: Box::new(QueryError::InvalidSQL(
+                        "Invalid query: only one AS OF clause can be provided",
+                    )),
+                    position,
+                }));
+            }
+            let block_time = significant
+                .get(i + 4)
+                .and_then(|index| match &self.tokens[*index].0 {
+                    Token::Number(number, _) => number.parse::<f64>().ok(),
+                    _ => None,
+                })
+                .ok_or(Error::Query(QueryError::InvalidSQLAt {
+                    error: Box::new(QueryError::InvalidSQL(
+                        "Invalid query: AS OF must be followed by a block time",
+                    )),
+                    position,
+                }))?;
+            block_time_clause = Some((window[2], significant[i + 4], block_time));
+        }
+
+        if let Some((first_index, last_index, block_time)) = block_time_clause {
+            self.tokens.drain(first_index..=last_index);
+            self.block_time = Some(block_time);
+        }
+        Ok(())
+    }
+
+    /// Parses the query, which must be a single statement.
+    pub(crate) fn parse_statement(&self) -> Result<Statement, Error> {
+        let dialect = GenericDialect {};
+        let tokens = self.tokens.iter().map(|(token, _)| token.clone()).collect();
+        let mut parser = Parser::new(tokens, &dialect);
+        let statement = parser
+            .parse_statement()
+            .map_err(|_| Error::Query(QueryError::InvalidSQL("Issue parsing sql")))?;
+        while parser.consume_token(&Token::SemiColon) {}
+        if parser.peek_token() != Token::EOF {
+            return Err(Error::Query(QueryError::InvalidSQL(
+                "Invalid query: only one statement can be provided",
+            )));
+        }
+        Ok(statement)
+    }
+
+    /// Finds the position of a fragment of the query, such as the rendering of one of its
+    /// expressions. Whitespace is ignored and keywords match whatever their case.
+    pub(crate) fn position_of(&self, fragment: &str) -> Option<SqlPosition> {
+        let dialect = GenericDialect {};
+        let fragment_tokens: Vec<Token> = Tokenizer::new(&dialect, fragment)
+            .tokenize()
+            .ok()?
+            .into_iter()
+            .filter(|token| !matches!(token, Token::Whitespace(_)))
+            .collect();
+        if fragment_tokens.is_empty() {
+            return None;
+        }
+        let significant: Vec<&(Token, SqlPosition)> = self
+            .tokens
+            .iter()
+            .filter(|(token, _)| !matches!(token, Token::Whitespace(_)))
+            .collect();
+        significant
+            .windows(fragment_tokens.len())
+            .find(|window| {
+                window
+                    .iter()
+                    .zip(fragment_tokens.iter())
+                    .all(|((token, _), fragment_token)| tokens_match(token, fragment_token))
+            })
+            .map(|window| window[0].1)
+    }
+
+    /// Attaches the position of a fragment of the query to a query error.
+    pub(crate) fn error_at(&self, fragment: &str, error: Error) -> Error {
+        match error {
+            Error::Query(error) if !matches!(error, QueryError::InvalidSQLAt { .. }) => {
+                match self.position_of(fragment) {
+                    Some(position) => Error::Query(QueryError::InvalidSQLAt {
+                        error: Box::new(error),
+                        position,
+                    }),
+                    None => Error::Query(error),
+                }
+            }
+            error => error,
+        }
+    }
+}
+
+/// The conditions of a selection that must all match.
+pub(crate) fn top_level_conditions(selection: &ast::Expr) -> Vec<&ast::Expr> {
+    match selection {
+        ast::Expr::Nested(expr) => top_level_conditions(expr),
+        ast::Expr::BinaryOp {
+            left,
+            op: ast::BinaryOperator::And,
+            right,
+        } => {
+            let mut conditions = top_level_conditions(left);
+            conditions.extend(top_level_conditions(right));
+            conditions
+        }
+        condition => vec![condition],
+    }
+}
+
+/// The start of the query if the condition is a comparison of `$id` with a value, the
+/// value and whether the start is included. Only the bound matching the order direction
+/// can start a query.
+pub(crate) fn start_condition(
+    condition: &ast::Expr,
+    ascending: bool,
+) -> Result<Option<(Value, bool)>, Error> {
+    let is_id =
+        |expr: &ast::Expr| matches!(expr, ast::Expr::Identifier(ident) if ident.value == "$id");
+    let (op, value_expr) = match condition {
+        ast::Expr::BinaryOp { left, op, right } if is_id(left) => (op.clone(), &**right),
+        ast::Expr::BinaryOp { left, op, right } if is_id(right) => {
+            let flipped_op = match op {
+                ast::BinaryOperator::Gt => ast::BinaryOperator::Lt,
+                ast::BinaryOperator::GtEq => ast::BinaryOperator::LtEq,
+                ast::BinaryOperator::Lt => ast::BinaryOperator::Gt,
+                ast::BinaryOperator::LtEq => ast::BinaryOperator::GtEq,
+                op => op.clone(),
+            };
+            (flipped_op, &**left)
+        }
+        _ => return Ok(None),
+    };
+
+    let included = match (op, ascending) {
+        (ast::BinaryOperator::GtEq, true) | (ast::BinaryOperator::LtEq, false) => true,
+        (ast::BinaryOperator::Gt, true) | (ast::BinaryOperator::Lt, false) => false,
+        (
+            ast::BinaryOperator::Gt
+            | ast::BinaryOperator::GtEq
+            | ast::BinaryOperator::Lt
+            | ast::BinaryOperator::LtEq,
+            _,
+        ) => {
+            return Err(Error::Query(QueryError::InvalidWhereClauseOrder(
+                "Invalid query: a condition on $id must be a lower bound for ascending queries and an upper bound for descending ones",
+            )))
+        }
+        _ => return Ok(None),
+    };
+
+    let value = sql_expr_to_cbor(value_expr).ok_or(Error::Query(QueryError::InvalidSQL(
+        "Invalid query: $id should be compared to a value",
+    )))?;
+    Ok(Some((value, included)))
+}
+
+/// Tokens match if they are equal, unquoted words match whatever their case.
+fn tokens_match(token: &Token, other: &Token) -> bool {
+    match (token, other) {
+        (Token::Word(word), Token::Word(other_word))
+            if word.quote_style.is_none() && other_word.quote_style.is_none() =>
+        {
+            word.value.eq_ignore_ascii_case(&other_word.value)
+        }
+        _ => token == other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SqlSource;
+    use crate::error::query::{QueryError, SqlPosition};
+    use crate::error::Error;
+
+    #[test]
+    fn test_block_time_is_extracted() {
+        let sql_source =
+            SqlSource::tokenize("select * from contact as of 1650000000 where toUserId = 'a'")
+                .expect("expected to tokenize");
+        assert_eq!(sql_source.block_time, Some(1650000000.0));
+        assert!(sql_source.parse_statement().is_ok());
+
+        assert!(matches!(
+            SqlSource::tokenize("select * from contact as of now"),
+            Err(Error::Query(QueryError::InvalidSQLAt {
+                position: SqlPosition {
+                    line: 1,
+                    column: 23
+                },
+                ..
+            }))
+        ));
+    }
+
+    #[test]
+    fn test_position_of_fragment() {
+        let sql_source =
+            SqlSource::tokenize("select * from person\nwhere   age>5 AND firstName LIKE 'C%'")
+                .expect("expected to tokenize");
+        assert_eq!(
+            sql_source.position_of("age > 5"),
+            Some(SqlPosition { line: 2, column: 9 })
+        );
+        assert_eq!(
+            sql_source.position_of("firstName like 'C%'"),
+            Some(SqlPosition {
+                line: 2,
+                column: 19
+            })
+        );
+        assert_eq!(sql_source.position_of("lastName = 'C'"), None);
+    }
+}
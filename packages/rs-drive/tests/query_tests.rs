@@ -30,6 +30,7 @@
 //! Query Tests
 //!
 
+use ciborium::value::{Integer, Value};
 use grovedb::TransactionArg;
 use std::collections::HashMap;
 use std::fs::File;
@@ -52,13 +53,19 @@ use drive::drive::batch::GroveDbOpBatch;
 use drive::drive::config::DriveConfig;
 use drive::drive::contract::add_init_contracts_structure_operations;
 use drive::drive::flags::StorageFlags;
-use drive::drive::object_size_info::DocumentInfo::DocumentRefAndSerialization;
+use drive::drive::object_size_info::DocumentInfo::{
+    DocumentRefAndSerialization, DocumentRefWithoutSerialization,
+};
 use drive::drive::object_size_info::{DocumentAndContractInfo, OwnedDocumentInfo};
 use drive::drive::Drive;
 use drive::error::{query::QueryError, Error};
+use drive::query::aggregate::{Aggregate, AggregateResult};
+use drive::query::continuation::ContinuationToken;
 use drive::query::DriveQuery;
 
-use dpp::data_contract::extra::DriveContractExt;
+use dpp::data_contract::extra::{
+    cbor_to_drive_protobuf, DriveContractExt, DriveEncoding, PROTOCOL_VERSION_PREFIX_LENGTH,
+};
 use dpp::data_contract::validation::data_contract_validator::DataContractValidator;
 
 use dpp::prelude::DataContract;
@@ -713,6 +720,121 @@ fn test_non_existence_reference_proof_single_index() {
     assert_eq!(results, proof_results);
 }
 
+#[test]
+fn test_contract_update_backfills_new_indices() {
+    let (drive, original_contract) = setup_family_tests_only_first_name_index(150, true, 73509);
+
+    let db_transaction = drive.grove.start_transaction();
+
+    let contract_cbor = common::json_document_to_cbor(
+        "tests/supporting_files/contract/family/family-contract.json",
+        Some(drive::drive::defaults::PROTOCOL_VERSION),
+    );
+    let contract = <Contract as DriveContractExt>::from_cbor(&contract_cbor, None)
+        .expect("contract should be deserialized");
+
+    let backfill_counts = drive
+        .index_backfill_document_counts(&contract, &original_contract, Some(&db_transaction))
+        .expect("expected to count the documents to backfill");
+    assert_eq!(backfill_counts.get("person"), Some(&150));
+
+    drive
+        .apply_contract_cbor(
+            contract_cbor,
+            None,
+            BlockInfo::default(),
+            true,
+            None,
+            Some(&db_transaction),
+        )
+        .expect("expected to update the contract");
+
+    // the age index was added by the update but still finds the documents inserted before
+    let query_value = json!({
+        "where": [
+            ["age", ">=", 0]
+        ],
+        "limit": 100,
+        "orderBy": [
+            ["age", "asc"]
+        ]
+    });
+    let where_cbor = common::value_to_cbor(query_value, None);
+    let person_document_type = contract
+        .document_types()
+        .get("person")
+        .expect("contract should have a person document type");
+    let query = DriveQuery::from_cbor(where_cbor.as_slice(), &contract, person_document_type)
+        .expect("query should be built");
+    let (results, _, _) = query
+        .execute_no_proof(&drive, None, Some(&db_transaction))
+        .expect("query should be executed");
+    assert_eq!(results.len(), 100);
+
+    let backfill_counts = drive
+        .index_backfill_document_counts(&contract, &contract, Some(&db_transaction))
+        .expect("expected to count the documents to backfill");
+    assert!(backfill_counts.is_empty());
+}
+
+#[test]
+fn test_verify_proof_without_drive() {
+    let (drive, contract) = setup_family_tests(10, true, 73509);
+
+    let db_transaction = drive.grove.start_transaction();
+
+    let root_hash = drive
+        .grove
+        .root_hash(Some(&db_transaction))
+        .unwrap()
+        .expect("there is always a root hash");
+
+    let query_value = json!({
+        "where": [
+            ["firstName", ">", "Chris"]
+        ],
+        "limit": 100,
+        "orderBy": [
+            ["firstName", "asc"]
+        ]
+    });
+    let where_cbor = common::value_to_cbor(query_value, None);
+    let person_document_type = contract
+        .document_types()
+        .get("person")
+        .expect("contract should have a person document type");
+    let query = DriveQuery::from_cbor(where_cbor.as_slice(), &contract, person_document_type)
+        .expect("query should be built");
+    let (results, _, _) = query
+        .execute_no_proof(&drive, None, Some(&db_transaction))
+        .expect("query should be executed");
+
+    let (proof, _) = DriveQuery::from_cbor(where_cbor.as_slice(), &contract, person_document_type)
+        .expect("query should be built")
+        .execute_with_proof(&drive, None, Some(&db_transaction))
+        .expect("we should be able to a proof");
+
+    // dropping drive makes sure verification doesn't depend on it
+    drop(db_transaction);
+    drop(drive);
+
+    let proof_results = query
+        .verify_proof_keep_serialized(proof.as_slice(), root_hash, None)
+        .expect("proof should be valid");
+    assert_eq!(results, proof_results);
+
+    let documents = query
+        .verify_proof(proof.as_slice(), root_hash)
+        .expect("proof should be valid");
+    assert_eq!(documents.len(), results.len());
+
+    let wrong_root_hash_result = query.verify_proof(proof.as_slice(), [0; 32]);
+    assert!(matches!(
+        wrong_root_hash_result,
+        Err(Error::Query(QueryError::InvalidProof(_)))
+    ));
+}
+
 #[test]
 fn test_family_basic_queries() {
     let (drive, contract) = setup_family_tests(10, true, 73509);
@@ -2340,63 +2462,506 @@ fn test_family_sql_query() {
     let query2 = DriveQuery::from_sql_expr(sql_string, &contract).expect("should build query");
 
     assert_eq!(query1, query2);
+
+    // Between clause
+    let query_cbor = common::value_to_cbor(
+        json!({
+            "where": [
+                ["age", "between", [10, 20]]
+            ],
+            "limit": 100,
+            "orderBy": [
+                ["age", "asc"]
+            ]
+        }),
+        None,
+    );
+    let query1 = DriveQuery::from_cbor(query_cbor.as_slice(), &contract, person_document_type)
+        .expect("should build query");
+
+    let sql_string = "select * from person where age between 10 and 20 order by age asc limit 100";
+    let query2 = DriveQuery::from_sql_expr(sql_string, &contract).expect("should build query");
+
+    assert_eq!(query1, query2);
+
+    // Negated comparison
+    let query_cbor = common::value_to_cbor(
+        json!({
+            "where": [
+                ["age", ">=", 10]
+            ],
+            "limit": 100,
+            "orderBy": [
+                ["age", "asc"]
+            ]
+        }),
+        None,
+    );
+    let query1 = DriveQuery::from_cbor(query_cbor.as_slice(), &contract, person_document_type)
+        .expect("should build query");
+
+    let sql_string = "select * from person where not (age < 10) order by age asc limit 100";
+    let query2 = DriveQuery::from_sql_expr(sql_string, &contract).expect("should build query");
+
+    assert_eq!(query1, query2);
+
+    // Not between becomes alternatives
+    let query_cbor = common::value_to_cbor(
+        json!({
+            "where": [
+                ["or", [
+                    [["age", "<", 10]],
+                    [["age", ">", 20]],
+                ]],
+            ],
+            "limit": 100,
+            "orderBy": [
+                ["age", "asc"]
+            ]
+        }),
+        None,
+    );
+    let query1 = DriveQuery::from_cbor(query_cbor.as_slice(), &contract, person_document_type)
+        .expect("should build query");
+
+    let sql_string =
+        "select * from person where age not between 10 and 20 order by age asc limit 100";
+    let query2 = DriveQuery::from_sql_expr(sql_string, &contract).expect("should build query");
+
+    assert_eq!(query1, query2);
+
+    // Start after and block time
+    let start_id = "AYjYxDqLy2hvGQADqE6FAkBnQEpJSzNd3CRw1tpS6vZ7";
+    let query_cbor = common::value_to_cbor(
+        json!({
+            "where": [
+                ["firstName", ">", "Chris"]
+            ],
+            "startAfter": start_id,
+            "blockTime": 1650000000,
+            "limit": 100,
+            "orderBy": [
+                ["firstName", "asc"]
+            ]
+        }),
+        None,
+    );
+    let query1 = DriveQuery::from_cbor(query_cbor.as_slice(), &contract, person_document_type)
+        .expect("should build query");
+
+    let sql_string = format!(
+        "select * from person as of 1650000000 where firstName > 'Chris' and \"$id\" > '{}' order by firstName asc limit 100",
+        start_id
+    );
+    let query2 = DriveQuery::from_sql_expr(&sql_string, &contract).expect("should build query");
+
+    assert_eq!(query1, query2);
+
+    // Start at in descending order, with an offset
+    let query_cbor = common::value_to_cbor(
+        json!({
+            "where": [
+                ["firstName", "<", "Chris"]
+            ],
+            "startAt": start_id,
+            "limit": 5,
+            "orderBy": [
+                ["firstName", "desc"]
+            ]
+        }),
+        None,
+    );
+    let mut query1 = DriveQuery::from_cbor(query_cbor.as_slice(), &contract, person_document_type)
+        .expect("should build query");
+    query1.offset = 3;
+
+    let sql_string = format!(
+        "select * from person where '{}' >= \"$id\" and firstName < 'Chris' order by firstName desc limit 5 offset 3",
+        start_id
+    );
+    let query2 = DriveQuery::from_sql_expr(&sql_string, &contract).expect("should build query");
+
+    assert_eq!(query1, query2);
+
+    // Errors point at the part of the query causing them
+    let sql_error = |sql_string: &str| -> (QueryError, u64, u64) {
+        match DriveQuery::from_sql_expr(sql_string, &contract) {
+            Err(Error::Query(QueryError::InvalidSQLAt { error, position })) => {
+                (*error, position.line, position.column)
+            }
+            Err(e) => panic!("expected an error with a position, got {}", e),
+            Ok(_) => panic!("expected an error"),
+        }
+    };
+
+    assert!(matches!(
+        sql_error("select * from person where firstName like 'C_%'"),
+        (QueryError::InvalidStartsWithClause(_), 1, 28)
+    ));
+    assert!(matches!(
+        sql_error("select * from person\nwhere age > 1 and not (firstName = 'Chris')"),
+        (QueryError::Unsupported(_), 2, 24)
+    ));
+    assert!(matches!(
+        sql_error("select * from person where firstName in (select firstName from person)"),
+        (QueryError::Unsupported(_), 1, 28)
+    ));
+    assert!(matches!(
+        sql_error("select * from person where \"$id\" < 'a' order by firstName asc"),
+        (QueryError::InvalidWhereClauseOrder(_), 1, 28)
+    ));
+    assert!(matches!(
+        sql_error("select * from person limit 500"),
+        (QueryError::InvalidLimit(_), 1, 22)
+    ));
+    assert!(matches!(
+        sql_error("select * from animal"),
+        (QueryError::DocumentTypeNotFound(_), 1, 15)
+    ));
 }
 
 #[test]
-fn test_family_with_nulls_query() {
-    let (drive, contract) = setup_family_tests_with_nulls(10, true, 30004);
+fn test_family_or_queries() {
+    let (drive, contract) = setup_family_tests(10, true, 73509);
 
     let db_transaction = drive.grove.start_transaction();
 
+    let person_document_type = contract
+        .document_types()
+        .get("person")
+        .expect("contract should have a person document type");
+
+    let first_names = |results: Vec<Vec<u8>>| -> Vec<String> {
+        results
+            .iter()
+            .map(|result| {
+                let document = Document::from_cbor(result.as_slice(), None, None)
+                    .expect("we should be able to deserialize the cbor");
+                let first_name = document
+                    .properties
+                    .get("firstName")
+                    .expect("we should be able to get the first name")
+                    .as_text()
+                    .expect("the first name should be a string");
+                String::from(first_name)
+            })
+            .collect()
+    };
+
+    // Alternatives matching the same document only return it once
+
+    let query_value = json!({
+        "where": [
+            ["or", [
+                [["firstName", "==", "Adey"]],
+                [["firstName", "in", ["Adey", "Cammi"]]],
+            ]],
+        ],
+        "limit": 100,
+        "orderBy": [
+            ["firstName", "asc"]
+        ]
+    });
+    let query_cbor = common::value_to_cbor(query_value, None);
+    let query = DriveQuery::from_cbor(query_cbor.as_slice(), &contract, person_document_type)
+        .expect("query should be built");
+    let (results, _, _) = query
+        .execute_no_proof(&drive, None, Some(&db_transaction))
+        .expect("query should be executed");
+
+    assert_eq!(first_names(results), vec!["Adey", "Cammi"]);
+
+    let sql_string = "select * from person where firstName = 'Adey' or firstName in ('Adey', 'Cammi') order by firstName asc limit 100";
+    let sql_query = DriveQuery::from_sql_expr(sql_string, &contract).expect("should build query");
+
+    assert_eq!(query, sql_query);
+
+    query
+        .execute_with_proof(&drive, None, Some(&db_transaction))
+        .expect_err("queries with or clauses can not be proved as a single path query");
+
+    // Each alternative is proved on its own and merged when verified
+
     let root_hash = drive
         .grove
         .root_hash(Some(&db_transaction))
         .unwrap()
         .expect("there is always a root hash");
 
-    let expected_app_hash = vec![
-        178, 241, 139, 253, 59, 145, 77, 207, 176, 244, 121, 203, 162, 159, 100, 120, 220, 117, 91,
-        40, 218, 22, 141, 147, 122, 64, 67, 200, 224, 233, 128, 203,
-    ];
+    let (proofs, _) = sql_query
+        .execute_with_proofs(&drive, None, Some(&db_transaction))
+        .expect("query should be proved");
 
-    assert_eq!(root_hash.as_slice(), expected_app_hash);
+    assert_eq!(proofs.len(), 2);
 
-    let all_names = [
-        "".to_string(),
-        "".to_string(),
-        "".to_string(),
-        "".to_string(),
-        "".to_string(),
-        "".to_string(),
-        "Alexia".to_string(),
-        "Gerti".to_string(),
-        "Latisha".to_string(),
-        "Norry".to_string(),
-    ];
+    let query = DriveQuery::from_cbor(query_cbor.as_slice(), &contract, person_document_type)
+        .expect("query should be built");
 
-    // A query getting all elements by firstName
+    let proved_results = query
+        .verify_proofs_keep_serialized(&proofs, root_hash, None)
+        .expect("proofs should be verified");
+
+    assert_eq!(first_names(proved_results), vec!["Adey", "Cammi"]);
+
+    query
+        .verify_proofs_keep_serialized(&proofs[..1], root_hash, None)
+        .expect_err("every alternative must be proved");
+
+    let (proof_root_hash, proof_results, _) = query
+        .execute_with_proof_only_get_elements(&drive, None, Some(&db_transaction))
+        .expect("query should be executed");
+
+    assert_eq!(proof_root_hash, root_hash);
+    assert_eq!(first_names(proof_results), vec!["Adey", "Cammi"]);
+
+    // Alternatives are merged in the order of the query
 
     let query_value = json!({
         "where": [
+            ["or", [
+                [["firstName", "==", "Adey"]],
+                [["firstName", "==", "Prissie"]],
+                [["firstName", "==", "Dalia"]],
+            ]],
         ],
         "limit": 100,
         "orderBy": [
-            ["firstName", "asc"]
+            ["firstName", "desc"]
         ]
     });
-    let where_cbor = common::value_to_cbor(query_value, None);
+    let query_cbor = common::value_to_cbor(query_value, None);
+    let query = DriveQuery::from_cbor(query_cbor.as_slice(), &contract, person_document_type)
+        .expect("query should be built");
+    let (results, _, _) = query
+        .execute_no_proof(&drive, None, Some(&db_transaction))
+        .expect("query should be executed");
+
+    assert_eq!(first_names(results), vec!["Prissie", "Dalia", "Adey"]);
+
+    // The limit applies to the merged alternatives
+
+    let sql_string = "select * from person where firstName = 'Prissie' or (firstName = 'Adey' or firstName = 'Dalia') order by firstName asc limit 2";
+    let query = DriveQuery::from_sql_expr(sql_string, &contract).expect("should build query");
+    let (results, _, _) = query
+        .execute_no_proof(&drive, None, Some(&db_transaction))
+        .expect("query should be executed");
+
+    assert_eq!(first_names(results), vec!["Adey", "Dalia"]);
+}
+
+#[test]
+fn test_family_range_filter_queries() {
+    let (drive, contract) = setup_family_tests(10, true, 73509);
+
+    let db_transaction = drive.grove.start_transaction();
+
     let person_document_type = contract
         .document_types()
         .get("person")
         .expect("contract should have a person document type");
-    let query = DriveQuery::from_cbor(where_cbor.as_slice(), &contract, person_document_type)
-        .expect("query should be built");
-    let (results, _, _) = query
-        .execute_no_proof(&drive, None, Some(&db_transaction))
-        .expect("proof should be executed");
-    let names: Vec<String> = results
-        .clone()
-        .into_iter()
+
+    let names_and_ages = |results: &[Vec<u8>]| -> Vec<(String, i128)> {
+        results
+            .iter()
+            .map(|result| {
+                let document = Document::from_cbor(result.as_slice(), None, None)
+                    .expect("we should be able to deserialize the cbor");
+                let first_name = document
+                    .properties
+                    .get("firstName")
+                    .expect("we should be able to get the first name")
+                    .as_text()
+                    .expect("the first name should be a string");
+                let age = document
+                    .properties
+                    .get("age")
+                    .expect("we should be able to get the age")
+                    .as_integer()
+                    .expect("the age should be an integer");
+                (String::from(first_name), i128::from(age))
+            })
+            .collect()
+    };
+
+    // All people after Briney, without a filter on the age
+
+    let query_value = json!({
+        "where": [
+            ["firstName", ">", "Briney"],
+        ],
+        "limit": 100,
+        "orderBy": [
+            ["firstName", "asc"]
+        ]
+    });
+    let query_cbor = common::value_to_cbor(query_value, None);
+    let query = DriveQuery::from_cbor(query_cbor.as_slice(), &contract, person_document_type)
+        .expect("query should be built");
+    let (results, _, _) = query
+        .execute_no_proof(&drive, None, Some(&db_transaction))
+        .expect("query should be executed");
+
+    let expected_names_and_ages = names_and_ages(&results)
+        .into_iter()
+        .filter(|(_, age)| *age < 50)
+        .collect::<Vec<(String, i128)>>();
+
+    // The range on the age filters the documents returned by the first name index
+
+    let query_value = json!({
+        "where": [
+            ["age", "<", 50],
+            ["firstName", ">", "Briney"],
+        ],
+        "limit": 100,
+        "orderBy": [
+            ["firstName", "asc"]
+        ]
+    });
+    let query_cbor = common::value_to_cbor(query_value, None);
+    let query = DriveQuery::from_cbor(query_cbor.as_slice(), &contract, person_document_type)
+        .expect("query should be built");
+    let (results, _, processing_fee) = query
+        .execute_no_proof(&drive, Some(BlockInfo::default()), Some(&db_transaction))
+        .expect("query should be executed");
+
+    assert_eq!(names_and_ages(&results), expected_names_and_ages);
+    assert!(processing_fee > 0);
+
+    let (_, proof_results, _) = query
+        .execute_with_proof_only_get_elements(&drive, None, Some(&db_transaction))
+        .expect("we should be able to get a proof");
+
+    assert_eq!(results, proof_results);
+
+    // Following pages are queried until the limit is reached
+
+    let sql_string =
+        "select * from person where firstName > 'Briney' and age < 50 order by firstName asc limit 2";
+    let query = DriveQuery::from_sql_expr(sql_string, &contract).expect("should build query");
+    let (results, _, _) = query
+        .execute_no_proof(&drive, None, Some(&db_transaction))
+        .expect("query should be executed");
+
+    assert_eq!(
+        names_and_ages(&results),
+        expected_names_and_ages
+            .into_iter()
+            .take(2)
+            .collect::<Vec<(String, i128)>>()
+    );
+
+    // Queries with range filter clauses can't be proved in a single proof
+
+    let sql_string =
+        "select * from person where firstName > 'Briney' and age < 50 order by firstName asc limit 2";
+    let query = DriveQuery::from_sql_expr(sql_string, &contract).expect("should build query");
+    assert!(matches!(
+        query.execute_with_proof(&drive, None, Some(&db_transaction)),
+        Err(Error::Query(QueryError::Unsupported(_)))
+    ));
+
+    // The scan stops after 10 pages of the index query, the query is then incomplete
+
+    let range_filter_query_value = |continuation_token: Option<&ContinuationToken>| {
+        let mut query_value = json!({
+            "where": [
+                ["firstName", ">", "A"],
+                ["age", ">", 1000],
+            ],
+            "limit": 1,
+            "orderBy": [
+                ["firstName", "asc"]
+            ]
+        });
+        if let Some(continuation_token) = continuation_token {
+            query_value["continuationToken"] = json!(continuation_token.to_bytes());
+        }
+        common::value_to_cbor(query_value, None)
+    };
+
+    let query_cbor = range_filter_query_value(None);
+    let query = DriveQuery::from_cbor(query_cbor.as_slice(), &contract, person_document_type)
+        .expect("query should be built");
+    assert!(matches!(
+        query.execute_no_proof(&drive, None, Some(&db_transaction)),
+        Err(Error::Query(QueryError::IncompleteQuery(_)))
+    ));
+
+    let (results, continuation_token, _) = query
+        .execute_page_no_proof(&drive, None, Some(&db_transaction))
+        .expect("query should be executed");
+    assert!(results.is_empty());
+    let continuation_token =
+        continuation_token.expect("an incomplete page should have a continuation token");
+
+    // All 10 people were scanned, so the continued query is complete
+
+    let query_cbor = range_filter_query_value(Some(&continuation_token));
+    let query = DriveQuery::from_cbor(query_cbor.as_slice(), &contract, person_document_type)
+        .expect("query should be built");
+    let (results, continuation_token, _) = query
+        .execute_page_no_proof(&drive, None, Some(&db_transaction))
+        .expect("query should be executed");
+    assert!(results.is_empty());
+    assert!(continuation_token.is_none());
+}
+
+#[test]
+fn test_family_with_nulls_query() {
+    let (drive, contract) = setup_family_tests_with_nulls(10, true, 30004);
+
+    let db_transaction = drive.grove.start_transaction();
+
+    let root_hash = drive
+        .grove
+        .root_hash(Some(&db_transaction))
+        .unwrap()
+        .expect("there is always a root hash");
+
+    let expected_app_hash = vec![
+        178, 241, 139, 253, 59, 145, 77, 207, 176, 244, 121, 203, 162, 159, 100, 120, 220, 117, 91,
+        40, 218, 22, 141, 147, 122, 64, 67, 200, 224, 233, 128, 203,
+    ];
+
+    assert_eq!(root_hash.as_slice(), expected_app_hash);
+
+    let all_names = [
+        "".to_string(),
+        "".to_string(),
+        "".to_string(),
+        "".to_string(),
+        "".to_string(),
+        "".to_string(),
+        "Alexia".to_string(),
+        "Gerti".to_string(),
+        "Latisha".to_string(),
+        "Norry".to_string(),
+    ];
+
+    // A query getting all elements by firstName
+
+    let query_value = json!({
+        "where": [
+        ],
+        "limit": 100,
+        "orderBy": [
+            ["firstName", "asc"]
+        ]
+    });
+    let where_cbor = common::value_to_cbor(query_value, None);
+    let person_document_type = contract
+        .document_types()
+        .get("person")
+        .expect("contract should have a person document type");
+    let query = DriveQuery::from_cbor(where_cbor.as_slice(), &contract, person_document_type)
+        .expect("query should be built");
+    let (results, _, _) = query
+        .execute_no_proof(&drive, None, Some(&db_transaction))
+        .expect("proof should be executed");
+    let names: Vec<String> = results
+        .clone()
+        .into_iter()
         .map(|result| {
             let document = Document::from_cbor(result.as_slice(), None, None)
                 .expect("we should be able to deserialize the cbor");
@@ -2477,8 +3042,9 @@ fn test_query_with_cached_contract() {
     assert_eq!(root_hash.as_slice(), expected_app_hash);
 
     // Make sure contract is not cached
-    let contract_ref =
-        drive.get_cached_contract_with_fetch_info(*contract.id.as_bytes(), Some(&db_transaction));
+    let contract_ref = drive
+        .get_cached_contract_with_fetch_info(*contract.id.as_bytes(), Some(&db_transaction))
+        .expect("expected to read the cache");
 
     assert!(contract_ref.is_none());
 
@@ -2509,6 +3075,7 @@ fn test_query_with_cached_contract() {
     // Cache was populated and there only two ref two the cached fetched info (here and cache)
     let contract_ref = drive
         .get_cached_contract_with_fetch_info(*contract.id.as_bytes(), Some(&db_transaction))
+        .expect("expected to read the cache")
         .expect("expected a reference counter to the contract");
 
     assert_eq!(Arc::strong_count(&contract_ref), 2);
@@ -4172,6 +4739,715 @@ fn test_query_a_b_c_d_e_contract() {
         .expect("should perform query");
 }
 
+#[test]
+fn test_family_pagination_with_continuation_tokens() {
+    let (drive, contract) = setup_family_tests(50, true, 73509);
+
+    let person_document_type = contract
+        .document_types()
+        .get("person")
+        .expect("contract should have a person document type");
+
+    // ages repeat between the people, so pages end in the middle of index values
+    let all_query_value = json!({
+        "where": [
+            ["age", ">=", 0]
+        ],
+        "limit": 100,
+        "orderBy": [
+            ["age", "asc"]
+        ]
+    });
+    let all_query_cbor = common::value_to_cbor(all_query_value, None);
+    let all_query =
+        DriveQuery::from_cbor(all_query_cbor.as_slice(), &contract, person_document_type)
+            .expect("query should be built");
+    let (all_results, continuation_token, _) = all_query
+        .execute_page_no_proof(&drive, None, None)
+        .expect("query should be executed");
+    assert_eq!(all_results.len(), 50);
+    assert!(continuation_token.is_none());
+
+    let root_hash = drive
+        .grove
+        .root_hash(None)
+        .unwrap()
+        .expect("there is always a root hash");
+
+    let mut paged_results = vec![];
+    let mut continuation_token: Option<ContinuationToken> = None;
+    loop {
+        let mut query_value = json!({
+            "where": [
+                ["age", ">=", 0]
+            ],
+            "limit": 7,
+            "orderBy": [
+                ["age", "asc"]
+            ]
+        });
+        if let Some(continuation_token) = &continuation_token {
+            query_value["continuationToken"] = json!(continuation_token.to_bytes());
+        }
+        let query_cbor = common::value_to_cbor(query_value, None);
+        let query = DriveQuery::from_cbor(query_cbor.as_slice(), &contract, person_document_type)
+            .expect("query should be built");
+
+        let (results, next_continuation_token, _) = query
+            .execute_page_no_proof(&drive, None, None)
+            .expect("query should be executed");
+
+        let (proof, proved_continuation_token, _) = query
+            .execute_page_with_proof(&drive, None, None)
+            .expect("query should be proved");
+        let proved_results = query
+            .verify_proof_keep_serialized(proof.as_slice(), root_hash, None)
+            .expect("proof should be verified");
+        assert_eq!(proved_results, results);
+        assert_eq!(proved_continuation_token, next_continuation_token);
+
+        paged_results.extend(results);
+        match next_continuation_token {
+            None => break,
+            Some(next_continuation_token) => continuation_token = Some(next_continuation_token),
+        }
+    }
+
+    assert_eq!(paged_results, all_results);
+
+    let other_type_token = ContinuationToken {
+        document_type_name: "other".to_string(),
+        ..continuation_token.expect("there should have been several pages")
+    };
+    let query_value = json!({
+        "where": [
+            ["age", ">=", 0]
+        ],
+        "orderBy": [
+            ["age", "asc"]
+        ],
+        "continuationToken": other_type_token.to_bytes(),
+    });
+    let query_cbor = common::value_to_cbor(query_value, None);
+    DriveQuery::from_cbor(query_cbor.as_slice(), &contract, person_document_type)
+        .expect_err("token of another document type should be rejected");
+}
+
+#[test]
+fn test_family_aggregate_queries() {
+    let (drive, contract) = setup_family_tests(10, true, 73509);
+    assert_family_aggregate_queries(&drive, &contract);
+
+    let (drive, contract, _) =
+        setup_family_tests_with_binary_documents(DriveEncoding::DriveCbor, 10, 73509);
+    assert_family_aggregate_queries(&drive, &contract);
+}
+
+/// Checks the aggregates of the people of the family contract set up with seed 73509.
+fn assert_family_aggregate_queries(drive: &Drive, contract: &Contract) {
+    let matching_ages: Vec<u64> = Person::random_people(10, 73509)
+        .into_iter()
+        .filter(|person| person.first_name.as_str() > "Cammi")
+        .map(|person| person.age as u64)
+        .collect();
+
+    let query_value = json!({
+        "where": [
+            ["firstName", ">", "Cammi"]
+        ],
+        "orderBy": [
+            ["firstName", "asc"]
+        ]
+    });
+    let where_cbor = common::value_to_cbor(query_value, None);
+    let person_document_type = contract
+        .document_types()
+        .get("person")
+        .expect("contract should have a person document type");
+    let query = DriveQuery::from_cbor(where_cbor.as_slice(), contract, person_document_type)
+        .expect("query should be built");
+
+    let root_hash = drive
+        .grove
+        .root_hash(None)
+        .unwrap()
+        .expect("there is always a root hash");
+
+    let expected_results = [
+        (
+            Aggregate::Count,
+            AggregateResult::Count(matching_ages.len() as u64),
+        ),
+        (
+            Aggregate::Sum("age".to_string()),
+            AggregateResult::Sum(Value::Integer(Integer::from(
+                matching_ages.iter().sum::<u64>(),
+            ))),
+        ),
+        (
+            Aggregate::Min("age".to_string()),
+            AggregateResult::Min(
+                matching_ages
+                    .iter()
+                    .min()
+                    .map(|age| Value::Integer((*age).into())),
+            ),
+        ),
+        (
+            Aggregate::Max("age".to_string()),
+            AggregateResult::Max(
+                matching_ages
+                    .iter()
+                    .max()
+                    .map(|age| Value::Integer((*age).into())),
+            ),
+        ),
+    ];
+
+    for (aggregate, expected_result) in expected_results {
+        let (result, _) = query
+            .execute_aggregate_no_proof(&aggregate, drive, None, None)
+            .expect("aggregate should be executed");
+        assert_eq!(result, expected_result);
+
+        let (proof, _) = query
+            .execute_aggregate_with_proof(&aggregate, drive, None, None)
+            .expect("aggregate should be proved");
+        let proved_result = query
+            .verify_aggregate_proof(&aggregate, proof.as_slice(), root_hash, None)
+            .expect("aggregate proof should be verified");
+        assert_eq!(proved_result, expected_result);
+    }
+
+    query
+        .execute_aggregate_no_proof(&Aggregate::Sum("lastName".to_string()), drive, None, None)
+        .expect_err("only numeric fields can be aggregated");
+}
+
+#[test]
+fn test_family_queries_with_protobuf_encoding() {
+    let (cbor_drive, _) = setup_family_tests(10, true, 73509);
+
+    let drive = setup_drive(Some(DriveConfig {
+        encoding: DriveEncoding::DriveProtobuf,
+        ..DriveConfig::default_with_batches()
+    }));
+
+    let db_transaction = drive.grove.start_transaction();
+
+    let mut batch = GroveDbOpBatch::new();
+
+    add_init_contracts_structure_operations(&mut batch);
+
+    drive
+        .grove_apply_batch(batch, false, Some(&db_transaction))
+        .expect("expected to create contracts tree successfully");
+
+    let contract_cbor = common::json_document_to_cbor(
+        "tests/supporting_files/contract/family/family-contract.json",
+        Some(drive::drive::defaults::PROTOCOL_VERSION),
+    );
+    let contract = <Contract as DriveContractExt>::from_cbor(&contract_cbor, None)
+        .expect("contract should be deserialized");
+    let contract_protobuf = DriveContractExt::serialize(&contract, DriveEncoding::DriveProtobuf)
+        .expect("contract should be serialized in protobuf");
+
+    let protobuf_contract = <Contract as DriveContractExt>::deserialize(
+        &contract_protobuf,
+        None,
+        DriveEncoding::DriveProtobuf,
+    )
+    .expect("contract should be deserialized from protobuf");
+    assert_eq!(
+        DriveContractExt::to_cbor(&protobuf_contract).expect("contract should be serialized"),
+        DriveContractExt::to_cbor(&contract).expect("contract should be serialized")
+    );
+
+    drive
+        .apply_serialized_contract(
+            &contract_protobuf,
+            None,
+            BlockInfo::default(),
+            true,
+            None,
+            Some(&db_transaction),
+        )
+        .expect("contract should be applied");
+
+    let storage_flags = Some(StorageFlags::SingleEpoch(0));
+
+    for person in Person::random_people(10, 73509) {
+        let value = serde_json::to_value(&person).expect("serialized person");
+        let document_cbor =
+            common::value_to_cbor(value, Some(drive::drive::defaults::PROTOCOL_VERSION));
+        let document_protobuf =
+            cbor_to_drive_protobuf(&document_cbor, PROTOCOL_VERSION_PREFIX_LENGTH)
+                .expect("document should be converted to protobuf");
+
+        drive
+            .add_serialized_document_for_contract(
+                &document_protobuf,
+                &contract,
+                "person",
+                None,
+                true,
+                BlockInfo::genesis(),
+                true,
+                storage_flags.as_ref(),
+                Some(&db_transaction),
+            )
+            .expect("document should be inserted");
+    }
+
+    drive
+        .grove
+        .commit_transaction(db_transaction)
+        .unwrap()
+        .expect("transaction should be committed");
+
+    let query_value = json!({
+        "where": [
+            ["firstName", ">", "Cammi"]
+        ],
+        "limit": 100,
+        "orderBy": [
+            ["firstName", "asc"]
+        ]
+    });
+    let query_cbor = common::value_to_cbor(query_value, None);
+    let query_protobuf =
+        cbor_to_drive_protobuf(&query_cbor, 0).expect("query should be converted to protobuf");
+
+    let (cbor_results, _, _) = cbor_drive
+        .query_documents(&query_cbor, *contract.id.as_bytes(), "person", None, None)
+        .expect("query should be executed");
+    let (protobuf_results, _, _) = drive
+        .query_documents(
+            &query_protobuf,
+            *contract.id.as_bytes(),
+            "person",
+            None,
+            None,
+        )
+        .expect("query should be executed");
+
+    let cbor_documents: Vec<Document> = cbor_results
+        .iter()
+        .map(|result| {
+            Document::from_cbor(result.as_slice(), None, None)
+                .expect("we should be able to deserialize the cbor")
+        })
+        .collect();
+    let protobuf_documents: Vec<Document> = protobuf_results
+        .iter()
+        .map(|result| {
+            Document::from_encoded(result.as_slice(), None, None, DriveEncoding::DriveProtobuf)
+                .expect("we should be able to deserialize the protobuf")
+        })
+        .collect();
+
+    assert_eq!(protobuf_documents.len(), 7);
+    assert_eq!(protobuf_documents, cbor_documents);
+}
+
+/// Sets up the family contract with people stored with the binary serialization of the
+/// person document type, as documents inserted without their serialization are
+fn setup_family_tests_with_binary_documents(
+    encoding: DriveEncoding,
+    count: u32,
+    seed: u64,
+) -> (Drive, Contract, Vec<Document>) {
+    let drive = setup_drive(Some(DriveConfig {
+        encoding,
+        ..DriveConfig::default_with_batches()
+    }));
+
+    let db_transaction = drive.grove.start_transaction();
+
+    let mut batch = GroveDbOpBatch::new();
+
+    add_init_contracts_structure_operations(&mut batch);
+
+    drive
+        .grove_apply_batch(batch, false, Some(&db_transaction))
+        .expect("expected to create contracts tree successfully");
+
+    let contract = common::setup_contract(
+        &drive,
+        "tests/supporting_files/contract/family/family-contract.json",
+        None,
+        Some(&db_transaction),
+    );
+
+    let document_type = contract
+        .document_type_for_name("person")
+        .expect("expected to get document type");
+
+    let storage_flags = Some(StorageFlags::SingleEpoch(0));
+
+    let documents: Vec<Document> = Person::random_people(count, seed)
+        .into_iter()
+        .map(|person| {
+            let value = serde_json::to_value(&person).expect("serialized person");
+            let document_cbor =
+                common::value_to_cbor(value, Some(drive::drive::defaults::PROTOCOL_VERSION));
+            Document::from_cbor(document_cbor.as_slice(), None, None)
+                .expect("document should be properly deserialized")
+        })
+        .collect();
+
+    for document in &documents {
+        drive
+            .add_document_for_contract(
+                DocumentAndContractInfo {
+                    owned_document_info: OwnedDocumentInfo {
+                        document_info: DocumentRefWithoutSerialization((
+                            document,
+                            storage_flags.as_ref(),
+                        )),
+                        owner_id: None,
+                    },
+                    contract: &contract,
+                    document_type,
+                },
+                true,
+                BlockInfo::genesis(),
+                true,
+                Some(&db_transaction),
+            )
+            .expect("document should be inserted");
+    }
+
+    drive
+        .grove
+        .commit_transaction(db_transaction)
+        .unwrap()
+        .expect("transaction should be committed");
+
+    (drive, contract, documents)
+}
+
+#[test]
+fn test_family_queries_of_binary_documents_in_every_encoding() {
+    for encoding in [DriveEncoding::DriveCbor, DriveEncoding::DriveProtobuf] {
+        let (drive, contract, documents) =
+            setup_family_tests_with_binary_documents(encoding, 10, 73509);
+
+        let document_type = contract
+            .document_type_for_name("person")
+            .expect("expected to get document type");
+
+        // the stored documents are read back with the document type
+        let expected_documents: HashMap<[u8; 32], Document> = documents
+            .iter()
+            .map(|document| {
+                let serialized_document = document
+                    .serialize(document_type)
+                    .expect("expected to serialize");
+                let stored_document = document_type
+                    .document_from_bytes(&serialized_document)
+                    .expect("expected to deserialize");
+                (stored_document.id, stored_document)
+            })
+            .collect();
+
+        let query_value = json!({
+            "where": [
+                ["firstName", ">", "Cammi"]
+            ],
+            "limit": 100,
+            "orderBy": [
+                ["firstName", "asc"]
+            ]
+        });
+        let query_cbor = common::value_to_cbor(query_value, None);
+        let serialized_query = match encoding {
+            DriveEncoding::DriveCbor => query_cbor,
+            DriveEncoding::DriveProtobuf => cbor_to_drive_protobuf(&query_cbor, 0)
+                .expect("query should be converted to protobuf"),
+        };
+
+        let (results, _, _) = drive
+            .query_documents_from_contract(&contract, document_type, &serialized_query, None, None)
+            .expect("query should be executed");
+
+        let (_, proof_results, _) = drive
+            .query_documents_from_contract_as_grove_proof_only_get_elements(
+                &contract,
+                document_type,
+                &serialized_query,
+                None,
+                None,
+            )
+            .expect("query should be executed");
+
+        assert_eq!(results, proof_results);
+        assert_eq!(results.len(), 7);
+
+        for result in results {
+            let document = Document::from_encoded(result.as_slice(), None, None, encoding)
+                .expect("we should be able to deserialize the document");
+            assert_eq!(Some(&document), expected_documents.get(&document.id));
+        }
+    }
+}
+
+#[test]
+fn test_verify_proof_of_binary_documents() {
+    let (drive, contract, documents) =
+        setup_family_tests_with_binary_documents(DriveEncoding::DriveCbor, 10, 73509);
+
+    let root_hash = drive
+        .grove
+        .root_hash(None)
+        .unwrap()
+        .expect("there is always a root hash");
+
+    let document_type = contract
+        .document_type_for_name("person")
+        .expect("expected to get document type");
+
+    let query_value = json!({
+        "where": [
+            ["firstName", ">", "Cammi"]
+        ],
+        "limit": 100,
+        "orderBy": [
+            ["firstName", "asc"]
+        ]
+    });
+    let where_cbor = common::value_to_cbor(query_value, None);
+    let query = DriveQuery::from_cbor(where_cbor.as_slice(), &contract, document_type)
+        .expect("query should be built");
+
+    let (proof, _) = DriveQuery::from_cbor(where_cbor.as_slice(), &contract, document_type)
+        .expect("query should be built")
+        .execute_with_proof(&drive, None, None)
+        .expect("we should be able to a proof");
+
+    let proved_documents = query
+        .verify_proof(proof.as_slice(), root_hash)
+        .expect("proof should be valid");
+    assert_eq!(proved_documents.len(), 7);
+
+    for proved_document in proved_documents {
+        let document = documents
+            .iter()
+            .find(|document| document.id == proved_document.id)
+            .expect("expected a stored document");
+        let stored_document = document_type
+            .document_from_bytes(
+                &document
+                    .serialize(document_type)
+                    .expect("expected to serialize"),
+            )
+            .expect("expected to deserialize");
+        assert_eq!(proved_document, stored_document);
+    }
+}
+
+#[test]
+fn test_family_or_queries_of_binary_documents() {
+    let (drive, contract, _) =
+        setup_family_tests_with_binary_documents(DriveEncoding::DriveCbor, 10, 73509);
+
+    let document_type = contract
+        .document_type_for_name("person")
+        .expect("expected to get document type");
+
+    let query_value = json!({
+        "where": [
+            ["or", [
+                [["firstName", "==", "Adey"]],
+                [["firstName", "in", ["Adey", "Cammi"]]],
+            ]],
+        ],
+        "limit": 100,
+        "orderBy": [
+            ["firstName", "asc"]
+        ]
+    });
+    let query_cbor = common::value_to_cbor(query_value, None);
+    let query = DriveQuery::from_cbor(query_cbor.as_slice(), &contract, document_type)
+        .expect("query should be built");
+    let (results, _, _) = query
+        .execute_no_proof(&drive, None, None)
+        .expect("query should be executed");
+
+    let first_names: Vec<String> = results
+        .iter()
+        .map(|result| {
+            let document = Document::from_stored_bytes(result.as_slice(), document_type)
+                .expect("we should be able to read the stored document");
+            document
+                .properties
+                .get("firstName")
+                .and_then(|first_name| first_name.as_text())
+                .expect("the first name should be a string")
+                .to_string()
+        })
+        .collect();
+
+    assert_eq!(first_names, vec!["Adey", "Cammi"]);
+}
+
+#[test]
+fn test_family_or_queries_of_binary_documents_paged_across_alternatives() {
+    let (drive, contract, documents) =
+        setup_family_tests_with_binary_documents(DriveEncoding::DriveCbor, 50, 73509);
+
+    let document_type = contract
+        .document_type_for_name("person")
+        .expect("expected to get document type");
+
+    let mut first_names: Vec<String> = vec![];
+    for document in documents.iter() {
+        let first_name = document
+            .properties
+            .get("firstName")
+            .and_then(|first_name| first_name.as_text())
+            .expect("the first name should be a string");
+        if !first_names.iter().any(|name| name == first_name) {
+            first_names.push(first_name.to_string());
+        }
+    }
+    assert!(first_names.len() >= 6);
+
+    let or_query_value = |limit: u16| {
+        json!({
+            "where": [
+                ["or", [
+                    [["firstName", "in", [first_names[0], first_names[1], first_names[2]]]],
+                    [["firstName", "==", first_names[3]]],
+                    [["firstName", "in", [first_names[2], first_names[4], first_names[5]]]],
+                ]],
+            ],
+            "limit": limit,
+            "orderBy": [
+                ["firstName", "asc"]
+            ]
+        })
+    };
+
+    let all_query_cbor = common::value_to_cbor(or_query_value(100), None);
+    let all_query = DriveQuery::from_cbor(all_query_cbor.as_slice(), &contract, document_type)
+        .expect("query should be built");
+    let (all_results, _, _) = all_query
+        .execute_no_proof(&drive, None, None)
+        .expect("query should be executed");
+    assert!(all_results.len() >= 6);
+
+    let mut paged_results = vec![];
+    let mut continuation_token: Option<ContinuationToken> = None;
+    loop {
+        let mut query_value = or_query_value(2);
+        if let Some(continuation_token) = &continuation_token {
+            query_value["continuationToken"] = json!(continuation_token.to_bytes());
+        }
+        let query_cbor = common::value_to_cbor(query_value, None);
+        let query = DriveQuery::from_cbor(query_cbor.as_slice(), &contract, document_type)
+            .expect("query should be built");
+
+        let (results, next_continuation_token, _) = query
+            .execute_page_no_proof(&drive, None, None)
+            .expect("query should be executed");
+
+        paged_results.extend(results);
+        match next_continuation_token {
+            None => break,
+            Some(next_continuation_token) => continuation_token = Some(next_continuation_token),
+        }
+    }
+
+    assert_eq!(paged_results, all_results);
+}
+
+#[test]
+fn test_family_range_filter_queries_of_binary_documents() {
+    let (drive, contract, _) =
+        setup_family_tests_with_binary_documents(DriveEncoding::DriveCbor, 10, 73509);
+
+    let root_hash = drive
+        .grove
+        .root_hash(None)
+        .unwrap()
+        .expect("there is always a root hash");
+
+    let document_type = contract
+        .document_type_for_name("person")
+        .expect("expected to get document type");
+
+    let names_and_ages = |results: &[Vec<u8>]| -> Vec<(String, i128)> {
+        results
+            .iter()
+            .map(|result| {
+                let document = Document::from_stored_bytes(result.as_slice(), document_type)
+                    .expect("we should be able to read the stored document");
+                let first_name = document
+                    .properties
+                    .get("firstName")
+                    .and_then(|first_name| first_name.as_text())
+                    .expect("the first name should be a string");
+                let age = document
+                    .properties
+                    .get("age")
+                    .and_then(|age| age.as_integer())
+                    .expect("the age should be an integer");
+                (String::from(first_name), i128::from(age))
+            })
+            .collect()
+    };
+
+    let query_value = json!({
+        "where": [
+            ["firstName", ">", "Briney"],
+        ],
+        "limit": 100,
+        "orderBy": [
+            ["firstName", "asc"]
+        ]
+    });
+    let query_cbor = common::value_to_cbor(query_value, None);
+    let query = DriveQuery::from_cbor(query_cbor.as_slice(), &contract, document_type)
+        .expect("query should be built");
+    let (results, _, _) = query
+        .execute_no_proof(&drive, None, None)
+        .expect("query should be executed");
+
+    let expected_names_and_ages = names_and_ages(&results)
+        .into_iter()
+        .filter(|(_, age)| *age < 50)
+        .collect::<Vec<(String, i128)>>();
+
+    let query_value = json!({
+        "where": [
+            ["age", "<", 50],
+            ["firstName", ">", "Briney"],
+        ],
+        "limit": 100,
+        "orderBy": [
+            ["firstName", "asc"]
+        ]
+    });
+    let query_cbor = common::value_to_cbor(query_value, None);
+    let query = DriveQuery::from_cbor(query_cbor.as_slice(), &contract, document_type)
+        .expect("query should be built");
+    let (results, _, _) = query
+        .execute_no_proof(&drive, None, None)
+        .expect("query should be executed");
+
+    assert_eq!(names_and_ages(&results), expected_names_and_ages);
+
+    let (proof, _, _) = query
+        .execute_page_with_proof(&drive, None, None)
+        .expect("query should be proved");
+    let proof_results = query
+        .verify_proof_keep_serialized(proof.as_slice(), root_hash, None)
+        .expect("proof should be valid");
+
+    assert_eq!(names_and_ages(&proof_results), expected_names_and_ages);
+}
+
 #[test]
 #[ignore]
 fn pwd() {
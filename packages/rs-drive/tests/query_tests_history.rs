@@ -1343,7 +1343,7 @@ fn test_query_historical() {
         .get("person")
         .expect("contract should have a person document type");
 
-    drive
+    let (results, _, _) = drive
         .query_documents_from_contract(
             &contract,
             person_document_type,
@@ -1351,33 +1351,26 @@ fn test_query_historical() {
             None,
             Some(&db_transaction),
         )
-        .expect_err("not yet implemented");
+        .expect("query should be executed");
 
-    // assert_eq!(results.len(), 12);
-    //
-    // let last_person = Document::from_cbor(results.first().unwrap().as_slice(), None, None)
-    //     .expect("we should be able to deserialize the cbor");
-    //
-    // assert_eq!(
-    //     last_person.id,
-    //     vec![
-    //         249, 170, 70, 122, 181, 31, 35, 176, 175, 131, 70, 150, 250, 223, 194, 203, 175, 200,
-    //         107, 252, 199, 227, 154, 105, 89, 57, 38, 85, 236, 192, 254, 88
-    //     ]
-    //         .as_slice()
-    // );
-    //
-    // let message_value = last_person.properties.get("message").unwrap();
-    //
-    // let message = message_value
-    //     .as_text()
-    //     .expect("the message should be a string")
-    //     .to_string();
-    //
-    // assert_eq!(
-    //     message,
-    //     String::from("“Since it’s the customer that pays our salary, our responsibility is to make the product they want, when they want it, and deliver quality that satisfies them.” Retired factory worker, Kiyoshi Tsutsumi (Osono et al 2008, 136)")
-    // );
+    assert_eq!(results.len(), 12);
+
+    let historical_person = Document::from_cbor(results.first().unwrap().as_slice(), None, None)
+        .expect("we should be able to deserialize the cbor");
+
+    assert_eq!(historical_person.id, last_person.id);
+
+    // the latest revision was stored at 1000 and the one at the block time at 100
+    let age = |person: &Document| {
+        person
+            .properties
+            .get("age")
+            .and_then(|age| age.as_integer())
+            .map(i128::from)
+            .expect("the age should be an integer")
+    };
+
+    assert_eq!(age(&historical_person), age(&last_person) - 9);
 
     //
     // // fetching with ownerId in a set of values
@@ -1546,3 +1539,237 @@ fn test_query_historical() {
         ]
     );
 }
+
+#[test]
+fn test_query_historical_at_block_time() {
+    let (drive, contract) = setup(10, None, true, 73509);
+
+    let db_transaction = drive.grove.start_transaction();
+
+    let root_hash = drive
+        .grove
+        .root_hash(Some(&db_transaction))
+        .unwrap()
+        .expect("there is always a root hash");
+
+    let person_document_type = contract
+        .document_types()
+        .get("person")
+        .expect("contract should have a person document type");
+
+    let age = |person: &Document| {
+        person
+            .properties
+            .get("age")
+            .and_then(|age| age.as_integer())
+            .map(i128::from)
+            .expect("the age should be an integer")
+    };
+
+    let query_value = json!({
+        "where": [],
+        "limit": 100,
+        "orderBy": [
+            ["firstName", "asc"]
+        ]
+    });
+    let query_cbor = common::value_to_cbor(query_value, None);
+    let query = DriveQuery::from_cbor(query_cbor.as_slice(), &contract, person_document_type)
+        .expect("query should be built");
+    let (results, _, _) = query
+        .execute_no_proof(&drive, None, Some(&db_transaction))
+        .expect("query should be executed");
+    let latest_people: Vec<Document> = results
+        .iter()
+        .map(|result| {
+            Document::from_cbor(result.as_slice(), None, None)
+                .expect("we should be able to deserialize the cbor")
+        })
+        .collect();
+
+    assert_eq!(latest_people.len(), 10);
+
+    // the same query on an index at a block time returns the revisions stored at 15
+
+    let query_value = json!({
+        "where": [],
+        "limit": 100,
+        "orderBy": [
+            ["firstName", "asc"]
+        ],
+        "blockTime": 50
+    });
+    let query_cbor = common::value_to_cbor(query_value, None);
+    let query = DriveQuery::from_cbor(query_cbor.as_slice(), &contract, person_document_type)
+        .expect("query should be built");
+    let (results, _, _) = query
+        .execute_no_proof(&drive, None, Some(&db_transaction))
+        .expect("query should be executed");
+    let historical_people: Vec<Document> = results
+        .iter()
+        .map(|result| {
+            Document::from_cbor(result.as_slice(), None, None)
+                .expect("we should be able to deserialize the cbor")
+        })
+        .collect();
+
+    assert_eq!(historical_people.len(), 10);
+
+    for (historical_person, latest_person) in historical_people.iter().zip(latest_people.iter()) {
+        assert_eq!(historical_person.id, latest_person.id);
+        assert_eq!(age(historical_person), age(latest_person) - 10);
+    }
+
+    let (documents_proof, revision_proofs, _) = query
+        .execute_at_block_time_with_proof(&drive, None, Some(&db_transaction))
+        .expect("query should be proved");
+
+    assert_eq!(revision_proofs.len(), 10);
+
+    let proved_people = query
+        .verify_at_block_time_proof(documents_proof.as_slice(), &revision_proofs, root_hash)
+        .expect("proof should be verified");
+
+    assert_eq!(proved_people, historical_people);
+
+    query
+        .execute_with_proof(&drive, None, Some(&db_transaction))
+        .expect_err("queries at a block time on several documents have several proofs");
+
+    // the where clauses are checked again on the revisions at the block time
+
+    let mut latest_ages = latest_people.iter().map(age).collect::<Vec<i128>>();
+    latest_ages.sort();
+    let min_age = latest_ages[5];
+
+    let query_value = json!({
+        "where": [
+            ["age", ">=", min_age as i64]
+        ],
+        "limit": 100,
+        "orderBy": [
+            ["age", "asc"]
+        ],
+        "blockTime": 50
+    });
+    let query_cbor = common::value_to_cbor(query_value, None);
+    let query = DriveQuery::from_cbor(query_cbor.as_slice(), &contract, person_document_type)
+        .expect("query should be built");
+    let (results, _, _) = query
+        .execute_no_proof(&drive, None, Some(&db_transaction))
+        .expect("query should be executed");
+    let matching_people: Vec<Document> = results
+        .iter()
+        .map(|result| {
+            Document::from_cbor(result.as_slice(), None, None)
+                .expect("we should be able to deserialize the cbor")
+        })
+        .collect();
+
+    let mut matching_ids = matching_people
+        .iter()
+        .map(|person| person.id)
+        .collect::<Vec<[u8; 32]>>();
+    matching_ids.sort();
+    let mut expected_ids = historical_people
+        .iter()
+        .filter(|person| age(person) >= min_age)
+        .map(|person| person.id)
+        .collect::<Vec<[u8; 32]>>();
+    expected_ids.sort();
+
+    assert_eq!(matching_ids, expected_ids);
+    assert!(matching_ids.len() < latest_ages.len() - 5);
+
+    let (documents_proof, revision_proofs, _) = query
+        .execute_at_block_time_with_proof(&drive, None, Some(&db_transaction))
+        .expect("query should be proved");
+
+    let proved_people = query
+        .verify_at_block_time_proof(documents_proof.as_slice(), &revision_proofs, root_hash)
+        .expect("proof should be verified");
+
+    assert_eq!(proved_people, matching_people);
+
+    // documents whose revision at the block time matches the where clauses are returned
+    // even if their latest revision doesn't, in the order of their revision at the block time
+
+    let query_value = json!({
+        "where": [
+            ["age", "<", min_age as i64]
+        ],
+        "limit": 100,
+        "orderBy": [
+            ["age", "asc"]
+        ],
+        "blockTime": 50
+    });
+    let query_cbor = common::value_to_cbor(query_value, None);
+    let query = DriveQuery::from_cbor(query_cbor.as_slice(), &contract, person_document_type)
+        .expect("query should be built");
+    let (results, _, _) = query
+        .execute_no_proof(&drive, None, Some(&db_transaction))
+        .expect("query should be executed");
+    let younger_people: Vec<Document> = results
+        .iter()
+        .map(|result| {
+            Document::from_stored_bytes(result.as_slice(), person_document_type)
+                .expect("we should be able to read the stored document")
+        })
+        .collect();
+
+    let mut expected_people = historical_people
+        .iter()
+        .filter(|person| age(person) < min_age)
+        .cloned()
+        .collect::<Vec<Document>>();
+    expected_people.sort_by_key(|person| (age(person), person.id));
+
+    assert_eq!(younger_people, expected_people);
+    assert!(younger_people.iter().any(|person| {
+        latest_people
+            .iter()
+            .any(|latest_person| latest_person.id == person.id && age(latest_person) >= min_age)
+    }));
+
+    let (documents_proof, revision_proofs, _) = query
+        .execute_at_block_time_with_proof(&drive, None, Some(&db_transaction))
+        .expect("query should be proved");
+
+    let proved_people = query
+        .verify_at_block_time_proof(documents_proof.as_slice(), &revision_proofs, root_hash)
+        .expect("proof should be verified");
+
+    assert_eq!(proved_people, younger_people);
+
+    // every revision of a document is listed with the time at which it was stored
+
+    let person_id = latest_people.first().expect("expected a person").id;
+
+    let (revisions, _) = drive
+        .fetch_document_history(&contract, "person", person_id, None, Some(&db_transaction))
+        .expect("expected to fetch the document history");
+
+    assert_eq!(
+        revisions
+            .iter()
+            .map(|(time_ms, _)| *time_ms)
+            .collect::<Vec<u64>>(),
+        vec![0, 15, 100, 1000]
+    );
+    assert!(revisions.iter().all(|(_, person)| person.id == person_id));
+    assert_eq!(
+        age(&revisions.last().expect("expected a revision").1),
+        age(latest_people.first().expect("expected a person"))
+    );
+
+    let (proof, _) = drive
+        .prove_document_history(&contract, "person", person_id, None, Some(&db_transaction))
+        .expect("expected to prove the document history");
+
+    let proved_revisions =
+        Drive::verify_document_history(proof.as_slice(), &contract, "person", person_id, root_hash)
+            .expect("expected to verify the document history");
+
+    assert_eq!(proved_revisions, revisions);
+}
@@ -0,0 +1,50 @@
+use crate::buffer::Buffer;
+use dpp::identifier::Identifier;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(js_name=DataContractOwnerIdMismatchError)]
+pub struct DataContractOwnerIdMismatchErrorWasm {
+    data_contract_id: Identifier,
+    owner_id: Identifier,
+    existing_owner_id: Identifier,
+    code: u32,
+}
+
+#[wasm_bindgen(js_class=DataContractOwnerIdMismatchError)]
+impl DataContractOwnerIdMismatchErrorWasm {
+    #[wasm_bindgen(js_name=getDataContractId)]
+    pub fn data_contract_id(&self) -> Buffer {
+        Buffer::from_bytes(self.data_contract_id.as_bytes())
+    }
+
+    #[wasm_bindgen(js_name=getOwnerId)]
+    pub fn owner_id(&self) -> Buffer {
+        Buffer::from_bytes(self.owner_id.as_bytes())
+    }
+
+    #[wasm_bindgen(js_name=getExistingOwnerId)]
+    pub fn existing_owner_id(&self) -> Buffer {
+        Buffer::from_bytes(self.existing_owner_id.as_bytes())
+    }
+
+    #[wasm_bindgen(js_name=getCode)]
+    pub fn get_code(&self) -> u32 {
+        self.code
+    }
+}
+
+impl DataContractOwnerIdMismatchErrorWasm {
+    pub fn new(
+        data_contract_id: Identifier,
+        owner_id: Identifier,
+        existing_owner_id: Identifier,
+        code: u32,
+    ) -> Self {
+        Self {
+            data_contract_id,
+            owner_id,
+            existing_owner_id,
+            code,
+        }
+    }
+}
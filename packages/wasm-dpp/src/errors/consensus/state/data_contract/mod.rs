@@ -1,4 +1,8 @@
 mod data_contract_already_present_error;
+mod data_contract_can_not_be_deleted_error;
+mod data_contract_owner_id_mismatch_error;
 pub mod data_trigger;
 
 pub use data_contract_already_present_error::*;
+pub use data_contract_can_not_be_deleted_error::*;
+pub use data_contract_owner_id_mismatch_error::*;
@@ -48,7 +48,10 @@ use crate::errors::consensus::signature::IdentityNotFoundErrorWasm;
 use crate::errors::consensus::state::data_contract::data_trigger::{
     DataTriggerConditionErrorWasm, DataTriggerExecutionErrorWasm,
 };
-use crate::errors::consensus::state::data_contract::DataContractAlreadyPresentErrorWasm;
+use crate::errors::consensus::state::data_contract::{
+    DataContractAlreadyPresentErrorWasm, DataContractCanNotBeDeletedErrorWasm,
+    DataContractOwnerIdMismatchErrorWasm,
+};
 use crate::errors::consensus::state::document::{
     DocumentAlreadyPresentErrorWasm, DocumentNotFoundErrorWasm, DocumentOwnerIdMismatchErrorWasm,
     DocumentTimestampWindowViolationErrorWasm, DocumentTimestampsMismatchErrorWasm,
@@ -216,6 +219,20 @@ fn from_state_error(state_error: &Box<StateError>) -> JsValue {
         StateError::DataContractAlreadyPresentError { data_contract_id } => {
             DataContractAlreadyPresentErrorWasm::new(data_contract_id.clone(), code).into()
         }
+        StateError::DataContractOwnerIdMismatchError {
+            data_contract_id,
+            owner_id,
+            existing_owner_id,
+        } => DataContractOwnerIdMismatchErrorWasm::new(
+            data_contract_id.clone(),
+            owner_id.clone(),
+            existing_owner_id.clone(),
+            code,
+        )
+        .into(),
+        StateError::DataContractCanNotBeDeletedError { data_contract_id } => {
+            DataContractCanNotBeDeletedErrorWasm::new(data_contract_id.clone(), code).into()
+        }
         StateError::DocumentNotFoundError { document_id } => {
             DocumentNotFoundErrorWasm::new(document_id.clone(), code).into()
         }
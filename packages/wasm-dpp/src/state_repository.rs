@@ -33,6 +33,13 @@ extern "C" {
         execution_context: StateTransitionExecutionContextWasm,
     ) -> JsValue;
 
+    #[wasm_bindgen(structural, method, js_name=deleteDataContract)]
+    pub fn delete_data_contract(
+        this: &ExternalStateRepositoryLike,
+        data_contract_id: IdentifierWrapper,
+        execution_context: StateTransitionExecutionContextWasm,
+    ) -> JsValue;
+
     // TODO add missing declarations
 }
 
@@ -82,6 +89,21 @@ impl StateRepositoryLike for ExternalStateRepositoryLikeWrapper {
         Ok(())
     }
 
+    async fn delete_data_contract(
+        &self,
+        data_contract_id: &Identifier,
+        execution_context: &StateTransitionExecutionContext,
+    ) -> anyhow::Result<()> {
+        self.0
+            .lock()
+            .expect("unexpected concurrency issue!")
+            .delete_data_contract(
+                data_contract_id.clone().into(),
+                execution_context.clone().into(),
+            );
+        Ok(())
+    }
+
     async fn fetch_documents<T>(
         &self,
         _contract_id: &Identifier,
@@ -188,6 +210,7 @@ impl StateRepositoryLike for ExternalStateRepositoryLikeWrapper {
     async fn mark_asset_lock_transaction_out_point_as_used(
         &self,
         _out_point_buffer: &[u8],
+        _execution_context: &StateTransitionExecutionContext,
     ) -> anyhow::Result<()> {
         todo!()
     }
@@ -223,6 +246,7 @@ impl StateRepositoryLike for ExternalStateRepositoryLikeWrapper {
         &self,
         _index: u64,
         _transaction_bytes: Vec<u8>,
+        _execution_context: &StateTransitionExecutionContext,
     ) -> anyhow::Result<()> {
         todo!()
     }